@@ -0,0 +1,39 @@
+//  \ O /
+//  / * \    aiur: the homeplanet for the famous executors
+// |' | '|   (c) 2020 - present, Vladimir Zvezda
+//   / \
+//
+// The runtime built on aiur without std: the crate only has core and alloc, the test in
+// tests/ runs it.
+#![no_std]
+
+use aiur::{Reactor, Tracer};
+
+/// The reactor without any I/O, the oneshot exchange does not need it.
+pub struct NoIoReactor;
+
+impl Reactor for NoIoReactor {
+    fn wait(&self) -> EventId {
+        panic!("NoIoReactor: wait() invoked with nothing to wait")
+    }
+}
+
+aiur::export_runtime!(NoIoReactor);
+
+/// Sends the value through the oneshot from one branch of join to another and returns the
+/// received value.
+pub fn oneshot_exchange(value: u32) -> u32 {
+    async fn exchange(rt: &Runtime, value: u32) -> u32 {
+        let (mut tx, rx) = oneshot::<u32>(rt);
+        let (_, received) = join!(
+            async {
+                tx.send(value).await.unwrap();
+            },
+            async { rx.await.unwrap() }
+        )
+        .await;
+        received
+    }
+
+    aiur::with_runtime_base(NoIoReactor, Tracer::new_empty(), exchange, value)
+}
@@ -0,0 +1,11 @@
+//  \ O /
+//  / * \    aiur: the homeplanet for the famous executors
+// |' | '|   (c) 2020 - present, Vladimir Zvezda
+//   / \
+//
+// The test harness needs std, but aiur and the runtime in this crate are built without it.
+
+#[test]
+fn nostd_oneshot_exchange() {
+    assert_eq!(aiur_nostd_smoke::oneshot_exchange(42), 42);
+}
@@ -2,9 +2,9 @@
 //  / * \    aiur: the homeplanet for the famous executors
 // |' | '|   (c) 2020 - present, Vladimir Zvezda
 //   / \
-use std::future::Future;
-use std::pin::Pin;
-use std::task::{Context, Poll};
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
 
 /// Used as result of [AnyOfN::next()] for two futures.
 pub enum OneOf2<T1, T2> {
@@ -69,6 +69,740 @@ pub enum OneOf8<T1, T2, T3, T4, T5, T6, T7, T8> {
     Eighth(T8),
 }
 
+/// Used as result of [AnyOfN::next()] for nine futures.
+pub enum OneOf9<T1, T2, T3, T4, T5, T6, T7, T8, T9> {
+    First(T1),
+    Second(T2),
+    Third(T3),
+    Fourth(T4),
+    Fifth(T5),
+    Sixth(T6),
+    Seventh(T7),
+    Eighth(T8),
+    Ninth(T9),
+}
+
+/// Used as result of [AnyOfN::next()] for ten futures.
+pub enum OneOf10<T1, T2, T3, T4, T5, T6, T7, T8, T9, T10> {
+    First(T1),
+    Second(T2),
+    Third(T3),
+    Fourth(T4),
+    Fifth(T5),
+    Sixth(T6),
+    Seventh(T7),
+    Eighth(T8),
+    Ninth(T9),
+    Tenth(T10),
+}
+
+/// Used as result of [AnyOfN::next()] for eleven futures.
+pub enum OneOf11<T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11> {
+    First(T1),
+    Second(T2),
+    Third(T3),
+    Fourth(T4),
+    Fifth(T5),
+    Sixth(T6),
+    Seventh(T7),
+    Eighth(T8),
+    Ninth(T9),
+    Tenth(T10),
+    Eleventh(T11),
+}
+
+/// Used as result of [AnyOfN::next()] for twelve futures.
+pub enum OneOf12<T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12> {
+    First(T1),
+    Second(T2),
+    Third(T3),
+    Fourth(T4),
+    Fifth(T5),
+    Sixth(T6),
+    Seventh(T7),
+    Eighth(T8),
+    Ninth(T9),
+    Tenth(T10),
+    Eleventh(T11),
+    Twelfth(T12),
+}
+
+impl<T1, T2> OneOf2<T1, T2> {
+    /// Returns the zero-based index of the variant, e.g. 1 for `Second`, the same as
+    /// [AnyOfN::next_indexed()] gives.
+    pub fn index(&self) -> u8 {
+        match self {
+            OneOf2::First(_) => 0,
+            OneOf2::Second(_) => 1,
+        }
+    }
+
+    /// Consumes the value with the function of its variant, so the functions can convert
+    /// the results of different types into one type.
+    pub fn fold<ResT, FnT1, FnT2>(self, f1: FnT1, f2: FnT2) -> ResT
+    where
+        FnT1: FnOnce(T1) -> ResT,
+        FnT2: FnOnce(T2) -> ResT,
+    {
+        match self {
+            OneOf2::First(x) => f1(x),
+            OneOf2::Second(x) => f2(x),
+        }
+    }
+}
+
+impl<T> OneOf2<T, T> {
+    /// Returns the value when both futures have the same output type.
+    pub fn into_inner(self) -> T {
+        match self {
+            OneOf2::First(x) | OneOf2::Second(x) => x,
+        }
+    }
+}
+
+impl<T1, T2, T3> OneOf3<T1, T2, T3> {
+    /// Returns the zero-based index of the variant, e.g. 1 for `Second`, the same as
+    /// [AnyOfN::next_indexed()] gives.
+    pub fn index(&self) -> u8 {
+        match self {
+            OneOf3::First(_) => 0,
+            OneOf3::Second(_) => 1,
+            OneOf3::Third(_) => 2,
+        }
+    }
+
+    /// Consumes the value with the function of its variant, so the functions can convert
+    /// the results of different types into one type.
+    pub fn fold<ResT, FnT1, FnT2, FnT3>(self, f1: FnT1, f2: FnT2, f3: FnT3) -> ResT
+    where
+        FnT1: FnOnce(T1) -> ResT,
+        FnT2: FnOnce(T2) -> ResT,
+        FnT3: FnOnce(T3) -> ResT,
+    {
+        match self {
+            OneOf3::First(x) => f1(x),
+            OneOf3::Second(x) => f2(x),
+            OneOf3::Third(x) => f3(x),
+        }
+    }
+}
+
+impl<T> OneOf3<T, T, T> {
+    /// Returns the value when all three futures have the same output type.
+    pub fn into_inner(self) -> T {
+        match self {
+            OneOf3::First(x) | OneOf3::Second(x) | OneOf3::Third(x) => x,
+        }
+    }
+}
+
+impl<T1, T2, T3, T4> OneOf4<T1, T2, T3, T4> {
+    /// Returns the zero-based index of the variant, e.g. 1 for `Second`, the same as
+    /// [AnyOfN::next_indexed()] gives.
+    pub fn index(&self) -> u8 {
+        match self {
+            OneOf4::First(_) => 0,
+            OneOf4::Second(_) => 1,
+            OneOf4::Third(_) => 2,
+            OneOf4::Fourth(_) => 3,
+        }
+    }
+
+    /// Consumes the value with the function of its variant, so the functions can convert
+    /// the results of different types into one type.
+    pub fn fold<ResT, FnT1, FnT2, FnT3, FnT4>(self, f1: FnT1, f2: FnT2, f3: FnT3, f4: FnT4) -> ResT
+    where
+        FnT1: FnOnce(T1) -> ResT,
+        FnT2: FnOnce(T2) -> ResT,
+        FnT3: FnOnce(T3) -> ResT,
+        FnT4: FnOnce(T4) -> ResT,
+    {
+        match self {
+            OneOf4::First(x) => f1(x),
+            OneOf4::Second(x) => f2(x),
+            OneOf4::Third(x) => f3(x),
+            OneOf4::Fourth(x) => f4(x),
+        }
+    }
+}
+
+impl<T> OneOf4<T, T, T, T> {
+    /// Returns the value when all four futures have the same output type.
+    pub fn into_inner(self) -> T {
+        match self {
+            OneOf4::First(x) | OneOf4::Second(x) | OneOf4::Third(x) | OneOf4::Fourth(x) => x,
+        }
+    }
+}
+
+impl<T1, T2, T3, T4, T5> OneOf5<T1, T2, T3, T4, T5> {
+    /// Returns the zero-based index of the variant, e.g. 1 for `Second`, the same as
+    /// [AnyOfN::next_indexed()] gives.
+    pub fn index(&self) -> u8 {
+        match self {
+            OneOf5::First(_) => 0,
+            OneOf5::Second(_) => 1,
+            OneOf5::Third(_) => 2,
+            OneOf5::Fourth(_) => 3,
+            OneOf5::Fifth(_) => 4,
+        }
+    }
+
+    /// Consumes the value with the function of its variant, so the functions can convert
+    /// the results of different types into one type.
+    pub fn fold<ResT, FnT1, FnT2, FnT3, FnT4, FnT5>(
+        self,
+        f1: FnT1,
+        f2: FnT2,
+        f3: FnT3,
+        f4: FnT4,
+        f5: FnT5,
+    ) -> ResT
+    where
+        FnT1: FnOnce(T1) -> ResT,
+        FnT2: FnOnce(T2) -> ResT,
+        FnT3: FnOnce(T3) -> ResT,
+        FnT4: FnOnce(T4) -> ResT,
+        FnT5: FnOnce(T5) -> ResT,
+    {
+        match self {
+            OneOf5::First(x) => f1(x),
+            OneOf5::Second(x) => f2(x),
+            OneOf5::Third(x) => f3(x),
+            OneOf5::Fourth(x) => f4(x),
+            OneOf5::Fifth(x) => f5(x),
+        }
+    }
+}
+
+impl<T> OneOf5<T, T, T, T, T> {
+    /// Returns the value when all five futures have the same output type.
+    pub fn into_inner(self) -> T {
+        match self {
+            OneOf5::First(x)
+            | OneOf5::Second(x)
+            | OneOf5::Third(x)
+            | OneOf5::Fourth(x)
+            | OneOf5::Fifth(x) => x,
+        }
+    }
+}
+
+impl<T1, T2, T3, T4, T5, T6> OneOf6<T1, T2, T3, T4, T5, T6> {
+    /// Returns the zero-based index of the variant, e.g. 1 for `Second`, the same as
+    /// [AnyOfN::next_indexed()] gives.
+    pub fn index(&self) -> u8 {
+        match self {
+            OneOf6::First(_) => 0,
+            OneOf6::Second(_) => 1,
+            OneOf6::Third(_) => 2,
+            OneOf6::Fourth(_) => 3,
+            OneOf6::Fifth(_) => 4,
+            OneOf6::Sixth(_) => 5,
+        }
+    }
+
+    /// Consumes the value with the function of its variant, so the functions can convert
+    /// the results of different types into one type.
+    pub fn fold<ResT, FnT1, FnT2, FnT3, FnT4, FnT5, FnT6>(
+        self,
+        f1: FnT1,
+        f2: FnT2,
+        f3: FnT3,
+        f4: FnT4,
+        f5: FnT5,
+        f6: FnT6,
+    ) -> ResT
+    where
+        FnT1: FnOnce(T1) -> ResT,
+        FnT2: FnOnce(T2) -> ResT,
+        FnT3: FnOnce(T3) -> ResT,
+        FnT4: FnOnce(T4) -> ResT,
+        FnT5: FnOnce(T5) -> ResT,
+        FnT6: FnOnce(T6) -> ResT,
+    {
+        match self {
+            OneOf6::First(x) => f1(x),
+            OneOf6::Second(x) => f2(x),
+            OneOf6::Third(x) => f3(x),
+            OneOf6::Fourth(x) => f4(x),
+            OneOf6::Fifth(x) => f5(x),
+            OneOf6::Sixth(x) => f6(x),
+        }
+    }
+}
+
+impl<T> OneOf6<T, T, T, T, T, T> {
+    /// Returns the value when all six futures have the same output type.
+    pub fn into_inner(self) -> T {
+        match self {
+            OneOf6::First(x)
+            | OneOf6::Second(x)
+            | OneOf6::Third(x)
+            | OneOf6::Fourth(x)
+            | OneOf6::Fifth(x)
+            | OneOf6::Sixth(x) => x,
+        }
+    }
+}
+
+impl<T1, T2, T3, T4, T5, T6, T7> OneOf7<T1, T2, T3, T4, T5, T6, T7> {
+    /// Returns the zero-based index of the variant, e.g. 1 for `Second`, the same as
+    /// [AnyOfN::next_indexed()] gives.
+    pub fn index(&self) -> u8 {
+        match self {
+            OneOf7::First(_) => 0,
+            OneOf7::Second(_) => 1,
+            OneOf7::Third(_) => 2,
+            OneOf7::Fourth(_) => 3,
+            OneOf7::Fifth(_) => 4,
+            OneOf7::Sixth(_) => 5,
+            OneOf7::Seventh(_) => 6,
+        }
+    }
+
+    /// Consumes the value with the function of its variant, so the functions can convert
+    /// the results of different types into one type.
+    pub fn fold<ResT, FnT1, FnT2, FnT3, FnT4, FnT5, FnT6, FnT7>(
+        self,
+        f1: FnT1,
+        f2: FnT2,
+        f3: FnT3,
+        f4: FnT4,
+        f5: FnT5,
+        f6: FnT6,
+        f7: FnT7,
+    ) -> ResT
+    where
+        FnT1: FnOnce(T1) -> ResT,
+        FnT2: FnOnce(T2) -> ResT,
+        FnT3: FnOnce(T3) -> ResT,
+        FnT4: FnOnce(T4) -> ResT,
+        FnT5: FnOnce(T5) -> ResT,
+        FnT6: FnOnce(T6) -> ResT,
+        FnT7: FnOnce(T7) -> ResT,
+    {
+        match self {
+            OneOf7::First(x) => f1(x),
+            OneOf7::Second(x) => f2(x),
+            OneOf7::Third(x) => f3(x),
+            OneOf7::Fourth(x) => f4(x),
+            OneOf7::Fifth(x) => f5(x),
+            OneOf7::Sixth(x) => f6(x),
+            OneOf7::Seventh(x) => f7(x),
+        }
+    }
+}
+
+impl<T> OneOf7<T, T, T, T, T, T, T> {
+    /// Returns the value when all seven futures have the same output type.
+    pub fn into_inner(self) -> T {
+        match self {
+            OneOf7::First(x)
+            | OneOf7::Second(x)
+            | OneOf7::Third(x)
+            | OneOf7::Fourth(x)
+            | OneOf7::Fifth(x)
+            | OneOf7::Sixth(x)
+            | OneOf7::Seventh(x) => x,
+        }
+    }
+}
+
+impl<T1, T2, T3, T4, T5, T6, T7, T8> OneOf8<T1, T2, T3, T4, T5, T6, T7, T8> {
+    /// Returns the zero-based index of the variant, e.g. 1 for `Second`, the same as
+    /// [AnyOfN::next_indexed()] gives.
+    pub fn index(&self) -> u8 {
+        match self {
+            OneOf8::First(_) => 0,
+            OneOf8::Second(_) => 1,
+            OneOf8::Third(_) => 2,
+            OneOf8::Fourth(_) => 3,
+            OneOf8::Fifth(_) => 4,
+            OneOf8::Sixth(_) => 5,
+            OneOf8::Seventh(_) => 6,
+            OneOf8::Eighth(_) => 7,
+        }
+    }
+
+    /// Consumes the value with the function of its variant, so the functions can convert
+    /// the results of different types into one type.
+    pub fn fold<ResT, FnT1, FnT2, FnT3, FnT4, FnT5, FnT6, FnT7, FnT8>(
+        self,
+        f1: FnT1,
+        f2: FnT2,
+        f3: FnT3,
+        f4: FnT4,
+        f5: FnT5,
+        f6: FnT6,
+        f7: FnT7,
+        f8: FnT8,
+    ) -> ResT
+    where
+        FnT1: FnOnce(T1) -> ResT,
+        FnT2: FnOnce(T2) -> ResT,
+        FnT3: FnOnce(T3) -> ResT,
+        FnT4: FnOnce(T4) -> ResT,
+        FnT5: FnOnce(T5) -> ResT,
+        FnT6: FnOnce(T6) -> ResT,
+        FnT7: FnOnce(T7) -> ResT,
+        FnT8: FnOnce(T8) -> ResT,
+    {
+        match self {
+            OneOf8::First(x) => f1(x),
+            OneOf8::Second(x) => f2(x),
+            OneOf8::Third(x) => f3(x),
+            OneOf8::Fourth(x) => f4(x),
+            OneOf8::Fifth(x) => f5(x),
+            OneOf8::Sixth(x) => f6(x),
+            OneOf8::Seventh(x) => f7(x),
+            OneOf8::Eighth(x) => f8(x),
+        }
+    }
+}
+
+impl<T> OneOf8<T, T, T, T, T, T, T, T> {
+    /// Returns the value when all eight futures have the same output type.
+    pub fn into_inner(self) -> T {
+        match self {
+            OneOf8::First(x)
+            | OneOf8::Second(x)
+            | OneOf8::Third(x)
+            | OneOf8::Fourth(x)
+            | OneOf8::Fifth(x)
+            | OneOf8::Sixth(x)
+            | OneOf8::Seventh(x)
+            | OneOf8::Eighth(x) => x,
+        }
+    }
+}
+
+impl<T1, T2, T3, T4, T5, T6, T7, T8, T9> OneOf9<T1, T2, T3, T4, T5, T6, T7, T8, T9> {
+    /// Returns the zero-based index of the variant, e.g. 1 for `Second`, the same as
+    /// [AnyOfN::next_indexed()] gives.
+    pub fn index(&self) -> u8 {
+        match self {
+            OneOf9::First(_) => 0,
+            OneOf9::Second(_) => 1,
+            OneOf9::Third(_) => 2,
+            OneOf9::Fourth(_) => 3,
+            OneOf9::Fifth(_) => 4,
+            OneOf9::Sixth(_) => 5,
+            OneOf9::Seventh(_) => 6,
+            OneOf9::Eighth(_) => 7,
+            OneOf9::Ninth(_) => 8,
+        }
+    }
+
+    /// Consumes the value with the function of its variant, so the functions can convert
+    /// the results of different types into one type.
+    pub fn fold<ResT, FnT1, FnT2, FnT3, FnT4, FnT5, FnT6, FnT7, FnT8, FnT9>(
+        self,
+        f1: FnT1,
+        f2: FnT2,
+        f3: FnT3,
+        f4: FnT4,
+        f5: FnT5,
+        f6: FnT6,
+        f7: FnT7,
+        f8: FnT8,
+        f9: FnT9,
+    ) -> ResT
+    where
+        FnT1: FnOnce(T1) -> ResT,
+        FnT2: FnOnce(T2) -> ResT,
+        FnT3: FnOnce(T3) -> ResT,
+        FnT4: FnOnce(T4) -> ResT,
+        FnT5: FnOnce(T5) -> ResT,
+        FnT6: FnOnce(T6) -> ResT,
+        FnT7: FnOnce(T7) -> ResT,
+        FnT8: FnOnce(T8) -> ResT,
+        FnT9: FnOnce(T9) -> ResT,
+    {
+        match self {
+            OneOf9::First(x) => f1(x),
+            OneOf9::Second(x) => f2(x),
+            OneOf9::Third(x) => f3(x),
+            OneOf9::Fourth(x) => f4(x),
+            OneOf9::Fifth(x) => f5(x),
+            OneOf9::Sixth(x) => f6(x),
+            OneOf9::Seventh(x) => f7(x),
+            OneOf9::Eighth(x) => f8(x),
+            OneOf9::Ninth(x) => f9(x),
+        }
+    }
+}
+
+impl<T> OneOf9<T, T, T, T, T, T, T, T, T> {
+    /// Returns the value when all nine futures have the same output type.
+    pub fn into_inner(self) -> T {
+        match self {
+            OneOf9::First(x)
+            | OneOf9::Second(x)
+            | OneOf9::Third(x)
+            | OneOf9::Fourth(x)
+            | OneOf9::Fifth(x)
+            | OneOf9::Sixth(x)
+            | OneOf9::Seventh(x)
+            | OneOf9::Eighth(x)
+            | OneOf9::Ninth(x) => x,
+        }
+    }
+}
+
+impl<T1, T2, T3, T4, T5, T6, T7, T8, T9, T10> OneOf10<T1, T2, T3, T4, T5, T6, T7, T8, T9, T10> {
+    /// Returns the zero-based index of the variant, e.g. 1 for `Second`, the same as
+    /// [AnyOfN::next_indexed()] gives.
+    pub fn index(&self) -> u8 {
+        match self {
+            OneOf10::First(_) => 0,
+            OneOf10::Second(_) => 1,
+            OneOf10::Third(_) => 2,
+            OneOf10::Fourth(_) => 3,
+            OneOf10::Fifth(_) => 4,
+            OneOf10::Sixth(_) => 5,
+            OneOf10::Seventh(_) => 6,
+            OneOf10::Eighth(_) => 7,
+            OneOf10::Ninth(_) => 8,
+            OneOf10::Tenth(_) => 9,
+        }
+    }
+
+    /// Consumes the value with the function of its variant, so the functions can convert
+    /// the results of different types into one type.
+    pub fn fold<ResT, FnT1, FnT2, FnT3, FnT4, FnT5, FnT6, FnT7, FnT8, FnT9, FnT10>(
+        self,
+        f1: FnT1,
+        f2: FnT2,
+        f3: FnT3,
+        f4: FnT4,
+        f5: FnT5,
+        f6: FnT6,
+        f7: FnT7,
+        f8: FnT8,
+        f9: FnT9,
+        f10: FnT10,
+    ) -> ResT
+    where
+        FnT1: FnOnce(T1) -> ResT,
+        FnT2: FnOnce(T2) -> ResT,
+        FnT3: FnOnce(T3) -> ResT,
+        FnT4: FnOnce(T4) -> ResT,
+        FnT5: FnOnce(T5) -> ResT,
+        FnT6: FnOnce(T6) -> ResT,
+        FnT7: FnOnce(T7) -> ResT,
+        FnT8: FnOnce(T8) -> ResT,
+        FnT9: FnOnce(T9) -> ResT,
+        FnT10: FnOnce(T10) -> ResT,
+    {
+        match self {
+            OneOf10::First(x) => f1(x),
+            OneOf10::Second(x) => f2(x),
+            OneOf10::Third(x) => f3(x),
+            OneOf10::Fourth(x) => f4(x),
+            OneOf10::Fifth(x) => f5(x),
+            OneOf10::Sixth(x) => f6(x),
+            OneOf10::Seventh(x) => f7(x),
+            OneOf10::Eighth(x) => f8(x),
+            OneOf10::Ninth(x) => f9(x),
+            OneOf10::Tenth(x) => f10(x),
+        }
+    }
+}
+
+impl<T> OneOf10<T, T, T, T, T, T, T, T, T, T> {
+    /// Returns the value when all ten futures have the same output type.
+    pub fn into_inner(self) -> T {
+        match self {
+            OneOf10::First(x)
+            | OneOf10::Second(x)
+            | OneOf10::Third(x)
+            | OneOf10::Fourth(x)
+            | OneOf10::Fifth(x)
+            | OneOf10::Sixth(x)
+            | OneOf10::Seventh(x)
+            | OneOf10::Eighth(x)
+            | OneOf10::Ninth(x)
+            | OneOf10::Tenth(x) => x,
+        }
+    }
+}
+
+impl<T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11>
+    OneOf11<T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11>
+{
+    /// Returns the zero-based index of the variant, e.g. 1 for `Second`, the same as
+    /// [AnyOfN::next_indexed()] gives.
+    pub fn index(&self) -> u8 {
+        match self {
+            OneOf11::First(_) => 0,
+            OneOf11::Second(_) => 1,
+            OneOf11::Third(_) => 2,
+            OneOf11::Fourth(_) => 3,
+            OneOf11::Fifth(_) => 4,
+            OneOf11::Sixth(_) => 5,
+            OneOf11::Seventh(_) => 6,
+            OneOf11::Eighth(_) => 7,
+            OneOf11::Ninth(_) => 8,
+            OneOf11::Tenth(_) => 9,
+            OneOf11::Eleventh(_) => 10,
+        }
+    }
+
+    /// Consumes the value with the function of its variant, so the functions can convert
+    /// the results of different types into one type.
+    pub fn fold<ResT, FnT1, FnT2, FnT3, FnT4, FnT5, FnT6, FnT7, FnT8, FnT9, FnT10, FnT11>(
+        self,
+        f1: FnT1,
+        f2: FnT2,
+        f3: FnT3,
+        f4: FnT4,
+        f5: FnT5,
+        f6: FnT6,
+        f7: FnT7,
+        f8: FnT8,
+        f9: FnT9,
+        f10: FnT10,
+        f11: FnT11,
+    ) -> ResT
+    where
+        FnT1: FnOnce(T1) -> ResT,
+        FnT2: FnOnce(T2) -> ResT,
+        FnT3: FnOnce(T3) -> ResT,
+        FnT4: FnOnce(T4) -> ResT,
+        FnT5: FnOnce(T5) -> ResT,
+        FnT6: FnOnce(T6) -> ResT,
+        FnT7: FnOnce(T7) -> ResT,
+        FnT8: FnOnce(T8) -> ResT,
+        FnT9: FnOnce(T9) -> ResT,
+        FnT10: FnOnce(T10) -> ResT,
+        FnT11: FnOnce(T11) -> ResT,
+    {
+        match self {
+            OneOf11::First(x) => f1(x),
+            OneOf11::Second(x) => f2(x),
+            OneOf11::Third(x) => f3(x),
+            OneOf11::Fourth(x) => f4(x),
+            OneOf11::Fifth(x) => f5(x),
+            OneOf11::Sixth(x) => f6(x),
+            OneOf11::Seventh(x) => f7(x),
+            OneOf11::Eighth(x) => f8(x),
+            OneOf11::Ninth(x) => f9(x),
+            OneOf11::Tenth(x) => f10(x),
+            OneOf11::Eleventh(x) => f11(x),
+        }
+    }
+}
+
+impl<T> OneOf11<T, T, T, T, T, T, T, T, T, T, T> {
+    /// Returns the value when all eleven futures have the same output type.
+    pub fn into_inner(self) -> T {
+        match self {
+            OneOf11::First(x)
+            | OneOf11::Second(x)
+            | OneOf11::Third(x)
+            | OneOf11::Fourth(x)
+            | OneOf11::Fifth(x)
+            | OneOf11::Sixth(x)
+            | OneOf11::Seventh(x)
+            | OneOf11::Eighth(x)
+            | OneOf11::Ninth(x)
+            | OneOf11::Tenth(x)
+            | OneOf11::Eleventh(x) => x,
+        }
+    }
+}
+
+impl<T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12>
+    OneOf12<T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12>
+{
+    /// Returns the zero-based index of the variant, e.g. 1 for `Second`, the same as
+    /// [AnyOfN::next_indexed()] gives.
+    pub fn index(&self) -> u8 {
+        match self {
+            OneOf12::First(_) => 0,
+            OneOf12::Second(_) => 1,
+            OneOf12::Third(_) => 2,
+            OneOf12::Fourth(_) => 3,
+            OneOf12::Fifth(_) => 4,
+            OneOf12::Sixth(_) => 5,
+            OneOf12::Seventh(_) => 6,
+            OneOf12::Eighth(_) => 7,
+            OneOf12::Ninth(_) => 8,
+            OneOf12::Tenth(_) => 9,
+            OneOf12::Eleventh(_) => 10,
+            OneOf12::Twelfth(_) => 11,
+        }
+    }
+
+    /// Consumes the value with the function of its variant, so the functions can convert
+    /// the results of different types into one type.
+    pub fn fold<ResT, FnT1, FnT2, FnT3, FnT4, FnT5, FnT6, FnT7, FnT8, FnT9, FnT10, FnT11, FnT12>(
+        self,
+        f1: FnT1,
+        f2: FnT2,
+        f3: FnT3,
+        f4: FnT4,
+        f5: FnT5,
+        f6: FnT6,
+        f7: FnT7,
+        f8: FnT8,
+        f9: FnT9,
+        f10: FnT10,
+        f11: FnT11,
+        f12: FnT12,
+    ) -> ResT
+    where
+        FnT1: FnOnce(T1) -> ResT,
+        FnT2: FnOnce(T2) -> ResT,
+        FnT3: FnOnce(T3) -> ResT,
+        FnT4: FnOnce(T4) -> ResT,
+        FnT5: FnOnce(T5) -> ResT,
+        FnT6: FnOnce(T6) -> ResT,
+        FnT7: FnOnce(T7) -> ResT,
+        FnT8: FnOnce(T8) -> ResT,
+        FnT9: FnOnce(T9) -> ResT,
+        FnT10: FnOnce(T10) -> ResT,
+        FnT11: FnOnce(T11) -> ResT,
+        FnT12: FnOnce(T12) -> ResT,
+    {
+        match self {
+            OneOf12::First(x) => f1(x),
+            OneOf12::Second(x) => f2(x),
+            OneOf12::Third(x) => f3(x),
+            OneOf12::Fourth(x) => f4(x),
+            OneOf12::Fifth(x) => f5(x),
+            OneOf12::Sixth(x) => f6(x),
+            OneOf12::Seventh(x) => f7(x),
+            OneOf12::Eighth(x) => f8(x),
+            OneOf12::Ninth(x) => f9(x),
+            OneOf12::Tenth(x) => f10(x),
+            OneOf12::Eleventh(x) => f11(x),
+            OneOf12::Twelfth(x) => f12(x),
+        }
+    }
+}
+
+impl<T> OneOf12<T, T, T, T, T, T, T, T, T, T, T, T> {
+    /// Returns the value when all twelve futures have the same output type.
+    pub fn into_inner(self) -> T {
+        match self {
+            OneOf12::First(x)
+            | OneOf12::Second(x)
+            | OneOf12::Third(x)
+            | OneOf12::Fourth(x)
+            | OneOf12::Fifth(x)
+            | OneOf12::Sixth(x)
+            | OneOf12::Seventh(x)
+            | OneOf12::Eighth(x)
+            | OneOf12::Ninth(x)
+            | OneOf12::Tenth(x)
+            | OneOf12::Eleventh(x)
+            | OneOf12::Twelfth(x) => x,
+        }
+    }
+}
+
 /// Stream to poll several futures concurrently.
 ///
 /// To create it use one of any_ofX() function or the macros [make_any_of!()](crate::make_any_of!) 
@@ -83,25 +817,73 @@ pub enum OneOf8<T1, T2, T3, T4, T5, T6, T7, T8> {
 ///
 /// When constructed the AnyOfN stream takes ownership over the futures. Futures are dropped
 /// only when AnyOfN is dropoped.
+///
+/// The first poll of the stream polls the futures in their order, the following polls start
+/// from the next future each time. So when several futures are ready at the same poll, none of
+/// them is always preferred.
 pub struct AnyOfN<TupleT> {
-    fs: TupleT, // (Fut1, Fut2, .. FutN)
-    active: u8, // bitfield for completed futures, u8 is ok for now
+    fs: TupleT,  // (Fut1, Fut2, .. FutN)
+    active: u16, // bitfield for completed futures
+    start: u8,   // the future to poll first, rotated on each poll
 }
 
 impl<TupleT> AnyOfN<TupleT> {
+    /// Same as `next()`, but also returns the zero-based index of the completed future, e.g.
+    /// 1 for `OneOf3::Second`. This is handy for the loops that only need to know which
+    /// future has been completed.
+    pub async fn next_indexed<OneOfT>(self: &mut Pin<&mut Self>) -> Option<(u8, OneOfT)>
+    where
+        for<'any> NextOfN<'any, TupleT>: Future<Output = Option<OneOfT>>,
+    {
+        let this = unsafe { self.as_mut().get_unchecked_mut() };
+        let active = this.active;
+        let one_of = (NextOfN { any: &mut *this }).await?;
+
+        // The completed future is the only bit that has been cleared by poll_n()
+        let index = (active & !this.active).trailing_zeros() as u8;
+        Some((index, one_of))
+    }
+
+    /// Polls the stream once and returns the result of the first completed future or `None` if
+    /// all futures of the stream has been completed. This is `next()` for the callers that have
+    /// a plain `Pin<&mut AnyOfN>`, e.g. from `Box::pin()` or a hand-written future, see also
+    /// [next_of()].
+    pub fn poll_next<OneOfT>(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Option<OneOfT>>
+    where
+        for<'any> NextOfN<'any, TupleT>: Future<Output = Option<OneOfT>>,
+    {
+        // Unsafe is ok: NextOfN polls the futures in place, they are not moved.
+        let this = unsafe { self.get_unchecked_mut() };
+        Pin::new(&mut NextOfN { any: this }).poll(ctx)
+    }
+
+    // Returns the future moved out by into_remaining() if it has not been completed
+    fn remaining<FutT>(active: u16, n: u8, fut: FutT) -> Option<FutT> {
+        (active & (1 << n) != 0).then_some(fut)
+    }
+
     // If all futures in this AnyOfN has been completed
     fn is_done(&self) -> bool {
         self.active == 0
     }
 
+    // Returns the index of the future to poll first and rotates it, so the futures that are
+    // ready often do not starve the futures after them. The first poll still goes in order of
+    // futures, the completed futures are skipped by poll_n() as before.
+    fn next_start(&mut self, count: u8) -> u8 {
+        let start = self.start % count;
+        self.start = (start + 1) % count;
+        start
+    }
+
     // Shared code to poll the future from self.fs
     fn poll_n<FutT: Future>(
         ctx: &mut Context,
         n: u8,
         fut: &mut FutT,
-        active: &mut u8,
+        active: &mut u16,
     ) -> Option<FutT::Output> {
-        let active_flag: u8 = 1 << n;
+        let active_flag: u16 = 1 << n;
 
         if *active & active_flag != 0 {
             // Unsafe is ok: the AnyOfN has to be Pin<&mut self> for next().
@@ -297,82 +1079,364 @@ where
     }
 }
 
-pub struct NextOfN<'any, TupleT> {
-    any: &'any mut AnyOfN<TupleT>,
-}
-
-impl<'any, FutT1, FutT2> Future for NextOfN<'any, (FutT1, FutT2)>
-where
-    FutT1: Future,
-    FutT2: Future,
-{
-    type Output = Option<OneOf2<FutT1::Output, FutT2::Output>>;
-
-    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
-        let this = unsafe { self.get_unchecked_mut() };
-
-        if this.any.is_done() {
-            Poll::Ready(None)
-        } else if let Some(x) = this.any.poll_f1(ctx) {
-            Poll::Ready(Some(OneOf2::First(x)))
-        } else if let Some(x) = this.any.poll_f2(ctx) {
-            Poll::Ready(Some(OneOf2::Second(x)))
-        } else {
-            Poll::Pending
-        }
-    }
-}
-
-impl<'any, FutT1, FutT2, FutT3> Future for NextOfN<'any, (FutT1, FutT2, FutT3)>
+impl<FutT1, FutT2, FutT3, FutT4, FutT5, FutT6, FutT7, FutT8, FutT9>
+    AnyOfN<(
+        FutT1,
+        FutT2,
+        FutT3,
+        FutT4,
+        FutT5,
+        FutT6,
+        FutT7,
+        FutT8,
+        FutT9,
+    )>
 where
     FutT1: Future,
     FutT2: Future,
     FutT3: Future,
+    FutT4: Future,
+    FutT5: Future,
+    FutT6: Future,
+    FutT7: Future,
+    FutT8: Future,
+    FutT9: Future,
 {
-    type Output = Option<OneOf3<FutT1::Output, FutT2::Output, FutT3::Output>>;
-
-    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
-        let this = unsafe { self.get_unchecked_mut() };
-
-        if this.any.is_done() {
-            Poll::Ready(None)
-        } else if let Some(x) = this.any.poll_f1(ctx) {
-            Poll::Ready(Some(OneOf3::First(x)))
-        } else if let Some(x) = this.any.poll_f2(ctx) {
-            Poll::Ready(Some(OneOf3::Second(x)))
-        } else if let Some(x) = this.any.poll_f3(ctx) {
-            Poll::Ready(Some(OneOf3::Third(x)))
-        } else {
-            Poll::Pending
-        }
+    fn poll_f1(&mut self, ctx: &mut Context) -> Option<FutT1::Output> {
+        Self::poll_n(ctx, 0, &mut self.fs.0, &mut self.active)
+    }
+    fn poll_f2(&mut self, ctx: &mut Context) -> Option<FutT2::Output> {
+        Self::poll_n(ctx, 1, &mut self.fs.1, &mut self.active)
+    }
+    fn poll_f3(&mut self, ctx: &mut Context) -> Option<FutT3::Output> {
+        Self::poll_n(ctx, 2, &mut self.fs.2, &mut self.active)
+    }
+    fn poll_f4(&mut self, ctx: &mut Context) -> Option<FutT4::Output> {
+        Self::poll_n(ctx, 3, &mut self.fs.3, &mut self.active)
+    }
+    fn poll_f5(&mut self, ctx: &mut Context) -> Option<FutT5::Output> {
+        Self::poll_n(ctx, 4, &mut self.fs.4, &mut self.active)
+    }
+    fn poll_f6(&mut self, ctx: &mut Context) -> Option<FutT6::Output> {
+        Self::poll_n(ctx, 5, &mut self.fs.5, &mut self.active)
+    }
+    fn poll_f7(&mut self, ctx: &mut Context) -> Option<FutT7::Output> {
+        Self::poll_n(ctx, 6, &mut self.fs.6, &mut self.active)
+    }
+    fn poll_f8(&mut self, ctx: &mut Context) -> Option<FutT8::Output> {
+        Self::poll_n(ctx, 7, &mut self.fs.7, &mut self.active)
+    }
+    fn poll_f9(&mut self, ctx: &mut Context) -> Option<FutT9::Output> {
+        Self::poll_n(ctx, 8, &mut self.fs.8, &mut self.active)
     }
 }
 
-impl<'any, FutT1, FutT2, FutT3, FutT4> Future for NextOfN<'any, (FutT1, FutT2, FutT3, FutT4)>
+impl<FutT1, FutT2, FutT3, FutT4, FutT5, FutT6, FutT7, FutT8, FutT9, FutT10>
+    AnyOfN<(
+        FutT1,
+        FutT2,
+        FutT3,
+        FutT4,
+        FutT5,
+        FutT6,
+        FutT7,
+        FutT8,
+        FutT9,
+        FutT10,
+    )>
 where
     FutT1: Future,
     FutT2: Future,
     FutT3: Future,
     FutT4: Future,
+    FutT5: Future,
+    FutT6: Future,
+    FutT7: Future,
+    FutT8: Future,
+    FutT9: Future,
+    FutT10: Future,
 {
-    type Output = Option<OneOf4<FutT1::Output, FutT2::Output, FutT3::Output, FutT4::Output>>;
-
-    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
-        let this = unsafe { self.get_unchecked_mut() };
-
+    fn poll_f1(&mut self, ctx: &mut Context) -> Option<FutT1::Output> {
+        Self::poll_n(ctx, 0, &mut self.fs.0, &mut self.active)
+    }
+    fn poll_f2(&mut self, ctx: &mut Context) -> Option<FutT2::Output> {
+        Self::poll_n(ctx, 1, &mut self.fs.1, &mut self.active)
+    }
+    fn poll_f3(&mut self, ctx: &mut Context) -> Option<FutT3::Output> {
+        Self::poll_n(ctx, 2, &mut self.fs.2, &mut self.active)
+    }
+    fn poll_f4(&mut self, ctx: &mut Context) -> Option<FutT4::Output> {
+        Self::poll_n(ctx, 3, &mut self.fs.3, &mut self.active)
+    }
+    fn poll_f5(&mut self, ctx: &mut Context) -> Option<FutT5::Output> {
+        Self::poll_n(ctx, 4, &mut self.fs.4, &mut self.active)
+    }
+    fn poll_f6(&mut self, ctx: &mut Context) -> Option<FutT6::Output> {
+        Self::poll_n(ctx, 5, &mut self.fs.5, &mut self.active)
+    }
+    fn poll_f7(&mut self, ctx: &mut Context) -> Option<FutT7::Output> {
+        Self::poll_n(ctx, 6, &mut self.fs.6, &mut self.active)
+    }
+    fn poll_f8(&mut self, ctx: &mut Context) -> Option<FutT8::Output> {
+        Self::poll_n(ctx, 7, &mut self.fs.7, &mut self.active)
+    }
+    fn poll_f9(&mut self, ctx: &mut Context) -> Option<FutT9::Output> {
+        Self::poll_n(ctx, 8, &mut self.fs.8, &mut self.active)
+    }
+    fn poll_f10(&mut self, ctx: &mut Context) -> Option<FutT10::Output> {
+        Self::poll_n(ctx, 9, &mut self.fs.9, &mut self.active)
+    }
+}
+
+impl<FutT1, FutT2, FutT3, FutT4, FutT5, FutT6, FutT7, FutT8, FutT9, FutT10, FutT11>
+    AnyOfN<(
+        FutT1,
+        FutT2,
+        FutT3,
+        FutT4,
+        FutT5,
+        FutT6,
+        FutT7,
+        FutT8,
+        FutT9,
+        FutT10,
+        FutT11,
+    )>
+where
+    FutT1: Future,
+    FutT2: Future,
+    FutT3: Future,
+    FutT4: Future,
+    FutT5: Future,
+    FutT6: Future,
+    FutT7: Future,
+    FutT8: Future,
+    FutT9: Future,
+    FutT10: Future,
+    FutT11: Future,
+{
+    fn poll_f1(&mut self, ctx: &mut Context) -> Option<FutT1::Output> {
+        Self::poll_n(ctx, 0, &mut self.fs.0, &mut self.active)
+    }
+    fn poll_f2(&mut self, ctx: &mut Context) -> Option<FutT2::Output> {
+        Self::poll_n(ctx, 1, &mut self.fs.1, &mut self.active)
+    }
+    fn poll_f3(&mut self, ctx: &mut Context) -> Option<FutT3::Output> {
+        Self::poll_n(ctx, 2, &mut self.fs.2, &mut self.active)
+    }
+    fn poll_f4(&mut self, ctx: &mut Context) -> Option<FutT4::Output> {
+        Self::poll_n(ctx, 3, &mut self.fs.3, &mut self.active)
+    }
+    fn poll_f5(&mut self, ctx: &mut Context) -> Option<FutT5::Output> {
+        Self::poll_n(ctx, 4, &mut self.fs.4, &mut self.active)
+    }
+    fn poll_f6(&mut self, ctx: &mut Context) -> Option<FutT6::Output> {
+        Self::poll_n(ctx, 5, &mut self.fs.5, &mut self.active)
+    }
+    fn poll_f7(&mut self, ctx: &mut Context) -> Option<FutT7::Output> {
+        Self::poll_n(ctx, 6, &mut self.fs.6, &mut self.active)
+    }
+    fn poll_f8(&mut self, ctx: &mut Context) -> Option<FutT8::Output> {
+        Self::poll_n(ctx, 7, &mut self.fs.7, &mut self.active)
+    }
+    fn poll_f9(&mut self, ctx: &mut Context) -> Option<FutT9::Output> {
+        Self::poll_n(ctx, 8, &mut self.fs.8, &mut self.active)
+    }
+    fn poll_f10(&mut self, ctx: &mut Context) -> Option<FutT10::Output> {
+        Self::poll_n(ctx, 9, &mut self.fs.9, &mut self.active)
+    }
+    fn poll_f11(&mut self, ctx: &mut Context) -> Option<FutT11::Output> {
+        Self::poll_n(ctx, 10, &mut self.fs.10, &mut self.active)
+    }
+}
+
+impl<FutT1, FutT2, FutT3, FutT4, FutT5, FutT6, FutT7, FutT8, FutT9, FutT10, FutT11, FutT12>
+    AnyOfN<(
+        FutT1,
+        FutT2,
+        FutT3,
+        FutT4,
+        FutT5,
+        FutT6,
+        FutT7,
+        FutT8,
+        FutT9,
+        FutT10,
+        FutT11,
+        FutT12,
+    )>
+where
+    FutT1: Future,
+    FutT2: Future,
+    FutT3: Future,
+    FutT4: Future,
+    FutT5: Future,
+    FutT6: Future,
+    FutT7: Future,
+    FutT8: Future,
+    FutT9: Future,
+    FutT10: Future,
+    FutT11: Future,
+    FutT12: Future,
+{
+    fn poll_f1(&mut self, ctx: &mut Context) -> Option<FutT1::Output> {
+        Self::poll_n(ctx, 0, &mut self.fs.0, &mut self.active)
+    }
+    fn poll_f2(&mut self, ctx: &mut Context) -> Option<FutT2::Output> {
+        Self::poll_n(ctx, 1, &mut self.fs.1, &mut self.active)
+    }
+    fn poll_f3(&mut self, ctx: &mut Context) -> Option<FutT3::Output> {
+        Self::poll_n(ctx, 2, &mut self.fs.2, &mut self.active)
+    }
+    fn poll_f4(&mut self, ctx: &mut Context) -> Option<FutT4::Output> {
+        Self::poll_n(ctx, 3, &mut self.fs.3, &mut self.active)
+    }
+    fn poll_f5(&mut self, ctx: &mut Context) -> Option<FutT5::Output> {
+        Self::poll_n(ctx, 4, &mut self.fs.4, &mut self.active)
+    }
+    fn poll_f6(&mut self, ctx: &mut Context) -> Option<FutT6::Output> {
+        Self::poll_n(ctx, 5, &mut self.fs.5, &mut self.active)
+    }
+    fn poll_f7(&mut self, ctx: &mut Context) -> Option<FutT7::Output> {
+        Self::poll_n(ctx, 6, &mut self.fs.6, &mut self.active)
+    }
+    fn poll_f8(&mut self, ctx: &mut Context) -> Option<FutT8::Output> {
+        Self::poll_n(ctx, 7, &mut self.fs.7, &mut self.active)
+    }
+    fn poll_f9(&mut self, ctx: &mut Context) -> Option<FutT9::Output> {
+        Self::poll_n(ctx, 8, &mut self.fs.8, &mut self.active)
+    }
+    fn poll_f10(&mut self, ctx: &mut Context) -> Option<FutT10::Output> {
+        Self::poll_n(ctx, 9, &mut self.fs.9, &mut self.active)
+    }
+    fn poll_f11(&mut self, ctx: &mut Context) -> Option<FutT11::Output> {
+        Self::poll_n(ctx, 10, &mut self.fs.10, &mut self.active)
+    }
+    fn poll_f12(&mut self, ctx: &mut Context) -> Option<FutT12::Output> {
+        Self::poll_n(ctx, 11, &mut self.fs.11, &mut self.active)
+    }
+}
+
+/// Future returned by [next_of()] and used by `AnyOfN::next()`.
+pub struct NextOfN<'any, TupleT> {
+    any: &'any mut AnyOfN<TupleT>,
+}
+
+/// Returns the future with the result of the first completed future of the stream or `None` if
+/// all futures of the stream has been completed. Same as [AnyOfN::next()], but takes the stream
+/// as the standard `Pin<&mut AnyOfN>`:
+///
+/// ```
+/// use aiur::toy_rt;
+/// use std::future::ready;
+///
+/// async fn async_main(_rt: &toy_rt::Runtime, _: ()) {
+///     let mut stream = Box::pin(toy_rt::any_of2(ready(1), ready(2)));
+///     let first = toy_rt::next_of(stream.as_mut()).await.unwrap();
+///     let second = toy_rt::next_of(stream.as_mut()).await.unwrap();
+///     assert_eq!((first.into_inner(), second.into_inner()), (1, 2));
+///     assert!(toy_rt::next_of(stream.as_mut()).await.is_none());
+/// }
+///
+/// toy_rt::with_runtime_in_mode(toy_rt::SleepMode::Emulated, async_main, ());
+/// ```
+pub fn next_of<TupleT>(stream: Pin<&mut AnyOfN<TupleT>>) -> NextOfN<'_, TupleT> {
+    // Unsafe is ok: NextOfN polls the futures in place, they are not moved.
+    NextOfN {
+        any: unsafe { stream.get_unchecked_mut() },
+    }
+}
+
+impl<'any, FutT1, FutT2> Future for NextOfN<'any, (FutT1, FutT2)>
+where
+    FutT1: Future,
+    FutT2: Future,
+{
+    type Output = Option<OneOf2<FutT1::Output, FutT2::Output>>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let this = unsafe { self.get_unchecked_mut() };
+
         if this.any.is_done() {
-            Poll::Ready(None)
-        } else if let Some(x) = this.any.poll_f1(ctx) {
-            Poll::Ready(Some(OneOf4::First(x)))
-        } else if let Some(x) = this.any.poll_f2(ctx) {
-            Poll::Ready(Some(OneOf4::Second(x)))
-        } else if let Some(x) = this.any.poll_f3(ctx) {
-            Poll::Ready(Some(OneOf4::Third(x)))
-        } else if let Some(x) = this.any.poll_f4(ctx) {
-            Poll::Ready(Some(OneOf4::Fourth(x)))
-        } else {
-            Poll::Pending
+            return Poll::Ready(None);
         }
+
+        let start = this.any.next_start(2);
+        for i in 0..2 {
+            let one_of = match (start + i) % 2 {
+                0 => this.any.poll_f1(ctx).map(OneOf2::First),
+                1 => this.any.poll_f2(ctx).map(OneOf2::Second),
+                _ => unreachable!(),
+            };
+            if one_of.is_some() {
+                return Poll::Ready(one_of);
+            }
+        }
+        Poll::Pending
+    }
+}
+
+impl<'any, FutT1, FutT2, FutT3> Future for NextOfN<'any, (FutT1, FutT2, FutT3)>
+where
+    FutT1: Future,
+    FutT2: Future,
+    FutT3: Future,
+{
+    type Output = Option<OneOf3<FutT1::Output, FutT2::Output, FutT3::Output>>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let this = unsafe { self.get_unchecked_mut() };
+
+        if this.any.is_done() {
+            return Poll::Ready(None);
+        }
+
+        let start = this.any.next_start(3);
+        for i in 0..3 {
+            let one_of = match (start + i) % 3 {
+                0 => this.any.poll_f1(ctx).map(OneOf3::First),
+                1 => this.any.poll_f2(ctx).map(OneOf3::Second),
+                2 => this.any.poll_f3(ctx).map(OneOf3::Third),
+                _ => unreachable!(),
+            };
+            if one_of.is_some() {
+                return Poll::Ready(one_of);
+            }
+        }
+        Poll::Pending
+    }
+}
+
+impl<'any, FutT1, FutT2, FutT3, FutT4> Future for NextOfN<'any, (FutT1, FutT2, FutT3, FutT4)>
+where
+    FutT1: Future,
+    FutT2: Future,
+    FutT3: Future,
+    FutT4: Future,
+{
+    type Output = Option<OneOf4<FutT1::Output, FutT2::Output, FutT3::Output, FutT4::Output>>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let this = unsafe { self.get_unchecked_mut() };
+
+        if this.any.is_done() {
+            return Poll::Ready(None);
+        }
+
+        let start = this.any.next_start(4);
+        for i in 0..4 {
+            let one_of = match (start + i) % 4 {
+                0 => this.any.poll_f1(ctx).map(OneOf4::First),
+                1 => this.any.poll_f2(ctx).map(OneOf4::Second),
+                2 => this.any.poll_f3(ctx).map(OneOf4::Third),
+                3 => this.any.poll_f4(ctx).map(OneOf4::Fourth),
+                _ => unreachable!(),
+            };
+            if one_of.is_some() {
+                return Poll::Ready(one_of);
+            }
+        }
+        Poll::Pending
     }
 }
 
@@ -392,20 +1456,24 @@ where
         let this = unsafe { self.get_unchecked_mut() };
 
         if this.any.is_done() {
-            Poll::Ready(None)
-        } else if let Some(x) = this.any.poll_f1(ctx) {
-            Poll::Ready(Some(OneOf5::First(x)))
-        } else if let Some(x) = this.any.poll_f2(ctx) {
-            Poll::Ready(Some(OneOf5::Second(x)))
-        } else if let Some(x) = this.any.poll_f3(ctx) {
-            Poll::Ready(Some(OneOf5::Third(x)))
-        } else if let Some(x) = this.any.poll_f4(ctx) {
-            Poll::Ready(Some(OneOf5::Fourth(x)))
-        } else if let Some(x) = this.any.poll_f5(ctx) {
-            Poll::Ready(Some(OneOf5::Fifth(x)))
-        } else {
-            Poll::Pending
+            return Poll::Ready(None);
         }
+
+        let start = this.any.next_start(5);
+        for i in 0..5 {
+            let one_of = match (start + i) % 5 {
+                0 => this.any.poll_f1(ctx).map(OneOf5::First),
+                1 => this.any.poll_f2(ctx).map(OneOf5::Second),
+                2 => this.any.poll_f3(ctx).map(OneOf5::Third),
+                3 => this.any.poll_f4(ctx).map(OneOf5::Fourth),
+                4 => this.any.poll_f5(ctx).map(OneOf5::Fifth),
+                _ => unreachable!(),
+            };
+            if one_of.is_some() {
+                return Poll::Ready(one_of);
+            }
+        }
+        Poll::Pending
     }
 }
 
@@ -434,22 +1502,25 @@ where
         let this = unsafe { self.get_unchecked_mut() };
 
         if this.any.is_done() {
-            Poll::Ready(None)
-        } else if let Some(x) = this.any.poll_f1(ctx) {
-            Poll::Ready(Some(OneOf6::First(x)))
-        } else if let Some(x) = this.any.poll_f2(ctx) {
-            Poll::Ready(Some(OneOf6::Second(x)))
-        } else if let Some(x) = this.any.poll_f3(ctx) {
-            Poll::Ready(Some(OneOf6::Third(x)))
-        } else if let Some(x) = this.any.poll_f4(ctx) {
-            Poll::Ready(Some(OneOf6::Fourth(x)))
-        } else if let Some(x) = this.any.poll_f5(ctx) {
-            Poll::Ready(Some(OneOf6::Fifth(x)))
-        } else if let Some(x) = this.any.poll_f6(ctx) {
-            Poll::Ready(Some(OneOf6::Sixth(x)))
-        } else {
-            Poll::Pending
+            return Poll::Ready(None);
+        }
+
+        let start = this.any.next_start(6);
+        for i in 0..6 {
+            let one_of = match (start + i) % 6 {
+                0 => this.any.poll_f1(ctx).map(OneOf6::First),
+                1 => this.any.poll_f2(ctx).map(OneOf6::Second),
+                2 => this.any.poll_f3(ctx).map(OneOf6::Third),
+                3 => this.any.poll_f4(ctx).map(OneOf6::Fourth),
+                4 => this.any.poll_f5(ctx).map(OneOf6::Fifth),
+                5 => this.any.poll_f6(ctx).map(OneOf6::Sixth),
+                _ => unreachable!(),
+            };
+            if one_of.is_some() {
+                return Poll::Ready(one_of);
+            }
         }
+        Poll::Pending
     }
 }
 
@@ -480,24 +1551,26 @@ where
         let this = unsafe { self.get_unchecked_mut() };
 
         if this.any.is_done() {
-            Poll::Ready(None)
-        } else if let Some(x) = this.any.poll_f1(ctx) {
-            Poll::Ready(Some(OneOf7::First(x)))
-        } else if let Some(x) = this.any.poll_f2(ctx) {
-            Poll::Ready(Some(OneOf7::Second(x)))
-        } else if let Some(x) = this.any.poll_f3(ctx) {
-            Poll::Ready(Some(OneOf7::Third(x)))
-        } else if let Some(x) = this.any.poll_f4(ctx) {
-            Poll::Ready(Some(OneOf7::Fourth(x)))
-        } else if let Some(x) = this.any.poll_f5(ctx) {
-            Poll::Ready(Some(OneOf7::Fifth(x)))
-        } else if let Some(x) = this.any.poll_f6(ctx) {
-            Poll::Ready(Some(OneOf7::Sixth(x)))
-        } else if let Some(x) = this.any.poll_f7(ctx) {
-            Poll::Ready(Some(OneOf7::Seventh(x)))
-        } else {
-            Poll::Pending
+            return Poll::Ready(None);
+        }
+
+        let start = this.any.next_start(7);
+        for i in 0..7 {
+            let one_of = match (start + i) % 7 {
+                0 => this.any.poll_f1(ctx).map(OneOf7::First),
+                1 => this.any.poll_f2(ctx).map(OneOf7::Second),
+                2 => this.any.poll_f3(ctx).map(OneOf7::Third),
+                3 => this.any.poll_f4(ctx).map(OneOf7::Fourth),
+                4 => this.any.poll_f5(ctx).map(OneOf7::Fifth),
+                5 => this.any.poll_f6(ctx).map(OneOf7::Sixth),
+                6 => this.any.poll_f7(ctx).map(OneOf7::Seventh),
+                _ => unreachable!(),
+            };
+            if one_of.is_some() {
+                return Poll::Ready(one_of);
+            }
         }
+        Poll::Pending
     }
 }
 
@@ -530,100 +1603,750 @@ where
         let this = unsafe { self.get_unchecked_mut() };
 
         if this.any.is_done() {
-            Poll::Ready(None)
-        } else if let Some(x) = this.any.poll_f1(ctx) {
-            Poll::Ready(Some(OneOf8::First(x)))
-        } else if let Some(x) = this.any.poll_f2(ctx) {
-            Poll::Ready(Some(OneOf8::Second(x)))
-        } else if let Some(x) = this.any.poll_f3(ctx) {
-            Poll::Ready(Some(OneOf8::Third(x)))
-        } else if let Some(x) = this.any.poll_f4(ctx) {
-            Poll::Ready(Some(OneOf8::Fourth(x)))
-        } else if let Some(x) = this.any.poll_f5(ctx) {
-            Poll::Ready(Some(OneOf8::Fifth(x)))
-        } else if let Some(x) = this.any.poll_f6(ctx) {
-            Poll::Ready(Some(OneOf8::Sixth(x)))
-        } else if let Some(x) = this.any.poll_f7(ctx) {
-            Poll::Ready(Some(OneOf8::Seventh(x)))
-        } else if let Some(x) = this.any.poll_f8(ctx) {
-            Poll::Ready(Some(OneOf8::Eighth(x)))
-        } else {
-            Poll::Pending
+            return Poll::Ready(None);
+        }
+
+        let start = this.any.next_start(8);
+        for i in 0..8 {
+            let one_of = match (start + i) % 8 {
+                0 => this.any.poll_f1(ctx).map(OneOf8::First),
+                1 => this.any.poll_f2(ctx).map(OneOf8::Second),
+                2 => this.any.poll_f3(ctx).map(OneOf8::Third),
+                3 => this.any.poll_f4(ctx).map(OneOf8::Fourth),
+                4 => this.any.poll_f5(ctx).map(OneOf8::Fifth),
+                5 => this.any.poll_f6(ctx).map(OneOf8::Sixth),
+                6 => this.any.poll_f7(ctx).map(OneOf8::Seventh),
+                7 => this.any.poll_f8(ctx).map(OneOf8::Eighth),
+                _ => unreachable!(),
+            };
+            if one_of.is_some() {
+                return Poll::Ready(one_of);
+            }
+        }
+        Poll::Pending
+    }
+}
+
+impl<'any, FutT1, FutT2, FutT3, FutT4, FutT5, FutT6, FutT7, FutT8, FutT9> Future
+    for NextOfN<
+        'any,
+        (
+            FutT1,
+            FutT2,
+            FutT3,
+            FutT4,
+            FutT5,
+            FutT6,
+            FutT7,
+            FutT8,
+            FutT9,
+        ),
+    >
+where
+    FutT1: Future,
+    FutT2: Future,
+    FutT3: Future,
+    FutT4: Future,
+    FutT5: Future,
+    FutT6: Future,
+    FutT7: Future,
+    FutT8: Future,
+    FutT9: Future,
+{
+    type Output = Option<
+        OneOf9<
+            FutT1::Output,
+            FutT2::Output,
+            FutT3::Output,
+            FutT4::Output,
+            FutT5::Output,
+            FutT6::Output,
+            FutT7::Output,
+            FutT8::Output,
+            FutT9::Output,
+        >,
+    >;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let this = unsafe { self.get_unchecked_mut() };
+
+        if this.any.is_done() {
+            return Poll::Ready(None);
+        }
+
+        let start = this.any.next_start(9);
+        for i in 0..9 {
+            let one_of = match (start + i) % 9 {
+                0 => this.any.poll_f1(ctx).map(OneOf9::First),
+                1 => this.any.poll_f2(ctx).map(OneOf9::Second),
+                2 => this.any.poll_f3(ctx).map(OneOf9::Third),
+                3 => this.any.poll_f4(ctx).map(OneOf9::Fourth),
+                4 => this.any.poll_f5(ctx).map(OneOf9::Fifth),
+                5 => this.any.poll_f6(ctx).map(OneOf9::Sixth),
+                6 => this.any.poll_f7(ctx).map(OneOf9::Seventh),
+                7 => this.any.poll_f8(ctx).map(OneOf9::Eighth),
+                8 => this.any.poll_f9(ctx).map(OneOf9::Ninth),
+                _ => unreachable!(),
+            };
+            if one_of.is_some() {
+                return Poll::Ready(one_of);
+            }
+        }
+        Poll::Pending
+    }
+}
+
+impl<'any, FutT1, FutT2, FutT3, FutT4, FutT5, FutT6, FutT7, FutT8, FutT9, FutT10> Future
+    for NextOfN<
+        'any,
+        (
+            FutT1,
+            FutT2,
+            FutT3,
+            FutT4,
+            FutT5,
+            FutT6,
+            FutT7,
+            FutT8,
+            FutT9,
+            FutT10,
+        ),
+    >
+where
+    FutT1: Future,
+    FutT2: Future,
+    FutT3: Future,
+    FutT4: Future,
+    FutT5: Future,
+    FutT6: Future,
+    FutT7: Future,
+    FutT8: Future,
+    FutT9: Future,
+    FutT10: Future,
+{
+    type Output = Option<
+        OneOf10<
+            FutT1::Output,
+            FutT2::Output,
+            FutT3::Output,
+            FutT4::Output,
+            FutT5::Output,
+            FutT6::Output,
+            FutT7::Output,
+            FutT8::Output,
+            FutT9::Output,
+            FutT10::Output,
+        >,
+    >;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let this = unsafe { self.get_unchecked_mut() };
+
+        if this.any.is_done() {
+            return Poll::Ready(None);
+        }
+
+        let start = this.any.next_start(10);
+        for i in 0..10 {
+            let one_of = match (start + i) % 10 {
+                0 => this.any.poll_f1(ctx).map(OneOf10::First),
+                1 => this.any.poll_f2(ctx).map(OneOf10::Second),
+                2 => this.any.poll_f3(ctx).map(OneOf10::Third),
+                3 => this.any.poll_f4(ctx).map(OneOf10::Fourth),
+                4 => this.any.poll_f5(ctx).map(OneOf10::Fifth),
+                5 => this.any.poll_f6(ctx).map(OneOf10::Sixth),
+                6 => this.any.poll_f7(ctx).map(OneOf10::Seventh),
+                7 => this.any.poll_f8(ctx).map(OneOf10::Eighth),
+                8 => this.any.poll_f9(ctx).map(OneOf10::Ninth),
+                9 => this.any.poll_f10(ctx).map(OneOf10::Tenth),
+                _ => unreachable!(),
+            };
+            if one_of.is_some() {
+                return Poll::Ready(one_of);
+            }
+        }
+        Poll::Pending
+    }
+}
+
+impl<'any, FutT1, FutT2, FutT3, FutT4, FutT5, FutT6, FutT7, FutT8, FutT9, FutT10, FutT11> Future
+    for NextOfN<
+        'any,
+        (
+            FutT1,
+            FutT2,
+            FutT3,
+            FutT4,
+            FutT5,
+            FutT6,
+            FutT7,
+            FutT8,
+            FutT9,
+            FutT10,
+            FutT11,
+        ),
+    >
+where
+    FutT1: Future,
+    FutT2: Future,
+    FutT3: Future,
+    FutT4: Future,
+    FutT5: Future,
+    FutT6: Future,
+    FutT7: Future,
+    FutT8: Future,
+    FutT9: Future,
+    FutT10: Future,
+    FutT11: Future,
+{
+    type Output = Option<
+        OneOf11<
+            FutT1::Output,
+            FutT2::Output,
+            FutT3::Output,
+            FutT4::Output,
+            FutT5::Output,
+            FutT6::Output,
+            FutT7::Output,
+            FutT8::Output,
+            FutT9::Output,
+            FutT10::Output,
+            FutT11::Output,
+        >,
+    >;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let this = unsafe { self.get_unchecked_mut() };
+
+        if this.any.is_done() {
+            return Poll::Ready(None);
         }
+
+        let start = this.any.next_start(11);
+        for i in 0..11 {
+            let one_of = match (start + i) % 11 {
+                0 => this.any.poll_f1(ctx).map(OneOf11::First),
+                1 => this.any.poll_f2(ctx).map(OneOf11::Second),
+                2 => this.any.poll_f3(ctx).map(OneOf11::Third),
+                3 => this.any.poll_f4(ctx).map(OneOf11::Fourth),
+                4 => this.any.poll_f5(ctx).map(OneOf11::Fifth),
+                5 => this.any.poll_f6(ctx).map(OneOf11::Sixth),
+                6 => this.any.poll_f7(ctx).map(OneOf11::Seventh),
+                7 => this.any.poll_f8(ctx).map(OneOf11::Eighth),
+                8 => this.any.poll_f9(ctx).map(OneOf11::Ninth),
+                9 => this.any.poll_f10(ctx).map(OneOf11::Tenth),
+                10 => this.any.poll_f11(ctx).map(OneOf11::Eleventh),
+                _ => unreachable!(),
+            };
+            if one_of.is_some() {
+                return Poll::Ready(one_of);
+            }
+        }
+        Poll::Pending
+    }
+}
+
+impl<
+        'any,
+        FutT1,
+        FutT2,
+        FutT3,
+        FutT4,
+        FutT5,
+        FutT6,
+        FutT7,
+        FutT8,
+        FutT9,
+        FutT10,
+        FutT11,
+        FutT12,
+    > Future
+    for NextOfN<
+        'any,
+        (
+            FutT1,
+            FutT2,
+            FutT3,
+            FutT4,
+            FutT5,
+            FutT6,
+            FutT7,
+            FutT8,
+            FutT9,
+            FutT10,
+            FutT11,
+            FutT12,
+        ),
+    >
+where
+    FutT1: Future,
+    FutT2: Future,
+    FutT3: Future,
+    FutT4: Future,
+    FutT5: Future,
+    FutT6: Future,
+    FutT7: Future,
+    FutT8: Future,
+    FutT9: Future,
+    FutT10: Future,
+    FutT11: Future,
+    FutT12: Future,
+{
+    type Output = Option<
+        OneOf12<
+            FutT1::Output,
+            FutT2::Output,
+            FutT3::Output,
+            FutT4::Output,
+            FutT5::Output,
+            FutT6::Output,
+            FutT7::Output,
+            FutT8::Output,
+            FutT9::Output,
+            FutT10::Output,
+            FutT11::Output,
+            FutT12::Output,
+        >,
+    >;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let this = unsafe { self.get_unchecked_mut() };
+
+        if this.any.is_done() {
+            return Poll::Ready(None);
+        }
+
+        let start = this.any.next_start(12);
+        for i in 0..12 {
+            let one_of = match (start + i) % 12 {
+                0 => this.any.poll_f1(ctx).map(OneOf12::First),
+                1 => this.any.poll_f2(ctx).map(OneOf12::Second),
+                2 => this.any.poll_f3(ctx).map(OneOf12::Third),
+                3 => this.any.poll_f4(ctx).map(OneOf12::Fourth),
+                4 => this.any.poll_f5(ctx).map(OneOf12::Fifth),
+                5 => this.any.poll_f6(ctx).map(OneOf12::Sixth),
+                6 => this.any.poll_f7(ctx).map(OneOf12::Seventh),
+                7 => this.any.poll_f8(ctx).map(OneOf12::Eighth),
+                8 => this.any.poll_f9(ctx).map(OneOf12::Ninth),
+                9 => this.any.poll_f10(ctx).map(OneOf12::Tenth),
+                10 => this.any.poll_f11(ctx).map(OneOf12::Eleventh),
+                11 => this.any.poll_f12(ctx).map(OneOf12::Twelfth),
+                _ => unreachable!(),
+            };
+            if one_of.is_some() {
+                return Poll::Ready(one_of);
+            }
+        }
+        Poll::Pending
+    }
+}
+
+/// Stream to run two futures concurrently.
+impl<FutT1, FutT2> AnyOfN<(FutT1, FutT2)>
+where
+    FutT1: Future,
+    FutT2: Future,
+{
+    /// Returns the result of the first completed future or None if all futures of the stream
+    /// has been completed.
+    pub async fn next(self: &mut Pin<&mut Self>) -> Option<OneOf2<FutT1::Output, FutT2::Output>> {
+        let this = unsafe { self.as_mut().get_unchecked_mut() };
+        (NextOfN { any: this }).await
+    }
+
+    /// Consumes the stream and returns the futures that have not been completed yet, `None`
+    /// for the completed ones, e.g. to move them into a larger join. The futures can only be
+    /// moved out of the stream that is not pinned anymore: the stream that has not been polled
+    /// yet or the stream of Unpin futures pinned with `Pin::new()`.
+    pub fn into_remaining(self) -> (Option<FutT1>, Option<FutT2>) {
+        let active = self.active;
+        let (f1, f2) = self.fs;
+        (
+            Self::remaining(active, 0, f1),
+            Self::remaining(active, 1, f2),
+        )
+    }
+}
+
+/// Stream to run three futures concurrently.
+impl<FutT1, FutT2, FutT3> AnyOfN<(FutT1, FutT2, FutT3)>
+where
+    FutT1: Future,
+    FutT2: Future,
+    FutT3: Future,
+{
+    /// Returns the result of the first completed future or None if all futures of the stream
+    /// has been completed.
+    pub async fn next(
+        self: &mut Pin<&mut Self>,
+    ) -> Option<OneOf3<FutT1::Output, FutT2::Output, FutT3::Output>> {
+        let this = unsafe { self.as_mut().get_unchecked_mut() };
+        (NextOfN { any: this }).await
+    }
+
+    /// Consumes the stream and returns the futures that have not been completed yet, `None`
+    /// for the completed ones, e.g. to move them into a larger join. The futures can only be
+    /// moved out of the stream that is not pinned anymore: the stream that has not been polled
+    /// yet or the stream of Unpin futures pinned with `Pin::new()`.
+    pub fn into_remaining(self) -> (Option<FutT1>, Option<FutT2>, Option<FutT3>) {
+        let active = self.active;
+        let (f1, f2, f3) = self.fs;
+        (
+            Self::remaining(active, 0, f1),
+            Self::remaining(active, 1, f2),
+            Self::remaining(active, 2, f3),
+        )
+    }
+}
+
+/// Stream to run four futures concurrently.
+impl<FutT1, FutT2, FutT3, FutT4> AnyOfN<(FutT1, FutT2, FutT3, FutT4)>
+where
+    FutT1: Future,
+    FutT2: Future,
+    FutT3: Future,
+    FutT4: Future,
+{
+    /// Returns the result of the first completed future or None if all futures of the stream
+    /// has been completed.
+    pub async fn next(
+        self: &mut Pin<&mut Self>,
+    ) -> Option<OneOf4<FutT1::Output, FutT2::Output, FutT3::Output, FutT4::Output>> {
+        let this = unsafe { self.as_mut().get_unchecked_mut() };
+        (NextOfN { any: this }).await
+    }
+
+    /// Consumes the stream and returns the futures that have not been completed yet, `None`
+    /// for the completed ones, e.g. to move them into a larger join. The futures can only be
+    /// moved out of the stream that is not pinned anymore: the stream that has not been polled
+    /// yet or the stream of Unpin futures pinned with `Pin::new()`.
+    pub fn into_remaining(self) -> (Option<FutT1>, Option<FutT2>, Option<FutT3>, Option<FutT4>) {
+        let active = self.active;
+        let (f1, f2, f3, f4) = self.fs;
+        (
+            Self::remaining(active, 0, f1),
+            Self::remaining(active, 1, f2),
+            Self::remaining(active, 2, f3),
+            Self::remaining(active, 3, f4),
+        )
+    }
+}
+
+/// Stream to run five futures concurrently.
+impl<FutT1, FutT2, FutT3, FutT4, FutT5> AnyOfN<(FutT1, FutT2, FutT3, FutT4, FutT5)>
+where
+    FutT1: Future,
+    FutT2: Future,
+    FutT3: Future,
+    FutT4: Future,
+    FutT5: Future,
+{
+    /// Returns the result of the first completed future or None if all futures of the stream
+    /// has been completed.
+    pub async fn next(
+        self: &mut Pin<&mut Self>,
+    ) -> Option<OneOf5<FutT1::Output, FutT2::Output, FutT3::Output, FutT4::Output, FutT5::Output>>
+    {
+        let this = unsafe { self.as_mut().get_unchecked_mut() };
+        (NextOfN { any: this }).await
+    }
+
+    /// Consumes the stream and returns the futures that have not been completed yet, `None`
+    /// for the completed ones, e.g. to move them into a larger join. The futures can only be
+    /// moved out of the stream that is not pinned anymore: the stream that has not been polled
+    /// yet or the stream of Unpin futures pinned with `Pin::new()`.
+    pub fn into_remaining(
+        self,
+    ) -> (
+        Option<FutT1>,
+        Option<FutT2>,
+        Option<FutT3>,
+        Option<FutT4>,
+        Option<FutT5>,
+    ) {
+        let active = self.active;
+        let (f1, f2, f3, f4, f5) = self.fs;
+        (
+            Self::remaining(active, 0, f1),
+            Self::remaining(active, 1, f2),
+            Self::remaining(active, 2, f3),
+            Self::remaining(active, 3, f4),
+            Self::remaining(active, 4, f5),
+        )
     }
 }
 
-/// Stream to run two futures concurrently.
-impl<FutT1, FutT2> AnyOfN<(FutT1, FutT2)>
+/// Stream to run six futures concurrently.
+impl<FutT1, FutT2, FutT3, FutT4, FutT5, FutT6> AnyOfN<(FutT1, FutT2, FutT3, FutT4, FutT5, FutT6)>
 where
     FutT1: Future,
     FutT2: Future,
+    FutT3: Future,
+    FutT4: Future,
+    FutT5: Future,
+    FutT6: Future,
 {
     /// Returns the result of the first completed future or None if all futures of the stream
     /// has been completed.
-    pub async fn next(self: &mut Pin<&mut Self>) -> Option<OneOf2<FutT1::Output, FutT2::Output>> {
+    pub async fn next(
+        self: &mut Pin<&mut Self>,
+    ) -> Option<
+        OneOf6<
+            FutT1::Output,
+            FutT2::Output,
+            FutT3::Output,
+            FutT4::Output,
+            FutT5::Output,
+            FutT6::Output,
+        >,
+    > {
         let this = unsafe { self.as_mut().get_unchecked_mut() };
         (NextOfN { any: this }).await
     }
+
+    /// Consumes the stream and returns the futures that have not been completed yet, `None`
+    /// for the completed ones, e.g. to move them into a larger join. The futures can only be
+    /// moved out of the stream that is not pinned anymore: the stream that has not been polled
+    /// yet or the stream of Unpin futures pinned with `Pin::new()`.
+    pub fn into_remaining(
+        self,
+    ) -> (
+        Option<FutT1>,
+        Option<FutT2>,
+        Option<FutT3>,
+        Option<FutT4>,
+        Option<FutT5>,
+        Option<FutT6>,
+    ) {
+        let active = self.active;
+        let (f1, f2, f3, f4, f5, f6) = self.fs;
+        (
+            Self::remaining(active, 0, f1),
+            Self::remaining(active, 1, f2),
+            Self::remaining(active, 2, f3),
+            Self::remaining(active, 3, f4),
+            Self::remaining(active, 4, f5),
+            Self::remaining(active, 5, f6),
+        )
+    }
 }
 
-/// Stream to run three futures concurrently.
-impl<FutT1, FutT2, FutT3> AnyOfN<(FutT1, FutT2, FutT3)>
+/// Stream to run seven futures concurrently.
+impl<FutT1, FutT2, FutT3, FutT4, FutT5, FutT6, FutT7>
+    AnyOfN<(FutT1, FutT2, FutT3, FutT4, FutT5, FutT6, FutT7)>
 where
     FutT1: Future,
     FutT2: Future,
     FutT3: Future,
+    FutT4: Future,
+    FutT5: Future,
+    FutT6: Future,
+    FutT7: Future,
 {
     /// Returns the result of the first completed future or None if all futures of the stream
     /// has been completed.
     pub async fn next(
         self: &mut Pin<&mut Self>,
-    ) -> Option<OneOf3<FutT1::Output, FutT2::Output, FutT3::Output>> {
+    ) -> Option<
+        OneOf7<
+            FutT1::Output,
+            FutT2::Output,
+            FutT3::Output,
+            FutT4::Output,
+            FutT5::Output,
+            FutT6::Output,
+            FutT7::Output,
+        >,
+    > {
         let this = unsafe { self.as_mut().get_unchecked_mut() };
         (NextOfN { any: this }).await
     }
+
+    /// Consumes the stream and returns the futures that have not been completed yet, `None`
+    /// for the completed ones, e.g. to move them into a larger join. The futures can only be
+    /// moved out of the stream that is not pinned anymore: the stream that has not been polled
+    /// yet or the stream of Unpin futures pinned with `Pin::new()`.
+    pub fn into_remaining(
+        self,
+    ) -> (
+        Option<FutT1>,
+        Option<FutT2>,
+        Option<FutT3>,
+        Option<FutT4>,
+        Option<FutT5>,
+        Option<FutT6>,
+        Option<FutT7>,
+    ) {
+        let active = self.active;
+        let (f1, f2, f3, f4, f5, f6, f7) = self.fs;
+        (
+            Self::remaining(active, 0, f1),
+            Self::remaining(active, 1, f2),
+            Self::remaining(active, 2, f3),
+            Self::remaining(active, 3, f4),
+            Self::remaining(active, 4, f5),
+            Self::remaining(active, 5, f6),
+            Self::remaining(active, 6, f7),
+        )
+    }
 }
 
-/// Stream to run four futures concurrently.
-impl<FutT1, FutT2, FutT3, FutT4> AnyOfN<(FutT1, FutT2, FutT3, FutT4)>
+/// Stream to run eight futures concurrently.
+impl<FutT1, FutT2, FutT3, FutT4, FutT5, FutT6, FutT7, FutT8>
+    AnyOfN<(FutT1, FutT2, FutT3, FutT4, FutT5, FutT6, FutT7, FutT8)>
 where
     FutT1: Future,
     FutT2: Future,
     FutT3: Future,
     FutT4: Future,
+    FutT5: Future,
+    FutT6: Future,
+    FutT7: Future,
+    FutT8: Future,
 {
     /// Returns the result of the first completed future or None if all futures of the stream
     /// has been completed.
     pub async fn next(
         self: &mut Pin<&mut Self>,
-    ) -> Option<OneOf4<FutT1::Output, FutT2::Output, FutT3::Output, FutT4::Output>> {
+    ) -> Option<
+        OneOf8<
+            FutT1::Output,
+            FutT2::Output,
+            FutT3::Output,
+            FutT4::Output,
+            FutT5::Output,
+            FutT6::Output,
+            FutT7::Output,
+            FutT8::Output,
+        >,
+    > {
         let this = unsafe { self.as_mut().get_unchecked_mut() };
         (NextOfN { any: this }).await
     }
+
+    /// Consumes the stream and returns the futures that have not been completed yet, `None`
+    /// for the completed ones, e.g. to move them into a larger join. The futures can only be
+    /// moved out of the stream that is not pinned anymore: the stream that has not been polled
+    /// yet or the stream of Unpin futures pinned with `Pin::new()`.
+    pub fn into_remaining(
+        self,
+    ) -> (
+        Option<FutT1>,
+        Option<FutT2>,
+        Option<FutT3>,
+        Option<FutT4>,
+        Option<FutT5>,
+        Option<FutT6>,
+        Option<FutT7>,
+        Option<FutT8>,
+    ) {
+        let active = self.active;
+        let (f1, f2, f3, f4, f5, f6, f7, f8) = self.fs;
+        (
+            Self::remaining(active, 0, f1),
+            Self::remaining(active, 1, f2),
+            Self::remaining(active, 2, f3),
+            Self::remaining(active, 3, f4),
+            Self::remaining(active, 4, f5),
+            Self::remaining(active, 5, f6),
+            Self::remaining(active, 6, f7),
+            Self::remaining(active, 7, f8),
+        )
+    }
 }
 
-/// Stream to run five futures concurrently.
-impl<FutT1, FutT2, FutT3, FutT4, FutT5> AnyOfN<(FutT1, FutT2, FutT3, FutT4, FutT5)>
+/// Stream to run nine futures concurrently.
+impl<FutT1, FutT2, FutT3, FutT4, FutT5, FutT6, FutT7, FutT8, FutT9>
+    AnyOfN<(
+        FutT1,
+        FutT2,
+        FutT3,
+        FutT4,
+        FutT5,
+        FutT6,
+        FutT7,
+        FutT8,
+        FutT9,
+    )>
 where
     FutT1: Future,
     FutT2: Future,
     FutT3: Future,
     FutT4: Future,
     FutT5: Future,
+    FutT6: Future,
+    FutT7: Future,
+    FutT8: Future,
+    FutT9: Future,
 {
     /// Returns the result of the first completed future or None if all futures of the stream
     /// has been completed.
     pub async fn next(
         self: &mut Pin<&mut Self>,
-    ) -> Option<OneOf5<FutT1::Output, FutT2::Output, FutT3::Output, FutT4::Output, FutT5::Output>>
-    {
+    ) -> Option<
+        OneOf9<
+            FutT1::Output,
+            FutT2::Output,
+            FutT3::Output,
+            FutT4::Output,
+            FutT5::Output,
+            FutT6::Output,
+            FutT7::Output,
+            FutT8::Output,
+            FutT9::Output,
+        >,
+    > {
         let this = unsafe { self.as_mut().get_unchecked_mut() };
         (NextOfN { any: this }).await
     }
+
+    /// Consumes the stream and returns the futures that have not been completed yet, `None`
+    /// for the completed ones, e.g. to move them into a larger join. The futures can only be
+    /// moved out of the stream that is not pinned anymore: the stream that has not been polled
+    /// yet or the stream of Unpin futures pinned with `Pin::new()`.
+    pub fn into_remaining(
+        self,
+    ) -> (
+        Option<FutT1>,
+        Option<FutT2>,
+        Option<FutT3>,
+        Option<FutT4>,
+        Option<FutT5>,
+        Option<FutT6>,
+        Option<FutT7>,
+        Option<FutT8>,
+        Option<FutT9>,
+    ) {
+        let active = self.active;
+        let (f1, f2, f3, f4, f5, f6, f7, f8, f9) = self.fs;
+        (
+            Self::remaining(active, 0, f1),
+            Self::remaining(active, 1, f2),
+            Self::remaining(active, 2, f3),
+            Self::remaining(active, 3, f4),
+            Self::remaining(active, 4, f5),
+            Self::remaining(active, 5, f6),
+            Self::remaining(active, 6, f7),
+            Self::remaining(active, 7, f8),
+            Self::remaining(active, 8, f9),
+        )
+    }
 }
 
-/// Stream to run six futures concurrently.
-impl<FutT1, FutT2, FutT3, FutT4, FutT5, FutT6> AnyOfN<(FutT1, FutT2, FutT3, FutT4, FutT5, FutT6)>
+/// Stream to run ten futures concurrently.
+impl<FutT1, FutT2, FutT3, FutT4, FutT5, FutT6, FutT7, FutT8, FutT9, FutT10>
+    AnyOfN<(
+        FutT1,
+        FutT2,
+        FutT3,
+        FutT4,
+        FutT5,
+        FutT6,
+        FutT7,
+        FutT8,
+        FutT9,
+        FutT10,
+    )>
 where
     FutT1: Future,
     FutT2: Future,
@@ -631,29 +2354,83 @@ where
     FutT4: Future,
     FutT5: Future,
     FutT6: Future,
+    FutT7: Future,
+    FutT8: Future,
+    FutT9: Future,
+    FutT10: Future,
 {
     /// Returns the result of the first completed future or None if all futures of the stream
     /// has been completed.
     pub async fn next(
         self: &mut Pin<&mut Self>,
     ) -> Option<
-        OneOf6<
+        OneOf10<
             FutT1::Output,
             FutT2::Output,
             FutT3::Output,
             FutT4::Output,
             FutT5::Output,
             FutT6::Output,
+            FutT7::Output,
+            FutT8::Output,
+            FutT9::Output,
+            FutT10::Output,
         >,
     > {
         let this = unsafe { self.as_mut().get_unchecked_mut() };
         (NextOfN { any: this }).await
     }
+
+    /// Consumes the stream and returns the futures that have not been completed yet, `None`
+    /// for the completed ones, e.g. to move them into a larger join. The futures can only be
+    /// moved out of the stream that is not pinned anymore: the stream that has not been polled
+    /// yet or the stream of Unpin futures pinned with `Pin::new()`.
+    pub fn into_remaining(
+        self,
+    ) -> (
+        Option<FutT1>,
+        Option<FutT2>,
+        Option<FutT3>,
+        Option<FutT4>,
+        Option<FutT5>,
+        Option<FutT6>,
+        Option<FutT7>,
+        Option<FutT8>,
+        Option<FutT9>,
+        Option<FutT10>,
+    ) {
+        let active = self.active;
+        let (f1, f2, f3, f4, f5, f6, f7, f8, f9, f10) = self.fs;
+        (
+            Self::remaining(active, 0, f1),
+            Self::remaining(active, 1, f2),
+            Self::remaining(active, 2, f3),
+            Self::remaining(active, 3, f4),
+            Self::remaining(active, 4, f5),
+            Self::remaining(active, 5, f6),
+            Self::remaining(active, 6, f7),
+            Self::remaining(active, 7, f8),
+            Self::remaining(active, 8, f9),
+            Self::remaining(active, 9, f10),
+        )
+    }
 }
 
-/// Stream to run seven futures concurrently.
-impl<FutT1, FutT2, FutT3, FutT4, FutT5, FutT6, FutT7>
-    AnyOfN<(FutT1, FutT2, FutT3, FutT4, FutT5, FutT6, FutT7)>
+/// Stream to run eleven futures concurrently.
+impl<FutT1, FutT2, FutT3, FutT4, FutT5, FutT6, FutT7, FutT8, FutT9, FutT10, FutT11>
+    AnyOfN<(
+        FutT1,
+        FutT2,
+        FutT3,
+        FutT4,
+        FutT5,
+        FutT6,
+        FutT7,
+        FutT8,
+        FutT9,
+        FutT10,
+        FutT11,
+    )>
 where
     FutT1: Future,
     FutT2: Future,
@@ -662,13 +2439,17 @@ where
     FutT5: Future,
     FutT6: Future,
     FutT7: Future,
+    FutT8: Future,
+    FutT9: Future,
+    FutT10: Future,
+    FutT11: Future,
 {
     /// Returns the result of the first completed future or None if all futures of the stream
     /// has been completed.
     pub async fn next(
         self: &mut Pin<&mut Self>,
     ) -> Option<
-        OneOf7<
+        OneOf11<
             FutT1::Output,
             FutT2::Output,
             FutT3::Output,
@@ -676,16 +2457,69 @@ where
             FutT5::Output,
             FutT6::Output,
             FutT7::Output,
+            FutT8::Output,
+            FutT9::Output,
+            FutT10::Output,
+            FutT11::Output,
         >,
     > {
         let this = unsafe { self.as_mut().get_unchecked_mut() };
         (NextOfN { any: this }).await
     }
+
+    /// Consumes the stream and returns the futures that have not been completed yet, `None`
+    /// for the completed ones, e.g. to move them into a larger join. The futures can only be
+    /// moved out of the stream that is not pinned anymore: the stream that has not been polled
+    /// yet or the stream of Unpin futures pinned with `Pin::new()`.
+    pub fn into_remaining(
+        self,
+    ) -> (
+        Option<FutT1>,
+        Option<FutT2>,
+        Option<FutT3>,
+        Option<FutT4>,
+        Option<FutT5>,
+        Option<FutT6>,
+        Option<FutT7>,
+        Option<FutT8>,
+        Option<FutT9>,
+        Option<FutT10>,
+        Option<FutT11>,
+    ) {
+        let active = self.active;
+        let (f1, f2, f3, f4, f5, f6, f7, f8, f9, f10, f11) = self.fs;
+        (
+            Self::remaining(active, 0, f1),
+            Self::remaining(active, 1, f2),
+            Self::remaining(active, 2, f3),
+            Self::remaining(active, 3, f4),
+            Self::remaining(active, 4, f5),
+            Self::remaining(active, 5, f6),
+            Self::remaining(active, 6, f7),
+            Self::remaining(active, 7, f8),
+            Self::remaining(active, 8, f9),
+            Self::remaining(active, 9, f10),
+            Self::remaining(active, 10, f11),
+        )
+    }
 }
 
-/// Stream to run eight futures concurrently.
-impl<FutT1, FutT2, FutT3, FutT4, FutT5, FutT6, FutT7, FutT8>
-    AnyOfN<(FutT1, FutT2, FutT3, FutT4, FutT5, FutT6, FutT7, FutT8)>
+/// Stream to run twelve futures concurrently.
+impl<FutT1, FutT2, FutT3, FutT4, FutT5, FutT6, FutT7, FutT8, FutT9, FutT10, FutT11, FutT12>
+    AnyOfN<(
+        FutT1,
+        FutT2,
+        FutT3,
+        FutT4,
+        FutT5,
+        FutT6,
+        FutT7,
+        FutT8,
+        FutT9,
+        FutT10,
+        FutT11,
+        FutT12,
+    )>
 where
     FutT1: Future,
     FutT2: Future,
@@ -695,13 +2529,17 @@ where
     FutT6: Future,
     FutT7: Future,
     FutT8: Future,
+    FutT9: Future,
+    FutT10: Future,
+    FutT11: Future,
+    FutT12: Future,
 {
     /// Returns the result of the first completed future or None if all futures of the stream
     /// has been completed.
     pub async fn next(
         self: &mut Pin<&mut Self>,
     ) -> Option<
-        OneOf8<
+        OneOf12<
             FutT1::Output,
             FutT2::Output,
             FutT3::Output,
@@ -710,11 +2548,53 @@ where
             FutT6::Output,
             FutT7::Output,
             FutT8::Output,
+            FutT9::Output,
+            FutT10::Output,
+            FutT11::Output,
+            FutT12::Output,
         >,
     > {
         let this = unsafe { self.as_mut().get_unchecked_mut() };
         (NextOfN { any: this }).await
     }
+
+    /// Consumes the stream and returns the futures that have not been completed yet, `None`
+    /// for the completed ones, e.g. to move them into a larger join. The futures can only be
+    /// moved out of the stream that is not pinned anymore: the stream that has not been polled
+    /// yet or the stream of Unpin futures pinned with `Pin::new()`.
+    pub fn into_remaining(
+        self,
+    ) -> (
+        Option<FutT1>,
+        Option<FutT2>,
+        Option<FutT3>,
+        Option<FutT4>,
+        Option<FutT5>,
+        Option<FutT6>,
+        Option<FutT7>,
+        Option<FutT8>,
+        Option<FutT9>,
+        Option<FutT10>,
+        Option<FutT11>,
+        Option<FutT12>,
+    ) {
+        let active = self.active;
+        let (f1, f2, f3, f4, f5, f6, f7, f8, f9, f10, f11, f12) = self.fs;
+        (
+            Self::remaining(active, 0, f1),
+            Self::remaining(active, 1, f2),
+            Self::remaining(active, 2, f3),
+            Self::remaining(active, 3, f4),
+            Self::remaining(active, 4, f5),
+            Self::remaining(active, 5, f6),
+            Self::remaining(active, 6, f7),
+            Self::remaining(active, 7, f8),
+            Self::remaining(active, 8, f9),
+            Self::remaining(active, 9, f10),
+            Self::remaining(active, 10, f11),
+            Self::remaining(active, 11, f12),
+        )
+    }
 }
 
 /// Creates the [AnyOfN] stream to poll two futures.
@@ -727,6 +2607,7 @@ where
     AnyOfN {
         fs: (f1, f2),
         active: 0b0011,
+        start: 0,
     }
 }
 
@@ -745,6 +2626,7 @@ where
     AnyOfN {
         fs: (f1, f2, f3),
         active: 0b0111,
+        start: 0,
     }
 }
 
@@ -765,6 +2647,7 @@ where
     AnyOfN {
         fs: (f1, f2, f3, f4),
         active: 0b1111,
+        start: 0,
     }
 }
 
@@ -787,6 +2670,7 @@ where
     AnyOfN {
         fs: (f1, f2, f3, f4, f5),
         active: 0b0001_1111,
+        start: 0,
     }
 }
 
@@ -811,6 +2695,7 @@ where
     AnyOfN {
         fs: (f1, f2, f3, f4, f5, f6),
         active: 0b0011_1111,
+        start: 0,
     }
 }
 
@@ -837,6 +2722,7 @@ where
     AnyOfN {
         fs: (f1, f2, f3, f4, f5, f6, f7),
         active: 0b0111_1111,
+        start: 0,
     }
 }
 
@@ -865,14 +2751,394 @@ where
     AnyOfN {
         fs: (f1, f2, f3, f4, f5, f6, f7, f8),
         active: 0b1111_1111,
+        start: 0,
+    }
+}
+
+/// Creates the [AnyOfN] stream to poll nine futures.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+#[allow(clippy::too_many_arguments)]
+pub fn any_of9<FutT1, FutT2, FutT3, FutT4, FutT5, FutT6, FutT7, FutT8, FutT9>(
+    f1: FutT1,
+    f2: FutT2,
+    f3: FutT3,
+    f4: FutT4,
+    f5: FutT5,
+    f6: FutT6,
+    f7: FutT7,
+    f8: FutT8,
+    f9: FutT9,
+) -> AnyOfN<(
+    FutT1,
+    FutT2,
+    FutT3,
+    FutT4,
+    FutT5,
+    FutT6,
+    FutT7,
+    FutT8,
+    FutT9,
+)>
+where
+    FutT1: Future,
+    FutT2: Future,
+    FutT3: Future,
+    FutT4: Future,
+    FutT5: Future,
+    FutT6: Future,
+    FutT7: Future,
+    FutT8: Future,
+    FutT9: Future,
+{
+    AnyOfN {
+        fs: (f1, f2, f3, f4, f5, f6, f7, f8, f9),
+        active: 0b0000_0001_1111_1111,
+        start: 0,
+    }
+}
+
+/// Creates the [AnyOfN] stream to poll ten futures.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+#[allow(clippy::too_many_arguments)]
+pub fn any_of10<FutT1, FutT2, FutT3, FutT4, FutT5, FutT6, FutT7, FutT8, FutT9, FutT10>(
+    f1: FutT1,
+    f2: FutT2,
+    f3: FutT3,
+    f4: FutT4,
+    f5: FutT5,
+    f6: FutT6,
+    f7: FutT7,
+    f8: FutT8,
+    f9: FutT9,
+    f10: FutT10,
+) -> AnyOfN<(
+    FutT1,
+    FutT2,
+    FutT3,
+    FutT4,
+    FutT5,
+    FutT6,
+    FutT7,
+    FutT8,
+    FutT9,
+    FutT10,
+)>
+where
+    FutT1: Future,
+    FutT2: Future,
+    FutT3: Future,
+    FutT4: Future,
+    FutT5: Future,
+    FutT6: Future,
+    FutT7: Future,
+    FutT8: Future,
+    FutT9: Future,
+    FutT10: Future,
+{
+    AnyOfN {
+        fs: (f1, f2, f3, f4, f5, f6, f7, f8, f9, f10),
+        active: 0b0000_0011_1111_1111,
+        start: 0,
+    }
+}
+
+/// Creates the [AnyOfN] stream to poll eleven futures.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+#[allow(clippy::too_many_arguments)]
+pub fn any_of11<FutT1, FutT2, FutT3, FutT4, FutT5, FutT6, FutT7, FutT8, FutT9, FutT10, FutT11>(
+    f1: FutT1,
+    f2: FutT2,
+    f3: FutT3,
+    f4: FutT4,
+    f5: FutT5,
+    f6: FutT6,
+    f7: FutT7,
+    f8: FutT8,
+    f9: FutT9,
+    f10: FutT10,
+    f11: FutT11,
+) -> AnyOfN<(
+    FutT1,
+    FutT2,
+    FutT3,
+    FutT4,
+    FutT5,
+    FutT6,
+    FutT7,
+    FutT8,
+    FutT9,
+    FutT10,
+    FutT11,
+)>
+where
+    FutT1: Future,
+    FutT2: Future,
+    FutT3: Future,
+    FutT4: Future,
+    FutT5: Future,
+    FutT6: Future,
+    FutT7: Future,
+    FutT8: Future,
+    FutT9: Future,
+    FutT10: Future,
+    FutT11: Future,
+{
+    AnyOfN {
+        fs: (f1, f2, f3, f4, f5, f6, f7, f8, f9, f10, f11),
+        active: 0b0000_0111_1111_1111,
+        start: 0,
+    }
+}
+
+/// Creates the [AnyOfN] stream to poll twelve futures.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+#[allow(clippy::too_many_arguments)]
+pub fn any_of12<
+    FutT1,
+    FutT2,
+    FutT3,
+    FutT4,
+    FutT5,
+    FutT6,
+    FutT7,
+    FutT8,
+    FutT9,
+    FutT10,
+    FutT11,
+    FutT12,
+>(
+    f1: FutT1,
+    f2: FutT2,
+    f3: FutT3,
+    f4: FutT4,
+    f5: FutT5,
+    f6: FutT6,
+    f7: FutT7,
+    f8: FutT8,
+    f9: FutT9,
+    f10: FutT10,
+    f11: FutT11,
+    f12: FutT12,
+) -> AnyOfN<(
+    FutT1,
+    FutT2,
+    FutT3,
+    FutT4,
+    FutT5,
+    FutT6,
+    FutT7,
+    FutT8,
+    FutT9,
+    FutT10,
+    FutT11,
+    FutT12,
+)>
+where
+    FutT1: Future,
+    FutT2: Future,
+    FutT3: Future,
+    FutT4: Future,
+    FutT5: Future,
+    FutT6: Future,
+    FutT7: Future,
+    FutT8: Future,
+    FutT9: Future,
+    FutT10: Future,
+    FutT11: Future,
+    FutT12: Future,
+{
+    AnyOfN {
+        fs: (f1, f2, f3, f4, f5, f6, f7, f8, f9, f10, f11, f12),
+        active: 0b0000_1111_1111_1111,
+        start: 0,
     }
 }
 
+/// Creates the [AnyOfN] stream to poll two futures that are pinned elsewhere, e.g. in a
+/// struct or with [pin_local!()](crate::pin_local!).
+///
+/// The stream polls the futures through the references and never drops them, so the futures
+/// outlive the stream and the ones still pending can be selected again by a new stream, e.g.
+/// in a retry loop. The future completed by one stream should not be given to the next one,
+/// the futures usually panic when polled after completion.
+pub fn any_of_pinned2<'fut, FutT1, FutT2>(
+    f1: Pin<&'fut mut FutT1>,
+    f2: Pin<&'fut mut FutT2>,
+) -> AnyOfN<(Pin<&'fut mut FutT1>, Pin<&'fut mut FutT2>)>
+where
+    FutT1: Future + ?Sized,
+    FutT2: Future + ?Sized,
+{
+    any_of2(f1, f2)
+}
+
+/// Creates the [AnyOfN] stream to poll three futures that are pinned elsewhere, see
+/// [any_of_pinned2()].
+pub fn any_of_pinned3<'fut, FutT1, FutT2, FutT3>(
+    f1: Pin<&'fut mut FutT1>,
+    f2: Pin<&'fut mut FutT2>,
+    f3: Pin<&'fut mut FutT3>,
+) -> AnyOfN<(
+    Pin<&'fut mut FutT1>,
+    Pin<&'fut mut FutT2>,
+    Pin<&'fut mut FutT3>,
+)>
+where
+    FutT1: Future + ?Sized,
+    FutT2: Future + ?Sized,
+    FutT3: Future + ?Sized,
+{
+    any_of3(f1, f2, f3)
+}
+
+/// Creates the [AnyOfN] stream to poll four futures that are pinned elsewhere, see
+/// [any_of_pinned2()].
+pub fn any_of_pinned4<'fut, FutT1, FutT2, FutT3, FutT4>(
+    f1: Pin<&'fut mut FutT1>,
+    f2: Pin<&'fut mut FutT2>,
+    f3: Pin<&'fut mut FutT3>,
+    f4: Pin<&'fut mut FutT4>,
+) -> AnyOfN<(
+    Pin<&'fut mut FutT1>,
+    Pin<&'fut mut FutT2>,
+    Pin<&'fut mut FutT3>,
+    Pin<&'fut mut FutT4>,
+)>
+where
+    FutT1: Future + ?Sized,
+    FutT2: Future + ?Sized,
+    FutT3: Future + ?Sized,
+    FutT4: Future + ?Sized,
+{
+    any_of4(f1, f2, f3, f4)
+}
+
+/// Creates the [AnyOfN] stream to poll five futures that are pinned elsewhere, see
+/// [any_of_pinned2()].
+pub fn any_of_pinned5<'fut, FutT1, FutT2, FutT3, FutT4, FutT5>(
+    f1: Pin<&'fut mut FutT1>,
+    f2: Pin<&'fut mut FutT2>,
+    f3: Pin<&'fut mut FutT3>,
+    f4: Pin<&'fut mut FutT4>,
+    f5: Pin<&'fut mut FutT5>,
+) -> AnyOfN<(
+    Pin<&'fut mut FutT1>,
+    Pin<&'fut mut FutT2>,
+    Pin<&'fut mut FutT3>,
+    Pin<&'fut mut FutT4>,
+    Pin<&'fut mut FutT5>,
+)>
+where
+    FutT1: Future + ?Sized,
+    FutT2: Future + ?Sized,
+    FutT3: Future + ?Sized,
+    FutT4: Future + ?Sized,
+    FutT5: Future + ?Sized,
+{
+    any_of5(f1, f2, f3, f4, f5)
+}
+
+/// Creates the [AnyOfN] stream to poll six futures that are pinned elsewhere, see
+/// [any_of_pinned2()].
+pub fn any_of_pinned6<'fut, FutT1, FutT2, FutT3, FutT4, FutT5, FutT6>(
+    f1: Pin<&'fut mut FutT1>,
+    f2: Pin<&'fut mut FutT2>,
+    f3: Pin<&'fut mut FutT3>,
+    f4: Pin<&'fut mut FutT4>,
+    f5: Pin<&'fut mut FutT5>,
+    f6: Pin<&'fut mut FutT6>,
+) -> AnyOfN<(
+    Pin<&'fut mut FutT1>,
+    Pin<&'fut mut FutT2>,
+    Pin<&'fut mut FutT3>,
+    Pin<&'fut mut FutT4>,
+    Pin<&'fut mut FutT5>,
+    Pin<&'fut mut FutT6>,
+)>
+where
+    FutT1: Future + ?Sized,
+    FutT2: Future + ?Sized,
+    FutT3: Future + ?Sized,
+    FutT4: Future + ?Sized,
+    FutT5: Future + ?Sized,
+    FutT6: Future + ?Sized,
+{
+    any_of6(f1, f2, f3, f4, f5, f6)
+}
+
+/// Creates the [AnyOfN] stream to poll seven futures that are pinned elsewhere, see
+/// [any_of_pinned2()].
+pub fn any_of_pinned7<'fut, FutT1, FutT2, FutT3, FutT4, FutT5, FutT6, FutT7>(
+    f1: Pin<&'fut mut FutT1>,
+    f2: Pin<&'fut mut FutT2>,
+    f3: Pin<&'fut mut FutT3>,
+    f4: Pin<&'fut mut FutT4>,
+    f5: Pin<&'fut mut FutT5>,
+    f6: Pin<&'fut mut FutT6>,
+    f7: Pin<&'fut mut FutT7>,
+) -> AnyOfN<(
+    Pin<&'fut mut FutT1>,
+    Pin<&'fut mut FutT2>,
+    Pin<&'fut mut FutT3>,
+    Pin<&'fut mut FutT4>,
+    Pin<&'fut mut FutT5>,
+    Pin<&'fut mut FutT6>,
+    Pin<&'fut mut FutT7>,
+)>
+where
+    FutT1: Future + ?Sized,
+    FutT2: Future + ?Sized,
+    FutT3: Future + ?Sized,
+    FutT4: Future + ?Sized,
+    FutT5: Future + ?Sized,
+    FutT6: Future + ?Sized,
+    FutT7: Future + ?Sized,
+{
+    any_of7(f1, f2, f3, f4, f5, f6, f7)
+}
+
+/// Creates the [AnyOfN] stream to poll eight futures that are pinned elsewhere, see
+/// [any_of_pinned2()].
+pub fn any_of_pinned8<'fut, FutT1, FutT2, FutT3, FutT4, FutT5, FutT6, FutT7, FutT8>(
+    f1: Pin<&'fut mut FutT1>,
+    f2: Pin<&'fut mut FutT2>,
+    f3: Pin<&'fut mut FutT3>,
+    f4: Pin<&'fut mut FutT4>,
+    f5: Pin<&'fut mut FutT5>,
+    f6: Pin<&'fut mut FutT6>,
+    f7: Pin<&'fut mut FutT7>,
+    f8: Pin<&'fut mut FutT8>,
+) -> AnyOfN<(
+    Pin<&'fut mut FutT1>,
+    Pin<&'fut mut FutT2>,
+    Pin<&'fut mut FutT3>,
+    Pin<&'fut mut FutT4>,
+    Pin<&'fut mut FutT5>,
+    Pin<&'fut mut FutT6>,
+    Pin<&'fut mut FutT7>,
+    Pin<&'fut mut FutT8>,
+)>
+where
+    FutT1: Future + ?Sized,
+    FutT2: Future + ?Sized,
+    FutT3: Future + ?Sized,
+    FutT4: Future + ?Sized,
+    FutT5: Future + ?Sized,
+    FutT6: Future + ?Sized,
+    FutT7: Future + ?Sized,
+    FutT8: Future + ?Sized,
+{
+    any_of8(f1, f2, f3, f4, f5, f6, f7, f8)
+}
+
 /// Creates [AnyOfN] stream from supplied futures.
 ///
 /// Internally it just select the correct any_ofN() function based on the number of agruments
 /// supplied. For example the `make_any_of!(fut1, fut2, fut3)` is the same as
 /// [`any_of3`]`(fut1, fut2, fut3)`.
+///
+/// Up to 12 futures are supported. The result is always flat, e.g. [OneOf12] for 12 futures,
+/// the futures are not grouped into nested streams.
 #[macro_export]
 macro_rules! make_any_of {
     ($f1:expr, $f2:expr $(,)?) => {
@@ -896,11 +3162,25 @@ macro_rules! make_any_of {
     ($f1:expr, $f2:expr, $f3:expr, $f4:expr, $f5:expr, $f6:expr, $f7:expr, $f8:expr $(,)?) => {
         $crate::any_of8($f1, $f2, $f3, $f4, $f5, $f6, $f7, $f8)
     };
+    ($f1:expr, $f2:expr, $f3:expr, $f4:expr, $f5:expr, $f6:expr, $f7:expr, $f8:expr, $f9:expr $(,)?) => {
+        $crate::any_of9($f1, $f2, $f3, $f4, $f5, $f6, $f7, $f8, $f9)
+    };
+    ($f1:expr, $f2:expr, $f3:expr, $f4:expr, $f5:expr, $f6:expr, $f7:expr, $f8:expr, $f9:expr, $f10:expr $(,)?) => {
+        $crate::any_of10($f1, $f2, $f3, $f4, $f5, $f6, $f7, $f8, $f9, $f10)
+    };
+    ($f1:expr, $f2:expr, $f3:expr, $f4:expr, $f5:expr, $f6:expr, $f7:expr, $f8:expr, $f9:expr, $f10:expr, $f11:expr $(,)?) => {
+        $crate::any_of11($f1, $f2, $f3, $f4, $f5, $f6, $f7, $f8, $f9, $f10, $f11)
+    };
+    ($f1:expr, $f2:expr, $f3:expr, $f4:expr, $f5:expr, $f6:expr, $f7:expr, $f8:expr, $f9:expr, $f10:expr, $f11:expr, $f12:expr $(,)?) => {
+        $crate::any_of12(
+            $f1, $f2, $f3, $f4, $f5, $f6, $f7, $f8, $f9, $f10, $f11, $f12,
+        )
+    };
 }
 
 /// Makes a pinned version of [AnyOfN] stream.
 ///
-/// It accept a streams variable name as first argument and up to 8 futures. For example, the
+/// It accept a streams variable name as first argument and up to 12 futures. For example, the
 /// `pinned_any_of!(stream, fut1, fut2)` is expanded to something like:
 ///
 /// ```
@@ -922,6 +3202,10 @@ macro_rules! make_any_of {
 ///    OneOf2::Second(_) => println!("fut2 made it"),
 /// }
 /// ```
+///
+/// The future that is already pinned (a `Pin<&mut F>` local) can be given with the `pinned:`
+/// marker, e.g. `pinned_any_of!(stream, pinned: sleep, fut2)`. The stream polls it by reference,
+/// so it is not moved and it can be awaited again after the stream is dropped.
 #[macro_export]
 macro_rules! pinned_any_of {
     ($var:ident, $f1:expr, $f2:expr $(,)?) => {
@@ -952,4 +3236,53 @@ macro_rules! pinned_any_of {
         let $var = $crate::any_of8($f1, $f2, $f3, $f4, $f5, $f6, $f7, $f8);
         $crate::pin_local!($var);
     };
+    ($var:ident, $f1:expr, $f2:expr, $f3:expr, $f4:expr, $f5:expr, $f6:expr, $f7:expr, $f8:expr, $f9:expr $(,)?) => {
+        let $var = $crate::any_of9($f1, $f2, $f3, $f4, $f5, $f6, $f7, $f8, $f9);
+        $crate::pin_local!($var);
+    };
+    ($var:ident, $f1:expr, $f2:expr, $f3:expr, $f4:expr, $f5:expr, $f6:expr, $f7:expr, $f8:expr, $f9:expr, $f10:expr $(,)?) => {
+        let $var = $crate::any_of10($f1, $f2, $f3, $f4, $f5, $f6, $f7, $f8, $f9, $f10);
+        $crate::pin_local!($var);
+    };
+    ($var:ident, $f1:expr, $f2:expr, $f3:expr, $f4:expr, $f5:expr, $f6:expr, $f7:expr, $f8:expr, $f9:expr, $f10:expr, $f11:expr $(,)?) => {
+        let $var = $crate::any_of11($f1, $f2, $f3, $f4, $f5, $f6, $f7, $f8, $f9, $f10, $f11);
+        $crate::pin_local!($var);
+    };
+    ($var:ident, $f1:expr, $f2:expr, $f3:expr, $f4:expr, $f5:expr, $f6:expr, $f7:expr, $f8:expr, $f9:expr, $f10:expr, $f11:expr, $f12:expr $(,)?) => {
+        let $var = $crate::any_of12(
+            $f1, $f2, $f3, $f4, $f5, $f6, $f7, $f8, $f9, $f10, $f11, $f12,
+        );
+        $crate::pin_local!($var);
+    };
+    // Some of the futures are marked with "pinned:", see __pinned_args!()
+    ($var:ident, $($args:tt)+) => {
+        $crate::__pinned_args!(pinned_any_of!($var,) {} [] $($args)+);
+    };
+}
+
+// Replaces the "pinned: fut" arguments of the macros with `fut.as_mut()`, so the future that
+// is already pinned elsewhere (the Pin<&mut F> local) is polled by reference instead of being
+// moved. Then invokes the macro with the expressions. It munches the arguments one by one:
+//
+//   __pinned_args!(join!() {} [] pinned: a, b) -> __pinned_args!(join!() {p} [a.as_mut(),] b)
+//                                              -> __pinned_args!(join!() {p} [a.as_mut(), b,])
+//                                              -> join!(a.as_mut(), b)
+//
+// The {p} is for the arguments without "pinned:": then the macro is not invoked again as
+// this is an unsupported number of arguments.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __pinned_args {
+    ($mac:ident!($($prefix:tt)*) {} [$($done:expr,)*] $(,)?) => {
+        compile_error!(concat!("aiur: unsupported arguments of ", stringify!($mac), "!()"))
+    };
+    ($mac:ident!($($prefix:tt)*) {p} [$($done:expr,)*] $(,)?) => {
+        $crate::$mac!($($prefix)* $($done),*)
+    };
+    ($mac:ident!($($prefix:tt)*) {$($p:ident)?} [$($done:expr,)*] pinned: $f:expr $(, $($rest:tt)*)?) => {
+        $crate::__pinned_args!($mac!($($prefix)*) {p} [$($done,)* $f.as_mut(),] $($($rest)*)?)
+    };
+    ($mac:ident!($($prefix:tt)*) {$($p:ident)?} [$($done:expr,)*] $f:expr $(, $($rest:tt)*)?) => {
+        $crate::__pinned_args!($mac!($($prefix)*) {$($p)?} [$($done,)* $f,] $($($rest)*)?)
+    };
 }
@@ -0,0 +1,179 @@
+//  \ O /
+//  / * \    aiur: the homeplanet for the famous executors
+// |' | '|   (c) 2020 - present, Vladimir Zvezda
+//   / \
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+/// Stream to poll a dynamic number of futures of the same type concurrently.
+///
+/// This is like [AnyOfN](crate::AnyOfN) but for the futures collected into a Vec. Invoking
+/// `[AnyOfVec::next()].await` returns the index of the future in the original Vec together with
+/// its result, the next call returns the result of the first completed of remaining futures,
+/// etc. It returns `None` when all futures have been completed.
+///
+/// Each future is boxed, so the futures are never moved once AnyOfVec is created and the
+/// stream itself does not have to be pinned. Completed futures are dropped right away, the
+/// remaining futures are dropped when AnyOfVec is dropped.
+///
+/// The polling starts from the future next to the last completed one, so when several futures
+/// are ready the ones in the beginning of the Vec do not always win.
+///
+/// The slots of completed futures stay in storage and next() skips them. For a large number of
+/// futures use [any_of_vec_fused()] that compacts the storage when a half of the futures has been
+/// completed, or invoke [AnyOfVec::compact()] explicitly.
+pub struct AnyOfVec<FutT: Future> {
+    // (original index, future), None for completed futures.
+    slots: Vec<Option<(usize, Pin<Box<FutT>>)>>,
+    completed: usize,
+    fused: bool,
+    next_start: usize, // slot position the poll starts from
+    added: usize,      // the index for the next pushed future
+}
+
+impl<FutT: Future> AnyOfVec<FutT> {
+    fn new(futures: Vec<FutT>, fused: bool) -> Self {
+        let added = futures.len();
+        AnyOfVec {
+            slots: futures
+                .into_iter()
+                .enumerate()
+                .map(|(index, future)| Some((index, Box::pin(future))))
+                .collect(),
+            completed: 0,
+            fused,
+            next_start: 0,
+            added,
+        }
+    }
+
+    /// Adds the future to the stream, it is polled by the next call of [AnyOfVec::next()].
+    /// Returns the index next() reports for the future: the futures are indexed as if they
+    /// were pushed to the end of the Vec the stream was created from.
+    pub fn push(&mut self, future: FutT) -> usize {
+        let index = self.added;
+        self.slots.push(Some((index, Box::pin(future))));
+        self.added += 1;
+        index
+    }
+
+    /// Returns the index of the first completed future (as it was in Vec the stream was created
+    /// from) and its result or None if all futures of the stream has been completed.
+    pub async fn next(&mut self) -> Option<(usize, FutT::Output)> {
+        NextOfVec { any: self }.await
+    }
+
+    /// Returns the number of futures that have not been completed yet.
+    pub fn len(&self) -> usize {
+        self.slots.len() - self.completed
+    }
+
+    /// Returns true if all the futures have been completed.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of slots next() scans: the futures that are not completed yet and
+    /// the completed ones that were not compacted.
+    pub fn slot_count(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Removes the slots of completed futures from the storage by moving the live futures to the
+    /// front. Futures themselves are boxed, so they keep their location in memory.
+    ///
+    /// Returns the remap of the slot positions: the element `old` of the returned Vec is the
+    /// new position of the slot or None if slot has been removed. The indexes returned by
+    /// [AnyOfVec::next()] do not change with compaction.
+    pub fn compact(&mut self) -> Vec<Option<usize>> {
+        let mut new_pos = 0;
+        let remap = self
+            .slots
+            .iter()
+            .map(|slot| {
+                slot.as_ref().map(|_| {
+                    new_pos += 1;
+                    new_pos - 1
+                })
+            })
+            .collect();
+
+        // the poll continues from the same live slot
+        let start = self.next_start.min(self.slots.len());
+        self.next_start = self.slots[..start]
+            .iter()
+            .filter(|slot| slot.is_some())
+            .count();
+
+        self.slots.retain(|slot| slot.is_some());
+        self.completed = 0;
+        remap
+    }
+
+    // Polls futures in slots starting from next_start, returns the first completed.
+    fn poll_slots(&mut self, ctx: &mut Context) -> Option<(usize, FutT::Output)> {
+        let slot_count = self.slots.len();
+        for offset in 0..slot_count {
+            let pos = (self.next_start + offset) % slot_count;
+            if let Some((index, future)) = &mut self.slots[pos] {
+                if let Poll::Ready(result) = future.as_mut().poll(ctx) {
+                    let index = *index;
+                    self.slots[pos] = None; // drop completed future
+                    self.completed += 1;
+                    self.next_start = pos + 1;
+                    return Some((index, result));
+                }
+            }
+        }
+
+        None
+    }
+
+    fn compact_if_needed(&mut self) {
+        if self.fused && self.completed * 2 >= self.slots.len() {
+            let _ = self.compact();
+        }
+    }
+}
+
+// Future returned by AnyOfVec::next()
+struct NextOfVec<'any, FutT: Future> {
+    any: &'any mut AnyOfVec<FutT>,
+}
+
+impl<'any, FutT: Future> Future for NextOfVec<'any, FutT> {
+    type Output = Option<(usize, FutT::Output)>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        // NextOfVec is Unpin: the futures are boxed in AnyOfVec.
+        let this = self.get_mut();
+
+        if this.any.is_empty() {
+            return Poll::Ready(None);
+        }
+
+        match this.any.poll_slots(ctx) {
+            Some(completed) => {
+                this.any.compact_if_needed();
+                Poll::Ready(Some(completed))
+            }
+            None => Poll::Pending,
+        }
+    }
+}
+
+/// Creates the [AnyOfVec] stream to poll the futures from Vec.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub fn any_of_vec<FutT: Future>(futures: Vec<FutT>) -> AnyOfVec<FutT> {
+    AnyOfVec::new(futures, false)
+}
+
+/// Creates the [AnyOfVec] stream that compacts its storage when a half of the futures has been
+/// completed, so next() does not scan the completed slots.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub fn any_of_vec_fused<FutT: Future>(futures: Vec<FutT>) -> AnyOfVec<FutT> {
+    AnyOfVec::new(futures, true)
+}
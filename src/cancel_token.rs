@@ -0,0 +1,105 @@
+//  \ O /
+//  / * \    aiur: the homeplanet for the famous executors
+// |' | '|   (c) 2020 - present, Vladimir Zvezda
+//   / \
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+use core::cell::{Cell, RefCell};
+
+use crate::channel::{channel_buffered, Sender};
+use crate::reactor::Reactor;
+use crate::runtime::Runtime;
+
+/// Token for cooperative cancellation: the work is raced against [CancelToken::cancelled()]
+/// and stops when somebody invokes [CancelToken::cancel()].
+///
+/// The clones of the token share the state, so the token can be cloned into the spawned
+/// futures and cancel all of them at once.
+///
+/// ```
+/// use aiur::toy_rt;
+/// use std::time::Duration;
+///
+/// async fn async_main(rt: &toy_rt::Runtime, _: ()) {
+///     let token = toy_rt::CancelToken::new(rt);
+///     let worker = async {
+///         let work = toy_rt::sleep(rt, Duration::from_secs(10));
+///         toy_rt::race2(work, token.cancelled()).await
+///     };
+///     let canceller = async {
+///         toy_rt::sleep(rt, Duration::from_secs(1)).await;
+///         token.cancel();
+///     };
+///     toy_rt::join!(worker, canceller).await;
+///     assert_eq!(rt.elapsed(), Duration::from_secs(1));
+/// }
+///
+/// toy_rt::with_runtime_in_mode(toy_rt::SleepMode::Emulated, async_main, ());
+/// ```
+pub struct CancelToken<'runtime, ReactorT: Reactor> {
+    rt: &'runtime Runtime<ReactorT>,
+    inner: Rc<CancelState<'runtime, ReactorT>>,
+}
+
+// The state shared by the clones of the token
+struct CancelState<'runtime, ReactorT: Reactor> {
+    cancelled: Cell<bool>,
+    // One sender for every cancelled() future that is waiting, cancel() sends to all of them
+    waiters: RefCell<Vec<Sender<'runtime, (), ReactorT>>>,
+}
+
+impl<'runtime, ReactorT: Reactor> CancelToken<'runtime, ReactorT> {
+    /// Creates the token that is not cancelled.
+    pub fn new(rt: &'runtime Runtime<ReactorT>) -> Self {
+        CancelToken {
+            rt,
+            inner: Rc::new(CancelState {
+                cancelled: Cell::new(false),
+                waiters: RefCell::new(Vec::new()),
+            }),
+        }
+    }
+
+    /// Cancels the token: all the [CancelToken::cancelled()] futures of this token and its
+    /// clones are awoken. Cancelling the token that is already cancelled does nothing.
+    pub fn cancel(&self) {
+        if !self.inner.cancelled.replace(true) {
+            for mut waiter in self.inner.waiters.take() {
+                // error: the cancelled() future has been dropped, nobody to wake
+                let _ = waiter.try_send(());
+            }
+        }
+    }
+
+    /// Returns true if the token has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.get()
+    }
+
+    /// Waits until the token is cancelled, completes right away if it already is.
+    pub async fn cancelled(&self) {
+        if self.is_cancelled() {
+            return;
+        }
+
+        let (tx, mut rx) = channel_buffered(self.rt, 1);
+        {
+            let mut waiters = self.inner.waiters.borrow_mut();
+            // forget the senders of cancelled() futures that have been dropped
+            waiters.retain(|waiter| waiter.is_connected());
+            waiters.push(tx);
+        }
+
+        // The sender is only dropped after it has sent, because this future borrows the token
+        let _ = rx.next().await;
+    }
+}
+
+impl<'runtime, ReactorT: Reactor> Clone for CancelToken<'runtime, ReactorT> {
+    fn clone(&self) -> Self {
+        CancelToken {
+            rt: self.rt,
+            inner: self.inner.clone(),
+        }
+    }
+}
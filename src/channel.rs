@@ -2,25 +2,37 @@
 //  / * \    aiur: the homeplanet for the famous executors
 // |' | '|   (c) 2020 - present, Vladimir Zvezda
 //   / \
-use std::future::Future;
-use std::marker::PhantomData;
-use std::pin::Pin;
-use std::task::{Context, Poll};
-
-use crate::channel_rt::{PeerRt, RecverRt, SenderRt, SwapResult};
-use crate::event_node::EventNode;
-use crate::reactor::{EventId, Reactor};
+#[cfg(feature = "futures")]
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::future::Future;
+use core::marker::PhantomData;
+#[cfg(feature = "futures")]
+use core::marker::PhantomPinned;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use core::time::Duration;
+
+use crate::channel_rt::{PeerRt, RecverRt, SenderRt, SwapResult, Watch};
+use crate::event_node::{panic_polled_after_completion, EventNode};
+use crate::pinned_any_of;
+use crate::OneOf2;
+use crate::reactor::{EventId, Reactor, TemporalReactor};
 use crate::runtime::Runtime;
+use crate::race::race2;
+use crate::timer::{sleep, Elapsed};
 
 // enable/disable output of modtrace! macro
 const MODTRACE: bool = true;
 
 /// Creates a new asynchronous channel returning the pair of (Sender, Receiver).
 ///
-/// This creates the bounded channel, so whenever a sender sends a data it is suspended
+/// This creates the rendezvous channel, so whenever a sender sends a data it is suspended
 /// in await point until either receiver had the data received or channel got disconnected.
+/// See [channel_buffered()] for the channel that allows senders to run ahead of receiver.
 ///
 /// Sender can be cloned to send data to the same channel, but only one Receiver is supported.
+/// See [channel_mpmc()] for the channel with many receivers.
 ///
 /// While there is a channel half that awaits transmission and another half is gone,
 /// operation Result would be an error. In a case of the receiver it would be RecvError. When
@@ -29,15 +41,123 @@ const MODTRACE: bool = true;
 pub fn channel<'runtime, T, ReactorT: Reactor>(
     rt: &'runtime Runtime<ReactorT>,
 ) -> (Sender<'runtime, T, ReactorT>, Recver<'runtime, T, ReactorT>) {
-    let channel_id = rt.channels().create();
+    create_channel(rt, 0)
+}
+
+/// Creates a new asynchronous channel with a buffer for `capacity` values, returning the pair
+/// of (Sender, Receiver).
+///
+/// Sender completes the send() without waiting for the receiver while there is a room in the
+/// buffer, otherwise it is suspended just like with rendezvous [channel()]. Receiver gets the
+/// buffered values first and it only gets the RecvError when all senders are gone and the
+/// buffer is empty.
+///
+/// Panics if capacity is 0: use [channel()] if you need a rendezvous channel.
+pub fn channel_buffered<'runtime, T, ReactorT: Reactor>(
+    rt: &'runtime Runtime<ReactorT>,
+    capacity: usize,
+) -> (Sender<'runtime, T, ReactorT>, Recver<'runtime, T, ReactorT>) {
+    assert!(
+        capacity > 0,
+        "aiur: channel_buffered() requires capacity > 0, use channel() for rendezvous channel"
+    );
+    create_channel(rt, capacity)
+}
+
+/// Creates a new rendezvous channel that supports many receivers, returning the pair of
+/// (Sender, MpmcRecver).
+///
+/// The [MpmcRecver] can be cloned, so receivers compete for values sent: each value goes to
+/// exactly one receiver, the receivers awaiting values are served in the order they started
+/// to await. Receivers get the RecvError when all senders are gone.
+pub fn channel_mpmc<'runtime, T, ReactorT: Reactor>(
+    rt: &'runtime Runtime<ReactorT>,
+) -> (Sender<'runtime, T, ReactorT>, MpmcRecver<'runtime, T, ReactorT>) {
+    let (tx, rx) = create_channel(rt, 0);
+    (tx, MpmcRecver { recver: rx })
+}
+
+fn create_channel<'runtime, T, ReactorT: Reactor>(
+    rt: &'runtime Runtime<ReactorT>,
+    capacity: usize,
+) -> (Sender<'runtime, T, ReactorT>, Recver<'runtime, T, ReactorT>) {
+    let channel_id = rt.channels().create(capacity);
     let sender_rt = rt.channels().sender_rt(channel_id);
     let recver_rt = rt.channels().recver_rt(channel_id);
-    (Sender::new(rt, sender_rt), Recver::new(rt, recver_rt))
+    (
+        Sender::new(rt, sender_rt, capacity),
+        Recver::new(rt, recver_rt, capacity),
+    )
+}
+
+/// Error type returned by Receiver.
+#[derive(Debug, PartialEq)] // Debug is required for Result.unwrap()
+pub enum RecvError {
+    /// All senders are gone, so no values can be received anymore.
+    Disconnected,
+    /// Runtime is shutting down, see [Runtime::begin_shutdown()].
+    Shutdown,
+}
+
+/// Error type returned by [Sender::send()], the value that was not sent is given back.
+#[derive(Debug, PartialEq)] // Debug is required for Result.unwrap()
+pub enum SendError<T> {
+    /// Receiver is gone, so the value cannot be sent anymore.
+    Disconnected(T),
+    /// Runtime is shutting down, see [Runtime::begin_shutdown()].
+    Shutdown(T),
+}
+
+impl<T> SendError<T> {
+    /// Returns the value that was not sent.
+    pub fn into_inner(self) -> T {
+        match self {
+            SendError::Disconnected(value) | SendError::Shutdown(value) => value,
+        }
+    }
+
+    // Makes the error of the same kind with another value
+    fn map<U, MapFn: FnOnce(T) -> U>(self, map_fn: MapFn) -> SendError<U> {
+        match self {
+            SendError::Disconnected(value) => SendError::Disconnected(map_fn(value)),
+            SendError::Shutdown(value) => SendError::Shutdown(map_fn(value)),
+        }
+    }
+}
+
+/// Error type returned by [Sender::try_send()], the value is given back in both cases.
+#[derive(Debug, PartialEq)] // Debug is required for Result.unwrap()
+pub enum TrySendError<T> {
+    /// The send would be suspended: the receiver is not awaiting the value in the rendezvous
+    /// channel or there is no room in the buffer of the buffered channel.
+    Full(T),
+    /// Receiver is gone, so the value cannot be sent anymore.
+    Disconnected(T),
 }
 
-/// Error type returned by Receiver: the only possible error is channel closed on sender's side.
-#[derive(Debug)] // Debug is required for Result.unwrap()
-pub struct RecvError;
+/// Error type returned by [Recver::try_recv()].
+#[derive(Debug, PartialEq)] // Debug is required for Result.unwrap()
+pub enum TryRecvError {
+    /// There is no value to receive right now, but senders are still alive.
+    Empty,
+    /// All senders are gone, so no values can be received anymore.
+    Disconnected,
+}
+
+/// Awaits the first value from either of two channels with possibly different value types.
+///
+/// Returns [OneOf2::First](crate::OneOf2::First) with the result of `rx_a.next()` or
+/// [OneOf2::Second](crate::OneOf2::Second) with the result of `rx_b.next()`, whichever was
+/// first. The receiving on another channel is cancelled, so its value (if any) stays in the
+/// channel for the next receive.
+pub async fn select2_recv<'runtime, A, B, ReactorT: Reactor>(
+    rx_a: &mut Recver<'runtime, A, ReactorT>,
+    rx_b: &mut Recver<'runtime, B, ReactorT>,
+) -> OneOf2<Result<A, RecvError>, Result<B, RecvError>> {
+    pinned_any_of!(stream, rx_a.next(), rx_b.next());
+    // the first next() of any_of stream always has a value
+    stream.next().await.unwrap()
+}
 
 // -----------------------------------------------------------------------------------------------
 /// The sending half of the channel created by [channel()] function.
@@ -46,34 +166,158 @@ pub struct RecvError;
 pub struct Sender<'runtime, T, ReactorT: Reactor> {
     rt: &'runtime Runtime<ReactorT>,
     sender_rt: SenderRt<'runtime>,
+    capacity: usize,
     temp: PhantomData<T>,
+    // The send started by futures::Sink::start_send() and not flushed yet
+    #[cfg(feature = "futures")]
+    sink_send: Option<Pin<Box<SenderFuture<'runtime, T, ReactorT>>>>,
 }
 
 impl<'runtime, T, ReactorT: Reactor> Sender<'runtime, T, ReactorT> {
-    fn new(rt: &'runtime Runtime<ReactorT>, sender_rt: SenderRt<'runtime>) -> Self {
+    fn new(
+        rt: &'runtime Runtime<ReactorT>,
+        sender_rt: SenderRt<'runtime>,
+        capacity: usize,
+    ) -> Self {
         sender_rt.inc_ref();
         Self {
             rt,
             sender_rt,
+            capacity,
             temp: PhantomData,
+            #[cfg(feature = "futures")]
+            sink_send: None,
         }
     }
 
+    /// Returns the buffer capacity of the channel, 0 is for the rendezvous channel.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
     /// Sends a value to a receiver half of communication channel.
     ///
-    /// The awaited send() operation does not return until receiver gets the data or
-    /// communication channel is gone by having receiver object dropped. In a case of
-    /// closed channel sender receives the value back.
-    pub async fn send(&mut self, value: T) -> Result<(), T> {
+    /// The awaited send() operation does not return until receiver gets the data (or the
+    /// data is put into buffer of the buffered channel) or communication channel is gone by
+    /// having receiver object dropped. In a case of closed channel sender receives the
+    /// value back with [SendError::Disconnected], when runtime is shutting down it is
+    /// [SendError::Shutdown].
+    pub async fn send(&mut self, value: T) -> Result<(), SendError<T>> {
         SenderFuture::new(self.rt, self.sender_rt, value).await
     }
+
+    /// Same as [Sender::send()], but the returned future can be cancelled without losing the
+    /// value: [SendPermit::cancel()] stops the send and gives the value back unless the
+    /// receiver has already got it.
+    ///
+    /// The send made by this future is exactly the same as by send(): it only starts on the
+    /// first poll, and the value is either received by exactly one receiver or returned back
+    /// either by the error or by `cancel()`. Dropping the permit without `cancel()` drops the
+    /// value that was not sent, like dropping the send() future does.
+    ///
+    /// ```
+    /// use aiur::toy_rt;
+    /// use std::time::Duration;
+    ///
+    /// async fn async_main(rt: &toy_rt::Runtime, _: ()) {
+    ///     let (mut tx, _rx) = toy_rt::channel::<u32>(rt); // nobody receives
+    ///     let permit = tx.send_cancellable(42);
+    ///     toy_rt::pin_local!(permit);
+    ///     toy_rt::race2(permit.as_mut(), toy_rt::sleep(rt, Duration::from_millis(10))).await;
+    ///     assert_eq!(permit.cancel(), Some(42));
+    /// }
+    ///
+    /// toy_rt::with_runtime_in_mode(toy_rt::SleepMode::Emulated, async_main, ());
+    /// ```
+    pub fn send_cancellable(&mut self, value: T) -> SendPermit<'_, 'runtime, T, ReactorT> {
+        SendPermit {
+            future: SenderFuture::new(self.rt, self.sender_rt, value),
+            _sender: PhantomData,
+        }
+    }
+
+    /// Sends the values one by one in order, like send() in a loop. When the channel is closed
+    /// in the middle, the error has the values that were not sent: the one that was being sent
+    /// and the rest of them.
+    pub async fn send_all<IterT: IntoIterator<Item = T>>(
+        &mut self,
+        values: IterT,
+    ) -> Result<(), SendError<Vec<T>>> {
+        let mut values = values.into_iter();
+        while let Some(value) = values.next() {
+            if let Err(err) = self.send(value).await {
+                return Err(err.map(|value| core::iter::once(value).chain(values).collect()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Waits until the send would not be suspended: the receiver is awaiting a value in the
+    /// rendezvous channel or there is a room in the buffer of the buffered channel. This is
+    /// the way to observe the backpressure without having a value ready to be sent.
+    ///
+    /// It also completes when the receiver is gone or runtime is shutting down, in this case
+    /// the following send() returns the value back right away. The room is not reserved, so
+    /// another sender of the same channel can take it first.
+    pub async fn writable(&mut self) {
+        WatcherFuture::new(self.rt, self.sender_rt, Watch::Writable).await
+    }
+
+    /// Waits until the receiver half of the channel is dropped, so the producer can stop
+    /// early instead of finding it out from the failed send(). It does not consume the sender,
+    /// and any number of senders can wait at the same time. It also completes when runtime
+    /// is shutting down.
+    ///
+    /// ```
+    /// use aiur::toy_rt;
+    /// use std::time::Duration;
+    ///
+    /// async fn async_main(rt: &toy_rt::Runtime, _: ()) {
+    ///     let (tx, rx) = toy_rt::channel::<u32>(rt);
+    ///     drop(rx);
+    ///     tx.closed().await;
+    ///     assert!(!tx.is_connected());
+    /// }
+    ///
+    /// toy_rt::with_runtime_in_mode(toy_rt::SleepMode::Emulated, async_main, ());
+    /// ```
+    pub async fn closed(&self) {
+        WatcherFuture::new(self.rt, self.sender_rt, Watch::Closed).await
+    }
+
+    /// Returns true if the receiver half of the channel is not dropped yet, so the values
+    /// sent can be received.
+    pub fn is_connected(&self) -> bool {
+        self.sender_rt.is_connected()
+    }
+
+    /// Sends a value without waiting: it only succeeds if the receiver is awaiting a value
+    /// in the rendezvous channel or there is a room in the buffer of the buffered channel.
+    /// Otherwise the value is returned back with [TrySendError].
+    pub fn try_send(&mut self, value: T) -> Result<(), TrySendError<T>> {
+        let mut data = Some(value);
+        match unsafe { self.sender_rt.try_send::<T>(&mut data) } {
+            SwapResult::Done => Ok(()),
+            SwapResult::Disconnected => Err(TrySendError::Disconnected(data.unwrap())),
+            SwapResult::TryLater => Err(TrySendError::Full(data.unwrap())),
+        }
+    }
+}
+
+// Prints the channel id and the state of the channel, e.g. "Sender(chan:1 (Idle <- [0]:1))"
+impl<'runtime, T, ReactorT: Reactor> core::fmt::Debug for Sender<'runtime, T, ReactorT> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("Sender(")?;
+        self.sender_rt.describe(f)?;
+        f.write_str(")")
+    }
 }
 
 // Sender is clonable: having many senders are ok
 impl<'runtime, T, ReactorT: Reactor> Clone for Sender<'runtime, T, ReactorT> {
     fn clone(&self) -> Self {
         // new() also increments sender's counters
-        Self::new(self.rt, self.sender_rt)
+        Self::new(self.rt, self.sender_rt, self.capacity)
     }
 }
 
@@ -85,6 +329,8 @@ impl<'runtime, T, ReactorT: Reactor> Drop for Sender<'runtime, T, ReactorT> {
         //    drop(sender)  <-- counter is decremented to 0, but channel is still alive
         //    fut.await; <-- channel is released as soon as this future dropped
         // answer: not possible, 'fut' borrows 'sender', so it cannot be dropped.
+        #[cfg(feature = "futures")]
+        drop(self.sink_send.take()); // the send has to be cancelled while channel is alive
         self.sender_rt.close();
     }
 }
@@ -96,31 +342,213 @@ impl<'runtime, T, ReactorT: Reactor> Drop for Sender<'runtime, T, ReactorT> {
 pub struct Recver<'runtime, T, ReactorT: Reactor> {
     rt: &'runtime Runtime<ReactorT>,
     recver_rt: RecverRt<'runtime>,
+    capacity: usize,
     temp: PhantomData<T>,
+    // The value received by peek() that next() returns before receiving from the channel
+    peeked: Option<T>,
+    // The receive started by futures::Stream::poll_next() and not completed yet
+    #[cfg(feature = "futures")]
+    stream_next: Option<Pin<Box<StreamNext<'runtime, T, ReactorT>>>>,
 }
 
 impl<'runtime, T, ReactorT: Reactor> Recver<'runtime, T, ReactorT> {
-    fn new(rt: &'runtime Runtime<ReactorT>, recver_rt: RecverRt<'runtime>) -> Self {
+    fn new(
+        rt: &'runtime Runtime<ReactorT>,
+        recver_rt: RecverRt<'runtime>,
+        capacity: usize,
+    ) -> Self {
         Self {
             rt,
             recver_rt,
+            capacity,
             temp: PhantomData,
+            peeked: None,
+            #[cfg(feature = "futures")]
+            stream_next: None,
         }
     }
 
+    /// Returns the buffer capacity of the channel, 0 is for the rendezvous channel.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
     /// Reads a next value from channel sent by sender half. Error is returned when all
-    /// senders are gone, so no values can be received anymore.
+    /// senders are gone, so no values can be received anymore, or when runtime is shutting
+    /// down.
     pub async fn next(&mut self) -> Result<T, RecvError> {
-        NextFuture::new(self.rt, self.recver_rt).await
+        let mut slot = None;
+        self.recv_into(&mut slot).await?;
+        Ok(slot.unwrap())
+    }
+
+    /// Same as [Recver::next()], but the value is received directly into the caller's slot,
+    /// so a big value is not moved through the future output. The previous value in the slot
+    /// is dropped, the slot is `None` when error is returned.
+    pub async fn recv_into(&mut self, slot: &mut Option<T>) -> Result<(), RecvError> {
+        if let Some(value) = self.peeked.take() {
+            *slot = Some(value);
+            return Ok(());
+        }
+
+        *slot = None; // the value in slot would go to the sender otherwise
+        self.cancel_stream_next();
+        NextFuture::new(self.rt, self.recver_rt, slot).await
+    }
+
+    /// Receives the next value like [Recver::next()] does, but the value is kept in the receiver
+    /// and returned again by the following `peek()`, `next()` or `try_recv()`. The value kept is
+    /// dropped with the receiver if it is not consumed.
+    pub async fn peek(&mut self) -> Result<&T, RecvError> {
+        if self.peeked.is_none() {
+            let mut slot = None;
+            self.recv_into(&mut slot).await?;
+            self.peeked = slot;
+        }
+        Ok(self.peeked.as_ref().unwrap())
+    }
+
+    /// Receives up to `limit` values into `buf` in one await and returns the number of values
+    /// received. It awaits until there is at least one value, then takes the values that are
+    /// available right away: the buffered ones and the values of senders awaiting in send().
+    /// Values are appended to `buf` in the order they would be returned by [Recver::next()].
+    ///
+    /// The error is only returned when nothing has been received, so when the channel is
+    /// disconnected after some values the count is returned and the next call gets the error.
+    pub async fn recv_many(&mut self, buf: &mut Vec<T>, limit: usize) -> Result<usize, RecvError> {
+        if limit == 0 {
+            return Ok(0);
+        }
+
+        buf.push(self.next().await?);
+        let drained = unsafe { self.recver_rt.drain_senders::<T>(buf, limit - 1) };
+        Ok(1 + drained)
+    }
+
+    /// Returns the number of sender halves alive including clones, 0 means that no more
+    /// values can be sent except the buffered ones.
+    pub fn sender_count(&self) -> u32 {
+        self.recver_rt.sender_count()
+    }
+
+    /// Returns the number of send() futures queued in the channel right now, e.g. to observe
+    /// the backpressure: for the rendezvous channel these are the senders blocked waiting to
+    /// hand off their values. It only reads the state of the channel.
+    #[cfg(feature = "channel-diagnostics")]
+    pub fn queued_senders(&self) -> usize {
+        self.recver_rt.queued_senders()
+    }
+
+    /// Consumes the receiver and invokes `f` for each value received until the channel is
+    /// disconnected or runtime is shutting down. The receiver is dropped when it returns.
+    pub async fn for_each<FnT: FnMut(T)>(mut self, mut f: FnT) {
+        while let Ok(value) = self.next().await {
+            f(value);
+        }
+    }
+
+    /// Consumes the receiver and returns all the values received until the channel is
+    /// disconnected or runtime is shutting down.
+    pub async fn collect(self) -> Vec<T> {
+        let mut values = Vec::new();
+        self.for_each(|value| values.push(value)).await;
+        values
+    }
+
+    /// Receives a value without waiting: the buffered value or the value of a sender that
+    /// is awaiting in send(). The sender's send() completes as if the value was received by
+    /// [Recver::next()].
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        if let Some(value) = self.peeked.take() {
+            return Ok(value);
+        }
+
+        self.cancel_stream_next();
+        match unsafe { self.recver_rt.try_recv::<T>() } {
+            Ok(value) => Ok(value),
+            Err(SwapResult::Disconnected) => Err(TryRecvError::Disconnected),
+            Err(_) => Err(TryRecvError::Empty),
+        }
+    }
+}
+
+impl<'runtime, T, ReactorT: Reactor> Recver<'runtime, T, ReactorT> {
+    // The receive of the Stream that has been abandoned by the futures combinator is
+    // cancelled, so there is only one receive of this Recver in the channel.
+    #[cfg(feature = "futures")]
+    fn cancel_stream_next(&mut self) {
+        self.stream_next = None;
+    }
+
+    #[cfg(not(feature = "futures"))]
+    fn cancel_stream_next(&mut self) {}
+}
+
+impl<'runtime, T, ReactorT: TemporalReactor> Recver<'runtime, T, ReactorT> {
+    /// Same as [Recver::next()], but it gives up with [Elapsed] error when nothing is received
+    /// within the duration. The receive is cancelled in this case, so the value sent later is
+    /// received by the next receive.
+    pub async fn next_timeout(
+        &mut self,
+        duration: Duration,
+    ) -> Result<Result<T, RecvError>, Elapsed> {
+        let rt = self.rt;
+        match race2(self.next(), sleep(rt, duration)).await {
+            OneOf2::First(result) => Ok(result),
+            OneOf2::Second(()) => Err(Elapsed),
+        }
+    }
+}
+
+// Prints the channel id and the state of the channel like the Debug of Sender
+impl<'runtime, T, ReactorT: Reactor> core::fmt::Debug for Recver<'runtime, T, ReactorT> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("Recver(")?;
+        self.recver_rt.describe(f)?;
+        f.write_str(")")
     }
 }
 
 impl<'runtime, T, ReactorT: Reactor> Drop for Recver<'runtime, T, ReactorT> {
     fn drop(&mut self) {
+        self.cancel_stream_next(); // the receive has to be cancelled while channel is alive
         self.recver_rt.close();
     }
 }
 
+// -----------------------------------------------------------------------------------------------
+/// The receiving half of the channel created by [channel_mpmc()] function.
+///
+/// It works like [Recver] (it derefs to it), but it also can be cloned to receive values from
+/// the same channel. The channel is disconnected for senders when all receivers are dropped.
+pub struct MpmcRecver<'runtime, T, ReactorT: Reactor> {
+    recver: Recver<'runtime, T, ReactorT>,
+}
+
+impl<'runtime, T, ReactorT: Reactor> Clone for MpmcRecver<'runtime, T, ReactorT> {
+    fn clone(&self) -> Self {
+        let recver = &self.recver;
+        recver.recver_rt.inc_ref();
+        MpmcRecver {
+            recver: Recver::new(recver.rt, recver.recver_rt, recver.capacity),
+        }
+    }
+}
+
+impl<'runtime, T, ReactorT: Reactor> core::ops::Deref for MpmcRecver<'runtime, T, ReactorT> {
+    type Target = Recver<'runtime, T, ReactorT>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.recver
+    }
+}
+
+impl<'runtime, T, ReactorT: Reactor> core::ops::DerefMut for MpmcRecver<'runtime, T, ReactorT> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.recver
+    }
+}
+
 // -----------------------------------------------------------------------------------------------
 #[derive(Debug)]
 enum PeerFutureState {
@@ -174,7 +602,7 @@ impl<'runtime, T, ReactorT: Reactor> SenderFuture<'runtime, T, ReactorT> {
         self.state = new_state;
     }
 
-    fn transmit(&mut self, event_id: EventId) -> Poll<Result<(), T>> {
+    fn transmit(&mut self, event_id: EventId) -> Poll<Result<(), SendError<T>>> {
         self.set_state(PeerFutureState::Exchanging);
 
         self.sender_rt
@@ -183,11 +611,19 @@ impl<'runtime, T, ReactorT: Reactor> SenderFuture<'runtime, T, ReactorT> {
         Poll::Pending
     }
 
-    fn close(&mut self) -> Poll<Result<(), T>> {
+    fn close(&mut self) -> Poll<Result<(), SendError<T>>> {
         if !self.event_node.is_awoken_for(self.rt) {
             return Poll::Pending; // not our event, ignore the poll
         }
 
+        // Nothing to exchange, sender was awoken because runtime is shutting down
+        let event_id = self.event_node.get_event_id();
+        if self.sender_rt.is_shutdown_wake(event_id) {
+            self.sender_rt.unpin(event_id);
+            self.set_state(PeerFutureState::Closed);
+            return Poll::Ready(Err(SendError::Shutdown(self.data.take().unwrap())));
+        }
+
         // Let make the exchange. When both sender and receiver futures are registered,
         // the receiver would be first to awake and make the actual memory swap. Sender awakes
         // after receiver and it receives a value if value were delivered to receiver.
@@ -204,7 +640,7 @@ impl<'runtime, T, ReactorT: Reactor> SenderFuture<'runtime, T, ReactorT> {
             // receiver is gone, nothing can be sent to this channel anymore
             {
                 self.set_state_closed(SwapResult::Disconnected);
-                Poll::Ready(Err(self.data.take().unwrap()))
+                Poll::Ready(Err(SendError::Disconnected(self.data.take().unwrap())))
             }
             SwapResult::TryLater =>
             // receiver future gone but receiver channel object is still alive,
@@ -226,7 +662,7 @@ impl<'runtime, T, ReactorT: Reactor> SenderFuture<'runtime, T, ReactorT> {
 }
 
 impl<'runtime, T, ReactorT: Reactor> Future for SenderFuture<'runtime, T, ReactorT> {
-    type Output = Result<(), T>;
+    type Output = Result<(), SendError<T>>;
 
     fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
         modtrace!(
@@ -240,21 +676,42 @@ impl<'runtime, T, ReactorT: Reactor> Future for SenderFuture<'runtime, T, Reacto
         let this = unsafe { self.get_unchecked_mut() };
 
         return match this.state {
+            PeerFutureState::Created if this.rt.is_shutting_down() => {
+                this.set_state(PeerFutureState::Closed);
+                Poll::Ready(Err(SendError::Shutdown(this.data.take().unwrap())))
+            }
             PeerFutureState::Created => {
+                // Buffered channel may take the value right away
+                if unsafe { this.sender_rt.try_push_buffered::<T>(&mut this.data) } {
+                    this.set_state_closed(SwapResult::Done);
+                    return Poll::Ready(Ok(()));
+                }
+
                 let event_id = unsafe { this.event_node.on_pin(ctx) };
                 this.transmit(event_id) // always Pending
             }
             PeerFutureState::Exchanging => this.close(),
             PeerFutureState::Closed => {
-                panic!(
-                    "aiur/channel_sender_future: {:?} was polled after completion.",
-                    this.sender_rt.channel_id
-                );
+                panic_polled_after_completion("channel_sender_future", &this.sender_rt.channel_id)
             }
         };
     }
 }
 
+impl<'runtime, T, ReactorT: Reactor> SenderFuture<'runtime, T, ReactorT> {
+    // Unregisters the future like drop() does and returns the value if it was not swapped
+    // to the receiver. The receiver takes the value in swap() before the sender is awoken, so
+    // the value in self.data after unpin() is the value that receiver never gets.
+    fn cancel(&mut self) -> Option<T> {
+        if matches!(self.state, PeerFutureState::Exchanging) {
+            self.sender_rt.unpin(self.event_node.get_event_id());
+            let _ = self.event_node.on_cancel(); // remove the events from frozen list
+        }
+        self.set_state(PeerFutureState::Closed);
+        self.data.take()
+    }
+}
+
 impl<'runtime, T, ReactorT: Reactor> Drop for SenderFuture<'runtime, T, ReactorT> {
     fn drop(&mut self) {
         if matches!(self.state, PeerFutureState::Exchanging) {
@@ -280,27 +737,173 @@ impl<'runtime, T, ReactorT: Reactor> Drop for SenderFuture<'runtime, T, ReactorT
 }
 
 // -----------------------------------------------------------------------------------------------
-// Leaf Future returned by async fn next() in Recver
+/// The future returned by [Sender::send_cancellable()]. It completes like the future of
+/// [Sender::send()] and it can also be cancelled with the value returned back.
+pub struct SendPermit<'sender, 'runtime, T, ReactorT: Reactor> {
+    future: SenderFuture<'runtime, T, ReactorT>,
+    _sender: PhantomData<&'sender mut Sender<'runtime, T, ReactorT>>,
+}
+
+impl<'sender, 'runtime, T, ReactorT: Reactor> SendPermit<'sender, 'runtime, T, ReactorT> {
+    /// Cancels the send and returns the value if the receiver has not got it. `None` means
+    /// that the value has been received (or given back in the result of the completed send).
+    /// The receiver never gets the value after this call.
+    ///
+    /// Panics if the permit is polled after cancel().
+    pub fn cancel(self: Pin<&mut Self>) -> Option<T> {
+        // Unsafe is ok: the future is not moved, it only cancels its registration
+        unsafe { self.get_unchecked_mut() }.future.cancel()
+    }
+}
+
+impl<'sender, 'runtime, T, ReactorT: Reactor> Future
+    for SendPermit<'sender, 'runtime, T, ReactorT>
+{
+    type Output = Result<(), SendError<T>>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        // This is okay because `future` is pinned when `self` is.
+        unsafe { self.map_unchecked_mut(|s| &mut s.future) }.poll(ctx)
+    }
+}
+
+// -----------------------------------------------------------------------------------------------
+// Leaf Future returned by async fn writable() and closed() in Sender. It does not pin a value
+// like the SenderFuture does, it is registered as a watcher that ChannelRt awakes when the
+// channel becomes writable or when the receiver is gone.
+struct WatcherFuture<'runtime, ReactorT: Reactor> {
+    rt: &'runtime Runtime<ReactorT>,
+    event_node: EventNode,
+    sender_rt: SenderRt<'runtime>,
+    watch: Watch,
+    state: PeerFutureState,
+}
+
+impl<'runtime, ReactorT: Reactor> WatcherFuture<'runtime, ReactorT> {
+    fn new(rt: &'runtime Runtime<ReactorT>, sender_rt: SenderRt<'runtime>, watch: Watch) -> Self {
+        Self {
+            rt,
+            event_node: EventNode::new(),
+            sender_rt,
+            watch,
+            state: PeerFutureState::Created,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self.watch {
+            Watch::Writable => "channel_writable_future",
+            Watch::Closed => "channel_closed_future",
+        }
+    }
+
+    // The watched condition is already met, so there is nothing to wait for
+    fn is_ready(&self) -> bool {
+        match self.watch {
+            Watch::Writable => self.sender_rt.is_writable(),
+            Watch::Closed => !self.sender_rt.is_connected(),
+        }
+    }
+
+    fn set_state(&mut self, new_state: PeerFutureState) {
+        modtrace!(
+            self.rt.tracer(),
+            "{}: {:?} state {:?} -> {:?}",
+            self.name(),
+            self.sender_rt.channel_id,
+            self.state,
+            new_state
+        );
+        self.state = new_state;
+    }
+}
+
+impl<'runtime, ReactorT: Reactor> Future for WatcherFuture<'runtime, ReactorT> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        // Unsafe usage: this function does not moves out data from self, as required by
+        // Pin::get_unchecked_mut().
+        let this = unsafe { self.get_unchecked_mut() };
+
+        match this.state {
+            PeerFutureState::Created if this.rt.is_shutting_down() || this.is_ready() => {
+                this.set_state(PeerFutureState::Closed);
+                Poll::Ready(())
+            }
+            PeerFutureState::Created => {
+                let event_id = unsafe { this.event_node.on_pin(ctx) };
+                this.sender_rt.add_watcher(event_id, this.watch);
+                this.set_state(PeerFutureState::Exchanging);
+                Poll::Pending
+            }
+            PeerFutureState::Exchanging => {
+                if !this.event_node.is_awoken_for(this.rt) {
+                    return Poll::Pending; // not our event, ignore the poll
+                }
+
+                // Awoken either because the watched condition is met or runtime is shutting
+                // down, the result is the same.
+                this.sender_rt.remove_watcher(this.event_node.get_event_id());
+                this.set_state(PeerFutureState::Closed);
+                Poll::Ready(())
+            }
+            PeerFutureState::Closed => {
+                panic_polled_after_completion(this.name(), &this.sender_rt.channel_id)
+            }
+        }
+    }
+}
+
+impl<'runtime, ReactorT: Reactor> Drop for WatcherFuture<'runtime, ReactorT> {
+    fn drop(&mut self) {
+        if matches!(self.state, PeerFutureState::Exchanging) {
+            self.sender_rt
+                .remove_watcher(self.event_node.get_event_id());
+            let _ = self.event_node.on_cancel(); // remove the events from frozen list
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------------------------
+// Leaf Future returned by async fn recv_into() in Recver, the next() is made on top of it.
+// The sender swaps the value right into the slot provided by the caller.
 //
 // Receiver's NextFuture has a lot of copy paste with SenderFuture, but unification
 // produced more code and less clarity.
 //
-pub struct NextFuture<'runtime, T, ReactorT: Reactor> {
+pub struct NextFuture<'runtime, 'slot, T, ReactorT: Reactor> {
     rt: &'runtime Runtime<ReactorT>,
     event_node: EventNode,
     recver_rt: RecverRt<'runtime>,
     state: PeerFutureState,
-    data: Option<T>,
+    // The pointer instead of &'slot mut Option<T>: StreamNext has the slot next to the future
+    slot: *mut Option<T>,
+    _slot: PhantomData<&'slot mut ()>,
 }
 
-impl<'runtime, T, ReactorT: Reactor> NextFuture<'runtime, T, ReactorT> {
-    fn new(rt: &'runtime Runtime<ReactorT>, recver_rt: RecverRt<'runtime>) -> Self {
+impl<'runtime, 'slot, T, ReactorT: Reactor> NextFuture<'runtime, 'slot, T, ReactorT> {
+    fn new(
+        rt: &'runtime Runtime<ReactorT>,
+        recver_rt: RecverRt<'runtime>,
+        slot: &'slot mut Option<T>,
+    ) -> Self {
+        unsafe { Self::from_raw_slot(rt, recver_rt, slot) }
+    }
+
+    // The slot must outlive the future and it must not be used while the future is alive
+    unsafe fn from_raw_slot(
+        rt: &'runtime Runtime<ReactorT>,
+        recver_rt: RecverRt<'runtime>,
+        slot: *mut Option<T>,
+    ) -> Self {
         Self {
             rt,
             event_node: EventNode::new(),
             recver_rt,
             state: PeerFutureState::Created,
-            data: None,
+            slot,
+            _slot: PhantomData,
         }
     }
 
@@ -329,35 +932,42 @@ impl<'runtime, T, ReactorT: Reactor> NextFuture<'runtime, T, ReactorT> {
         self.state = new_state;
     }
 
-    fn transmit(&mut self, event_id: EventId) -> Poll<Result<T, RecvError>> {
+    fn transmit(&mut self, event_id: EventId) -> Poll<Result<(), RecvError>> {
         self.set_state(PeerFutureState::Exchanging);
-        self.recver_rt
-            .pin(event_id, (&mut self.data) as *mut Option<T> as *mut ());
+        self.recver_rt.pin(event_id, self.slot as *mut ());
 
         Poll::Pending
     }
 
-    fn close(&mut self) -> Poll<Result<T, RecvError>> {
+    fn close(&mut self) -> Poll<Result<(), RecvError>> {
         if !self.event_node.is_awoken_for(self.rt) {
             return Poll::Pending; // not our event, ignore the poll
         }
 
+        // Nothing to exchange, receiver was awoken because runtime is shutting down
+        let event_id = self.event_node.get_event_id();
+        if self.recver_rt.is_shutdown_wake(event_id) {
+            self.recver_rt.unpin(event_id);
+            self.set_state(PeerFutureState::Closed);
+            return Poll::Ready(Err(RecvError::Shutdown));
+        }
+
         // Let make the exchange. When both sender and receiver futures are registered,
         // the receiver would be first to awake and make the actual memory swap. It can
         // also happen that receiver was awoken because all sender channels are dropped,
         // and it would receive disconnected event.
         return match unsafe { self.recver_rt.swap::<T>() } {
             SwapResult::Done =>
-            // exchange was perfect, return value to app
+            // exchange was perfect, the value is in the slot
             {
                 self.set_state_closed(SwapResult::Done);
-                Poll::Ready(Ok(self.data.take().unwrap()))
+                Poll::Ready(Ok(()))
             }
             SwapResult::Disconnected =>
             // all senders are gone, no more values to recv
             {
                 self.set_state_closed(SwapResult::Disconnected);
-                Poll::Ready(Err(RecvError))
+                Poll::Ready(Err(RecvError::Disconnected))
             }
             SwapResult::TryLater =>
             // sender future gone, will wait for a new one
@@ -377,7 +987,7 @@ impl<'runtime, T, ReactorT: Reactor> NextFuture<'runtime, T, ReactorT> {
     }
 }
 
-impl<'runtime, T, ReactorT: Reactor> Drop for NextFuture<'runtime, T, ReactorT> {
+impl<'runtime, 'slot, T, ReactorT: Reactor> Drop for NextFuture<'runtime, 'slot, T, ReactorT> {
     fn drop(&mut self) {
         if matches!(self.state, PeerFutureState::Exchanging) {
             modtrace!(
@@ -398,8 +1008,8 @@ impl<'runtime, T, ReactorT: Reactor> Drop for NextFuture<'runtime, T, ReactorT>
     }
 }
 
-impl<'runtime, T, ReactorT: Reactor> Future for NextFuture<'runtime, T, ReactorT> {
-    type Output = Result<T, RecvError>;
+impl<'runtime, 'slot, T, ReactorT: Reactor> Future for NextFuture<'runtime, 'slot, T, ReactorT> {
+    type Output = Result<(), RecvError>;
 
     fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
         modtrace!(
@@ -413,17 +1023,165 @@ impl<'runtime, T, ReactorT: Reactor> Future for NextFuture<'runtime, T, ReactorT
         let this = unsafe { self.get_unchecked_mut() };
 
         return match this.state {
+            PeerFutureState::Created if this.rt.is_shutting_down() => {
+                this.set_state(PeerFutureState::Closed);
+                Poll::Ready(Err(RecvError::Shutdown))
+            }
             PeerFutureState::Created => {
+                // Receive the buffered value right away if there is one
+                if let Some(value) = unsafe { this.recver_rt.try_pop_buffered::<T>() } {
+                    this.set_state_closed(SwapResult::Done);
+                    unsafe { *this.slot = Some(value) };
+                    return Poll::Ready(Ok(()));
+                }
+
                 let event_id = unsafe { this.event_node.on_pin(ctx) };
                 this.transmit(event_id) // always Pending
             }
             PeerFutureState::Exchanging => this.close(),
             PeerFutureState::Closed => {
-                panic!(
-                    "aiur/channel_next_future: {:?} was polled after completion.",
-                    this.recver_rt.channel_id
-                )
+                panic_polled_after_completion("channel_next_future", &this.recver_rt.channel_id)
             }
         };
     }
 }
+
+// -----------------------------------------------------------------------------------------------
+// The compatibility with futures crate: Recver is a Stream and Sender is a Sink. They are
+// polled by aiur tasks like the futures of aiur, the combinators that poll with their own
+// Waker (e.g. FuturesUnordered) cannot be used with them: the poll panics.
+
+// Panics if the stream or sink is polled with the waker not made by aiur task
+#[cfg(feature = "futures")]
+#[track_caller]
+fn assert_aiur_waker(ctx: &Context, what: &str) {
+    assert!(
+        crate::task::is_aiur_waker(ctx.waker()),
+        "aiur/{}: polled with a foreign waker, it can only be polled by aiur task",
+        what
+    );
+}
+
+// Recver and Sender do not pin the values, the in-progress futures are boxed
+#[cfg(feature = "futures")]
+impl<'runtime, T, ReactorT: Reactor> Unpin for Recver<'runtime, T, ReactorT> {}
+
+#[cfg(feature = "futures")]
+impl<'runtime, T, ReactorT: Reactor> Unpin for Sender<'runtime, T, ReactorT> {}
+
+// The receive of Recver::poll_next() that has not been completed: the NextFuture receives into
+// the slot next to it, the box keeps the slot in place.
+#[cfg(feature = "futures")]
+struct StreamNext<'runtime, T, ReactorT: Reactor> {
+    next: Option<NextFuture<'runtime, 'runtime, T, ReactorT>>, // dropped before slot
+    slot: Option<T>,
+    _pin: PhantomPinned,
+}
+
+#[cfg(feature = "futures")]
+impl<'runtime, T, ReactorT: Reactor> StreamNext<'runtime, T, ReactorT> {
+    fn new(rt: &'runtime Runtime<ReactorT>, recver_rt: RecverRt<'runtime>) -> Pin<Box<Self>> {
+        let mut stream_next = Box::pin(StreamNext {
+            next: None,
+            slot: None,
+            _pin: PhantomPinned,
+        });
+
+        // Unsafe is ok: the slot does not move with the box and it is only used when
+        // NextFuture is completed and dropped.
+        unsafe {
+            let this = stream_next.as_mut().get_unchecked_mut();
+            let slot = &mut this.slot as *mut Option<T>;
+            this.next = Some(NextFuture::from_raw_slot(rt, recver_rt, slot));
+        }
+        stream_next
+    }
+}
+
+#[cfg(feature = "futures")]
+impl<'runtime, T, ReactorT: Reactor> Future for StreamNext<'runtime, T, ReactorT> {
+    type Output = Option<T>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let this = unsafe { self.get_unchecked_mut() };
+        let next = unsafe { Pin::new_unchecked(this.next.as_mut().unwrap()) };
+        match next.poll(ctx) {
+            Poll::Pending => Poll::Pending,
+            Poll::Ready(result) => {
+                this.next = None; // the slot is not used by the future anymore
+                Poll::Ready(result.ok().and_then(|()| this.slot.take()))
+            }
+        }
+    }
+}
+
+/// The stream of the values received by [Recver::next()], it ends when the channel is
+/// disconnected or runtime is shutting down.
+#[cfg(feature = "futures")]
+impl<'runtime, T, ReactorT: Reactor> futures_core::Stream for Recver<'runtime, T, ReactorT> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Option<T>> {
+        let this = self.get_mut();
+        if let Some(value) = this.peeked.take() {
+            return Poll::Ready(Some(value));
+        }
+
+        assert_aiur_waker(ctx, "channel_recver");
+        let (rt, recver_rt) = (this.rt, this.recver_rt);
+        let stream_next = this
+            .stream_next
+            .get_or_insert_with(|| StreamNext::new(rt, recver_rt));
+
+        match stream_next.as_mut().poll(ctx) {
+            Poll::Pending => Poll::Pending,
+            Poll::Ready(value) => {
+                this.stream_next = None;
+                Poll::Ready(value)
+            }
+        }
+    }
+}
+
+/// The sink that sends the values with [Sender::send()]: the value given to `start_send()` is
+/// sent by `poll_flush()` and `poll_ready()` flushes the previous one, so the sink holds at
+/// most one value. The error gives the value back like [Sender::send()] does.
+#[cfg(feature = "futures")]
+impl<'runtime, T, ReactorT: Reactor> futures_sink::Sink<T> for Sender<'runtime, T, ReactorT> {
+    type Error = SendError<T>;
+
+    fn poll_ready(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        self.poll_flush(ctx)
+    }
+
+    fn start_send(self: Pin<&mut Self>, value: T) -> Result<(), Self::Error> {
+        let this = self.get_mut();
+        assert!(
+            this.sink_send.is_none(),
+            "aiur/channel_sender: start_send() invoked without poll_ready()"
+        );
+        this.sink_send = Some(Box::pin(SenderFuture::new(this.rt, this.sender_rt, value)));
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        let this = self.get_mut();
+        let sink_send = match this.sink_send {
+            None => return Poll::Ready(Ok(())),
+            Some(ref mut sink_send) => sink_send,
+        };
+
+        assert_aiur_waker(ctx, "channel_sender");
+        match sink_send.as_mut().poll(ctx) {
+            Poll::Pending => Poll::Pending,
+            Poll::Ready(result) => {
+                this.sink_send = None;
+                Poll::Ready(result)
+            }
+        }
+    }
+
+    fn poll_close(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        self.poll_flush(ctx)
+    }
+}
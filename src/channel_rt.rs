@@ -2,21 +2,35 @@
 //  / * \    aiur: the home planet for the famous executors
 // |' | '|   (c) 2020 - present, Vladimir Zvezda
 //   / \
-use std::cell::RefCell;
+use alloc::collections::VecDeque;
+use alloc::format;
+use alloc::vec::Vec;
+use core::cell::RefCell;
 
+use crate::node_store::{panic_stale_id, NodeStore, SlabKey, SlabStore};
 use crate::reactor::EventId;
 use crate::tracer::Tracer;
+use crate::value_ring::ValueRing;
 
 // enable/disable output of modtrace! macro
 const MODTRACE: bool = true;
 
 // Channel handle used by this low level channel API, which is only has crate visibility.
+// It is the key of the node in the store, so the node is found without scanning. It has the
+// generation of the slot, so the stale id is not confused with the id of a new channel that
+// has reused the slot.
 #[derive(Copy, Clone, Eq, PartialEq)]
-pub(crate) struct ChannelId(u32);
-
-impl std::fmt::Debug for ChannelId {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_fmt(format_args!("chan:{}", self.0))
+pub(crate) struct ChannelId(SlabKey);
+
+// The slots are counted from 1 in the text form, e.g. "chan:1" or "chan:1.2" for the channel
+// that has reused the first slot twice.
+impl core::fmt::Debug for ChannelId {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let number = self.0.slot() + 1;
+        match self.0.generation() {
+            0 => f.write_fmt(format_args!("chan:{}", number)),
+            generation => f.write_fmt(format_args!("chan:{}.{}", number, generation)),
+        }
     }
 }
 
@@ -28,6 +42,13 @@ pub(crate) enum SwapResult {
     TryLater, // a new state in compare to oneshot
 }
 
+// What the sender watcher waits for: see Sender::writable() and Sender::closed()
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub(crate) enum Watch {
+    Writable,
+    Closed,
+}
+
 // Both sender and receiver has almost the same API which is in this trait.
 pub(crate) trait PeerRt {
     fn pin(&self, event_id: EventId, pointer: *mut ());
@@ -35,6 +56,10 @@ pub(crate) trait PeerRt {
     fn close(&self);
 
     unsafe fn swap<T>(&self) -> SwapResult;
+
+    // Returns true if the peer future was awoken by the event not for the exchange but because
+    // the runtime is shutting down.
+    fn is_shutdown_wake(&self, event_id: EventId) -> bool;
 }
 
 // Sender API
@@ -48,6 +73,37 @@ impl<'rt> SenderRt<'rt> {
     pub(crate) fn inc_ref(&self) {
         self.channel_rt.inc_sender(self.channel_id)
     }
+
+    pub(crate) unsafe fn try_push_buffered<T>(&self, value: &mut Option<T>) -> bool {
+        self.channel_rt
+            .try_push_buffered::<T>(self.channel_id, value)
+    }
+
+    pub(crate) unsafe fn try_send<T>(&self, value: &mut Option<T>) -> SwapResult {
+        self.channel_rt.try_send::<T>(self.channel_id, value)
+    }
+
+    // Writable watcher is not a sender future: it has no value and it is just awoken when
+    // the send would not be suspended.
+    pub(crate) fn is_writable(&self) -> bool {
+        self.channel_rt.is_writable(self.channel_id)
+    }
+
+    pub(crate) fn add_watcher(&self, event_id: EventId, watch: Watch) {
+        self.channel_rt.add_watcher(self.channel_id, event_id, watch)
+    }
+
+    pub(crate) fn is_connected(&self) -> bool {
+        self.channel_rt.is_receiver_alive(self.channel_id)
+    }
+
+    pub(crate) fn describe(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.channel_rt.describe(self.channel_id, f)
+    }
+
+    pub(crate) fn remove_watcher(&self, event_id: EventId) {
+        self.channel_rt.remove_watcher(self.channel_id, event_id)
+    }
 }
 
 impl<'rt> PeerRt for SenderRt<'rt> {
@@ -61,6 +117,9 @@ impl<'rt> PeerRt for SenderRt<'rt> {
     unsafe fn swap<T>(&self) -> SwapResult {
         self.channel_rt.swap_sender::<T>(self.channel_id)
     }
+    fn is_shutdown_wake(&self, event_id: EventId) -> bool {
+        self.channel_rt.is_shutdown_wake(self.channel_id, event_id)
+    }
     fn close(&self) {
         self.channel_rt.dec_sender(self.channel_id)
     }
@@ -73,17 +132,53 @@ pub(crate) struct RecverRt<'rt> {
     pub(crate) channel_id: ChannelId, // visible as it used for tracing
 }
 
+impl<'rt> RecverRt<'rt> {
+    // Only the receiver of channel_mpmc() is cloned
+    pub(crate) fn inc_ref(&self) {
+        self.channel_rt.inc_receiver(self.channel_id)
+    }
+
+    pub(crate) unsafe fn try_pop_buffered<T>(&self) -> Option<T> {
+        self.channel_rt.try_pop_buffered::<T>(self.channel_id)
+    }
+
+    pub(crate) unsafe fn try_recv<T>(&self) -> Result<T, SwapResult> {
+        self.channel_rt.try_recv::<T>(self.channel_id)
+    }
+
+    pub(crate) fn sender_count(&self) -> u32 {
+        self.channel_rt.senders_alive(self.channel_id)
+    }
+
+    #[cfg(feature = "channel-diagnostics")]
+    pub(crate) fn queued_senders(&self) -> usize {
+        self.channel_rt.queued_senders(self.channel_id)
+    }
+
+    pub(crate) fn describe(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.channel_rt.describe(self.channel_id, f)
+    }
+
+    pub(crate) unsafe fn drain_senders<T>(&self, rx_data: &mut Vec<T>, max: usize) -> usize {
+        self.channel_rt
+            .drain_senders::<T>(self.channel_id, rx_data, max)
+    }
+}
+
 impl<'rt> PeerRt for RecverRt<'rt> {
     fn pin(&self, event_id: EventId, pointer: *mut ()) {
         self.channel_rt
             .reg_receiver_fut(self.channel_id, event_id, pointer)
     }
-    fn unpin(&self, _event_id: EventId) {
-        self.channel_rt.cancel_receiver_fut(self.channel_id)
+    fn unpin(&self, event_id: EventId) {
+        self.channel_rt.cancel_receiver_fut(self.channel_id, event_id)
     }
     unsafe fn swap<T>(&self) -> SwapResult {
         self.channel_rt.swap_receiver::<T>(self.channel_id)
     }
+    fn is_shutdown_wake(&self, event_id: EventId) -> bool {
+        self.channel_rt.is_shutdown_wake(self.channel_id, event_id)
+    }
     fn close(&self) {
         self.channel_rt.close_receiver(self.channel_id)
     }
@@ -103,8 +198,8 @@ impl ChannelRt {
         }
     }
 
-    pub(crate) fn create(&self) -> ChannelId {
-        self.inner.borrow_mut().create()
+    pub(crate) fn create(&self, capacity: usize) -> ChannelId {
+        self.inner.borrow_mut().create(capacity)
     }
 
     pub(crate) fn sender_rt<'rt>(&'rt self, channel_id: ChannelId) -> SenderRt<'rt> {
@@ -126,11 +221,74 @@ impl ChannelRt {
         self.inner.borrow().is_exist(channel_id)
     }
 
-    
     pub(crate) fn get_awake_event_id(&self) -> Option<EventId> {
         self.inner.borrow_mut().get_awake_event_id()
     }
 
+    // Adds the events of all the channels that are ready to awake, the first one is the event
+    // returned by get_awake_event_id().
+    pub(crate) fn awake_event_ids(&self, events: &mut Vec<EventId>) {
+        self.inner.borrow_mut().awake_event_ids(events)
+    }
+
+    // Number of channels that are alive, see Runtime::stats()
+    pub(crate) fn node_count(&self) -> usize {
+        self.inner.borrow().nodes.len()
+    }
+
+    // Adds the events of the pinned futures and writable watchers, e.g. to find the tasks
+    // that are blocked by deadlock.
+    pub(crate) fn registered_events(&self, events: &mut Vec<EventId>) {
+        self.inner.borrow().registered_events(events);
+    }
+
+    // After this call all pinned sender and receiver futures are going to be awoken, so they
+    // can complete with error.
+    pub(crate) fn begin_shutdown(&self) {
+        self.inner.borrow_mut().begin_shutdown();
+    }
+
+    fn is_shutdown_wake(&self, channel_id: ChannelId, event_id: EventId) -> bool {
+        self.inner.borrow_mut().is_shutdown_wake(channel_id, event_id)
+    }
+
+    // Sender's fast path: moves the value into the channel buffer if there is a room for it,
+    // so sender future does not have to register. Returns false if value was not moved.
+    unsafe fn try_push_buffered<T>(&self, channel_id: ChannelId, value: &mut Option<T>) -> bool {
+        self.inner
+            .borrow_mut()
+            .try_push_buffered::<T>(channel_id, value)
+    }
+
+    // Receiver's fast path: takes the oldest value from channel buffer if there is any.
+    unsafe fn try_pop_buffered<T>(&self, channel_id: ChannelId) -> Option<T> {
+        self.inner.borrow_mut().try_pop_buffered::<T>(channel_id)
+    }
+
+    // Non-blocking send: the value is taken only if the send would not be suspended. Returns
+    // TryLater if there is no receiver or room in buffer to take it right now.
+    unsafe fn try_send<T>(&self, channel_id: ChannelId, value: &mut Option<T>) -> SwapResult {
+        self.inner.borrow_mut().try_send::<T>(channel_id, value)
+    }
+
+    // Non-blocking receive: takes the buffered value or the value of the pinned sender.
+    unsafe fn try_recv<T>(&self, channel_id: ChannelId) -> Result<T, SwapResult> {
+        self.inner.borrow_mut().try_recv::<T>(channel_id)
+    }
+
+    // Receiver's batch path: moves up to max values into rx_data in one borrow instead of
+    // one exchange per runtime iteration. Returns the number of values moved.
+    unsafe fn drain_senders<T>(
+        &self,
+        channel_id: ChannelId,
+        rx_data: &mut Vec<T>,
+        max: usize,
+    ) -> usize {
+        self.inner
+            .borrow_mut()
+            .drain_senders::<T>(channel_id, rx_data, max)
+    }
+
     fn add_sender_fut(
         &self,
         channel_id: ChannelId,
@@ -165,12 +323,16 @@ impl ChannelRt {
         self.inner.borrow_mut().inc_sender(channel_id);
     }
 
+    // The channel node removed is dropped when the RefCell is no longer borrowed: the drop
+    // of buffered values is the user code that can use channels or panic.
     fn dec_sender(&self, channel_id: ChannelId) {
-        self.inner.borrow_mut().dec_sender(channel_id);
+        let dropped_node = self.inner.borrow_mut().dec_sender(channel_id);
+        drop(dropped_node);
     }
 
     fn close_receiver(&self, channel_id: ChannelId) {
-        self.inner.borrow_mut().close_receiver(channel_id);
+        let dropped_node = self.inner.borrow_mut().close_receiver(channel_id);
+        drop(dropped_node);
     }
 
     fn cancel_sender_fut(&self, channel_id: ChannelId, event_id: EventId) {
@@ -179,8 +341,69 @@ impl ChannelRt {
             .cancel_sender_fut(channel_id, event_id);
     }
 
-    fn cancel_receiver_fut(&self, channel_id: ChannelId) {
-        self.inner.borrow_mut().cancel_receiver_fut(channel_id);
+    fn is_writable(&self, channel_id: ChannelId) -> bool {
+        self.inner.borrow_mut().is_writable(channel_id)
+    }
+
+    fn add_watcher(&self, channel_id: ChannelId, event_id: EventId, watch: Watch) {
+        self.inner
+            .borrow_mut()
+            .add_watcher(channel_id, event_id, watch);
+    }
+
+    // The state queries below do not mutate the channel and they do not panic if the channel
+    // node has been already removed: the channel is disconnected in this case.
+    fn senders_alive(&self, channel_id: ChannelId) -> u32 {
+        self.inner
+            .borrow()
+            .find_node(channel_id)
+            .map_or(0, |node| node.senders_alive)
+    }
+
+    #[cfg(feature = "channel-diagnostics")]
+    fn queued_senders(&self, channel_id: ChannelId) -> usize {
+        self.inner
+            .borrow()
+            .find_node(channel_id)
+            .map_or(0, |node| node.tx_queue.len())
+    }
+
+    fn is_receiver_alive(&self, channel_id: ChannelId) -> bool {
+        self.inner
+            .borrow()
+            .find_node(channel_id)
+            .is_some_and(|node| !matches!(node.rx_state, RxState::Gone))
+    }
+
+    // Writes the id and the state of the channel node like the trace does for the Debug of
+    // Sender and Recver. It is invoked by the user code, so it does not panic when the
+    // channel is being mutated right now.
+    fn describe(
+        &self,
+        channel_id: ChannelId,
+        f: &mut core::fmt::Formatter<'_>,
+    ) -> core::fmt::Result {
+        match self.inner.try_borrow() {
+            Ok(inner) => match inner.find_node(channel_id) {
+                Some(node) => write!(f, "{:?} {:?}", channel_id, node),
+                None => write!(f, "{:?} disconnected", channel_id),
+            },
+            Err(_) => write!(f, "{:?} busy", channel_id),
+        }
+    }
+
+    fn remove_watcher(&self, channel_id: ChannelId, event_id: EventId) {
+        self.inner.borrow_mut().remove_watcher(channel_id, event_id);
+    }
+
+    fn cancel_receiver_fut(&self, channel_id: ChannelId, event_id: EventId) {
+        self.inner
+            .borrow_mut()
+            .cancel_receiver_fut(channel_id, event_id);
+    }
+
+    fn inc_receiver(&self, channel_id: ChannelId) {
+        self.inner.borrow_mut().inc_receiver(channel_id);
     }
 }
 
@@ -233,6 +456,7 @@ impl TxState {
 enum Peer {
     Sender,
     Receiver,
+    Watcher,
 }
 
 struct WakeEvent {
@@ -257,17 +481,43 @@ impl WakeEvent {
 struct ChannelNode {
     id: ChannelId,
     rx_state: RxState,
+    // The pinned receiver of the rendezvous channel has got the value into its slot from
+    // try_send(), it is awoken to complete without the exchange.
+    rx_filled: bool,
+    // The receivers of channel_mpmc() pinned after the one in rx_state, the first of them
+    // takes rx_state when the pinned receiver is done or cancelled.
+    rx_queue: VecDeque<RegInfo>,
+    receivers_alive: u32,
     tx_queue: Vec<TxState>,
     senders_alive: u32,
+    // Buffered channel keeps up to 'capacity' values that were sent but not yet received.
+    // ChannelNode is not generic, so the type of values is erased in the ring, it also drops
+    // the values if channel is destroyed with values in buffer.
+    capacity: usize,
+    buffer: ValueRing,
+    // Sender::writable() futures waiting for the send that would not be suspended
+    watchers: Vec<EventId>,
+    // Sender::closed() futures waiting for the receiver to be gone
+    closed_watchers: Vec<EventId>,
+    // The node is in the ready queue of InnerChannelRt, this prevents the duplicates there
+    in_ready_queue: bool,
 }
 
 impl ChannelNode {
-    fn new(channel_id: ChannelId, tracer: &Tracer) -> Self {
+    fn new(channel_id: ChannelId, capacity: usize, tracer: &Tracer) -> Self {
         let node = Self {
             id: channel_id,
             rx_state: RxState::Idle,
+            rx_filled: false,
+            rx_queue: VecDeque::new(),
+            receivers_alive: 1,
             tx_queue: Vec::new(),
             senders_alive: 0, // intially incremented by ChSender::new()
+            capacity,
+            buffer: ValueRing::new(capacity),
+            watchers: Vec::new(),
+            closed_watchers: Vec::new(),
+            in_ready_queue: false,
         };
 
         modtrace!(tracer, "channel_rt: new {:?} {:?}", channel_id, node);
@@ -283,9 +533,10 @@ impl ChannelNode {
         op: &str,
         mut_state_fn: MutateStateFn,
     ) {
-        if MODTRACE {
+        // The old state is rendered to String, so it is only done when somebody reads traces
+        if MODTRACE && tracer.is_enabled() {
             // remember the old state
-            let old_self = format!("{:?}", self); // TODO: remove alloc usage here
+            let old_self = format!("{:?}", self);
 
             // mutate the channel node
             mut_state_fn(self);
@@ -312,7 +563,25 @@ impl ChannelNode {
 
     fn reg_recv_future(&mut self, reg_info: RegInfo, tracer: &Tracer) {
         self.traced(tracer, "reg receiver future", |node| {
-            node.rx_state = RxState::Pinned(reg_info);
+            match node.rx_state {
+                RxState::Pinned(..) => node.rx_queue.push_back(reg_info),
+                _ => node.rx_state = RxState::Pinned(reg_info),
+            }
+        });
+    }
+
+    // The pinned receiver is done, the next receiver in queue (if any) is pinned now
+    fn next_receiver(&mut self) {
+        self.rx_filled = false;
+        self.rx_state = match self.rx_queue.pop_front() {
+            Some(reg_info) => RxState::Pinned(reg_info),
+            None => RxState::Idle,
+        };
+    }
+
+    fn inc_receiver(&mut self, tracer: &Tracer) {
+        self.traced(tracer, "inc receivers", |node| {
+            node.receivers_alive += 1;
         });
     }
 
@@ -339,10 +608,178 @@ impl ChannelNode {
         });
     }
 
-    fn cancel_receiver_fut(&mut self, tracer: &Tracer) {
-        self.traced(tracer, "receiver future canceled", |node| {
-            node.rx_state = RxState::Idle;
+    fn cancel_receiver_fut(&mut self, event_id: EventId, tracer: &Tracer) {
+        self.traced(tracer, "receiver future canceled", |node| match node.rx_state {
+            RxState::Pinned(ref rx_reg_info) if rx_reg_info.event_id == event_id => {
+                node.next_receiver();
+            }
+            _ => node.rx_queue.retain(|reg_info| reg_info.event_id != event_id),
+        });
+    }
+
+    fn add_watcher(&mut self, event_id: EventId, watch: Watch, tracer: &Tracer) {
+        self.traced(tracer, "add watcher", |node| match watch {
+            Watch::Writable => node.watchers.push(event_id),
+            Watch::Closed => node.closed_watchers.push(event_id),
+        });
+    }
+
+    fn remove_watcher(&mut self, event_id: EventId, tracer: &Tracer) {
+        self.traced(tracer, "remove watcher", |node| {
+            node.watchers.retain(|watcher| *watcher != event_id);
+            node.closed_watchers.retain(|watcher| *watcher != event_id);
+        });
+    }
+
+    // The send would not be suspended: there is no senders ahead and either the pinned
+    // receiver (rendezvous) or a room in buffer (buffered channel) would take the value. The
+    // gone receiver also makes the send complete right away, with the value returned back.
+    fn is_writable(&self) -> bool {
+        if !self.tx_queue.is_empty() {
+            return false;
+        }
+
+        match self.rx_state {
+            RxState::Gone => true,
+            _ if self.capacity > 0 => self.has_buffer_room(),
+            RxState::Pinned(..) => !self.rx_filled, // see try_send()
+            RxState::Idle => false,
+        }
+    }
+
+    fn has_buffer_room(&self) -> bool {
+        self.buffer.len() < self.capacity
+    }
+
+    // Unsafe: T must be the type of the channel
+    unsafe fn push_buffered<T>(&mut self, value: T) {
+        self.buffer.push::<T>(value);
+    }
+
+    // Unsafe: T must be the type of the channel
+    unsafe fn pop_buffered<T>(&mut self) -> Option<T> {
+        self.buffer.pop::<T>()
+    }
+
+    // Values can be moved to buffer without registering the sender future when there is
+    // a room in buffer and there are no other senders waiting (otherwise we break the order).
+    unsafe fn try_push_buffered<T>(&mut self, value: &mut Option<T>, tracer: &Tracer) -> bool {
+        let can_push = self.has_buffer_room()
+            && self.tx_queue.is_empty()
+            && !matches!(self.rx_state, RxState::Gone);
+
+        if can_push {
+            self.traced(tracer, "value buffered", |node| {
+                node.push_buffered::<T>(value.take().unwrap());
+            });
+        }
+
+        can_push
+    }
+
+    unsafe fn try_pop_buffered<T>(&mut self, tracer: &Tracer) -> Option<T> {
+        if self.buffer.is_empty() {
+            return None;
+        }
+
+        let mut value = None;
+        self.traced(tracer, "value taken from buffer", |node| {
+            value = node.pop_buffered::<T>();
+        });
+        value
+    }
+
+    // The value is taken when the send would not be suspended, see is_writable(). The
+    // rendezvous channel never buffers: the value is moved right into the slot of the pinned
+    // receiver, which is awoken to complete with it.
+    //
+    // Unsafe: T must be the type of the channel
+    unsafe fn try_send<T>(&mut self, value: &mut Option<T>, tracer: &Tracer) -> SwapResult {
+        if matches!(self.rx_state, RxState::Gone) {
+            return SwapResult::Disconnected;
+        }
+
+        if !self.is_writable() {
+            return SwapResult::TryLater;
+        }
+
+        if let RxState::Pinned(ref rx_reg_info) = self.rx_state {
+            if self.capacity == 0 {
+                let rx_data = &mut *(rx_reg_info.data as *mut Option<T>);
+                self.traced(tracer, "value sent to receiver", |node| {
+                    *rx_data = value.take();
+                    node.rx_filled = true;
+                });
+                return SwapResult::Done;
+            }
+        }
+
+        self.traced(tracer, "value sent", |node| {
+            node.push_buffered::<T>(value.take().unwrap());
+        });
+        SwapResult::Done
+    }
+
+    // Takes the buffered value or the value of the first pinned sender, the sender is awoken
+    // with Done as if it was drained. TryLater is for the alive senders without values.
+    //
+    // Unsafe: T must be the type of the channel
+    unsafe fn try_recv<T>(&mut self, tracer: &Tracer) -> Result<T, SwapResult> {
+        if let Some(value) = self.try_pop_buffered::<T>(tracer) {
+            return Ok(value);
+        }
+
+        let has_pinned_sender = self
+            .tx_queue
+            .iter()
+            .any(|tx_state| matches!(tx_state.completion, TxCompletion::Pinned(..)));
+
+        if has_pinned_sender {
+            let mut rx_data = Vec::with_capacity(1);
+            self.drain_senders::<T>(&mut rx_data, 1, tracer);
+            Ok(rx_data.pop().unwrap())
+        } else if self.senders_alive == 0 {
+            Err(SwapResult::Disconnected)
+        } else {
+            Err(SwapResult::TryLater)
+        }
+    }
+
+    // Takes up to max values: the buffered ones first as they were sent before any sender in
+    // the queue, then the values of the pinned senders. Such senders become Emptied, so they
+    // are awoken one by one in the queue order and get SwapResult::Done like after a regular
+    // exchange.
+    //
+    // Unsafe: T must be the type of the channel
+    unsafe fn drain_senders<T>(
+        &mut self,
+        rx_data: &mut Vec<T>,
+        max: usize,
+        tracer: &Tracer,
+    ) -> usize {
+        let mut drained = 0;
+        self.traced(tracer, "senders drained", |node| {
+            while drained < max {
+                match node.pop_buffered::<T>() {
+                    Some(value) => rx_data.push(value),
+                    None => break,
+                }
+                drained += 1;
+            }
+
+            for tx_state in node.tx_queue.iter_mut() {
+                if drained == max {
+                    break;
+                }
+                if let TxCompletion::Pinned(tx_ptr) = tx_state.completion {
+                    let tx_data = &mut *(tx_ptr as *mut Option<T>);
+                    rx_data.push(tx_data.take().unwrap());
+                    tx_state.completion = TxCompletion::Emptied;
+                    drained += 1;
+                }
+            }
         });
+        drained
     }
 
     fn is_channel_alive(&self) -> bool {
@@ -351,13 +788,40 @@ impl ChannelNode {
 
     fn close_receiver(&mut self, tracer: &Tracer) {
         self.traced(tracer, "receiver gone", |node| {
-            node.rx_state = RxState::Gone;
+            node.receivers_alive -= 1;
+            if node.receivers_alive == 0 {
+                node.rx_state = RxState::Gone;
+            }
         });
     }
 
     // This is the implementation for the runtime if this ChannelNode ready to produce any
-    // event. 
+    // event. Exchange goes first, the watchers are awoken when nothing is to exchange.
     fn get_wake_event(&self) -> Option<WakeEvent> {
+        self.get_exchange_event()
+            .or_else(|| match self.watchers.first() {
+                Some(event_id) if self.is_writable() => {
+                    Some(WakeEvent::new(Peer::Watcher, *event_id))
+                }
+                _ => None,
+            })
+            .or_else(|| match self.closed_watchers.first() {
+                Some(event_id) if matches!(self.rx_state, RxState::Gone) => {
+                    Some(WakeEvent::new(Peer::Watcher, *event_id))
+                }
+                _ => None,
+            })
+    }
+
+    fn get_exchange_event(&self) -> Option<WakeEvent> {
+        // The receiver that has got the value from try_send() goes first, it does not need
+        // any sender.
+        if let RxState::Pinned(ref rx_reg_info) = self.rx_state {
+            if self.rx_filled {
+                return Some(WakeEvent::new(Peer::Receiver, rx_reg_info.event_id));
+            }
+        }
+
         // Verify if there is a sender future that just got its data transferred to a receiver,
         // that should be awoken. It does not matter in what state the receiver is.
         if let Some(ref first_tx_state) = self.tx_queue.first() {
@@ -370,7 +834,21 @@ impl ChannelNode {
         }
 
         if matches!(self.rx_state, RxState::Idle) {
-            return None; // Receiver alive but Idle -> nobody to awake
+            // Receiver alive but Idle: the only sender can be awoken to move its value into
+            // the buffer if there is a room for it.
+            return match self.tx_queue.first() {
+                Some(first_tx_state) if self.has_buffer_room() => {
+                    Some(WakeEvent::new(Peer::Sender, first_tx_state.event_id))
+                }
+                _ => None, // nobody to awake
+            };
+        }
+
+        // Pinned receiver takes the buffered values first
+        if let RxState::Pinned(ref rx_reg_info) = self.rx_state {
+            if !self.buffer.is_empty() {
+                return Some(WakeEvent::new(Peer::Receiver, rx_reg_info.event_id));
+            }
         }
 
         if self.tx_queue.is_empty() && self.senders_alive > 0 {
@@ -431,19 +909,49 @@ impl ChannelNode {
         None
     }
 
+    // When runtime is shutting down, the pinned futures are awoken even if there is nothing
+    // to exchange. It is only used when get_wake_event() returns None for all channels.
+    fn registered_events(&self, events: &mut Vec<EventId>) {
+        if let RxState::Pinned(ref rx_reg_info) = self.rx_state {
+            events.push(rx_reg_info.event_id);
+        }
+        events.extend(self.rx_queue.iter().map(|reg_info| reg_info.event_id));
+        events.extend(self.tx_queue.iter().map(|tx_state| tx_state.event_id));
+        events.extend(self.watchers.iter().copied());
+        events.extend(self.closed_watchers.iter().copied());
+    }
+
+    fn get_shutdown_event(&self) -> Option<WakeEvent> {
+        if let RxState::Pinned(ref rx_reg_info) = self.rx_state {
+            return Some(WakeEvent::new(Peer::Receiver, rx_reg_info.event_id));
+        }
+
+        self.tx_queue
+            .iter()
+            .find(|tx_state| matches!(tx_state.completion, TxCompletion::Pinned(..)))
+            .map(|tx_state| WakeEvent::new(Peer::Sender, tx_state.event_id))
+            .or_else(|| {
+                self.watchers
+                    .first()
+                    .or(self.closed_watchers.first())
+                    .map(|event_id| WakeEvent::new(Peer::Watcher, *event_id))
+            })
+    }
+
     // Makes the data exchange using std::mem::swap, copy data from one future into another
     // future.
     // Unsafe: Caller should guaranty the validity of the pointers and the data type. This
     // is achieved in public crate API by using Unpin futures.
     unsafe fn exchange_impl<T>(tx_data: *mut (), rx_data: *mut ()) {
-        let tx_data = std::mem::transmute::<*mut (), *mut Option<T>>(tx_data);
-        let rx_data = std::mem::transmute::<*mut (), *mut Option<T>>(rx_data);
-        std::mem::swap(&mut *tx_data, &mut *rx_data);
+        let tx_data = core::mem::transmute::<*mut (), *mut Option<T>>(tx_data);
+        let rx_data = core::mem::transmute::<*mut (), *mut Option<T>>(rx_data);
+        core::mem::swap(&mut *tx_data, &mut *rx_data);
     }
 
     // This is invoked by Sender future and it should be asserted that there is a
     // sender future is Pinned with either Emptied value or not.
     unsafe fn swap_sender<T>(&mut self, tracer: &Tracer) -> SwapResult {
+        let has_buffer_room = self.has_buffer_room();
         if let Some(first_tx_state) = self.tx_queue.first_mut() {
             match (&self.rx_state, &first_tx_state.completion) {
                 (RxState::Gone, TxCompletion::Pinned(..)) => {
@@ -461,6 +969,16 @@ impl ChannelNode {
                     // the value out of sender storage and replanced it with None.
                     SwapResult::Done
                 }
+                (_, TxCompletion::Pinned(tx_ptr)) if has_buffer_room => {
+                    let tx_data = &mut *(*tx_ptr as *mut Option<T>);
+                    self.traced(tracer, "awoken sender buffered", |node| {
+                        node.push_buffered::<T>(tx_data.take().unwrap());
+                        node.tx_queue.remove(0);
+                    });
+                    // Buffered channel: the value is in the buffer now, for sender this
+                    // is the same as if receiver had the value received.
+                    SwapResult::Done
+                }
                 (_, _) => {
                     panic!(
                         "ChannelRt: {:?} swap_sender unexpected state: {:?}",
@@ -480,6 +998,25 @@ impl ChannelNode {
     // This is invoked by Receiver future and the precondition that receiver future has
     // pinned.
     unsafe fn swap_receiver<T>(&mut self, tracer: &Tracer) -> SwapResult {
+        if self.rx_filled {
+            // The value is already in the slot, see try_send()
+            self.traced(tracer, "received from try_send", |node| node.next_receiver());
+            return SwapResult::Done;
+        }
+
+        if !self.buffer.is_empty() {
+            // Buffered values were sent before any sender in the tx_queue.
+            if let RxState::Pinned(ref rx_reg_info) = self.rx_state {
+                let rx_data = &mut *(rx_reg_info.data as *mut Option<T>);
+                self.traced(tracer, "taken from buffer", |node| {
+                    *rx_data = node.pop_buffered::<T>();
+                    node.next_receiver();
+                });
+                return SwapResult::Done;
+            }
+            panic!("ChannelRt: swap_receiver unexpected {:?}", self);
+        }
+
         if let Some(first_tx_state) = self.tx_queue.first_mut() {
             // Just do the actual data exchange between receiver and first sender in queue.
             // It can happen that between we awake the receiver and it invokes swap_receiver()
@@ -489,11 +1026,15 @@ impl ChannelNode {
                 (RxState::Pinned(ref rx_reg_info), TxCompletion::Pinned(tx_ptr)) => {
                     Self::exchange_impl::<T>(rx_reg_info.data, *tx_ptr);
                     self.traced(tracer, "mem::swapped", move |node| {
-                        node.rx_state = RxState::Idle;
+                        node.next_receiver();
                         node.tx_queue[0].completion = TxCompletion::Emptied;
                     });
                     SwapResult::Done
                 }
+                // The value of the first sender was taken by try_recv() or drain_senders()
+                // of another receiver of channel_mpmc() after this one was awoken. The
+                // receiver is awoken again when the emptied sender leaves the queue.
+                (RxState::Pinned(..), TxCompletion::Emptied) => SwapResult::TryLater,
                 // other state are not legal and should be asserted by Channel Futures:
                 //    * Receiver: it must not call exhange_receiver() if not in Pinned state
                 //    * Sender: there should be no way swap_receiver() is invoked while
@@ -504,7 +1045,11 @@ impl ChannelNode {
             // There is no sender future
             if self.senders_alive == 0 {
                 // The receiver might awoken because there is no senders anymore, so
-                // the sender's end of the channel is Disconnected.
+                // the sender's end of the channel is Disconnected. The receiver future is
+                // completed, so it should not be awoken again.
+                self.traced(tracer, "disconnected", |node| {
+                    node.next_receiver();
+                });
                 SwapResult::Disconnected
             } else {
                 // It looks like the sender future was dropped after Receiver future is awoken.
@@ -524,9 +1069,15 @@ impl ChannelNode {
 //                        | +----------------state of the receiver
 //                        +------------------'@' indicates a future to be awoken in this state
 //
+// Buffered channel also has the number of buffered values and capacity: "(Idle <- [0]:1 {2/4})".
+// The number of Sender::writable() watchers is shown when there are any: "(Idle <- [0]:1 w:1)",
+// the same for Sender::closed() watchers: "(Idle <- [0]:1 c:1)".
+//
 // Receivers states are:
 //     * 'Idle' - when receiver side is alive but did not provide pointer for swap
-//     * 'Pin' - means that receiver provided pointer for swap
+//     * 'Pin' - means that receiver provided pointer for swap, 'Pin:2' is for the receivers
+//       of channel_mpmc() queued for swap
+//     * 'Fill' - means that receiver has got the value from try_send() into its slot
 //     * 'Gone' - means that receiver's side of the channel is dropped
 //
 // Senders states are:
@@ -534,21 +1085,23 @@ impl ChannelNode {
 //     * 'Empt' - means that receiver has taken data from sender
 //
 // Senders are organized as a queue, the only top sender can be 'Empt', other senders in
-// queue are always 'Pin'. If 'Pin' sender in the middle of queue is closed, it just removed
+// queue are always 'Pin'. The exception is drain_senders() that can make several senders on
+// top of the queue 'Empt' at once, it is shown as 'Empt:3'. If 'Pin' sender in the middle of queue is closed, it just removed
 // from queue without receiver knowing about that.
 //
 // When the number of senders is 0, this is just like Gone for receiver that there are no
 // more sender and channel looks disconnected on Receiver side.
 //
 // When recv is Gone and # of senders is 0 - channel closed.
-impl std::fmt::Debug for ChannelNode {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Debug for ChannelNode {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         // "@"
         let (rx_event_tag, tx_event_tag) =
             self.get_wake_event()
                 .map_or(("", ""), |wake_event| match wake_event.peer {
                     Peer::Sender => ("", "@"),
                     Peer::Receiver => ("@", ""),
+                    Peer::Watcher => ("", ""),
                 });
 
         f.write_str("(")?;
@@ -556,6 +1109,10 @@ impl std::fmt::Debug for ChannelNode {
 
         match self.rx_state {
             RxState::Idle => f.write_str("Idle <- "),
+            RxState::Pinned(..) if !self.rx_queue.is_empty() => {
+                f.write_fmt(format_args!("Pin:{} <- ", self.rx_queue.len() + 1))
+            }
+            RxState::Pinned(..) if self.rx_filled => f.write_str("Fill <- "),
             RxState::Pinned(..) => f.write_str("Pin <- "),
             RxState::Gone => f.write_str("Gone <- "),
         }?;
@@ -566,13 +1123,21 @@ impl std::fmt::Debug for ChannelNode {
             f.write_str("[")?;
             f.write_str(tx_event_tag)?;
 
-            match self.tx_queue[0].completion {
-                TxCompletion::Pinned(..) => f.write_str("Pin"),
-                TxCompletion::Emptied => f.write_str("Empt"),
+            let emptied = self
+                .tx_queue
+                .iter()
+                .filter(|tx_state| matches!(tx_state.completion, TxCompletion::Emptied))
+                .count();
+
+            match emptied {
+                0 => f.write_str("Pin"),
+                1 => f.write_str("Empt"),
+                _ => f.write_fmt(format_args!("Empt:{}", emptied)),
             }?;
 
-            if tx_len > 1 {
-                f.write_fmt(format_args!(", Pin:{}]:", tx_len - 1))?;
+            let top = emptied.max(1);
+            if tx_len > top {
+                f.write_fmt(format_args!(", Pin:{}]:", tx_len - top))?;
             } else {
                 f.write_str("]:")?;
             }
@@ -582,12 +1147,24 @@ impl std::fmt::Debug for ChannelNode {
 
         f.write_fmt(format_args!("{}", self.senders_alive))?;
 
+        if self.capacity > 0 {
+            f.write_fmt(format_args!(" {{{}/{}}}", self.buffer.len(), self.capacity))?;
+        }
+
+        if !self.watchers.is_empty() {
+            f.write_fmt(format_args!(" w:{}", self.watchers.len()))?;
+        }
+
+        if !self.closed_watchers.is_empty() {
+            f.write_fmt(format_args!(" c:{}", self.closed_watchers.len()))?;
+        }
+
         f.write_str(")")
     }
 }
 
 // Actual implementation of internal Channel API with functions that do have &mut self.
-struct InnerChannelRt {
+struct InnerChannelRt<StoreT = SlabStore<ChannelNode>> {
     // Improvement ideas:
     //
     // There are various ideas how we can improve containers for channels in order to
@@ -597,54 +1174,81 @@ struct InnerChannelRt {
     //     * it can be the caller that provides store the channel node like
     //       let channel: Pin<&mut ChannelNode> = ...
     //
-    // Another improvement idea that currently executor has to scan all channel objects
-    // to verify if there is an event. Whenever there is a channel node that goes into
-    // the state that produces awake event, it can be stored somewhere in a queue.
-    // The queue can be also made by an intrusive list.
-    //
     // These ideas seems to require preparing the benching.
-    nodes: Vec<ChannelNode>,
-    last_id: u32,
+    //
+    // The fixed size table is possible with FixedStore from node_store.rs.
+    nodes: StoreT,
+    // The channels that may produce the awake event. The node is added here after the state
+    // mutation when it has an event, so the executor does not have to scan all the nodes. The
+    // node is removed from queue lazily when it no longer produces events or has been removed.
+    ready: VecDeque<ChannelId>,
+    shutdown: bool,
     tracer: Tracer,
 }
 
 impl InnerChannelRt {
     fn new(tracer: &Tracer) -> Self {
+        Self::with_store(SlabStore::new(), tracer)
+    }
+}
+
+impl<StoreT: NodeStore<ChannelNode>> InnerChannelRt<StoreT> {
+    fn with_store(nodes: StoreT, tracer: &Tracer) -> Self {
         InnerChannelRt {
-            nodes: Vec::new(),
-            last_id: 0,
+            nodes,
+            ready: VecDeque::new(),
+            shutdown: false,
             tracer: tracer.clone(),
         }
     }
 
-    fn create(&mut self) -> ChannelId {
-        self.last_id += 1;
-        let channel_id = ChannelId(self.last_id);
-        self.nodes.push(ChannelNode::new(channel_id, &self.tracer));
+    fn create(&mut self, capacity: usize) -> ChannelId {
+        let channel_id = ChannelId(self.nodes.next_key());
+        self.nodes
+            .insert(ChannelNode::new(channel_id, capacity, &self.tracer));
         channel_id
     }
 
     #[cfg(test)]
     fn is_exist(&self, channel_id: ChannelId) -> bool {
-        self.nodes
-            .iter()
-            .find(|node| node.id == channel_id)
-            .is_some()
+        self.find_node(channel_id).is_some()
     }
 
-    // Returns mutable reference to node
-    fn get_node_mut(&mut self, channel_id: ChannelId) -> &mut ChannelNode {
-        self.nodes
-            .iter_mut()
-            .find(|node| node.id == channel_id)
-            .unwrap() // panics if channel_id is not found
+    // Returns mutable reference to node, it only borrows the nodes, so the ready queue can be
+    // updated while the node is borrowed.
+    fn get_node_mut(nodes: &mut StoreT, channel_id: ChannelId) -> &mut ChannelNode {
+        match nodes.get_mut(channel_id.0) {
+            Some(node) => node,
+            None => panic_stale_id(&channel_id),
+        }
     }
 
     fn get_node(&mut self, channel_id: ChannelId) -> &ChannelNode {
-        self.nodes
-            .iter()
-            .find(|node| node.id == channel_id)
-            .unwrap() // panics if channel_id is not found
+        self.find_node(channel_id)
+            .unwrap_or_else(|| panic_stale_id(&channel_id))
+    }
+
+    // Same as get_node() but returns None if channel_id is not found
+    fn find_node(&self, channel_id: ChannelId) -> Option<&ChannelNode> {
+        self.nodes.get(channel_id.0)
+    }
+
+    // Runs the mutation of the node state and puts the node in the ready queue if the node
+    // has an awake event after it.
+    //
+    // Panics if channel_id is not found.
+    fn mutate<ResT, MutateFn: FnOnce(&mut ChannelNode, &Tracer) -> ResT>(
+        &mut self,
+        channel_id: ChannelId,
+        mutate_fn: MutateFn,
+    ) -> ResT {
+        let node = Self::get_node_mut(&mut self.nodes, channel_id);
+        let res = mutate_fn(node, &self.tracer);
+        if !node.in_ready_queue && node.get_wake_event().is_some() {
+            node.in_ready_queue = true;
+            self.ready.push_back(channel_id);
+        }
+        res
     }
 
     fn add_sender_fut(
@@ -654,9 +1258,7 @@ impl InnerChannelRt {
         data: *mut (),
     ) {
         let reg_info = RegInfo::new(data, event_id);
-        let tracer = self.tracer.clone();
-        self.get_node_mut(channel_id)
-            .add_sender_future(reg_info, &tracer);
+        self.mutate(channel_id, |node, tracer| node.add_sender_future(reg_info, tracer));
     }
 
     fn reg_receiver_fut(
@@ -665,10 +1267,8 @@ impl InnerChannelRt {
         event_id: EventId,
         data: *mut (),
     ) {
-        let tracer = self.tracer.clone();
         let reg_info = RegInfo::new(data, event_id);
-        self.get_node_mut(channel_id)
-            .reg_recv_future(reg_info, &tracer);
+        self.mutate(channel_id, |node, tracer| node.reg_recv_future(reg_info, tracer));
     }
 
     // This is invoked by Sender future and it should be asserted that there is a
@@ -676,8 +1276,7 @@ impl InnerChannelRt {
     //
     // Panics if channel_id is not found and if channel id is inconsistent state.
     unsafe fn swap_sender<T>(&mut self, channel_id: ChannelId) -> SwapResult {
-        let tracer = self.tracer.clone();
-        self.get_node_mut(channel_id).swap_sender::<T>(&tracer)
+        self.mutate(channel_id, |node, tracer| node.swap_sender::<T>(tracer))
     }
 
     // This is invoked by Receiver future and the precondition that receiver future has
@@ -685,8 +1284,32 @@ impl InnerChannelRt {
     //
     // Panics if channel_id is not found and if channel id is inconsistent state.
     unsafe fn swap_receiver<T>(&mut self, channel_id: ChannelId) -> SwapResult {
-        let tracer = self.tracer.clone();
-        self.get_node_mut(channel_id).swap_receiver::<T>(&tracer)
+        self.mutate(channel_id, |node, tracer| node.swap_receiver::<T>(tracer))
+    }
+
+    unsafe fn try_push_buffered<T>(&mut self, channel_id: ChannelId, value: &mut Option<T>) -> bool {
+        self.mutate(channel_id, |node, tracer| node.try_push_buffered::<T>(value, tracer))
+    }
+
+    unsafe fn try_pop_buffered<T>(&mut self, channel_id: ChannelId) -> Option<T> {
+        self.mutate(channel_id, |node, tracer| node.try_pop_buffered::<T>(tracer))
+    }
+
+    unsafe fn try_send<T>(&mut self, channel_id: ChannelId, value: &mut Option<T>) -> SwapResult {
+        self.mutate(channel_id, |node, tracer| node.try_send::<T>(value, tracer))
+    }
+
+    unsafe fn try_recv<T>(&mut self, channel_id: ChannelId) -> Result<T, SwapResult> {
+        self.mutate(channel_id, |node, tracer| node.try_recv::<T>(tracer))
+    }
+
+    unsafe fn drain_senders<T>(
+        &mut self,
+        channel_id: ChannelId,
+        rx_data: &mut Vec<T>,
+        max: usize,
+    ) -> usize {
+        self.mutate(channel_id, |node, tracer| node.drain_senders::<T>(rx_data, max, tracer))
     }
 
     // Awakes the waker and returns its EventId
@@ -694,50 +1317,110 @@ impl InnerChannelRt {
         node.get_wake_event().map(|ev| ev.get_event_id())
     }
 
+    // Takes the event from the first node in ready queue. The node stays in the queue while it
+    // has events, the nodes that have no events anymore are removed from the queue.
     fn get_awake_event_id(&mut self) -> Option<EventId> {
-        self.nodes
-            .iter()
-            .find_map(|node| Self::get_event_id_for_node(&node))
+        let mut event_id = None;
+        while let Some(&channel_id) = self.ready.front() {
+            // The removed channels are left in the queue, see remove_channel_if_needed()
+            if let Some(node) = self.nodes.get_mut(channel_id.0) {
+                event_id = Self::get_event_id_for_node(node);
+                if event_id.is_some() {
+                    break;
+                }
+                node.in_ready_queue = false;
+            }
+
+            self.ready.pop_front();
+        }
+
+        // Shutdown wakes the futures that have nothing to exchange, so here all nodes are scanned
+        if event_id.is_none() && self.shutdown {
+            return self
+                .nodes
+                .iter()
+                .find_map(|node| node.get_shutdown_event())
+                .map(|ev| ev.get_event_id());
+        }
+
+        event_id
+    }
+
+    // Each channel in the ready queue has at most one event. The front of the queue is cleaned
+    // up by get_awake_event_id(), the rest nodes may have no events already.
+    fn awake_event_ids(&mut self, events: &mut Vec<EventId>) {
+        if let Some(first) = self.get_awake_event_id() {
+            events.push(first);
+            events.extend(self.ready.iter().skip(1).filter_map(|channel_id| {
+                self.find_node(*channel_id)
+                    .and_then(Self::get_event_id_for_node)
+            }));
+        }
+    }
+
+    fn registered_events(&self, events: &mut Vec<EventId>) {
+        for node in self.nodes.iter() {
+            node.registered_events(events);
+        }
+    }
+
+    fn begin_shutdown(&mut self) {
+        modtrace!(&self.tracer, "channel_rt: begin shutdown");
+        self.shutdown = true;
+    }
+
+    fn is_shutdown_wake(&mut self, channel_id: ChannelId, event_id: EventId) -> bool {
+        self.shutdown && Self::get_event_id_for_node(self.get_node(channel_id)) != Some(event_id)
     }
 
     fn inc_sender(&mut self, channel_id: ChannelId) {
-        let tracer = self.tracer.clone();
-        self.get_node_mut(channel_id).inc_sender(&tracer);
+        self.mutate(channel_id, |node, tracer| node.inc_sender(tracer));
     }
 
-    fn dec_sender(&mut self, channel_id: ChannelId) {
-        let tracer = self.tracer.clone();
-        self.get_node_mut(channel_id).dec_sender(&tracer);
-        self.drop_channel_if_needed(channel_id);
+    fn dec_sender(&mut self, channel_id: ChannelId) -> Option<ChannelNode> {
+        self.mutate(channel_id, |node, tracer| node.dec_sender(tracer));
+        self.remove_channel_if_needed(channel_id)
     }
 
-    fn close_receiver(&mut self, channel_id: ChannelId) {
-        let tracer = self.tracer.clone();
-        self.get_node_mut(channel_id).close_receiver(&tracer);
-        self.drop_channel_if_needed(channel_id);
+    fn close_receiver(&mut self, channel_id: ChannelId) -> Option<ChannelNode> {
+        self.mutate(channel_id, |node, tracer| node.close_receiver(tracer));
+        self.remove_channel_if_needed(channel_id)
     }
 
-    fn drop_channel_if_needed(&mut self, channel_id: ChannelId) {
+    // Returns the node removed, so the caller can drop it later, see ChannelRt::dec_sender().
+    // The id of removed node is left in the ready queue: it is stale and is skipped there.
+    fn remove_channel_if_needed(&mut self, channel_id: ChannelId) -> Option<ChannelNode> {
         if !self.get_node(channel_id).is_channel_alive() {
-            self.nodes.remove(
-                self.nodes
-                    .iter()
-                    .position(|node| node.id == channel_id)
-                    .unwrap(),
-            );
-            modtrace!(&self.tracer, "channel_rt: {:?} has been dropped", channel_id);
+            let node = self.nodes.remove(channel_id.0).unwrap();
+            modtrace!(&self.tracer, "channel_rt: {:?} has been removed", channel_id);
+            Some(node)
+        } else {
+            None
         }
     }
 
     fn cancel_sender_fut(&mut self, channel_id: ChannelId, event_id: EventId) {
-        let tracer = self.tracer.clone();
-        self.get_node_mut(channel_id)
-            .cancel_sender_fut(event_id, &tracer);
+        self.mutate(channel_id, |node, tracer| node.cancel_sender_fut(event_id, tracer));
+    }
+
+    fn cancel_receiver_fut(&mut self, channel_id: ChannelId, event_id: EventId) {
+        self.mutate(channel_id, |node, tracer| node.cancel_receiver_fut(event_id, tracer));
+    }
+
+    fn inc_receiver(&mut self, channel_id: ChannelId) {
+        self.mutate(channel_id, |node, tracer| node.inc_receiver(tracer));
     }
 
-    fn cancel_receiver_fut(&mut self, channel_id: ChannelId) {
-        let tracer = self.tracer.clone();
-        self.get_node_mut(channel_id).cancel_receiver_fut(&tracer);
+    fn is_writable(&mut self, channel_id: ChannelId) -> bool {
+        self.get_node(channel_id).is_writable()
+    }
+
+    fn add_watcher(&mut self, channel_id: ChannelId, event_id: EventId, watch: Watch) {
+        self.mutate(channel_id, |node, tracer| node.add_watcher(event_id, watch, tracer));
+    }
+
+    fn remove_watcher(&mut self, channel_id: ChannelId, event_id: EventId) {
+        self.mutate(channel_id, |node, tracer| node.remove_watcher(event_id, tracer));
     }
 }
 
@@ -777,7 +1460,7 @@ mod tests {
         }
 
         unsafe fn exchange(&self, expected: SwapResult) {
-            assert_eq!(self.peer_rt.swap::<Option<u32>>(), expected);
+            assert_eq!(self.peer_rt.swap::<u32>(), expected);
         }
 
         unsafe fn assert_completion(
@@ -833,7 +1516,7 @@ mod tests {
     fn api_test_dec_references_destroys_channel() {
         let mut crt = InnerChannelRt::new(&Tracer::new_testing());
 
-        let channel_id = crt.create();
+        let channel_id = crt.create(0);
         assert!(crt.is_exist(channel_id));
         crt.inc_sender(channel_id);
         assert!(crt.is_exist(channel_id));
@@ -846,6 +1529,66 @@ mod tests {
         assert!(!crt.is_exist(channel_id));
     }
 
+    /// The id of the removed channel is reported as stale instead of an index panic.
+    #[test]
+    #[should_panic(expected = "aiur: chan:1 is stale, its node has been removed.")]
+    fn api_test_stale_channel_id_is_rejected() {
+        let mut crt = InnerChannelRt::new(&Tracer::new_testing());
+
+        let stale_id = crt.create(0);
+        crt.close_receiver(stale_id);
+        assert!(!crt.is_exist(stale_id));
+
+        let channel_id = crt.create(0);
+        assert_ne!(stale_id, channel_id);
+        crt.inc_sender(stale_id);
+    }
+
+    /// The removed channel stays in the ready queue until it is skipped there, the new
+    /// channel that has reused its slot is not confused with it.
+    #[test]
+    fn api_test_removed_channel_skipped_in_ready_queue() {
+        let crt = ChannelRt::new(&Tracer::new_testing());
+
+        let mut recver: Option<u32> = None;
+        let removed_id = crt.create(0);
+        let sender = SenderEmu::new(&crt, removed_id, &mut None);
+        let recver = RecverEmu::new(&crt, removed_id, &mut recver);
+
+        recver.register();
+        drop(sender); // the receiver is to be awoken with disconnected
+        assert_eq!(crt.inner.borrow().ready.len(), 1);
+        recver.cancel();
+        drop(recver);
+        assert!(!crt.is_exist(removed_id));
+        assert_eq!(crt.inner.borrow().ready.len(), 1);
+
+        let channel_id = crt.create(0);
+        assert_eq!(channel_id.0.slot(), removed_id.0.slot());
+        assert!(crt.get_awake_event_id().is_none());
+        assert!(crt.inner.borrow().ready.is_empty());
+        crt.close_receiver(channel_id);
+    }
+
+    /// The state queries return the disconnected state for the removed channel node.
+    #[test]
+    fn api_test_state_queries_of_removed_channel() {
+        let crt = ChannelRt::new(&Tracer::new_testing());
+
+        let channel_id = crt.create(0);
+        crt.inc_sender(channel_id);
+        assert_eq!(crt.senders_alive(channel_id), 1);
+        assert!(crt.is_receiver_alive(channel_id));
+
+        crt.close_receiver(channel_id);
+        assert!(!crt.is_receiver_alive(channel_id));
+        crt.dec_sender(channel_id);
+        assert!(!crt.is_exist(channel_id));
+
+        assert_eq!(crt.senders_alive(channel_id), 0);
+        assert!(!crt.is_receiver_alive(channel_id));
+    }
+
     /// Verifies that sender and receiver has value changed after being pinned and
     /// invoking exchange().
     #[test]
@@ -855,7 +1598,7 @@ mod tests {
         let mut sender: Option<u32> = Some(100);
         let mut recver: Option<u32> = None;
 
-        let channel_id = crt.create();
+        let channel_id = crt.create(0);
         assert!(crt.is_exist(channel_id));
 
         // Hide the storage variable above to avoid having multiple mutable references
@@ -891,7 +1634,7 @@ mod tests {
         let mut sender: Option<u32> = Some(100);
         let mut recver: Option<u32> = None;
 
-        let channel_id = crt.create();
+        let channel_id = crt.create(0);
 
         // Hide the storage variable above to avoid having multiple mutable references
         // to the same object.
@@ -933,7 +1676,7 @@ mod tests {
         let mut sender2: Option<u32> = Some(50);
         let mut recver: Option<u32> = None;
 
-        let channel_id = crt.create();
+        let channel_id = crt.create(0);
 
         // Hide the storage variable above to avoid having multiple mutable references
         // to the same object.
@@ -977,7 +1720,7 @@ mod tests {
         let mut sender2: Option<u32> = Some(50);
         let mut recver: Option<u32> = None;
 
-        let channel_id = crt.create();
+        let channel_id = crt.create(0);
 
         // Hide the storage variable above to avoid having multiple mutable references
         // to the same object.
@@ -1020,7 +1763,7 @@ mod tests {
         let mut sender2: Option<u32> = Some(50);
         let mut recver: Option<u32> = None;
 
-        let channel_id = crt.create();
+        let channel_id = crt.create(0);
 
         // Hide the storage variable above to avoid having multiple mutable references
         // to the same object.
@@ -1047,4 +1790,140 @@ mod tests {
             sender1.assert_completion(crt.get_awake_event_id(), SwapResult::Done, &None);
         }
     }
+
+    /// Buffered channel takes the value from the pinned sender while there is a room in
+    /// buffer, the receiver gets it later.
+    #[test]
+    fn api_test_buffered_sender_done_before_receiver_pinned() {
+        let crt = ChannelRt::new(&Tracer::new_testing());
+
+        // storage for exchange
+        let mut sender: Option<u32> = Some(100);
+        let mut recver: Option<u32> = None;
+
+        let channel_id = crt.create(1);
+
+        // Hide the storage variable above to avoid having multiple mutable references
+        // to the same object.
+        let sender = SenderEmu::new(&crt, channel_id, &mut sender);
+        let recver = RecverEmu::new(&crt, channel_id, &mut recver);
+
+        sender.register();
+
+        unsafe {
+            // sender is awoken to put the value into buffer
+            sender.assert_completion(crt.get_awake_event_id(), SwapResult::Done, &None);
+            assert!(crt.get_awake_event_id().is_none());
+
+            recver.register();
+            recver.assert_completion(crt.get_awake_event_id(), SwapResult::Done, &Some(100));
+            assert!(crt.get_awake_event_id().is_none());
+        }
+    }
+
+    /// Verifies the fixed size node store can be used by InnerChannelRt: channels can be
+    /// created again when they are dropped, but exceeding the capacity panics.
+    #[test]
+    #[should_panic(expected = "aiur: FixedStore capacity 2 exceeded")]
+    fn api_test_fixed_store_panics_when_full() {
+        use crate::node_store::FixedStore;
+
+        let mut crt =
+            InnerChannelRt::with_store(FixedStore::<ChannelNode, 2>::new(), &Tracer::new_testing());
+
+        let channel_id = crt.create(0);
+        crt.create(0);
+        crt.close_receiver(channel_id);
+        assert!(!crt.is_exist(channel_id));
+
+        crt.create(0);
+        crt.create(0); // panics: no room for the third channel
+    }
+
+    /// Drains 3 senders of 5 queued in one call: the drained senders are awoken in order with
+    /// Done, the rest are exchanged as usual.
+    #[test]
+    fn api_test_drain_senders_takes_values_of_queued_senders() {
+        let crt = ChannelRt::new(&Tracer::new_testing());
+
+        let mut storage: Vec<Option<u32>> = (0..5).map(Some).collect();
+        let mut recver: Option<u32> = None;
+
+        let channel_id = crt.create(0);
+        let senders: Vec<SenderEmu> = storage
+            .iter_mut()
+            .map(|sender| SenderEmu::new(&crt, channel_id, sender))
+            .collect();
+        let recver = RecverEmu::new(&crt, channel_id, &mut recver);
+
+        senders.iter().for_each(|sender| sender.register());
+
+        unsafe {
+            let mut rx_data = Vec::new();
+            assert_eq!(recver.peer_rt.drain_senders::<u32>(&mut rx_data, 3), 3);
+            assert_eq!(rx_data, vec![0, 1, 2]);
+
+            // drained senders are awoken one by one in the queue order
+            for sender in &senders[..3] {
+                sender.assert_completion(crt.get_awake_event_id(), SwapResult::Done, &None);
+            }
+            assert!(crt.get_awake_event_id().is_none());
+
+            // the rest of senders are exchanged with pinned receiver
+            recver.register();
+            recver.assert_completion(crt.get_awake_event_id(), SwapResult::Done, &Some(3));
+            senders[3].assert_completion(crt.get_awake_event_id(), SwapResult::Done, &None);
+
+            rx_data.clear();
+            assert_eq!(recver.peer_rt.drain_senders::<u32>(&mut rx_data, 3), 1);
+            assert_eq!(rx_data, vec![4]);
+            senders[4].assert_completion(crt.get_awake_event_id(), SwapResult::Done, &None);
+        }
+
+        drop(senders);
+        drop(recver);
+        assert!(!crt.is_exist(channel_id));
+    }
+
+    /// Creates 1000 channels with pinned receivers and makes only one of them ready: the
+    /// event of that channel is found in the ready queue and the node is found by the slot of
+    /// its id without scanning the other nodes.
+    #[test]
+    fn api_test_ready_queue_with_1000_channels() {
+        let crt = ChannelRt::new(&Tracer::new_testing());
+
+        let mut tx_storage: Vec<Option<u32>> = (0..1000).map(Some).collect();
+        let mut rx_storage: Vec<Option<u32>> = vec![None; 1000];
+
+        let channel_ids: Vec<ChannelId> = (0..1000).map(|_| crt.create(0)).collect();
+        let senders: Vec<SenderEmu> = tx_storage
+            .iter_mut()
+            .zip(&channel_ids)
+            .map(|(storage, channel_id)| SenderEmu::new(&crt, *channel_id, storage))
+            .collect();
+        let recvers: Vec<RecverEmu> = rx_storage
+            .iter_mut()
+            .zip(&channel_ids)
+            .map(|(storage, channel_id)| RecverEmu::new(&crt, *channel_id, storage))
+            .collect();
+
+        recvers.iter().for_each(|recver| recver.register());
+        assert!(crt.get_awake_event_id().is_none());
+        assert!(crt.inner.borrow().ready.is_empty());
+
+        senders[700].register();
+        assert_eq!(crt.inner.borrow().ready.len(), 1);
+        assert_eq!(channel_ids[700].0.slot(), 700);
+
+        unsafe {
+            recvers[700].assert_completion(crt.get_awake_event_id(), SwapResult::Done, &Some(700));
+            senders[700].assert_completion(crt.get_awake_event_id(), SwapResult::Done, &None);
+        }
+        assert!(crt.get_awake_event_id().is_none());
+        assert!(crt.inner.borrow().ready.is_empty());
+
+        drop(senders);
+        drop(recvers);
+        assert!(channel_ids.iter().all(|channel_id| !crt.is_exist(*channel_id)));
+    }
 }
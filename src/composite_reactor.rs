@@ -0,0 +1,94 @@
+//  \ O /
+//  / * \    aiur: the homeplanet for the famous executors
+// |' | '|   (c) 2020 - present, Vladimir Zvezda
+//   / \
+use core::task::Waker;
+use core::time::Duration;
+
+use crate::reactor::{EventId, Reactor, TemporalReactor, TimeoutReactor};
+
+/// Reactor made of two reactors: the I/O comes from the first one and the timers from the
+/// second one, so the reactor with I/O does not have to implement timers.
+///
+/// The wait for I/O is limited by the time left until the first timer is due (see
+/// [TemporalReactor::next_timer_in()]), when it times out the timer reactor is awaited for the
+/// timer event.
+pub struct CompositeReactor<IoT: TimeoutReactor, TimersT: TemporalReactor> {
+    io: IoT,
+    timers: TimersT,
+}
+
+impl<IoT: TimeoutReactor, TimersT: TemporalReactor> CompositeReactor<IoT, TimersT> {
+    /// Creates the reactor from the I/O and timer reactors.
+    pub fn new(io: IoT, timers: TimersT) -> Self {
+        CompositeReactor { io, timers }
+    }
+
+    /// Returns the reactor that does I/O, e.g. to register I/O events in a leaf future.
+    pub fn io_reactor(&self) -> &IoT {
+        &self.io
+    }
+
+    /// Returns the reactor that does timers.
+    pub fn timer_reactor(&self) -> &TimersT {
+        &self.timers
+    }
+}
+
+impl<IoT: TimeoutReactor, TimersT: TemporalReactor> Reactor for CompositeReactor<IoT, TimersT> {
+    fn wait(&self) -> EventId {
+        match self.timers.next_timer_in() {
+            None => self.io.wait(), // no timers, only I/O can wake us
+            Some(timeout) => match self.io.wait_timeout(timeout) {
+                Some(event_id) => event_id,
+                None => self.timers.wait(), // the first timer is due
+            },
+        }
+    }
+
+    fn try_wait(&self) -> Option<EventId> {
+        self.io
+            .wait_timeout(Duration::ZERO)
+            .or_else(|| self.timers.try_wait())
+    }
+
+    fn has_pending(&self) -> bool {
+        self.io.has_pending() || self.timers.has_pending()
+    }
+
+    fn register_event(&self, waker: Waker, event_id: EventId) {
+        self.io.register_event(waker, event_id);
+    }
+
+    fn deregister_event(&self, event_id: EventId) {
+        self.io.deregister_event(event_id);
+    }
+}
+
+impl<IoT: TimeoutReactor, TimersT: TemporalReactor> TemporalReactor
+    for CompositeReactor<IoT, TimersT>
+{
+    fn schedule_timer(&self, event_id: EventId, duration: Duration) {
+        self.timers.schedule_timer(event_id, duration);
+    }
+
+    fn cancel_timer(&self, event_id: EventId) {
+        self.timers.cancel_timer(event_id);
+    }
+
+    fn schedule_timer_at(&self, event_id: EventId, deadline_ms: u32) {
+        self.timers.schedule_timer_at(event_id, deadline_ms);
+    }
+
+    fn now(&self) -> Duration {
+        self.timers.now()
+    }
+
+    fn next_timer_in(&self) -> Option<Duration> {
+        self.timers.next_timer_in()
+    }
+
+    fn cancel_all_timers(&self) {
+        self.timers.cancel_all_timers();
+    }
+}
@@ -0,0 +1,127 @@
+//  \ O /
+//  / * \    aiur: the homeplanet for the famous executors
+// |' | '|   (c) 2020 - present, Vladimir Zvezda
+//   / \
+use core::cell::{Cell, UnsafeCell};
+use core::ops::{Deref, DerefMut};
+
+use crate::reactor::Reactor;
+use crate::runtime::Runtime;
+
+/// The state shared by futures of the same runtime that can be borrowed mutably by one future
+/// at a time, the borrow can be held across await points.
+///
+/// Unlike `&mut state` passed down to async functions, the context can be shared by the
+/// futures that run concurrently, e.g. in [join!](crate::join). Unlike `RefCell` the illegal
+/// borrow is reported as [ContextError] that tells if the context is held by the same
+/// runtime loop or by the outer one that was interrupted by [Runtime::nested_loop()].
+///
+/// ```
+/// use aiur::toy_rt;
+///
+/// async fn async_main(rt: &toy_rt::Runtime, _: ()) -> u32 {
+///     let counter = aiur::Context::new(0);
+///     toy_rt::join!(
+///         async { *counter.borrow_mut(rt) += 1 },
+///         async { *counter.borrow_mut(rt) += 2 }
+///     )
+///     .await;
+///     counter.into_inner()
+/// }
+///
+/// let sum = toy_rt::with_runtime_in_mode(toy_rt::SleepMode::Emulated, async_main, ());
+/// assert_eq!(sum, 3);
+/// ```
+pub struct Context<T> {
+    value: UnsafeCell<T>,
+    borrowed_in_loop: Cell<Option<u32>>, // the depth of runtime loop that holds the borrow
+}
+
+/// Error returned by [Context::try_borrow_mut()].
+#[derive(Debug, PartialEq)]
+pub enum ContextError {
+    /// The context is borrowed by another future in the same runtime loop, e.g. by concurrent
+    /// branch of join!() that holds the borrow across await point.
+    Borrowed,
+    /// The context is borrowed by a future suspended in the outer runtime loop, e.g. the
+    /// future that holds the borrow has invoked the [Runtime::nested_loop()] that is running
+    /// now.
+    BorrowedByOuterLoop,
+}
+
+impl<T> Context<T> {
+    /// Creates the context with initial value.
+    pub fn new(value: T) -> Self {
+        Context {
+            value: UnsafeCell::new(value),
+            borrowed_in_loop: Cell::new(None),
+        }
+    }
+
+    /// Borrows the context mutably until the returned guard is dropped.
+    pub fn try_borrow_mut<ReactorT: Reactor>(
+        &self,
+        rt: &Runtime<ReactorT>,
+    ) -> Result<ContextRefMut<'_, T>, ContextError> {
+        let loop_depth = rt.nesting_depth();
+        match self.borrowed_in_loop.get() {
+            None => {
+                self.borrowed_in_loop.set(Some(loop_depth));
+                Ok(ContextRefMut { context: self })
+            }
+            Some(depth) if depth < loop_depth => Err(ContextError::BorrowedByOuterLoop),
+            Some(_) => Err(ContextError::Borrowed),
+        }
+    }
+
+    /// Same as [Context::try_borrow_mut()], but panics if the context is already borrowed.
+    pub fn borrow_mut<ReactorT: Reactor>(&self, rt: &Runtime<ReactorT>) -> ContextRefMut<'_, T> {
+        match self.try_borrow_mut(rt) {
+            Ok(guard) => guard,
+            Err(ContextError::Borrowed) => {
+                panic!("aiur: Context is already borrowed by another future")
+            }
+            Err(ContextError::BorrowedByOuterLoop) => panic!(concat!(
+                "aiur: Context is already borrowed by a future in the outer loop, ",
+                "the borrow is held across the nested_loop()"
+            )),
+        }
+    }
+
+    /// Returns true if the context is borrowed right now.
+    pub fn is_borrowed(&self) -> bool {
+        self.borrowed_in_loop.get().is_some()
+    }
+
+    /// Consumes the context and returns the value.
+    pub fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+}
+
+/// The mutable borrow of [Context], it is released when the guard is dropped.
+pub struct ContextRefMut<'context, T> {
+    context: &'context Context<T>,
+}
+
+impl<'context, T> Deref for ContextRefMut<'context, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // Safe: the guard is the only one that exists for the context
+        unsafe { &*self.context.value.get() }
+    }
+}
+
+impl<'context, T> DerefMut for ContextRefMut<'context, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        // Safe: the guard is the only one that exists for the context
+        unsafe { &mut *self.context.value.get() }
+    }
+}
+
+impl<'context, T> Drop for ContextRefMut<'context, T> {
+    fn drop(&mut self) {
+        self.context.borrowed_in_loop.set(None);
+    }
+}
@@ -2,8 +2,8 @@
 //  / * \    aiur: the home planet for the famous executors
 // |' | '|   (c) 2020 - present, Vladimir Zvezda
 //   / \
-use std::marker::PhantomPinned;
-use std::task::Context;
+use core::marker::PhantomPinned;
+use core::task::Context;
 
 // Perhaps EventNode and these imports below should be elsewhere
 use crate::task::waker_as_task_ptr;
@@ -37,8 +37,8 @@ impl EventNode {
     /// Construction
     pub fn new() -> Self {
         EventNode {
-            next: std::ptr::null_mut(),
-            prev: std::ptr::null_mut(),
+            next: core::ptr::null_mut(),
+            prev: core::ptr::null_mut(),
             task_ptr: None,
             _pin: PhantomPinned,
         }
@@ -79,7 +79,7 @@ impl EventNode {
     fn is_self_in_list(&self) -> bool {
         // If event is in list there must be a non-null prev, which is either a prev element
         // in list or head node of the list.
-        self.prev != std::ptr::null_mut()
+        self.prev != core::ptr::null_mut()
     }
 
     // Removes EventNode from the list by linking the next/prev pointer to each other. This
@@ -91,11 +91,24 @@ impl EventNode {
         (*(self.prev)).next = self.next;
 
         // next can be null
-        if self.next != std::ptr::null_mut() {
+        if self.next != core::ptr::null_mut() {
             (*(self.next)).prev = self.prev;
         }
-        self.next = std::ptr::null_mut();
-        self.prev = std::ptr::null_mut();
+        self.next = core::ptr::null_mut();
+        self.prev = core::ptr::null_mut();
+    }
+
+    // Number of events in the list after this node, the bookmarks are not counted.
+    pub(crate) unsafe fn list_len(&self) -> usize {
+        let mut len = 0;
+        let mut cur = self.next;
+        while !cur.is_null() {
+            if (*cur).task_ptr.is_some() {
+                len += 1;
+            }
+            cur = (*cur).next;
+        }
+        len
     }
 
     pub(crate) fn get_itask_ptr(&self) -> *const dyn ITask {
@@ -104,7 +117,7 @@ impl EventNode {
 
     pub(crate) unsafe fn push_back(&mut self, event_id: EventId) {
         let mut cur = self;
-        while cur.next != std::ptr::null_mut() {
+        while cur.next != core::ptr::null_mut() {
             cur = &mut *(cur.next);
         }
 
@@ -115,19 +128,56 @@ impl EventNode {
         (*cur).next = node;
     }
 
-    // This supposed to be called on head event_node only
-    pub(crate) unsafe fn find_unfrozen(&mut self) -> Option<EventId> {
-        let mut cur = self;
-        while cur.next != std::ptr::null_mut() {
-            cur = &mut *(cur.next);
-            // Verify if event task is no longer frozen
-            if !(*(cur.task_ptr.unwrap())).is_frozen() {
-                cur.remove_self_from_list();
-                return Some(cur.get_event_id());
+    // Inserts the bookmark node right after this node. Bookmark is the EventNode without task
+    // that marks the position in the list of frozen events where the scan should continue.
+    pub(crate) unsafe fn insert_bookmark(&mut self, bookmark: *mut EventNode) {
+        debug_assert!((*bookmark).task_ptr.is_none());
+
+        (*bookmark).prev = self;
+        (*bookmark).next = self.next;
+        if self.next != core::ptr::null_mut() {
+            (*(self.next)).prev = bookmark;
+        }
+        self.next = bookmark;
+    }
+
+    // Removes the bookmark from the list, see insert_bookmark().
+    pub(crate) unsafe fn remove_bookmark(&mut self) {
+        debug_assert!(self.task_ptr.is_none());
+        self.remove_self_from_list();
+    }
+
+    // This supposed to be called on bookmark node only, see insert_bookmark(). Scans the list
+    // after the bookmark for the event of a task that is not frozen, removes this event from
+    // the list and moves the bookmark to its place, so the next scan continues from there.
+    //
+    // Returns the event found and the number of nodes visited.
+    pub(crate) unsafe fn find_unfrozen(&mut self) -> (Option<EventId>, usize) {
+        let mut visited = 0;
+        let mut cur = self.next;
+        while cur != core::ptr::null_mut() {
+            visited += 1;
+
+            // Skip the bookmarks and verify if event task is no longer frozen
+            let unfrozen = match (*cur).task_ptr {
+                Some(task_ptr) => !(*task_ptr).is_frozen(),
+                None => false,
+            };
+
+            if unfrozen {
+                let prev = (*cur).prev;
+                (*cur).remove_self_from_list();
+                if !core::ptr::eq(prev, self) {
+                    self.remove_self_from_list();
+                    (*prev).insert_bookmark(self);
+                }
+                return (Some((*cur).get_event_id()), visited);
             }
+
+            cur = (*cur).next;
         }
 
-        None
+        (None, visited)
     }
 }
 
@@ -140,3 +190,11 @@ impl Drop for EventNode {
         );
     }
 }
+
+// Panics for the leaf future that has been polled after it returned Poll::Ready. The leaf
+// futures of this crate use it for such misuse, so it is reported with the same message, e.g.
+// "aiur/oneshot_recver_future: oneshot:3.0 was polled after completion."
+#[track_caller]
+pub(crate) fn panic_polled_after_completion(future: &str, id: &dyn core::fmt::Debug) -> ! {
+    panic!("aiur/{}: {:?} was polled after completion.", future, id)
+}
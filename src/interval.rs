@@ -0,0 +1,78 @@
+//  \ O /
+//  / * \    aiur: the homeplanet for the famous executors
+// |' | '|   (c) 2020 - present, Vladimir Zvezda
+//   / \
+use core::time::Duration;
+
+use crate::runtime::Runtime;
+use crate::timer::{now_ms, sleep_until};
+use crate::TemporalReactor;
+
+/// Creates the [Interval] that ticks every `period`, the first tick is one period from now.
+///
+/// Panics if the period is zero or it exceeds the maximum timer duration
+/// (MAX_TIMER_DURATION_MS).
+pub fn interval<ReactorT: TemporalReactor>(
+    rt: &Runtime<ReactorT>,
+    period: Duration,
+) -> Interval<'_, ReactorT> {
+    let period_ms = period.as_millis();
+    assert!(
+        period_ms > 0 && period_ms <= ReactorT::MAX_TIMER_DURATION_MS as u128,
+        "aiur: interval() period {:?} is out of range",
+        period
+    );
+
+    let period_ms = period_ms as u32;
+    Interval {
+        rt,
+        period_ms,
+        next_deadline: now_ms(rt) + period_ms,
+    }
+}
+
+/// Periodic timer created by [interval()].
+///
+/// The deadline of the next tick is advanced by the period from the deadline of the previous
+/// one, not from the time the tick was awaited, so the ticks do not drift. When the caller is
+/// late by more than one period the next tick fires right away and the missed ticks are
+/// skipped rather than fired in a burst.
+///
+/// ```
+/// use aiur::toy_rt;
+/// use std::time::Duration;
+///
+/// async fn async_main(rt: &toy_rt::Runtime, _: ()) {
+///     let mut interval = toy_rt::interval(rt, Duration::from_millis(100));
+///     for _ in 0..3 {
+///         interval.tick().await;
+///     }
+///     assert_eq!(rt.elapsed(), Duration::from_millis(300));
+/// }
+///
+/// toy_rt::with_runtime_in_mode(toy_rt::SleepMode::Emulated, async_main, ());
+/// ```
+pub struct Interval<'runtime, ReactorT: TemporalReactor> {
+    rt: &'runtime Runtime<ReactorT>,
+    period_ms: u32,
+    next_deadline: u32,
+}
+
+impl<'runtime, ReactorT: TemporalReactor> Interval<'runtime, ReactorT> {
+    /// Returns the period of the ticks.
+    pub fn period(&self) -> Duration {
+        Duration::from_millis(self.period_ms as u64)
+    }
+
+    /// Waits for the next tick.
+    pub async fn tick(&mut self) {
+        sleep_until(self.rt, self.next_deadline).await;
+
+        self.next_deadline += self.period_ms;
+        let now = now_ms(self.rt);
+        if self.next_deadline <= now {
+            // late by more than one period: skip the missed ticks
+            self.next_deadline = now + self.period_ms;
+        }
+    }
+}
@@ -2,8 +2,10 @@
 //  / * \    aiur: the homeplanet for the famous executors
 // |' | '|   (c) 2020 - present, Vladimir Zvezda
 //   / \
+use alloc::vec::Vec;
+use crate::any_of_vec::{any_of_vec, any_of_vec_fused};
 use crate::pinned_any_of;
-use std::future::Future;
+use core::future::Future;
 
 /// Waits concurrently until all futures are completed.
 ///
@@ -14,6 +16,9 @@ use std::future::Future;
 ///
 /// Please note that unlike join implementation in other crates this one returns future and
 /// requires `.await` to start execution.
+///
+/// The future that is already pinned can be given with the `pinned:` marker like in
+/// [pinned_any_of!](crate::pinned_any_of), e.g. `join!(pinned: fut1, fut2)`.
 #[macro_export]
 macro_rules! join {
     ($f1:expr, $f2:expr $(,)?) => {
@@ -37,6 +42,10 @@ macro_rules! join {
     ($f1:expr, $f2:expr, $f3:expr, $f4:expr, $f5:expr, $f6:expr, $f7:expr, $f8:expr $(,)?) => {
         $crate::join8($f1, $f2, $f3, $f4, $f5, $f6, $f7, $f8)
     };
+    // Some of the futures are marked with "pinned:"
+    ($($args:tt)+) => {
+        $crate::__pinned_args!(join!() {} [] $($args)+)
+    };
 }
 
 /// Polls two futures concurrently until both are completed.
@@ -46,17 +55,18 @@ where
     FutT2: Future,
 {
     pinned_any_of!(stream, f1, f2);
-    let mut res = std::mem::MaybeUninit::<(FutT1::Output, FutT2::Output)>::uninit();
-    unsafe {
-        let ptr = res.as_mut_ptr();
-        while let Some(val) = stream.next().await {
-            match val {
-                crate::OneOf2::First(x) => (*ptr).0 = x,
-                crate::OneOf2::Second(x) => (*ptr).1 = x,
-            }
+    let mut res1 = None;
+    let mut res2 = None;
+
+    // The outputs in the options are dropped if the join is dropped or a future panics before
+    // all the futures are completed
+    while let Some(val) = stream.next().await {
+        match val {
+            crate::OneOf2::First(x) => res1 = Some(x),
+            crate::OneOf2::Second(x) => res2 = Some(x),
         }
     }
-    unsafe { res.assume_init() }
+    (res1.unwrap(), res2.unwrap())
 }
 
 /// Polls three futures concurrently until all are completed.
@@ -70,21 +80,21 @@ where
     FutT2: Future,
     FutT3: Future,
 {
-    let mut res = std::mem::MaybeUninit::<(FutT1::Output, FutT2::Output, FutT3::Output)>::uninit();
-
     pinned_any_of!(stream, f1, f2, f3);
+    let mut res1 = None;
+    let mut res2 = None;
+    let mut res3 = None;
 
-    unsafe {
-        let ptr = res.as_mut_ptr();
-        while let Some(val) = stream.next().await {
-            match val {
-                crate::OneOf3::First(x) => (*ptr).0 = x,
-                crate::OneOf3::Second(x) => (*ptr).1 = x,
-                crate::OneOf3::Third(x) => (*ptr).2 = x,
-            }
+    // The outputs in the options are dropped if the join is dropped or a future panics before
+    // all the futures are completed
+    while let Some(val) = stream.next().await {
+        match val {
+            crate::OneOf3::First(x) => res1 = Some(x),
+            crate::OneOf3::Second(x) => res2 = Some(x),
+            crate::OneOf3::Third(x) => res3 = Some(x),
         }
     }
-    unsafe { res.assume_init() }
+    (res1.unwrap(), res2.unwrap(), res3.unwrap())
 }
 
 /// Polls four futures concurrently until all are completed.
@@ -100,27 +110,23 @@ where
     FutT3: Future,
     FutT4: Future,
 {
-    let mut res = std::mem::MaybeUninit::<(
-        FutT1::Output,
-        FutT2::Output,
-        FutT3::Output,
-        FutT4::Output,
-    )>::uninit();
-
     pinned_any_of!(stream, f1, f2, f3, f4);
+    let mut res1 = None;
+    let mut res2 = None;
+    let mut res3 = None;
+    let mut res4 = None;
 
-    unsafe {
-        let ptr = res.as_mut_ptr();
-        while let Some(val) = stream.next().await {
-            match val {
-                crate::OneOf4::First(x) => (*ptr).0 = x,
-                crate::OneOf4::Second(x) => (*ptr).1 = x,
-                crate::OneOf4::Third(x) => (*ptr).2 = x,
-                crate::OneOf4::Fourth(x) => (*ptr).3 = x,
-            }
+    // The outputs in the options are dropped if the join is dropped or a future panics before
+    // all the futures are completed
+    while let Some(val) = stream.next().await {
+        match val {
+            crate::OneOf4::First(x) => res1 = Some(x),
+            crate::OneOf4::Second(x) => res2 = Some(x),
+            crate::OneOf4::Third(x) => res3 = Some(x),
+            crate::OneOf4::Fourth(x) => res4 = Some(x),
         }
     }
-    unsafe { res.assume_init() }
+    (res1.unwrap(), res2.unwrap(), res3.unwrap(), res4.unwrap())
 }
 
 /// Polls five futures concurrently until all are completed.
@@ -144,29 +150,25 @@ where
     FutT4: Future,
     FutT5: Future,
 {
-    let mut res = std::mem::MaybeUninit::<(
-        FutT1::Output,
-        FutT2::Output,
-        FutT3::Output,
-        FutT4::Output,
-        FutT5::Output,
-    )>::uninit();
-
     pinned_any_of!(stream, f1, f2, f3, f4, f5);
+    let mut res1 = None;
+    let mut res2 = None;
+    let mut res3 = None;
+    let mut res4 = None;
+    let mut res5 = None;
 
-    unsafe {
-        let ptr = res.as_mut_ptr();
-        while let Some(val) = stream.next().await {
-            match val {
-                crate::OneOf5::First(x) => (*ptr).0 = x,
-                crate::OneOf5::Second(x) => (*ptr).1 = x,
-                crate::OneOf5::Third(x) => (*ptr).2 = x,
-                crate::OneOf5::Fourth(x) => (*ptr).3 = x,
-                crate::OneOf5::Fifth(x) => (*ptr).4 = x,
-            }
+    // The outputs in the options are dropped if the join is dropped or a future panics before
+    // all the futures are completed
+    while let Some(val) = stream.next().await {
+        match val {
+            crate::OneOf5::First(x) => res1 = Some(x),
+            crate::OneOf5::Second(x) => res2 = Some(x),
+            crate::OneOf5::Third(x) => res3 = Some(x),
+            crate::OneOf5::Fourth(x) => res4 = Some(x),
+            crate::OneOf5::Fifth(x) => res5 = Some(x),
         }
     }
-    unsafe { res.assume_init() }
+    (res1.unwrap(), res2.unwrap(), res3.unwrap(), res4.unwrap(), res5.unwrap())
 }
 
 /// Polls six futures concurrently until all are completed.
@@ -193,31 +195,27 @@ where
     FutT5: Future,
     FutT6: Future,
 {
-    let mut res = std::mem::MaybeUninit::<(
-        FutT1::Output,
-        FutT2::Output,
-        FutT3::Output,
-        FutT4::Output,
-        FutT5::Output,
-        FutT6::Output,
-    )>::uninit();
-
     pinned_any_of!(stream, f1, f2, f3, f4, f5, f6);
+    let mut res1 = None;
+    let mut res2 = None;
+    let mut res3 = None;
+    let mut res4 = None;
+    let mut res5 = None;
+    let mut res6 = None;
 
-    unsafe {
-        let ptr = res.as_mut_ptr();
-        while let Some(val) = stream.next().await {
-            match val {
-                crate::OneOf6::First(x) => (*ptr).0 = x,
-                crate::OneOf6::Second(x) => (*ptr).1 = x,
-                crate::OneOf6::Third(x) => (*ptr).2 = x,
-                crate::OneOf6::Fourth(x) => (*ptr).3 = x,
-                crate::OneOf6::Fifth(x) => (*ptr).4 = x,
-                crate::OneOf6::Sixth(x) => (*ptr).5 = x,
-            }
+    // The outputs in the options are dropped if the join is dropped or a future panics before
+    // all the futures are completed
+    while let Some(val) = stream.next().await {
+        match val {
+            crate::OneOf6::First(x) => res1 = Some(x),
+            crate::OneOf6::Second(x) => res2 = Some(x),
+            crate::OneOf6::Third(x) => res3 = Some(x),
+            crate::OneOf6::Fourth(x) => res4 = Some(x),
+            crate::OneOf6::Fifth(x) => res5 = Some(x),
+            crate::OneOf6::Sixth(x) => res6 = Some(x),
         }
     }
-    unsafe { res.assume_init() }
+    (res1.unwrap(), res2.unwrap(), res3.unwrap(), res4.unwrap(), res5.unwrap(), res6.unwrap())
 }
 
 /// Polls seven futures concurrently until all are completed.
@@ -247,33 +245,37 @@ where
     FutT6: Future,
     FutT7: Future,
 {
-    let mut res = std::mem::MaybeUninit::<(
-        FutT1::Output,
-        FutT2::Output,
-        FutT3::Output,
-        FutT4::Output,
-        FutT5::Output,
-        FutT6::Output,
-        FutT7::Output,
-    )>::uninit();
-
     pinned_any_of!(stream, f1, f2, f3, f4, f5, f6, f7);
+    let mut res1 = None;
+    let mut res2 = None;
+    let mut res3 = None;
+    let mut res4 = None;
+    let mut res5 = None;
+    let mut res6 = None;
+    let mut res7 = None;
 
-    unsafe {
-        let ptr = res.as_mut_ptr();
-        while let Some(val) = stream.next().await {
-            match val {
-                crate::OneOf7::First(x) => (*ptr).0 = x,
-                crate::OneOf7::Second(x) => (*ptr).1 = x,
-                crate::OneOf7::Third(x) => (*ptr).2 = x,
-                crate::OneOf7::Fourth(x) => (*ptr).3 = x,
-                crate::OneOf7::Fifth(x) => (*ptr).4 = x,
-                crate::OneOf7::Sixth(x) => (*ptr).5 = x,
-                crate::OneOf7::Seventh(x) => (*ptr).6 = x,
-            }
+    // The outputs in the options are dropped if the join is dropped or a future panics before
+    // all the futures are completed
+    while let Some(val) = stream.next().await {
+        match val {
+            crate::OneOf7::First(x) => res1 = Some(x),
+            crate::OneOf7::Second(x) => res2 = Some(x),
+            crate::OneOf7::Third(x) => res3 = Some(x),
+            crate::OneOf7::Fourth(x) => res4 = Some(x),
+            crate::OneOf7::Fifth(x) => res5 = Some(x),
+            crate::OneOf7::Sixth(x) => res6 = Some(x),
+            crate::OneOf7::Seventh(x) => res7 = Some(x),
         }
     }
-    unsafe { res.assume_init() }
+    (
+        res1.unwrap(),
+        res2.unwrap(),
+        res3.unwrap(),
+        res4.unwrap(),
+        res5.unwrap(),
+        res6.unwrap(),
+        res7.unwrap(),
+    )
 }
 
 /// Polls eight futures concurrently until all are completed.
@@ -306,33 +308,390 @@ where
     FutT7: Future,
     FutT8: Future,
 {
-    let mut res = std::mem::MaybeUninit::<(
-        FutT1::Output,
-        FutT2::Output,
-        FutT3::Output,
-        FutT4::Output,
-        FutT5::Output,
-        FutT6::Output,
-        FutT7::Output,
-        FutT8::Output,
-    )>::uninit();
+    pinned_any_of!(stream, f1, f2, f3, f4, f5, f6, f7, f8);
+    let mut res1 = None;
+    let mut res2 = None;
+    let mut res3 = None;
+    let mut res4 = None;
+    let mut res5 = None;
+    let mut res6 = None;
+    let mut res7 = None;
+    let mut res8 = None;
+
+    // The outputs in the options are dropped if the join is dropped or a future panics before
+    // all the futures are completed
+    while let Some(val) = stream.next().await {
+        match val {
+            crate::OneOf8::First(x) => res1 = Some(x),
+            crate::OneOf8::Second(x) => res2 = Some(x),
+            crate::OneOf8::Third(x) => res3 = Some(x),
+            crate::OneOf8::Fourth(x) => res4 = Some(x),
+            crate::OneOf8::Fifth(x) => res5 = Some(x),
+            crate::OneOf8::Sixth(x) => res6 = Some(x),
+            crate::OneOf8::Seventh(x) => res7 = Some(x),
+            crate::OneOf8::Eighth(x) => res8 = Some(x),
+        }
+    }
+    (
+        res1.unwrap(),
+        res2.unwrap(),
+        res3.unwrap(),
+        res4.unwrap(),
+        res5.unwrap(),
+        res6.unwrap(),
+        res7.unwrap(),
+        res8.unwrap(),
+    )
+}
+
+/// Polls the futures from Vec concurrently until all are completed. The outputs are returned in
+/// the same order as the futures in Vec.
+pub async fn join_vec<FutT: Future>(futures: Vec<FutT>) -> Vec<FutT::Output> {
+    let mut res: Vec<Option<FutT::Output>> = (0..futures.len()).map(|_| None).collect();
+
+    let mut stream = any_of_vec(futures);
+    while let Some((index, output)) = stream.next().await {
+        res[index] = Some(output);
+    }
+
+    res.into_iter().map(|output| output.unwrap()).collect()
+}
+
+/// Polls the futures from Vec like [join_vec()], but at most `max_concurrent` of them at the
+/// same time: the next future from Vec is started when one of the running futures is
+/// completed. The outputs are returned in the same order as the futures in Vec.
+///
+/// Panics if `max_concurrent` is 0.
+pub async fn join_buffered<FutT: Future>(
+    futures: Vec<FutT>,
+    max_concurrent: usize,
+) -> Vec<FutT::Output> {
+    assert!(max_concurrent > 0, "aiur: join_buffered() requires max_concurrent > 0");
+    let mut res: Vec<Option<FutT::Output>> = (0..futures.len()).map(|_| None).collect();
+
+    // The futures are pushed in the order of Vec, so the stream reports their Vec indexes
+    let mut pending = futures.into_iter();
+    let mut stream = any_of_vec_fused(pending.by_ref().take(max_concurrent).collect());
+    while let Some((index, output)) = stream.next().await {
+        res[index] = Some(output);
+        if let Some(future) = pending.next() {
+            stream.push(future);
+        }
+    }
+
+    res.into_iter().map(|output| output.unwrap()).collect()
+}
+
+/// Polls two fallible futures concurrently until both are completed or one of them fails.
+/// On the first `Err` the other future is dropped and the error is returned, the result of
+/// the future completed before is discarded.
+pub async fn try_join2<FutT1, FutT2, T1, T2, ErrT>(f1: FutT1, f2: FutT2) -> Result<(T1, T2), ErrT>
+where
+    FutT1: Future<Output = Result<T1, ErrT>>,
+    FutT2: Future<Output = Result<T2, ErrT>>,
+{
+    pinned_any_of!(stream, f1, f2);
+    let mut res1 = None;
+    let mut res2 = None;
+
+    // The return on error drops the stream with the futures that are not completed
+    while let Some(val) = stream.next().await {
+        match val {
+            crate::OneOf2::First(x) => res1 = Some(x?),
+            crate::OneOf2::Second(x) => res2 = Some(x?),
+        }
+    }
+    Ok((res1.unwrap(), res2.unwrap()))
+}
+
+/// Polls three fallible futures concurrently until all are completed or one of them fails,
+/// see [try_join2()].
+pub async fn try_join3<FutT1, FutT2, FutT3, T1, T2, T3, ErrT>(
+    f1: FutT1,
+    f2: FutT2,
+    f3: FutT3,
+) -> Result<(T1, T2, T3), ErrT>
+where
+    FutT1: Future<Output = Result<T1, ErrT>>,
+    FutT2: Future<Output = Result<T2, ErrT>>,
+    FutT3: Future<Output = Result<T3, ErrT>>,
+{
+    pinned_any_of!(stream, f1, f2, f3);
+    let mut res1 = None;
+    let mut res2 = None;
+    let mut res3 = None;
+
+    // The return on error drops the stream with the futures that are not completed
+    while let Some(val) = stream.next().await {
+        match val {
+            crate::OneOf3::First(x) => res1 = Some(x?),
+            crate::OneOf3::Second(x) => res2 = Some(x?),
+            crate::OneOf3::Third(x) => res3 = Some(x?),
+        }
+    }
+    Ok((res1.unwrap(), res2.unwrap(), res3.unwrap()))
+}
+
+/// Polls four fallible futures concurrently until all are completed or one of them fails,
+/// see [try_join2()].
+pub async fn try_join4<FutT1, FutT2, FutT3, FutT4, T1, T2, T3, T4, ErrT>(
+    f1: FutT1,
+    f2: FutT2,
+    f3: FutT3,
+    f4: FutT4,
+) -> Result<(T1, T2, T3, T4), ErrT>
+where
+    FutT1: Future<Output = Result<T1, ErrT>>,
+    FutT2: Future<Output = Result<T2, ErrT>>,
+    FutT3: Future<Output = Result<T3, ErrT>>,
+    FutT4: Future<Output = Result<T4, ErrT>>,
+{
+    pinned_any_of!(stream, f1, f2, f3, f4);
+    let mut res1 = None;
+    let mut res2 = None;
+    let mut res3 = None;
+    let mut res4 = None;
+
+    // The return on error drops the stream with the futures that are not completed
+    while let Some(val) = stream.next().await {
+        match val {
+            crate::OneOf4::First(x) => res1 = Some(x?),
+            crate::OneOf4::Second(x) => res2 = Some(x?),
+            crate::OneOf4::Third(x) => res3 = Some(x?),
+            crate::OneOf4::Fourth(x) => res4 = Some(x?),
+        }
+    }
+    Ok((res1.unwrap(), res2.unwrap(), res3.unwrap(), res4.unwrap()))
+}
+
+/// Polls five fallible futures concurrently until all are completed or one of them fails,
+/// see [try_join2()].
+pub async fn try_join5<FutT1, FutT2, FutT3, FutT4, FutT5, T1, T2, T3, T4, T5, ErrT>(
+    f1: FutT1,
+    f2: FutT2,
+    f3: FutT3,
+    f4: FutT4,
+    f5: FutT5,
+) -> Result<(T1, T2, T3, T4, T5), ErrT>
+where
+    FutT1: Future<Output = Result<T1, ErrT>>,
+    FutT2: Future<Output = Result<T2, ErrT>>,
+    FutT3: Future<Output = Result<T3, ErrT>>,
+    FutT4: Future<Output = Result<T4, ErrT>>,
+    FutT5: Future<Output = Result<T5, ErrT>>,
+{
+    pinned_any_of!(stream, f1, f2, f3, f4, f5);
+    let mut res1 = None;
+    let mut res2 = None;
+    let mut res3 = None;
+    let mut res4 = None;
+    let mut res5 = None;
+
+    // The return on error drops the stream with the futures that are not completed
+    while let Some(val) = stream.next().await {
+        match val {
+            crate::OneOf5::First(x) => res1 = Some(x?),
+            crate::OneOf5::Second(x) => res2 = Some(x?),
+            crate::OneOf5::Third(x) => res3 = Some(x?),
+            crate::OneOf5::Fourth(x) => res4 = Some(x?),
+            crate::OneOf5::Fifth(x) => res5 = Some(x?),
+        }
+    }
+    Ok((
+        res1.unwrap(),
+        res2.unwrap(),
+        res3.unwrap(),
+        res4.unwrap(),
+        res5.unwrap(),
+    ))
+}
+
+/// Polls six fallible futures concurrently until all are completed or one of them fails,
+/// see [try_join2()].
+pub async fn try_join6<FutT1, FutT2, FutT3, FutT4, FutT5, FutT6, T1, T2, T3, T4, T5, T6, ErrT>(
+    f1: FutT1,
+    f2: FutT2,
+    f3: FutT3,
+    f4: FutT4,
+    f5: FutT5,
+    f6: FutT6,
+) -> Result<(T1, T2, T3, T4, T5, T6), ErrT>
+where
+    FutT1: Future<Output = Result<T1, ErrT>>,
+    FutT2: Future<Output = Result<T2, ErrT>>,
+    FutT3: Future<Output = Result<T3, ErrT>>,
+    FutT4: Future<Output = Result<T4, ErrT>>,
+    FutT5: Future<Output = Result<T5, ErrT>>,
+    FutT6: Future<Output = Result<T6, ErrT>>,
+{
+    pinned_any_of!(stream, f1, f2, f3, f4, f5, f6);
+    let mut res1 = None;
+    let mut res2 = None;
+    let mut res3 = None;
+    let mut res4 = None;
+    let mut res5 = None;
+    let mut res6 = None;
+
+    // The return on error drops the stream with the futures that are not completed
+    while let Some(val) = stream.next().await {
+        match val {
+            crate::OneOf6::First(x) => res1 = Some(x?),
+            crate::OneOf6::Second(x) => res2 = Some(x?),
+            crate::OneOf6::Third(x) => res3 = Some(x?),
+            crate::OneOf6::Fourth(x) => res4 = Some(x?),
+            crate::OneOf6::Fifth(x) => res5 = Some(x?),
+            crate::OneOf6::Sixth(x) => res6 = Some(x?),
+        }
+    }
+    Ok((
+        res1.unwrap(),
+        res2.unwrap(),
+        res3.unwrap(),
+        res4.unwrap(),
+        res5.unwrap(),
+        res6.unwrap(),
+    ))
+}
+
+/// Polls seven fallible futures concurrently until all are completed or one of them fails,
+/// see [try_join2()].
+pub async fn try_join7<
+    FutT1,
+    FutT2,
+    FutT3,
+    FutT4,
+    FutT5,
+    FutT6,
+    FutT7,
+    T1,
+    T2,
+    T3,
+    T4,
+    T5,
+    T6,
+    T7,
+    ErrT,
+>(
+    f1: FutT1,
+    f2: FutT2,
+    f3: FutT3,
+    f4: FutT4,
+    f5: FutT5,
+    f6: FutT6,
+    f7: FutT7,
+) -> Result<(T1, T2, T3, T4, T5, T6, T7), ErrT>
+where
+    FutT1: Future<Output = Result<T1, ErrT>>,
+    FutT2: Future<Output = Result<T2, ErrT>>,
+    FutT3: Future<Output = Result<T3, ErrT>>,
+    FutT4: Future<Output = Result<T4, ErrT>>,
+    FutT5: Future<Output = Result<T5, ErrT>>,
+    FutT6: Future<Output = Result<T6, ErrT>>,
+    FutT7: Future<Output = Result<T7, ErrT>>,
+{
+    pinned_any_of!(stream, f1, f2, f3, f4, f5, f6, f7);
+    let mut res1 = None;
+    let mut res2 = None;
+    let mut res3 = None;
+    let mut res4 = None;
+    let mut res5 = None;
+    let mut res6 = None;
+    let mut res7 = None;
 
+    // The return on error drops the stream with the futures that are not completed
+    while let Some(val) = stream.next().await {
+        match val {
+            crate::OneOf7::First(x) => res1 = Some(x?),
+            crate::OneOf7::Second(x) => res2 = Some(x?),
+            crate::OneOf7::Third(x) => res3 = Some(x?),
+            crate::OneOf7::Fourth(x) => res4 = Some(x?),
+            crate::OneOf7::Fifth(x) => res5 = Some(x?),
+            crate::OneOf7::Sixth(x) => res6 = Some(x?),
+            crate::OneOf7::Seventh(x) => res7 = Some(x?),
+        }
+    }
+    Ok((
+        res1.unwrap(),
+        res2.unwrap(),
+        res3.unwrap(),
+        res4.unwrap(),
+        res5.unwrap(),
+        res6.unwrap(),
+        res7.unwrap(),
+    ))
+}
+
+/// Polls eight fallible futures concurrently until all are completed or one of them fails,
+/// see [try_join2()].
+pub async fn try_join8<
+    FutT1,
+    FutT2,
+    FutT3,
+    FutT4,
+    FutT5,
+    FutT6,
+    FutT7,
+    FutT8,
+    T1,
+    T2,
+    T3,
+    T4,
+    T5,
+    T6,
+    T7,
+    T8,
+    ErrT,
+>(
+    f1: FutT1,
+    f2: FutT2,
+    f3: FutT3,
+    f4: FutT4,
+    f5: FutT5,
+    f6: FutT6,
+    f7: FutT7,
+    f8: FutT8,
+) -> Result<(T1, T2, T3, T4, T5, T6, T7, T8), ErrT>
+where
+    FutT1: Future<Output = Result<T1, ErrT>>,
+    FutT2: Future<Output = Result<T2, ErrT>>,
+    FutT3: Future<Output = Result<T3, ErrT>>,
+    FutT4: Future<Output = Result<T4, ErrT>>,
+    FutT5: Future<Output = Result<T5, ErrT>>,
+    FutT6: Future<Output = Result<T6, ErrT>>,
+    FutT7: Future<Output = Result<T7, ErrT>>,
+    FutT8: Future<Output = Result<T8, ErrT>>,
+{
     pinned_any_of!(stream, f1, f2, f3, f4, f5, f6, f7, f8);
+    let mut res1 = None;
+    let mut res2 = None;
+    let mut res3 = None;
+    let mut res4 = None;
+    let mut res5 = None;
+    let mut res6 = None;
+    let mut res7 = None;
+    let mut res8 = None;
 
-    unsafe {
-        let ptr = res.as_mut_ptr();
-        while let Some(val) = stream.next().await {
-            match val {
-                crate::OneOf8::First(x) => (*ptr).0 = x,
-                crate::OneOf8::Second(x) => (*ptr).1 = x,
-                crate::OneOf8::Third(x) => (*ptr).2 = x,
-                crate::OneOf8::Fourth(x) => (*ptr).3 = x,
-                crate::OneOf8::Fifth(x) => (*ptr).4 = x,
-                crate::OneOf8::Sixth(x) => (*ptr).5 = x,
-                crate::OneOf8::Seventh(x) => (*ptr).6 = x,
-                crate::OneOf8::Eighth(x) => (*ptr).7 = x,
-            }
+    // The return on error drops the stream with the futures that are not completed
+    while let Some(val) = stream.next().await {
+        match val {
+            crate::OneOf8::First(x) => res1 = Some(x?),
+            crate::OneOf8::Second(x) => res2 = Some(x?),
+            crate::OneOf8::Third(x) => res3 = Some(x?),
+            crate::OneOf8::Fourth(x) => res4 = Some(x?),
+            crate::OneOf8::Fifth(x) => res5 = Some(x?),
+            crate::OneOf8::Sixth(x) => res6 = Some(x?),
+            crate::OneOf8::Seventh(x) => res7 = Some(x?),
+            crate::OneOf8::Eighth(x) => res8 = Some(x?),
         }
     }
-    unsafe { res.assume_init() }
+    Ok((
+        res1.unwrap(),
+        res2.unwrap(),
+        res3.unwrap(),
+        res4.unwrap(),
+        res5.unwrap(),
+        res6.unwrap(),
+        res7.unwrap(),
+        res8.unwrap(),
+    ))
 }
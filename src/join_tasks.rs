@@ -2,20 +2,28 @@
 //  / * \    aiur: the homeplanet for the famous executors
 // |' | '|   (c) 2020 - present, Vladimir Zvezda
 //   / \
+use alloc::vec::Vec;
 use crate::task::{ITask, Task};
 
-use std::future::Future;
-use std::pin::Pin;
-use std::task::{Context, Poll};
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
 
 /// Waits concurrently until all futures are completed as tasks.
 ///
 /// Internally it just select the correct `join_tasksN()` function based on the number of arguments
-/// supplied. For example the `join_tasks!(rt, fut1, fut2, fut3).await` is the same as
-/// [`join_tasks3`]`(rt, fut1, fut2, fut3).await`.
+/// supplied. For example the `join_tasks!(fut1, fut2, fut3).await` is the same as
+/// [`join_tasks3`]`(fut1, fut2, fut3).await`.
+///
+/// Like [join!](crate::join) it does not require the runtime reference: the tasks find their
+/// parent task from the `Context` of the first poll, so it can be used as is by other
+/// combinators.
 ///
 /// Please note that unlike join implementation in other crates this one returns future and
 /// requires `.await` to start execution.
+///
+/// If a future panics, its task is completed with the panic and the other tasks keep running.
+/// The panic is resumed by `.await` when all the tasks are completed.
 #[macro_export]
 macro_rules! join_tasks {
     ($f1:expr, $f2:expr $(,)?) => {
@@ -456,6 +464,38 @@ where
     }
 }
 
+// Tasks for join_tasks_vec(). The vector is not resized after creation, so the tasks keep their
+// addresses while TaskJoin is polled.
+impl<FutT: Future> TaskStorage for Vec<Task<FutT>> {
+    type Output = Vec<FutT::Output>;
+
+    fn assign_parent(&self, ctx: &mut Context<'_>) {
+        if let Some((first, rest)) = self.split_first() {
+            if first.assign_parent(ctx) {
+                for task in rest {
+                    task.assign_parent(ctx);
+                }
+            } else {
+                // all parents already assigned.
+            }
+        }
+    }
+
+    fn poll(&self) {
+        for task in self {
+            task.poll();
+        }
+    }
+
+    fn is_completed(&self) -> bool {
+        self.iter().all(|task| task.is_completed())
+    }
+
+    fn take_result(&self) -> Self::Output {
+        self.iter().map(|task| task.take_result()).collect()
+    }
+}
+
 // Leaf future impl for use in join_tasksN() functions.
 struct TaskJoin<TaskStorageT: TaskStorage> {
     storage: TaskStorageT,
@@ -493,7 +533,7 @@ where
     FutT2: Future,
 {
     TaskJoin {
-        storage: (Task::new(f1), Task::new(f2)),
+        storage: (Task::new_catching(f1), Task::new_catching(f2)),
     }
     .await
 }
@@ -511,9 +551,9 @@ where
 {
     TaskJoin {
         storage: (
-            Task::new(f1),
-            Task::new(f2),
-            Task::new(f3),
+            Task::new_catching(f1),
+            Task::new_catching(f2),
+            Task::new_catching(f3),
         ),
     }
     .await
@@ -534,10 +574,10 @@ where
 {
     TaskJoin {
         storage: (
-            Task::new(f1),
-            Task::new(f2),
-            Task::new(f3),
-            Task::new(f4),
+            Task::new_catching(f1),
+            Task::new_catching(f2),
+            Task::new_catching(f3),
+            Task::new_catching(f4),
         ),
     }
     .await
@@ -566,11 +606,11 @@ where
 {
     TaskJoin {
         storage: (
-            Task::new(f1),
-            Task::new(f2),
-            Task::new(f3),
-            Task::new(f4),
-            Task::new(f5),
+            Task::new_catching(f1),
+            Task::new_catching(f2),
+            Task::new_catching(f3),
+            Task::new_catching(f4),
+            Task::new_catching(f5),
         ),
     }
     .await
@@ -602,12 +642,12 @@ where
 {
     TaskJoin {
         storage: (
-            Task::new(f1),
-            Task::new(f2),
-            Task::new(f3),
-            Task::new(f4),
-            Task::new(f5),
-            Task::new(f6),
+            Task::new_catching(f1),
+            Task::new_catching(f2),
+            Task::new_catching(f3),
+            Task::new_catching(f4),
+            Task::new_catching(f5),
+            Task::new_catching(f6),
         ),
     }
     .await
@@ -642,13 +682,13 @@ where
 {
     TaskJoin {
         storage: (
-            Task::new(f1),
-            Task::new(f2),
-            Task::new(f3),
-            Task::new(f4),
-            Task::new(f5),
-            Task::new(f6),
-            Task::new(f7),
+            Task::new_catching(f1),
+            Task::new_catching(f2),
+            Task::new_catching(f3),
+            Task::new_catching(f4),
+            Task::new_catching(f5),
+            Task::new_catching(f6),
+            Task::new_catching(f7),
         ),
     }
     .await
@@ -686,15 +726,153 @@ where
 {
     TaskJoin {
         storage: (
-            Task::new(f1),
-            Task::new(f2),
-            Task::new(f3),
-            Task::new(f4),
-            Task::new(f5),
-            Task::new(f6),
-            Task::new(f7),
-            Task::new(f8),
+            Task::new_catching(f1),
+            Task::new_catching(f2),
+            Task::new_catching(f3),
+            Task::new_catching(f4),
+            Task::new_catching(f5),
+            Task::new_catching(f6),
+            Task::new_catching(f7),
+            Task::new_catching(f8),
         ),
     }
     .await
 }
+
+/// Polls the futures from Vec concurrently as tasks until all are completed. The outputs are
+/// returned in the same order as the futures in Vec.
+pub async fn join_tasks_vec<FutT: Future>(futures: Vec<FutT>) -> Vec<FutT::Output> {
+    TaskJoin {
+        storage: futures.into_iter().map(Task::new_catching).collect::<Vec<_>>(),
+    }
+    .await
+}
+
+/// Stream of the tasks that returns the output of each task as soon as the task is completed,
+/// see [join_tasks_select()].
+///
+/// Invoking `[TaskSelect::next()].await` returns the index of the first completed task (as it
+/// was in Vec the stream was created from) together with its output, the other tasks keep
+/// running and the next call returns the output of the first completed of remaining tasks, etc.
+/// It returns `None` when the outputs of all the tasks have been returned.
+///
+/// The tasks make progress only while `next()` is awaited, so the stream is expected to be
+/// awaited by the same task until it is exhausted or dropped. Dropping the stream drops the
+/// remaining tasks.
+pub struct TaskSelect<FutT: Future> {
+    // The Vec is never resized, so the tasks are not moved when the stream is moved.
+    tasks: Vec<Task<FutT>>,
+    taken: Vec<bool>, // true for tasks which output has been returned by next()
+    remaining: usize,
+}
+
+impl<FutT: Future> TaskSelect<FutT> {
+    /// Returns the index of the first completed task and its output or None if outputs of all
+    /// the tasks have been returned.
+    pub async fn next(&mut self) -> Option<(usize, FutT::Output)> {
+        NextTask { select: self }.await
+    }
+
+    /// Returns the number of tasks which output has not been returned yet.
+    pub fn len(&self) -> usize {
+        self.remaining
+    }
+
+    /// Returns true if the outputs of all the tasks have been returned.
+    pub fn is_empty(&self) -> bool {
+        self.remaining == 0
+    }
+}
+
+// Leaf future returned by TaskSelect::next()
+struct NextTask<'select, FutT: Future> {
+    select: &'select mut TaskSelect<FutT>,
+}
+
+impl<FutT: Future> Future for NextTask<'_, FutT> {
+    type Output = Option<(usize, FutT::Output)>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let select = &mut *self.get_mut().select;
+        if select.remaining == 0 {
+            return Poll::Ready(None);
+        }
+
+        // Unlike TaskJoin the taken tasks are not polled: their future has been completed
+        // and there is no result stored in task anymore.
+        for (task, taken) in select.tasks.iter().zip(select.taken.iter()) {
+            if !*taken {
+                task.assign_parent(ctx);
+                task.poll();
+            }
+        }
+
+        // The completion is checked after all polls like in TaskJoin: the poll of the later
+        // task can complete the earlier one because of a nested loop.
+        let completed = select
+            .tasks
+            .iter()
+            .zip(select.taken.iter())
+            .position(|(task, taken)| !*taken && task.is_completed());
+
+        match completed {
+            Some(index) => {
+                select.taken[index] = true;
+                select.remaining -= 1;
+                Poll::Ready(Some((index, select.tasks[index].take_result())))
+            }
+            None => Poll::Pending,
+        }
+    }
+}
+
+/// Polls the futures from Vec concurrently as tasks and returns the stream that yields the
+/// output of each task as soon as it is completed, while the other tasks keep running.
+///
+/// ```
+/// use aiur::toy_rt;
+/// use std::time::Duration;
+///
+/// async fn sleep_and_ret(rt: &toy_rt::Runtime, ms: u64) -> u64 {
+///     toy_rt::sleep(rt, Duration::from_millis(ms)).await;
+///     ms
+/// }
+///
+/// async fn async_main(rt: &toy_rt::Runtime, _: ()) {
+///     let mut select =
+///         toy_rt::join_tasks_select(vec![sleep_and_ret(rt, 200), sleep_and_ret(rt, 100)]);
+///     assert_eq!(select.next().await, Some((1, 100)));
+///     assert_eq!(select.next().await, Some((0, 200)));
+///     assert_eq!(select.next().await, None);
+/// }
+///
+/// toy_rt::with_runtime_in_mode(toy_rt::SleepMode::Emulated, async_main, ());
+/// ```
+pub fn join_tasks_select<FutT: Future>(futures: Vec<FutT>) -> TaskSelect<FutT> {
+    let count = futures.len();
+    TaskSelect {
+        tasks: futures.into_iter().map(Task::new_catching).collect(),
+        taken: alloc::vec![false; count],
+        remaining: count,
+    }
+}
+
+/// Polls the futures from Vec concurrently as tasks until all are completed and invokes the
+/// `callback` with the index of each task (as it was in Vec) and its output as soon as the
+/// task is completed, while the other tasks keep running. E.g. the first result can be shown
+/// before the rest are ready.
+///
+/// The tasks make no progress while the callback runs. If a future panics, the panic is
+/// resumed when its task is reported and the remaining tasks are dropped.
+pub async fn join_tasks_each<FutT, CallbackT>(futures: Vec<FutT>, mut callback: CallbackT)
+where
+    FutT: Future,
+    CallbackT: FnMut(usize, FutT::Output),
+{
+    // NextTask checks the completion after all the tasks are polled, so the task completed by
+    // a nested loop in the poll of another task is not missed.
+    let mut select = join_tasks_select(futures);
+    while let Some((index, output)) = select.next().await {
+        callback(index, output);
+    }
+}
@@ -0,0 +1,106 @@
+//  \ O /
+//  / * \    aiur: the homeplanet for the famous executors
+// |' | '|   (c) 2020 - present, Vladimir Zvezda
+//   / \
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+use crate::any_of::{any_of2, AnyOfN};
+use crate::join::join2;
+
+// Possible states of the lazy future
+enum LazyState<FuncT, FutT> {
+    NotStarted(FuncT),
+    Running(FutT),
+    Done,
+}
+
+/// Future that builds the inner future by invoking the closure on the first poll, see
+/// [lazy()].
+pub struct Lazy<FuncT, FutT> {
+    state: LazyState<FuncT, FutT>,
+}
+
+/// Makes the future that invokes `func` to build the inner future only when it is polled for
+/// the first time. The inner future is dropped as soon as it is completed.
+///
+/// This is for the combinators like [any_of2()](crate::any_of2) or [join2()](crate::join2)
+/// when building all futures up front would borrow the same resource twice: the closure
+/// borrows it only when its future is about to be polled, and the borrow ends with the
+/// completion of the future.
+///
+/// If the lazy future is dropped without being polled the closure is dropped without being
+/// invoked, so nothing it would borrow or start happens.
+pub fn lazy<FuncT, FutT>(func: FuncT) -> Lazy<FuncT, FutT>
+where
+    FuncT: FnOnce() -> FutT,
+    FutT: Future,
+{
+    Lazy {
+        state: LazyState::NotStarted(func),
+    }
+}
+
+impl<FuncT, FutT> Future for Lazy<FuncT, FutT>
+where
+    FuncT: FnOnce() -> FutT,
+    FutT: Future,
+{
+    type Output = FutT::Output;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        // Unsafe usage: the inner future is never moved once it is Running, it is only dropped
+        // in place when it is completed.
+        let state = unsafe { &mut self.get_unchecked_mut().state };
+
+        if let LazyState::NotStarted(_) = state {
+            // The closure can be moved out: nothing has been pinned yet
+            match core::mem::replace(state, LazyState::Done) {
+                LazyState::NotStarted(func) => *state = LazyState::Running(func()),
+                _ => unreachable!(),
+            }
+        }
+
+        let future = match state {
+            LazyState::Running(future) => unsafe { Pin::new_unchecked(future) },
+            _ => panic!("aiur/lazy: was polled after completion."),
+        };
+
+        let poll = future.poll(ctx);
+        if poll.is_ready() {
+            *state = LazyState::Done; // releases what the inner future has borrowed
+        }
+        poll
+    }
+}
+
+/// Creates the [AnyOfN] stream of two futures that are built by the closures on their first
+/// poll, see [lazy()]. For more futures use [make_any_of!](crate::make_any_of) with [lazy()].
+pub fn any_of_with2<FuncT1, FutT1, FuncT2, FutT2>(
+    f1: FuncT1,
+    f2: FuncT2,
+) -> AnyOfN<(Lazy<FuncT1, FutT1>, Lazy<FuncT2, FutT2>)>
+where
+    FuncT1: FnOnce() -> FutT1,
+    FutT1: Future,
+    FuncT2: FnOnce() -> FutT2,
+    FutT2: Future,
+{
+    any_of2(lazy(f1), lazy(f2))
+}
+
+/// Polls two futures concurrently until both are completed like [join2()](crate::join2), the
+/// futures are built by the closures on their first poll, see [lazy()].
+pub async fn join_with2<FuncT1, FutT1, FuncT2, FutT2>(
+    f1: FuncT1,
+    f2: FuncT2,
+) -> (FutT1::Output, FutT2::Output)
+where
+    FuncT1: FnOnce() -> FutT1,
+    FutT1: Future,
+    FuncT2: FnOnce() -> FutT2,
+    FutT2: Future,
+{
+    join2(lazy(f1), lazy(f2)).await
+}
@@ -34,6 +34,10 @@
 //! As aiur does not have anything OS-specific, it can be used on any OS with std. For testing
 //! purposes it has toy reactor that only supports async sleeping.
 //!
+//! Without the default `std` feature the crate is `no_std` and only needs `alloc`: the toy
+//! reactor and printing of the traces are not available then, the panics of the tasks are
+//! not caught and the reactor has to come from the app.
+//!
 //! Major distinction from popular libraries that aiur only provides a single thread executor. It
 //! seems to be a lager topic and can be discussed later.
 //!
@@ -42,44 +46,108 @@
 //!   * async destruction
 //!   * nostd
 //!
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
 
 #[macro_use]
 mod modtrace_macro;
 
 mod any_of;
+mod any_of_vec;
+mod cancel_token;
 mod channel;
 mod channel_rt;
+mod composite_reactor;
+mod context;
 mod event_node;
+mod interval;
 mod join;
 mod join_tasks;
+mod lazy;
+mod node_store;
 mod oneshot;
 mod oneshot_rt;
 mod pin_macro;
+mod pipeline;
+mod race;
+mod race_detached;
 mod reactor;
+mod reactor_future;
+mod root_task;
 mod runtime;
+mod scope;
+mod select;
 mod task;
 mod timer;
 mod tracer;
+mod value_ring;
 mod with_runtime;
 
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(feature = "std")]
 pub mod toy_rt;
 
-pub use any_of::AnyOfN;
-pub use any_of::{any_of2, any_of3, any_of4, any_of5, any_of6, any_of7, any_of8};
-pub use any_of::{OneOf2, OneOf3, OneOf4, OneOf5, OneOf6, OneOf7, OneOf8};
-pub use channel::{channel, Recver, Sender};
+pub use any_of::{next_of, AnyOfN, NextOfN};
+pub use any_of::{
+    any_of10, any_of11, any_of12, any_of2, any_of3, any_of4, any_of5, any_of6, any_of7, any_of8,
+    any_of9,
+};
+pub use any_of::{
+    any_of_pinned2, any_of_pinned3, any_of_pinned4, any_of_pinned5, any_of_pinned6,
+    any_of_pinned7, any_of_pinned8,
+};
+pub use any_of::{
+    OneOf10, OneOf11, OneOf12, OneOf2, OneOf3, OneOf4, OneOf5, OneOf6, OneOf7, OneOf8, OneOf9,
+};
+pub use any_of_vec::{any_of_vec, any_of_vec_fused, AnyOfVec};
+pub use cancel_token::CancelToken;
+pub use channel::{
+    channel, channel_buffered, channel_mpmc, select2_recv, MpmcRecver, RecvError, Recver,
+    SendError, SendPermit, Sender, TryRecvError, TrySendError,
+};
+pub use composite_reactor::CompositeReactor;
+pub use context::{Context, ContextError, ContextRefMut};
 pub use event_node::EventNode;
-pub use join::{join2, join3, join4, join5, join6, join7, join8};
+pub use interval::{interval, Interval};
+pub use join::{join2, join3, join4, join5, join6, join7, join8, join_buffered, join_vec};
+pub use join::{try_join2, try_join3, try_join4, try_join5, try_join6, try_join7, try_join8};
 pub use join_tasks::{
     join_tasks2, join_tasks3, join_tasks4, join_tasks5, join_tasks6, join_tasks7, join_tasks8,
+    join_tasks_each, join_tasks_select, join_tasks_vec, TaskSelect,
+};
+pub use lazy::{any_of_with2, join_with2, lazy, Lazy};
+pub use oneshot::{
+    oneshot, oneshot_broadcast, select_oneshots, BroadcastSender, RecverOnce, SenderOnce,
 };
-pub use oneshot::{oneshot, RecverOnce, SenderOnce};
-pub use reactor::{EventId, Reactor, TemporalReactor};
-pub use runtime::Runtime;
-pub use timer::sleep;
+pub use pipeline::{pipeline2, pipeline3};
+pub use race::{race2, race3, race4, race5, race6, race7, race8};
+pub use race_detached::{
+    race_detached2, race_detached3, race_detached4, race_detached5, race_detached6,
+    race_detached7, race_detached8,
+};
+pub use reactor::{EventId, GetEventId, Reactor, TemporalReactor, TimeoutReactor};
+pub use reactor_future::{ReactorFuture, ReactorOp};
+pub use root_task::RootTask;
+pub use runtime::{
+    RunError, Runtime, RuntimeBuilder, RuntimeConfig, RuntimeHooks, RuntimeStats, SchedulePolicy,
+    DEFAULT_MAX_NESTING_DEPTH,
+};
+pub use scope::{JoinHandle, Scope};
+pub use select::select_or_default;
+pub use task::TaskId;
+pub use timer::{elapsed_since, sleep, sleep_measured, sleep_until, Elapsed};
+#[cfg(feature = "std")]
 pub use toy_rt::ToyReactor;
-pub use tracer::Tracer;
-pub use with_runtime::{with_runtime_base, LifetimeLinkerFn};
+pub use tracer::{TraceSink, Tracer};
+pub use with_runtime::{try_with_runtime_base, with_runtime_base, LifetimeLinkerFn};
+
+// The paths used by export_runtime!() that are not in the prelude of no_std crates
+#[doc(hidden)]
+pub mod __private {
+    pub use alloc::vec::Vec;
+}
 
 /// This is a help macro to create API for your own runtime based on re-exporting aiur runtime
 /// and specialize it with your reactor.
@@ -87,32 +155,78 @@ pub use with_runtime::{with_runtime_base, LifetimeLinkerFn};
 macro_rules! export_runtime {
     ($reactor:ident) => {
         pub type Runtime = $crate::Runtime<$reactor>;
+        pub type RuntimeBuilder = $crate::RuntimeBuilder<$reactor>;
         pub type EventId = $crate::EventId;
-        pub use $crate::sleep;
+        pub use $crate::RuntimeConfig;
+        pub use $crate::RuntimeHooks;
+        pub use $crate::RuntimeStats;
+        pub use $crate::SchedulePolicy;
+        pub use $crate::RunError;
+        pub use $crate::DEFAULT_MAX_NESTING_DEPTH;
+        pub use $crate::select2_recv;
+        pub use $crate::select_oneshots;
+        pub use $crate::{pipeline2, pipeline3};
+        pub use $crate::{race2, race3, race4, race5, race6, race7, race8};
+        pub use $crate::{
+            race_detached2, race_detached3, race_detached4, race_detached5, race_detached6,
+            race_detached7, race_detached8,
+        };
+        pub use $crate::RecvError;
+        pub use $crate::SendError;
+        pub use $crate::{TryRecvError, TrySendError};
+        pub use $crate::{Context, ContextError, ContextRefMut};
+        pub use $crate::select_or_default;
+        pub use $crate::select;
+        pub use $crate::TaskId;
+        pub use $crate::{elapsed_since, sleep, sleep_measured, sleep_until, Elapsed};
+        pub use $crate::interval;
         pub use $crate::EventNode;
+        pub use $crate::GetEventId;
+        pub use $crate::ReactorOp;
 
         // joins
         pub use $crate::join;
         pub use $crate::join_tasks;
-        pub use $crate::{join2, join3, join4, join5, join6, join7, join8};
+        pub use $crate::{
+            join2, join3, join4, join5, join6, join7, join8, join_buffered, join_vec,
+        };
+        pub use $crate::{any_of_with2, join_with2, lazy, Lazy};
+        pub use $crate::{
+            try_join2, try_join3, try_join4, try_join5, try_join6, try_join7, try_join8,
+        };
         pub use $crate::{
             join_tasks2, join_tasks3, join_tasks4, join_tasks5, join_tasks6, join_tasks7,
-            join_tasks8,
+            join_tasks8, join_tasks_each, join_tasks_select, join_tasks_vec, TaskSelect,
         };
 
         // any_of
         pub use $crate::make_any_of;
         pub use $crate::pinned_any_of;
-        pub use $crate::AnyOfN;
+        pub use $crate::{next_of, AnyOfN, NextOfN};
         pub use $crate::{any_of2, any_of3, any_of4, any_of5, any_of6, any_of7, any_of8};
+        pub use $crate::{any_of9, any_of10, any_of11, any_of12};
+        pub use $crate::{any_of_pinned2, any_of_pinned3, any_of_pinned4, any_of_pinned5};
+        pub use $crate::{any_of_pinned6, any_of_pinned7, any_of_pinned8};
         pub use $crate::{OneOf2, OneOf3, OneOf4, OneOf5, OneOf6, OneOf7, OneOf8};
+        pub use $crate::{OneOf9, OneOf10, OneOf11, OneOf12};
+        pub use $crate::{any_of_vec, any_of_vec_fused, AnyOfVec};
 
         pub use $crate::pin_local;
 
         pub type RecverOnce<'runtime, T> = $crate::RecverOnce<'runtime, T, $reactor>;
         pub type SenderOnce<'runtime, T> = $crate::SenderOnce<'runtime, T, $reactor>;
+        pub type BroadcastSender<'runtime, T> = $crate::BroadcastSender<'runtime, T, $reactor>;
         pub type Recver<'runtime, T> = $crate::Recver<'runtime, T, $reactor>;
+        pub type MpmcRecver<'runtime, T> = $crate::MpmcRecver<'runtime, T, $reactor>;
         pub type Sender<'runtime, T> = $crate::Sender<'runtime, T, $reactor>;
+        pub type SendPermit<'sender, 'runtime, T> =
+            $crate::SendPermit<'sender, 'runtime, T, $reactor>;
+        pub type RootTask<'runtime, FutureT> = $crate::RootTask<'runtime, $reactor, FutureT>;
+        pub type Scope<'runtime, 'scope> = $crate::Scope<'runtime, 'scope, $reactor>;
+        pub type JoinHandle<'runtime, T> = $crate::JoinHandle<'runtime, T, $reactor>;
+        pub type CancelToken<'runtime> = $crate::CancelToken<'runtime, $reactor>;
+        pub type Interval<'runtime> = $crate::Interval<'runtime, $reactor>;
+        pub type ReactorFuture<'runtime, OpT> = $crate::ReactorFuture<'runtime, $reactor, OpT>;
 
         pub fn oneshot<'runtime, T>(
             rt: &'runtime Runtime,
@@ -123,6 +237,16 @@ macro_rules! export_runtime {
             $crate::oneshot::<T, $reactor>(rt)
         }
 
+        pub fn oneshot_broadcast<'runtime, T: Clone>(
+            rt: &'runtime Runtime,
+            receivers: usize,
+        ) -> (
+            $crate::BroadcastSender<'runtime, T, $reactor>,
+            $crate::__private::Vec<$crate::RecverOnce<'runtime, T, $reactor>>,
+        ) {
+            $crate::oneshot_broadcast::<T, $reactor>(rt, receivers)
+        }
+
         pub fn channel<'runtime, T>(
             rt: &'runtime Runtime,
         ) -> (
@@ -132,6 +256,39 @@ macro_rules! export_runtime {
             $crate::channel::<T, $reactor>(rt)
         }
 
+        pub fn channel_buffered<'runtime, T>(
+            rt: &'runtime Runtime,
+            capacity: usize,
+        ) -> (
+            $crate::Sender<'runtime, T, $reactor>,
+            $crate::Recver<'runtime, T, $reactor>,
+        ) {
+            $crate::channel_buffered::<T, $reactor>(rt, capacity)
+        }
+
+        pub fn channel_mpmc<'runtime, T>(
+            rt: &'runtime Runtime,
+        ) -> (
+            $crate::Sender<'runtime, T, $reactor>,
+            $crate::MpmcRecver<'runtime, T, $reactor>,
+        ) {
+            $crate::channel_mpmc::<T, $reactor>(rt)
+        }
+
+        pub fn block_on<FutureT: ::core::future::Future>(
+            rt: &Runtime,
+            future: FutureT,
+        ) -> FutureT::Output {
+            rt.block_on(future)
+        }
+
+        pub fn try_block_on<FutureT: ::core::future::Future>(
+            rt: &Runtime,
+            future: FutureT,
+        ) -> Result<FutureT::Output, $crate::RunError> {
+            rt.try_block_on(future)
+        }
+
         pub fn with_runtime<ReactorFn, FuncT, InitT, ResT>(
             reactor_constructor: ReactorFn,
             tracer: $crate::Tracer,
@@ -145,5 +302,19 @@ macro_rules! export_runtime {
         {
             $crate::with_runtime_base(reactor_constructor(), tracer, async_function, init)
         }
+
+        pub fn try_with_runtime<ReactorFn, FuncT, InitT, ResT>(
+            reactor_constructor: ReactorFn,
+            tracer: $crate::Tracer,
+            async_function: FuncT,
+            init: InitT,
+        ) -> Result<ResT, $crate::RunError>
+        where
+            // async fn foo(rt: &Runtime, param: ParamT) -> ResT
+            FuncT: for<'runtime> $crate::LifetimeLinkerFn<'runtime, $reactor, InitT, ResT>,
+            ReactorFn: FnOnce() -> $reactor,
+        {
+            $crate::try_with_runtime_base(reactor_constructor(), tracer, async_function, init)
+        }
     };
 }
@@ -0,0 +1,278 @@
+//  \ O /
+//  / * \    aiur: the homeplanet for the famous executors
+// |' | '|   (c) 2020 - present, Vladimir Zvezda
+//   / \
+//
+// Storage for the channel and oneshot nodes in runtime. The node is found by the key without
+// scanning. By default nodes are kept in SlabStore, but the storage is abstracted by NodeStore
+// trait, so the allocation free FixedStore can be used for small workloads with known bound
+// of simultaneously existing channels. This is a step for nostd research.
+use alloc::vec::Vec;
+
+// Panics for the id of the node that has been removed from the store. Such id would find
+// nothing or, worse, the node that reused its place, so it is reported instead.
+#[track_caller]
+pub(crate) fn panic_stale_id(id: &dyn core::fmt::Debug) -> ! {
+    panic!("aiur: {:?} is stale, its node has been removed.", id)
+}
+
+// The key of the node in the store: the slot index and the generation of the slot when the
+// node was inserted.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub(crate) struct SlabKey {
+    slot: u32,
+    generation: u32,
+}
+
+impl SlabKey {
+    pub(crate) fn slot(&self) -> u32 {
+        self.slot
+    }
+
+    pub(crate) fn generation(&self) -> u32 {
+        self.generation
+    }
+}
+
+struct Slot<NodeT> {
+    generation: u32,
+    node: Option<NodeT>,
+}
+
+impl<NodeT> Slot<NodeT> {
+    const fn vacant() -> Self {
+        Slot {
+            generation: 0,
+            node: None,
+        }
+    }
+
+    fn get(&self, key: SlabKey) -> Option<&NodeT> {
+        if self.generation == key.generation {
+            self.node.as_ref()
+        } else {
+            None
+        }
+    }
+
+    fn get_mut(&mut self, key: SlabKey) -> Option<&mut NodeT> {
+        if self.generation == key.generation {
+            self.node.as_mut()
+        } else {
+            None
+        }
+    }
+
+    // The generation of slot is incremented on removal, so the key of the removed node does
+    // not find the node that later took the slot.
+    fn remove(&mut self, key: SlabKey) -> Option<NodeT> {
+        if self.generation != key.generation {
+            return None;
+        }
+
+        let node = self.node.take()?;
+        self.generation = self.generation.wrapping_add(1);
+        Some(node)
+    }
+}
+
+// Storage of the nodes used by InnerChannelRt and InnerOneshotRt with O(1) access by key.
+pub(crate) trait NodeStore<NodeT> {
+    // Returns the key that the next insert() gives to the node, e.g. the node can keep its id.
+    fn next_key(&self) -> SlabKey;
+
+    // Inserts the node into the free slot, returns the key of the node. Panics if the store
+    // cannot hold more nodes.
+    fn insert(&mut self, node: NodeT) -> SlabKey;
+
+    fn get(&self, key: SlabKey) -> Option<&NodeT>;
+    fn get_mut(&mut self, key: SlabKey) -> Option<&mut NodeT>;
+
+    // Removes the node and makes its slot free. Returns None if the key is stale.
+    fn remove(&mut self, key: SlabKey) -> Option<NodeT>;
+
+    fn iter<'a>(&'a self) -> impl Iterator<Item = &'a NodeT>
+    where
+        NodeT: 'a;
+
+    // Number of nodes in the store, the free slots are not counted.
+    fn len(&self) -> usize;
+}
+
+// Default storage: the slots of removed nodes are reused by the new nodes.
+pub(crate) struct SlabStore<NodeT> {
+    slots: Vec<Slot<NodeT>>,
+    free: Vec<u32>,
+}
+
+impl<NodeT> SlabStore<NodeT> {
+    pub(crate) fn new() -> Self {
+        SlabStore {
+            slots: Vec::new(),
+            free: Vec::new(),
+        }
+    }
+}
+
+impl<NodeT> NodeStore<NodeT> for SlabStore<NodeT> {
+    fn next_key(&self) -> SlabKey {
+        match self.free.last() {
+            Some(&slot) => SlabKey {
+                slot,
+                generation: self.slots[slot as usize].generation,
+            },
+            None => SlabKey {
+                slot: self.slots.len() as u32,
+                generation: 0,
+            },
+        }
+    }
+
+    fn insert(&mut self, node: NodeT) -> SlabKey {
+        let key = self.next_key();
+        match self.free.pop() {
+            Some(_) => self.slots[key.slot as usize].node = Some(node),
+            None => self.slots.push(Slot {
+                generation: 0,
+                node: Some(node),
+            }),
+        }
+        key
+    }
+
+    fn get(&self, key: SlabKey) -> Option<&NodeT> {
+        self.slots.get(key.slot as usize)?.get(key)
+    }
+
+    fn get_mut(&mut self, key: SlabKey) -> Option<&mut NodeT> {
+        self.slots.get_mut(key.slot as usize)?.get_mut(key)
+    }
+
+    fn remove(&mut self, key: SlabKey) -> Option<NodeT> {
+        let node = self.slots.get_mut(key.slot as usize)?.remove(key)?;
+        self.free.push(key.slot);
+        Some(node)
+    }
+
+    fn iter<'a>(&'a self) -> impl Iterator<Item = &'a NodeT>
+    where
+        NodeT: 'a,
+    {
+        self.slots.iter().filter_map(|entry| entry.node.as_ref())
+    }
+
+    fn len(&self) -> usize {
+        self.slots.len() - self.free.len()
+    }
+}
+
+// Fixed size storage that does not use allocator, it can hold up to N nodes at the same time.
+#[allow(dead_code)] // only tests use it for now
+pub(crate) struct FixedStore<NodeT, const N: usize> {
+    slots: [Slot<NodeT>; N],
+    free: [u32; N], // stack of free slots, the slots above `used` have never been taken
+    free_len: usize,
+    used: usize,
+}
+
+#[allow(dead_code)]
+impl<NodeT, const N: usize> FixedStore<NodeT, N> {
+    pub(crate) fn new() -> Self {
+        FixedStore {
+            slots: core::array::from_fn(|_| Slot::vacant()),
+            free: [0; N],
+            free_len: 0,
+            used: 0,
+        }
+    }
+}
+
+impl<NodeT, const N: usize> NodeStore<NodeT> for FixedStore<NodeT, N> {
+    fn next_key(&self) -> SlabKey {
+        let slot = match self.free_len {
+            0 if self.used == N => panic!("aiur: FixedStore capacity {} exceeded", N),
+            0 => self.used as u32,
+            free_len => self.free[free_len - 1],
+        };
+
+        SlabKey {
+            slot,
+            generation: self.slots[slot as usize].generation,
+        }
+    }
+
+    fn insert(&mut self, node: NodeT) -> SlabKey {
+        let key = self.next_key();
+        match self.free_len {
+            0 => self.used += 1,
+            _ => self.free_len -= 1,
+        }
+        self.slots[key.slot as usize].node = Some(node);
+        key
+    }
+
+    fn get(&self, key: SlabKey) -> Option<&NodeT> {
+        self.slots.get(key.slot as usize)?.get(key)
+    }
+
+    fn get_mut(&mut self, key: SlabKey) -> Option<&mut NodeT> {
+        self.slots.get_mut(key.slot as usize)?.get_mut(key)
+    }
+
+    fn remove(&mut self, key: SlabKey) -> Option<NodeT> {
+        let node = self.slots.get_mut(key.slot as usize)?.remove(key)?;
+        self.free[self.free_len] = key.slot;
+        self.free_len += 1;
+        Some(node)
+    }
+
+    fn iter<'a>(&'a self) -> impl Iterator<Item = &'a NodeT>
+    where
+        NodeT: 'a,
+    {
+        self.slots[..self.used]
+            .iter()
+            .filter_map(|entry| entry.node.as_ref())
+    }
+
+    fn len(&self) -> usize {
+        self.used - self.free_len
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The key of the removed node does not find the node that has reused its slot
+    #[test]
+    fn slab_key_of_removed_node_is_rejected() {
+        let mut store = SlabStore::new();
+        let stale = store.insert(1);
+        assert_eq!(store.remove(stale), Some(1));
+
+        let recycled = store.insert(2);
+        assert_eq!(stale.slot(), recycled.slot());
+        assert_eq!(store.get(stale), None);
+        assert_eq!(store.remove(stale), None);
+        assert_eq!(store.get(recycled), Some(&2));
+    }
+
+    // The same for the fixed store, the next key is the key given by insert()
+    #[test]
+    fn fixed_store_key_of_removed_node_is_rejected() {
+        let mut store = FixedStore::<u32, 2>::new();
+        let stale = store.insert(1);
+        let other = store.insert(2);
+        assert_eq!(store.remove(stale), Some(1));
+
+        let next = store.next_key();
+        let recycled = store.insert(3);
+        assert_eq!(next, recycled);
+        assert_eq!(stale.slot(), recycled.slot());
+        assert_eq!(store.get(stale), None);
+        assert_eq!(store.get(recycled), Some(&3));
+        assert_eq!(store.get(other), Some(&2));
+        assert_eq!(store.len(), 2);
+    }
+}
@@ -2,12 +2,14 @@
 //  / * \    aiur: the homeplanet for the famous executors
 // |' | '|   (c) 2020 - present, Vladimir Zvezda
 //   / \
-use std::future::Future;
-use std::marker::PhantomData;
-use std::pin::Pin;
-use std::task::{Context, Poll};
-
-use crate::event_node::EventNode;
+use alloc::vec::Vec;
+use core::future::Future;
+use core::marker::PhantomData;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+use crate::any_of_vec::any_of_vec;
+use crate::event_node::{panic_polled_after_completion, EventNode};
 use crate::oneshot_rt::OneshotId;
 use crate::reactor::{EventId, Reactor};
 use crate::runtime::Runtime;
@@ -39,6 +41,53 @@ pub fn oneshot<'runtime, T, ReactorT: Reactor>(
     )
 }
 
+/// Awaits the first of the oneshot receivers to resolve and returns its index in the Vec with
+/// the result. The rest of the receivers are dropped, so their senders get the error.
+///
+/// Panics if the Vec is empty.
+pub async fn select_oneshots<'runtime, T, ReactorT: Reactor>(
+    recvers: Vec<RecverOnce<'runtime, T, ReactorT>>,
+) -> (usize, Result<T, RecvError>) {
+    any_of_vec(recvers)
+        .next()
+        .await
+        .expect("aiur: select_oneshots() requires at least one receiver")
+}
+
+/// Creates a oneshot channel with one sender and `receivers` receivers, each of them gets
+/// a clone of the value sent. More receivers can be added later with
+/// [BroadcastSender::subscribe()], e.g. to fan out a "ready" signal to the subtasks.
+///
+/// ```
+/// use aiur::toy_rt;
+///
+/// async fn async_main(rt: &toy_rt::Runtime, _: ()) {
+///     let (mut tx, rxs) = toy_rt::oneshot_broadcast::<u32>(rt, 2);
+///     assert_eq!(tx.send(42), 2);
+///     for rx in rxs {
+///         assert_eq!(rx.await.unwrap(), 42);
+///     }
+///     assert_eq!(tx.subscribe().await.unwrap(), 42); // late receiver still gets the value
+/// }
+///
+/// toy_rt::with_runtime_in_mode(toy_rt::SleepMode::Emulated, async_main, ());
+/// ```
+pub fn oneshot_broadcast<'runtime, T: Clone, ReactorT: Reactor>(
+    rt: &'runtime Runtime<ReactorT>,
+    receivers: usize,
+) -> (
+    BroadcastSender<'runtime, T, ReactorT>,
+    Vec<RecverOnce<'runtime, T, ReactorT>>,
+) {
+    let mut tx = BroadcastSender {
+        rt,
+        senders: Vec::new(),
+        value: None,
+    };
+    let rxs = (0..receivers).map(|_| tx.subscribe()).collect();
+    (tx, rxs)
+}
+
 /// Error type returned by Receiver: the only possible error is oneshot channel closed
 /// on sender's side.
 #[derive(Debug)] // Debug required for Result.unwrap()
@@ -72,10 +121,22 @@ impl<'runtime, ReactorT: Reactor> RuntimeOneshot<'runtime, ReactorT> {
         self.rt.oneshots().exchange::<T>(self.oneshot_id)
     }
 
+    unsafe fn try_recv<T>(&self, rx_data: &mut Option<T>) -> Option<bool> {
+        self.rt.oneshots().try_recv::<T>(self.oneshot_id, rx_data)
+    }
+
+    unsafe fn send_now<T>(&self, value: T) -> Result<(), T> {
+        self.rt.oneshots().send_now::<T>(self.oneshot_id, value)
+    }
+
     fn cancel_sender(&self) {
         self.rt.oneshots().cancel_sender(self.oneshot_id);
     }
 
+    fn is_receiver_dropped(&self) -> bool {
+        self.rt.oneshots().is_receiver_dropped(self.oneshot_id)
+    }
+
     fn cancel_receiver(&self) {
         self.rt.oneshots().cancel_receiver(self.oneshot_id);
     }
@@ -107,7 +168,7 @@ impl<'runtime, T, ReactorT: Reactor> SenderOnce<'runtime, T, ReactorT> {
     /// Sends value to the receiver side of the channel. If receiver end is already closed,
     /// the original value returned as error in result.
     pub async fn send(&mut self, value: T) -> Result<(), T> {
-        let prev = std::mem::replace(&mut self.inner, SenderInner::Sent(PhantomData));
+        let prev = core::mem::replace(&mut self.inner, SenderInner::Sent(PhantomData));
 
         // TODO: perhaps send should receive (self,..) instead of (&mut self)?
         match prev {
@@ -119,6 +180,35 @@ impl<'runtime, T, ReactorT: Reactor> SenderOnce<'runtime, T, ReactorT> {
             SenderInner::Created(ref rc) => SenderFuture::new(rc, value).await,
         }
     }
+
+    /// Sends value to the receiver without awaiting. If the receiver is already awaiting the
+    /// value is given to it right away, otherwise the value is left in the channel for the
+    /// receiver to take it later. If receiver end is already closed, the original value
+    /// returned as error in result.
+    ///
+    /// Unlike the send() there is no way to know if receiver has actually taken the value.
+    pub fn send_now(mut self, value: T) -> Result<(), T> {
+        let prev = core::mem::replace(&mut self.inner, SenderInner::Sent(PhantomData));
+        match prev {
+            SenderInner::Sent(_) => panic!(concat!(
+                "aiur: oneshot::SenderOnce::send_now() invoked after send().",
+                "Oneshot channel can be only used for one transfer."
+            )),
+            SenderInner::Created(ref rc) => unsafe { rc.send_now(value) },
+        }
+    }
+
+    /// Returns true if the receiver is dropped, so the value is not needed anymore. It is
+    /// also true after send() was invoked.
+    ///
+    /// This is a hint only: the send() can still return the value back as error even if it
+    /// was false, e.g. when the receiver is dropped later before it gets the value.
+    pub fn is_closed(&self) -> bool {
+        match self.inner {
+            SenderInner::Created(ref runtime_channel) => runtime_channel.is_receiver_dropped(),
+            SenderInner::Sent(_) => true,
+        }
+    }
 }
 
 impl<'runtime, T, ReactorT: Reactor> Drop for SenderOnce<'runtime, T, ReactorT> {
@@ -214,9 +304,10 @@ impl<'runtime, T, ReactorT: Reactor> Future for SenderFuture<'runtime, T, Reacto
                 this.transmit(event_id) // always returns Pending
             }
             PeerFutureState::Exchanging => this.close(),
-            PeerFutureState::Closed => {
-                panic!("aiur/oneshot_sender_future: was polled after completion.")
-            }
+            PeerFutureState::Closed => panic_polled_after_completion(
+                "oneshot_sender_future",
+                &this.runtime_channel.oneshot_id(),
+            ),
         };
     }
 }
@@ -266,6 +357,26 @@ impl<'runtime, T, ReactorT: Reactor> RecverOnce<'runtime, T, ReactorT> {
         self.state = new_state;
     }
 
+    /// Receives the value without awaiting: `Ok(None)` means that sender is alive but it has
+    /// not sent the value yet, the error means that sender is dropped without sending.
+    ///
+    /// Panics if the value has been already received or if the receiver has been already
+    /// polled as a future.
+    pub fn try_recv(&mut self) -> Result<Option<T>, RecvError> {
+        if !matches!(self.state, PeerFutureState::Created) {
+            panic!("aiur/oneshot_recver_future: try_recv() invoked after poll or receive.");
+        }
+
+        match unsafe { self.runtime_channel.try_recv::<T>(&mut self.data) } {
+            None => Ok(None),
+            Some(true) => {
+                self.set_state(PeerFutureState::Closed);
+                Ok(self.data.take())
+            }
+            Some(false) => Err(RecvError),
+        }
+    }
+
     fn transmit(&mut self, event_id: EventId) -> Poll<Result<T, RecvError>> {
         self.set_state(PeerFutureState::Exchanging);
         self.runtime_channel
@@ -316,9 +427,61 @@ impl<'runtime, T, ReactorT: Reactor> Future for RecverOnce<'runtime, T, ReactorT
                 this.transmit(event_id) // always returns Pending
             }
             PeerFutureState::Exchanging => this.close(),
-            PeerFutureState::Closed => {
-                panic!("aiur/oneshot_recver_future: was polled after completion.")
-            }
+            PeerFutureState::Closed => panic_polled_after_completion(
+                "oneshot_recver_future",
+                &this.runtime_channel.oneshot_id(),
+            ),
         };
     }
 }
+
+// -----------------------------------------------------------------------------------------------
+/// The sending half of the oneshot channel created by [oneshot_broadcast()] function.
+///
+/// It is made of a oneshot channel for each receiver: the value is cloned to every receiver
+/// with [SenderOnce::send_now()], so the sender does not wait for receivers to take it. The
+/// sender keeps the value for the receivers subscribed after the send.
+pub struct BroadcastSender<'runtime, T: Clone, ReactorT: Reactor> {
+    rt: &'runtime Runtime<ReactorT>,
+    senders: Vec<SenderOnce<'runtime, T, ReactorT>>, // receivers subscribed before send()
+    value: Option<T>,                                // the value sent for late subscribers
+}
+
+impl<'runtime, T: Clone, ReactorT: Reactor> BroadcastSender<'runtime, T, ReactorT> {
+    /// Adds a receiver. If the value has been already sent the receiver gets it right away.
+    pub fn subscribe(&mut self) -> RecverOnce<'runtime, T, ReactorT> {
+        let (tx, rx) = oneshot(self.rt);
+        match self.value {
+            // the receiver is alive, so send_now() cannot fail
+            Some(ref value) => {
+                let _ = tx.send_now(value.clone());
+            }
+            None => self.senders.push(tx),
+        }
+        rx
+    }
+
+    /// Sends a clone of the value to each receiver and returns the number of the receivers that
+    /// have not been dropped.
+    ///
+    /// Panics if invoked twice.
+    pub fn send(&mut self, value: T) -> usize {
+        if self.value.is_some() {
+            panic!("aiur: oneshot::BroadcastSender::send() invoked twice.");
+        }
+
+        let delivered = self
+            .senders
+            .drain(..)
+            .map(|tx| tx.send_now(value.clone()))
+            .filter(Result::is_ok)
+            .count();
+        self.value = Some(value);
+        delivered
+    }
+
+    /// Returns true if the value has been sent.
+    pub fn is_sent(&self) -> bool {
+        self.value.is_some()
+    }
+}
@@ -13,21 +13,29 @@
 //
 // As soon as both channel sides has their data registered, runtime wakes the
 // Receiver to get the data, then it wakes the Sender.
-use std::cell::RefCell;
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::cell::RefCell;
 
+use crate::node_store::{panic_stale_id, NodeStore, SlabKey, SlabStore};
 use crate::tracer::Tracer;
 use crate::reactor::EventId;
 
 // enable/disable output of modtrace! macro
 const MODTRACE: bool = true;
 
-// Channel handle used by this low level channel API (which is only has crate visibility)
+// Channel handle used by this low level channel API (which is only has crate visibility).
+// It is the key of the node in the slab, so the node is found without scanning.
 #[derive(Copy, Clone, Eq, PartialEq)]
-pub(crate) struct OneshotId(u32);
-
-impl std::fmt::Debug for OneshotId {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_fmt(format_args!("oneshot:{}", self.0))
+pub(crate) struct OneshotId(SlabKey);
+
+impl core::fmt::Debug for OneshotId {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_fmt(format_args!(
+            "oneshot:{}.{}",
+            self.0.slot(),
+            self.0.generation()
+        ))
     }
 }
 
@@ -47,21 +55,50 @@ impl RegInfo {
     }
 }
 
+// The value left by SenderOnce::send_now() for the receiver that has not been registered
+// yet. It is freed explicitly with free() when the value is taken or the receiver is dropped,
+// the clones of the node made for trace do not own it.
+#[derive(Debug, Clone)]
+struct PostedValue {
+    data: *mut (), // *mut Option<T>
+    drop_fn: unsafe fn(*mut ()),
+}
+
+impl PostedValue {
+    fn new<T>(value: T) -> Self {
+        PostedValue {
+            data: Box::into_raw(Box::new(Some(value))) as *mut (),
+            drop_fn: Self::drop_impl::<T>,
+        }
+    }
+
+    unsafe fn drop_impl<T>(data: *mut ()) {
+        drop(Box::from_raw(data as *mut Option<T>));
+    }
+
+    // Must be invoked once for the posted value
+    unsafe fn free(&self) {
+        (self.drop_fn)(self.data);
+    }
+}
+
 // Where is a sender or  receiver in the communication phase
 #[derive(Clone)] 
 enum PeerState {
     Created,
     Registered(RegInfo),
+    Posted(PostedValue), // sender only: the value is left by send_now()
     Exchanged,
     Dropped,
 }
 
 // Debug
-impl std::fmt::Debug for PeerState {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Debug for PeerState {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             PeerState::Created => f.write_str("Created"),
             PeerState::Registered(..) => f.write_str("Registered"),
+            PeerState::Posted(..) => f.write_str("Posted"),
             PeerState::Exchanged => f.write_str("Exchanged"),
             PeerState::Dropped => f.write_str("Dropped"),
         }
@@ -70,7 +107,6 @@ impl std::fmt::Debug for PeerState {
 
 #[derive(Clone)]
 struct OneshotNode {
-    id: OneshotId,
     sender: PeerState,
     receiver: PeerState,
     // we need just one more bit for our state machine, see state machine diagram below
@@ -78,9 +114,8 @@ struct OneshotNode {
 }
 
 impl OneshotNode {
-    fn new(id: u32) -> Self {
+    fn new() -> Self {
         Self {
-            id: OneshotId(id),
             sender: PeerState::Created,
             receiver: PeerState::Created,
             recv_exchanged: false,
@@ -97,8 +132,8 @@ impl OneshotNode {
 }
 
 // Produce a state like "(C->R}". See the state machine chart in code below for meaning.
-impl std::fmt::Debug for OneshotNode {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Debug for OneshotNode {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self.sender {
             PeerState::Created => f.write_str("(C->"),
             PeerState::Registered(..) => {
@@ -110,6 +145,7 @@ impl std::fmt::Debug for OneshotNode {
                     f.write_str("{R->")
                 }
             }
+            PeerState::Posted(..) => f.write_str("(P->"),
             PeerState::Exchanged => f.write_str("(E->"),
             PeerState::Dropped => f.write_str("(D->"),
         }?;
@@ -123,6 +159,7 @@ impl std::fmt::Debug for OneshotNode {
                     f.write_str("R}")
                 }
             }
+            PeerState::Posted(..) => f.write_str("P)"), // not possible for receiver
             PeerState::Exchanged => f.write_str("E)"),
             PeerState::Dropped => {
                 if self.recv_exchanged && matches!(self.sender, PeerState::Registered(..)) {
@@ -179,103 +216,115 @@ impl OneshotRt {
         self.inner.borrow().get_awake_event_id()
     }
 
+    // Adds the events of all the oneshots that are ready to awake, the first one is the event
+    // returned by get_awake_event_id().
+    pub(crate) fn awake_event_ids(&self, events: &mut Vec<EventId>) {
+        let inner = self.inner.borrow();
+        events.extend(inner.nodes.iter().filter_map(InnerOneshotRt::get_event_id_for_node));
+    }
+
+    // Number of oneshots that are alive, see Runtime::stats()
+    pub(crate) fn node_count(&self) -> usize {
+        self.inner.borrow().nodes.len()
+    }
+
     pub(crate) unsafe fn exchange<T>(&self, oneshot_id: OneshotId) -> bool {
         self.inner.borrow_mut().exchange::<T>(oneshot_id)
     }
 
+    pub(crate) unsafe fn try_recv<T>(
+        &self,
+        oneshot_id: OneshotId,
+        rx_data: &mut Option<T>,
+    ) -> Option<bool> {
+        self.inner.borrow_mut().try_recv::<T>(oneshot_id, rx_data)
+    }
+
+    pub(crate) unsafe fn send_now<T>(&self, oneshot_id: OneshotId, value: T) -> Result<(), T> {
+        self.inner.borrow_mut().send_now::<T>(oneshot_id, value)
+    }
+
     pub(crate) fn cancel_sender(&self, oneshot_id: OneshotId) {
         self.inner.borrow_mut().cancel_sender(oneshot_id);
     }
 
+    pub(crate) fn is_receiver_dropped(&self, oneshot_id: OneshotId) -> bool {
+        self.inner.borrow().is_receiver_dropped(oneshot_id)
+    }
+
+    // The value posted by send_now() is freed when the RefCell is no longer borrowed: the
+    // drop of the value is the user code that can use oneshots or panic.
     pub(crate) fn cancel_receiver(&self, oneshot_id: OneshotId) {
-        self.inner.borrow_mut().cancel_receiver(oneshot_id);
+        let posted = self.inner.borrow_mut().cancel_receiver(oneshot_id);
+        if let Some(posted) = posted {
+            unsafe { posted.free() };
+        }
+    }
+
+    // Adds the events of the registered senders and receivers, e.g. to find the tasks that
+    // are blocked by deadlock.
+    pub(crate) fn registered_events(&self, events: &mut Vec<EventId>) {
+        self.inner.borrow().registered_events(events);
     }
 }
 
 struct InnerOneshotRt {
-    nodes: Vec<OneshotNode>,
-    last_id: u32,
+    nodes: SlabStore<OneshotNode>,
     tracer: Tracer,
 }
 
 impl InnerOneshotRt {
     fn new(tracer: &Tracer) -> Self {
         InnerOneshotRt {
-            nodes: Vec::new(),
-            last_id: 0,
+            nodes: SlabStore::new(),
             tracer: tracer.clone(),
         }
     }
 
-    // find the index in self.nodes() for given oneshot_id. Panics if not found.
-    fn find_index(&self, oneshot_id: OneshotId) -> usize {
+    // Returns the node for given oneshot_id. Panics if not found.
+    fn get_node(&self, oneshot_id: OneshotId) -> &OneshotNode {
         self.nodes
-            .iter()
-            .position(|node| node.id == oneshot_id)
-            .unwrap()
+            .get(oneshot_id.0)
+            .unwrap_or_else(|| panic_stale_id(&oneshot_id))
     }
 
-    // Changes the state of the sender. Panics if channel_id is not found.
-    fn set_sender(&mut self, oneshot_id: OneshotId, sender: PeerState, log_context: &str) {
-        // TODO: too many index access
-        let idx = self.find_index(oneshot_id);
-        let old = self.nodes[idx].clone();
-
-        self.nodes[idx] = OneshotNode {
-            id: old.id,
-            sender: sender,
-            receiver: old.receiver.clone(),
-            recv_exchanged: old.recv_exchanged,
+    // Changes the state of the node with the function, removes the node if both sides are
+    // dropped after it. Panics if oneshot_id is not found.
+    fn set_state<SetStateFn: FnOnce(&mut OneshotNode)>(
+        &mut self,
+        oneshot_id: OneshotId,
+        set_state_fn: SetStateFn,
+        log_context: &str,
+    ) {
+        let node = match self.nodes.get_mut(oneshot_id.0) {
+            Some(node) => node,
+            None => panic_stale_id(&oneshot_id),
         };
+        let old = node.clone();
+        set_state_fn(node);
 
         modtrace!(
             self.tracer,
             "oneshot_rt: {:?} state {:?} -> {:?} ({})",
             oneshot_id,
             old,
-            self.nodes[idx],
+            node,
             log_context
         );
 
-        if self.nodes[idx].can_be_dropped() {
-            modtrace!(
-                self.tracer,
-                "oneshot_rt: remove {:?} from idx {}",
-                oneshot_id,
-                idx
-            );
-            self.nodes.remove(idx);
+        if node.can_be_dropped() {
+            self.nodes.remove(oneshot_id.0);
+            modtrace!(self.tracer, "oneshot_rt: remove {:?}", oneshot_id);
         }
     }
 
-    fn set_receiver(&mut self, oneshot_id: OneshotId, receiver: PeerState, log_context: &str) {
-        let idx = self.find_index(oneshot_id);
-        let old = self.nodes[idx].clone();
-        self.nodes[idx] = OneshotNode {
-            id: old.id,
-            sender: old.sender.clone(),
-            receiver: receiver,
-            recv_exchanged: old.recv_exchanged,
-        };
-
-        modtrace!(
-            self.tracer,
-            "oneshot_rt: {:?} state {:?} -> {:?} ({})",
-            oneshot_id,
-            old,
-            self.nodes[idx],
-            log_context
-        );
+    // Changes the state of the sender. Panics if oneshot_id is not found.
+    fn set_sender(&mut self, oneshot_id: OneshotId, sender: PeerState, log_context: &str) {
+        self.set_state(oneshot_id, |node| node.sender = sender, log_context);
+    }
 
-        if self.nodes[idx].can_be_dropped() {
-            self.nodes.remove(idx);
-            modtrace!(
-                self.tracer,
-                "oneshot_rt: remove {:?} from idx {}",
-                oneshot_id,
-                idx
-            );
-        }
+    fn set_receiver(&mut self, oneshot_id: OneshotId, receiver: PeerState, log_context: &str) {
+        self.set_state(oneshot_id, |node| node.receiver = receiver, log_context);
     }
 
     fn set_receiver_ext(
@@ -285,28 +334,18 @@ impl InnerOneshotRt {
         recv_exchanged: bool,
         log_context: &str,
     ) {
-        let idx = self.find_index(oneshot_id);
-        let old = self.nodes[idx].clone();
-        self.nodes[idx] = OneshotNode {
-            id: old.id,
-            sender: old.sender.clone(),
-            receiver: receiver,
-            recv_exchanged: recv_exchanged,
-        };
-        modtrace!(
-            self.tracer,
-            "oneshot_rt: {:?} state {:?} -> {:?} ({})",
+        self.set_state(
             oneshot_id,
-            old,
-            self.nodes[idx],
-            log_context
+            |node| {
+                node.receiver = receiver;
+                node.recv_exchanged = recv_exchanged;
+            },
+            log_context,
         );
     }
 
     fn create(&mut self) -> OneshotId {
-        self.last_id = self.last_id.wrapping_add(1);
-        self.nodes.push(OneshotNode::new(self.last_id));
-        OneshotId(self.last_id)
+        OneshotId(self.nodes.insert(OneshotNode::new()))
     }
 
     fn reg_sender(
@@ -373,6 +412,18 @@ impl InnerOneshotRt {
      *        * E: Exchanged
      *        * D: Dropped
      *
+     *   The receiver's try_recv() makes (R,C)->{R,E) transition without registering, the
+     *   sender is awoken as if the receiver got the value after awake.
+     *
+     *   The sender's send_now() does not register and there is nothing to awake on sender
+     *   side, so the sender goes to Dropped state once the value is given away:
+     *      * (C,R} -> (D,R}: the value is swapped right away, recv_exchanged tells the
+     *        receiver that it has the value when it is awoken
+     *      * (C,C) -> (P,C): the value is posted for the receiver. The receiver takes it by
+     *        try_recv() or after registration (P,R} and goes to (D,E). The posted value is
+     *        freed if the receiver is dropped without taking it: (P,C) -> (D,D)
+     *      * (C,D) -> (D,D): the value is returned back as error
+     *
      *   Everything starts from (C,C) and in (D,D) all channel resources are released. (D,D) has
      *   two instances on the diagram above for clarity, but this is the same state.
      *
@@ -436,14 +487,14 @@ impl InnerOneshotRt {
     }
 
     unsafe fn exhange_impl<T>(tx_data: *mut (), rx_data: *mut (), tracer: &Tracer) {
-        let tx_data = std::mem::transmute::<*mut (), *mut Option<T>>(tx_data);
-        let rx_data = std::mem::transmute::<*mut (), *mut Option<T>>(rx_data);
-        std::mem::swap(&mut *tx_data, &mut *rx_data);
+        let tx_data = core::mem::transmute::<*mut (), *mut Option<T>>(tx_data);
+        let rx_data = core::mem::transmute::<*mut (), *mut Option<T>>(rx_data);
+        core::mem::swap(&mut *tx_data, &mut *rx_data);
         modtrace!(tracer, "oneshot_rt: exchange<T> mem::swap() just happened");
     }
 
     pub(crate) unsafe fn exchange<T>(&mut self, oneshot_id: OneshotId) -> bool {
-        let node = self.nodes[self.find_index(oneshot_id)].clone();
+        let node = self.get_node(oneshot_id).clone();
         match (&node.sender, &node.receiver) {
             (PeerState::Registered(..), PeerState::Exchanged) => {
                 self.set_sender(oneshot_id, PeerState::Exchanged, "by exchange()");
@@ -456,7 +507,12 @@ impl InnerOneshotRt {
             }
             (PeerState::Dropped, PeerState::Registered(..)) => {
                 self.set_receiver(oneshot_id, PeerState::Exchanged, "by exchange()");
-                return false;
+                // The value was swapped by send_now() before sender was dropped
+                return node.recv_exchanged;
+            }
+            (PeerState::Posted(ref posted), PeerState::Registered(ref rx)) => {
+                self.take_posted::<T>(oneshot_id, posted, rx.data, "by exchange()");
+                return true;
             }
             (PeerState::Registered(ref tx), PeerState::Registered(ref rx)) => {
                 Self::exhange_impl::<T>(tx.data, rx.data, &self.tracer);
@@ -478,11 +534,160 @@ impl InnerOneshotRt {
         }
     }
 
+    // Receives the value without registering the receiver, it is only possible when the
+    // receiver is in Created state. Returns None when sender has not sent the value yet
+    // (C,C), otherwise returns the same as exchange(). The transitions are the same as if the
+    // receiver was registered and awoken right away:
+    //    * (R,C) -> {R,E): the value is swapped, sender is awoken to complete with success
+    //    * (D,C): nothing is changed, the receiver is dropped later as usual
+    pub(crate) unsafe fn try_recv<T>(
+        &mut self,
+        oneshot_id: OneshotId,
+        rx_data: &mut Option<T>,
+    ) -> Option<bool> {
+        let node = self.get_node(oneshot_id).clone();
+        match (&node.sender, &node.receiver) {
+            (PeerState::Created, PeerState::Created) => None,
+            (PeerState::Dropped, PeerState::Created) => Some(false),
+            (PeerState::Registered(ref tx), PeerState::Created) => {
+                let rx_data = rx_data as *mut Option<T> as *mut ();
+                Self::exhange_impl::<T>(tx.data, rx_data, &self.tracer);
+                self.set_receiver_ext(oneshot_id, PeerState::Exchanged, true, "by try_recv()");
+                Some(true)
+            }
+            (PeerState::Posted(ref posted), PeerState::Created) => {
+                let rx_data = rx_data as *mut Option<T> as *mut ();
+                self.take_posted::<T>(oneshot_id, posted, rx_data, "by try_recv()");
+                Some(true)
+            }
+            _ => panic!(
+                concat!(
+                    "aiur: oneshot::try_recv() invoked in unexpected state. ",
+                    "Sender: {:?}, receiver: {:?}"
+                ),
+                node.sender, node.receiver
+            ),
+        }
+    }
+
+    // Moves the value posted by send_now() to the receiver: (P,C) or (P,R} -> (D,E)
+    unsafe fn take_posted<T>(
+        &mut self,
+        oneshot_id: OneshotId,
+        posted: &PostedValue,
+        rx_data: *mut (),
+        log_context: &str,
+    ) {
+        Self::exhange_impl::<T>(posted.data, rx_data, &self.tracer);
+        posted.free();
+        self.set_state(
+            oneshot_id,
+            |node| {
+                node.sender = PeerState::Dropped;
+                node.receiver = PeerState::Exchanged;
+                node.recv_exchanged = true;
+            },
+            log_context,
+        );
+    }
+
+    // Sends the value without registering the sender, see the state machine above. Returns
+    // the value back if the receiver is dropped.
+    pub(crate) unsafe fn send_now<T>(&mut self, oneshot_id: OneshotId, value: T) -> Result<(), T> {
+        let node = self.get_node(oneshot_id).clone();
+        match (&node.sender, &node.receiver) {
+            (PeerState::Created, PeerState::Created) => {
+                let posted = PeerState::Posted(PostedValue::new(value));
+                self.set_sender(oneshot_id, posted, "by send_now()");
+                Ok(())
+            }
+            (PeerState::Created, PeerState::Registered(ref rx)) => {
+                let mut tx_data = Some(value);
+                let tx_ptr = &mut tx_data as *mut Option<T> as *mut ();
+                Self::exhange_impl::<T>(tx_ptr, rx.data, &self.tracer);
+                self.set_state(
+                    oneshot_id,
+                    |node| {
+                        node.sender = PeerState::Dropped;
+                        node.recv_exchanged = true;
+                    },
+                    "by send_now()",
+                );
+                Ok(())
+            }
+            (PeerState::Created, PeerState::Dropped) => {
+                self.set_sender(oneshot_id, PeerState::Dropped, "by send_now()");
+                Err(value)
+            }
+            _ => panic!(
+                concat!(
+                    "aiur: oneshot::send_now() invoked in unexpected state. ",
+                    "Sender: {:?}, receiver: {:?}"
+                ),
+                node.sender, node.receiver
+            ),
+        }
+    }
+
     pub(crate) fn cancel_sender(&mut self, oneshot_id: OneshotId) {
         self.set_sender(oneshot_id, PeerState::Dropped, "by cancel_sender()");
     }
 
-    pub(crate) fn cancel_receiver(&mut self, oneshot_id: OneshotId) {
-        self.set_receiver(oneshot_id, PeerState::Dropped, "by cancel_receiver()");
+    // The removed node is for the receiver dropped too, unlike get_node() it does not panic.
+    fn is_receiver_dropped(&self, oneshot_id: OneshotId) -> bool {
+        self.nodes
+            .get(oneshot_id.0)
+            .is_none_or(|node| matches!(node.receiver, PeerState::Dropped))
+    }
+
+    // Returns the value posted by send_now() that nobody is going to take, the caller must
+    // free it.
+    fn cancel_receiver(&mut self, oneshot_id: OneshotId) -> Option<PostedValue> {
+        let mut orphan = None;
+        self.set_state(
+            oneshot_id,
+            |node| {
+                node.receiver = PeerState::Dropped;
+                if let PeerState::Posted(ref posted) = node.sender {
+                    orphan = Some(posted.clone());
+                    node.sender = PeerState::Dropped;
+                }
+            },
+            "by cancel_receiver()",
+        );
+        orphan
+    }
+
+    fn registered_events(&self, events: &mut Vec<EventId>) {
+        for node in self.nodes.iter() {
+            for peer in [&node.sender, &node.receiver] {
+                if let PeerState::Registered(ref reg_info) = peer {
+                    events.push(reg_info.event_id);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The slot of removed oneshot is reused by the next one, the id of removed oneshot does
+    /// not refer to the new oneshot.
+    #[test]
+    fn api_test_removed_oneshot_slot_is_reused() {
+        let mut ort = InnerOneshotRt::new(&Tracer::new_testing());
+
+        let first = ort.create();
+        ort.cancel_sender(first);
+        assert!(ort.cancel_receiver(first).is_none());
+
+        let second = ort.create();
+        assert_eq!(first.0.slot(), second.0.slot());
+        assert_ne!(first, second);
+
+        assert!(ort.is_receiver_dropped(first));
+        assert!(!ort.is_receiver_dropped(second));
     }
 }
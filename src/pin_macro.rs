@@ -31,7 +31,11 @@
 /// As original value is shadowed, there is no both pin and unpin references of the same data
 /// exist which justifies the usage of unsafe is ok.
 ///
+/// With the `std-pin` feature the macro delegates to [std::pin::pin!()] instead, the binding
+/// is the same `std::pin::Pin<&mut T>`.
+///
 /// The similar macro exists in other crates, e.g.  `pin_mut!()` in pin-utils or `pin!()` in tokio.
+#[cfg(not(feature = "std-pin"))]
 #[macro_export]
 macro_rules! pin_local {
     ($var:ident) => {
@@ -43,3 +47,15 @@ macro_rules! pin_local {
         let mut $var = unsafe { core::pin::Pin::new_unchecked(&mut $var) };
     };
 }
+
+
+/// Pins the stack variable with [std::pin::pin!()], see the docs of the version without the
+/// `std-pin` feature.
+#[cfg(feature = "std-pin")]
+#[macro_export]
+macro_rules! pin_local {
+    ($var:ident) => {
+        #[allow(unused_mut)]
+        let mut $var = core::pin::pin!($var);
+    };
+}
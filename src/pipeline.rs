@@ -0,0 +1,92 @@
+//  \ O /
+//  / * \    aiur: the homeplanet for the famous executors
+// |' | '|   (c) 2020 - present, Vladimir Zvezda
+//   / \
+use core::future::Future;
+
+use crate::channel::{channel, Recver, Sender};
+use crate::reactor::Reactor;
+use crate::runtime::Runtime;
+use crate::{join2, join3};
+
+/// Connects two stages with channels: stage1 receives the input values and sends to stage2,
+/// stage2 sends the output values.
+///
+/// Each stage is a function like `async fn stage(rx: Recver<In>, tx: Sender<Out>)` that is
+/// supposed to run until its receiver is disconnected. Returns the sender for the input
+/// values, the receiver of the output values and the future that runs the stages
+/// concurrently. The future has to be awaited concurrently with the code that feeds the
+/// pipeline, e.g. with [join!](crate::join).
+pub fn pipeline2<'runtime, InT, MidT, OutT, ReactorT, Stage1T, Fut1T, Stage2T, Fut2T>(
+    rt: &'runtime Runtime<ReactorT>,
+    stage1: Stage1T,
+    stage2: Stage2T,
+) -> (
+    Sender<'runtime, InT, ReactorT>,
+    Recver<'runtime, OutT, ReactorT>,
+    impl Future<Output = ()>,
+)
+where
+    ReactorT: Reactor,
+    Stage1T: FnOnce(Recver<'runtime, InT, ReactorT>, Sender<'runtime, MidT, ReactorT>) -> Fut1T,
+    Stage2T: FnOnce(Recver<'runtime, MidT, ReactorT>, Sender<'runtime, OutT, ReactorT>) -> Fut2T,
+    Fut1T: Future<Output = ()>,
+    Fut2T: Future<Output = ()>,
+{
+    let (tx_in, rx_in) = channel(rt);
+    let (tx_mid, rx_mid) = channel(rt);
+    let (tx_out, rx_out) = channel(rt);
+
+    let stages = join2(stage1(rx_in, tx_mid), stage2(rx_mid, tx_out));
+    (tx_in, rx_out, async move {
+        stages.await;
+    })
+}
+
+/// Connects three stages with channels, see [pipeline2()].
+pub fn pipeline3<
+    'runtime,
+    InT,
+    Mid1T,
+    Mid2T,
+    OutT,
+    ReactorT,
+    Stage1T,
+    Fut1T,
+    Stage2T,
+    Fut2T,
+    Stage3T,
+    Fut3T,
+>(
+    rt: &'runtime Runtime<ReactorT>,
+    stage1: Stage1T,
+    stage2: Stage2T,
+    stage3: Stage3T,
+) -> (
+    Sender<'runtime, InT, ReactorT>,
+    Recver<'runtime, OutT, ReactorT>,
+    impl Future<Output = ()>,
+)
+where
+    ReactorT: Reactor,
+    Stage1T: FnOnce(Recver<'runtime, InT, ReactorT>, Sender<'runtime, Mid1T, ReactorT>) -> Fut1T,
+    Stage2T: FnOnce(Recver<'runtime, Mid1T, ReactorT>, Sender<'runtime, Mid2T, ReactorT>) -> Fut2T,
+    Stage3T: FnOnce(Recver<'runtime, Mid2T, ReactorT>, Sender<'runtime, OutT, ReactorT>) -> Fut3T,
+    Fut1T: Future<Output = ()>,
+    Fut2T: Future<Output = ()>,
+    Fut3T: Future<Output = ()>,
+{
+    let (tx_in, rx_in) = channel(rt);
+    let (tx_mid1, rx_mid1) = channel(rt);
+    let (tx_mid2, rx_mid2) = channel(rt);
+    let (tx_out, rx_out) = channel(rt);
+
+    let stages = join3(
+        stage1(rx_in, tx_mid1),
+        stage2(rx_mid1, tx_mid2),
+        stage3(rx_mid2, tx_out),
+    );
+    (tx_in, rx_out, async move {
+        stages.await;
+    })
+}
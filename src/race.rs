@@ -0,0 +1,182 @@
+//  \ O /
+//  / * \    aiur: the homeplanet for the famous executors
+// |' | '|   (c) 2020 - present, Vladimir Zvezda
+//   / \
+use core::future::Future;
+
+use crate::any_of::{OneOf2, OneOf3, OneOf4, OneOf5, OneOf6, OneOf7, OneOf8};
+use crate::pinned_any_of;
+
+/// Polls two futures concurrently and returns the output of the first one completed. The
+/// other future is dropped before race2() returns, so whatever it has registered in runtime
+/// (a timer, a pinned channel future) is cancelled right at the await point. This is unlike
+/// [any_of2()](crate::any_of2) stream which keeps the futures until the stream is dropped.
+///
+/// ```
+/// use aiur::toy_rt;
+/// use std::time::Duration;
+///
+/// async fn async_main(rt: &toy_rt::Runtime, _: ()) {
+///     let first = toy_rt::race2(
+///         toy_rt::sleep(rt, Duration::from_millis(100)),
+///         toy_rt::sleep(rt, Duration::from_millis(1000)),
+///     )
+///     .await;
+///     assert!(matches!(first, toy_rt::OneOf2::First(())));
+///     assert!(!rt.io().has_pending()); // the timer of the second sleep is cancelled
+/// }
+///
+/// toy_rt::with_runtime_in_mode(toy_rt::SleepMode::Emulated, async_main, ());
+/// ```
+pub async fn race2<FutT1, FutT2>(f1: FutT1, f2: FutT2) -> OneOf2<FutT1::Output, FutT2::Output>
+where
+    FutT1: Future,
+    FutT2: Future,
+{
+    pinned_any_of!(stream, f1, f2);
+    stream.next().await.unwrap() // the first next() of any_of stream always has a value
+}
+
+/// Polls three futures concurrently and returns the output of the first one completed,
+/// the others are dropped before returning, see [race2()].
+pub async fn race3<FutT1, FutT2, FutT3>(
+    f1: FutT1,
+    f2: FutT2,
+    f3: FutT3,
+) -> OneOf3<FutT1::Output, FutT2::Output, FutT3::Output>
+where
+    FutT1: Future,
+    FutT2: Future,
+    FutT3: Future,
+{
+    pinned_any_of!(stream, f1, f2, f3);
+    stream.next().await.unwrap() // the first next() of any_of stream always has a value
+}
+
+/// Polls four futures concurrently and returns the output of the first one completed,
+/// the others are dropped before returning, see [race2()].
+pub async fn race4<FutT1, FutT2, FutT3, FutT4>(
+    f1: FutT1,
+    f2: FutT2,
+    f3: FutT3,
+    f4: FutT4,
+) -> OneOf4<FutT1::Output, FutT2::Output, FutT3::Output, FutT4::Output>
+where
+    FutT1: Future,
+    FutT2: Future,
+    FutT3: Future,
+    FutT4: Future,
+{
+    pinned_any_of!(stream, f1, f2, f3, f4);
+    stream.next().await.unwrap() // the first next() of any_of stream always has a value
+}
+
+/// Polls five futures concurrently and returns the output of the first one completed,
+/// the others are dropped before returning, see [race2()].
+pub async fn race5<FutT1, FutT2, FutT3, FutT4, FutT5>(
+    f1: FutT1,
+    f2: FutT2,
+    f3: FutT3,
+    f4: FutT4,
+    f5: FutT5,
+) -> OneOf5<FutT1::Output, FutT2::Output, FutT3::Output, FutT4::Output, FutT5::Output>
+where
+    FutT1: Future,
+    FutT2: Future,
+    FutT3: Future,
+    FutT4: Future,
+    FutT5: Future,
+{
+    pinned_any_of!(stream, f1, f2, f3, f4, f5);
+    stream.next().await.unwrap() // the first next() of any_of stream always has a value
+}
+
+/// Polls six futures concurrently and returns the output of the first one completed,
+/// the others are dropped before returning, see [race2()].
+pub async fn race6<FutT1, FutT2, FutT3, FutT4, FutT5, FutT6>(
+    f1: FutT1,
+    f2: FutT2,
+    f3: FutT3,
+    f4: FutT4,
+    f5: FutT5,
+    f6: FutT6,
+) -> OneOf6<FutT1::Output, FutT2::Output, FutT3::Output, FutT4::Output, FutT5::Output, FutT6::Output>
+where
+    FutT1: Future,
+    FutT2: Future,
+    FutT3: Future,
+    FutT4: Future,
+    FutT5: Future,
+    FutT6: Future,
+{
+    pinned_any_of!(stream, f1, f2, f3, f4, f5, f6);
+    stream.next().await.unwrap() // the first next() of any_of stream always has a value
+}
+
+/// Polls seven futures concurrently and returns the output of the first one completed,
+/// the others are dropped before returning, see [race2()].
+pub async fn race7<FutT1, FutT2, FutT3, FutT4, FutT5, FutT6, FutT7>(
+    f1: FutT1,
+    f2: FutT2,
+    f3: FutT3,
+    f4: FutT4,
+    f5: FutT5,
+    f6: FutT6,
+    f7: FutT7,
+) -> OneOf7<
+    FutT1::Output,
+    FutT2::Output,
+    FutT3::Output,
+    FutT4::Output,
+    FutT5::Output,
+    FutT6::Output,
+    FutT7::Output,
+>
+where
+    FutT1: Future,
+    FutT2: Future,
+    FutT3: Future,
+    FutT4: Future,
+    FutT5: Future,
+    FutT6: Future,
+    FutT7: Future,
+{
+    pinned_any_of!(stream, f1, f2, f3, f4, f5, f6, f7);
+    stream.next().await.unwrap() // the first next() of any_of stream always has a value
+}
+
+/// Polls eight futures concurrently and returns the output of the first one completed,
+/// the others are dropped before returning, see [race2()].
+#[allow(clippy::too_many_arguments)]
+pub async fn race8<FutT1, FutT2, FutT3, FutT4, FutT5, FutT6, FutT7, FutT8>(
+    f1: FutT1,
+    f2: FutT2,
+    f3: FutT3,
+    f4: FutT4,
+    f5: FutT5,
+    f6: FutT6,
+    f7: FutT7,
+    f8: FutT8,
+) -> OneOf8<
+    FutT1::Output,
+    FutT2::Output,
+    FutT3::Output,
+    FutT4::Output,
+    FutT5::Output,
+    FutT6::Output,
+    FutT7::Output,
+    FutT8::Output,
+>
+where
+    FutT1: Future,
+    FutT2: Future,
+    FutT3: Future,
+    FutT4: Future,
+    FutT5: Future,
+    FutT6: Future,
+    FutT7: Future,
+    FutT8: Future,
+{
+    pinned_any_of!(stream, f1, f2, f3, f4, f5, f6, f7, f8);
+    stream.next().await.unwrap() // the first next() of any_of stream always has a value
+}
@@ -0,0 +1,313 @@
+//  \ O /
+//  / * \    aiur: the homeplanet for the famous executors
+// |' | '|   (c) 2020 - present, Vladimir Zvezda
+//   / \
+use core::future::Future;
+
+use crate::any_of::{OneOf2, OneOf3, OneOf4, OneOf5, OneOf6, OneOf7, OneOf8};
+use crate::oneshot::RecverOnce;
+use crate::pinned_any_of;
+use crate::reactor::Reactor;
+use crate::scope::Scope;
+
+// Awaits the output of the task spawned by Scope::spawn_owned(). The task can only be cancelled
+// by dropping the scope, which is borrowed while the race is awaited.
+async fn task_output<T, ReactorT: Reactor>(rx: RecverOnce<'_, T, ReactorT>) -> T {
+    rx.await
+        .expect("aiur: race_detached() task is cancelled before it had the output sent")
+}
+
+/// Spawns two futures as the tasks of the scope and returns the output of the first one
+/// completed. Unlike [any_of2()](crate::any_of2) the rest of the futures are not cancelled, they
+/// keep running in the scope until completed, see [Scope::run()].
+///
+/// ```
+/// use aiur::toy_rt;
+/// use std::cell::Cell;
+/// use std::time::Duration;
+///
+/// async fn async_main(rt: &toy_rt::Runtime, _: ()) {
+///     let slow_done = Cell::new(false);
+///     let scope = toy_rt::Scope::new(rt);
+///     scope
+///         .run(async {
+///             let first = toy_rt::race_detached2(
+///                 &scope,
+///                 async { 1 },
+///                 async {
+///                     toy_rt::sleep(rt, Duration::from_millis(1000)).await;
+///                     slow_done.set(true);
+///                     2
+///                 },
+///             )
+///             .await;
+///             assert!(matches!(first, aiur::OneOf2::First(1)));
+///         })
+///         .await;
+///     assert!(slow_done.get()); // run() has waited for the slow one
+/// }
+///
+/// toy_rt::with_runtime_in_mode(toy_rt::SleepMode::Emulated, async_main, ());
+/// ```
+pub async fn race_detached2<'runtime, 'scope, ReactorT, FutT1, FutT2>(
+    scope: &Scope<'runtime, 'scope, ReactorT>,
+    f1: FutT1,
+    f2: FutT2,
+) -> OneOf2<FutT1::Output, FutT2::Output>
+where
+    ReactorT: Reactor,
+    FutT1: Future + 'scope,
+    FutT2: Future + 'scope,
+    'runtime: 'scope,
+{
+    pinned_any_of!(
+        stream,
+        task_output(scope.spawn_owned(f1)),
+        task_output(scope.spawn_owned(f2)),
+    );
+    stream.next().await.unwrap() // the first next() of any_of stream always has a value
+}
+
+/// Spawns three futures as the tasks of the scope and returns the output of the first one
+/// completed, see [race_detached2()].
+pub async fn race_detached3<'runtime, 'scope, ReactorT, FutT1, FutT2, FutT3>(
+    scope: &Scope<'runtime, 'scope, ReactorT>,
+    f1: FutT1,
+    f2: FutT2,
+    f3: FutT3,
+) -> OneOf3<FutT1::Output, FutT2::Output, FutT3::Output>
+where
+    ReactorT: Reactor,
+    FutT1: Future + 'scope,
+    FutT2: Future + 'scope,
+    FutT3: Future + 'scope,
+    'runtime: 'scope,
+{
+    pinned_any_of!(
+        stream,
+        task_output(scope.spawn_owned(f1)),
+        task_output(scope.spawn_owned(f2)),
+        task_output(scope.spawn_owned(f3)),
+    );
+    stream.next().await.unwrap() // the first next() of any_of stream always has a value
+}
+
+/// Spawns four futures as the tasks of the scope and returns the output of the first one
+/// completed, see [race_detached2()].
+pub async fn race_detached4<'runtime, 'scope, ReactorT, FutT1, FutT2, FutT3, FutT4>(
+    scope: &Scope<'runtime, 'scope, ReactorT>,
+    f1: FutT1,
+    f2: FutT2,
+    f3: FutT3,
+    f4: FutT4,
+) -> OneOf4<FutT1::Output, FutT2::Output, FutT3::Output, FutT4::Output>
+where
+    ReactorT: Reactor,
+    FutT1: Future + 'scope,
+    FutT2: Future + 'scope,
+    FutT3: Future + 'scope,
+    FutT4: Future + 'scope,
+    'runtime: 'scope,
+{
+    pinned_any_of!(
+        stream,
+        task_output(scope.spawn_owned(f1)),
+        task_output(scope.spawn_owned(f2)),
+        task_output(scope.spawn_owned(f3)),
+        task_output(scope.spawn_owned(f4)),
+    );
+    stream.next().await.unwrap() // the first next() of any_of stream always has a value
+}
+
+/// Spawns five futures as the tasks of the scope and returns the output of the first one
+/// completed, see [race_detached2()].
+pub async fn race_detached5<'runtime, 'scope, ReactorT, FutT1, FutT2, FutT3, FutT4, FutT5>(
+    scope: &Scope<'runtime, 'scope, ReactorT>,
+    f1: FutT1,
+    f2: FutT2,
+    f3: FutT3,
+    f4: FutT4,
+    f5: FutT5,
+) -> OneOf5<
+    FutT1::Output,
+    FutT2::Output,
+    FutT3::Output,
+    FutT4::Output,
+    FutT5::Output,
+>
+where
+    ReactorT: Reactor,
+    FutT1: Future + 'scope,
+    FutT2: Future + 'scope,
+    FutT3: Future + 'scope,
+    FutT4: Future + 'scope,
+    FutT5: Future + 'scope,
+    'runtime: 'scope,
+{
+    pinned_any_of!(
+        stream,
+        task_output(scope.spawn_owned(f1)),
+        task_output(scope.spawn_owned(f2)),
+        task_output(scope.spawn_owned(f3)),
+        task_output(scope.spawn_owned(f4)),
+        task_output(scope.spawn_owned(f5)),
+    );
+    stream.next().await.unwrap() // the first next() of any_of stream always has a value
+}
+
+/// Spawns six futures as the tasks of the scope and returns the output of the first one
+/// completed, see [race_detached2()].
+pub async fn race_detached6<'runtime, 'scope, ReactorT, FutT1, FutT2, FutT3, FutT4, FutT5, FutT6>(
+    scope: &Scope<'runtime, 'scope, ReactorT>,
+    f1: FutT1,
+    f2: FutT2,
+    f3: FutT3,
+    f4: FutT4,
+    f5: FutT5,
+    f6: FutT6,
+) -> OneOf6<
+    FutT1::Output,
+    FutT2::Output,
+    FutT3::Output,
+    FutT4::Output,
+    FutT5::Output,
+    FutT6::Output,
+>
+where
+    ReactorT: Reactor,
+    FutT1: Future + 'scope,
+    FutT2: Future + 'scope,
+    FutT3: Future + 'scope,
+    FutT4: Future + 'scope,
+    FutT5: Future + 'scope,
+    FutT6: Future + 'scope,
+    'runtime: 'scope,
+{
+    pinned_any_of!(
+        stream,
+        task_output(scope.spawn_owned(f1)),
+        task_output(scope.spawn_owned(f2)),
+        task_output(scope.spawn_owned(f3)),
+        task_output(scope.spawn_owned(f4)),
+        task_output(scope.spawn_owned(f5)),
+        task_output(scope.spawn_owned(f6)),
+    );
+    stream.next().await.unwrap() // the first next() of any_of stream always has a value
+}
+
+/// Spawns seven futures as the tasks of the scope and returns the output of the first one
+/// completed, see [race_detached2()].
+#[allow(clippy::too_many_arguments)]
+pub async fn race_detached7<
+    'runtime,
+    'scope,
+    ReactorT,
+    FutT1,
+    FutT2,
+    FutT3,
+    FutT4,
+    FutT5,
+    FutT6,
+    FutT7,
+>(
+    scope: &Scope<'runtime, 'scope, ReactorT>,
+    f1: FutT1,
+    f2: FutT2,
+    f3: FutT3,
+    f4: FutT4,
+    f5: FutT5,
+    f6: FutT6,
+    f7: FutT7,
+) -> OneOf7<
+    FutT1::Output,
+    FutT2::Output,
+    FutT3::Output,
+    FutT4::Output,
+    FutT5::Output,
+    FutT6::Output,
+    FutT7::Output,
+>
+where
+    ReactorT: Reactor,
+    FutT1: Future + 'scope,
+    FutT2: Future + 'scope,
+    FutT3: Future + 'scope,
+    FutT4: Future + 'scope,
+    FutT5: Future + 'scope,
+    FutT6: Future + 'scope,
+    FutT7: Future + 'scope,
+    'runtime: 'scope,
+{
+    pinned_any_of!(
+        stream,
+        task_output(scope.spawn_owned(f1)),
+        task_output(scope.spawn_owned(f2)),
+        task_output(scope.spawn_owned(f3)),
+        task_output(scope.spawn_owned(f4)),
+        task_output(scope.spawn_owned(f5)),
+        task_output(scope.spawn_owned(f6)),
+        task_output(scope.spawn_owned(f7)),
+    );
+    stream.next().await.unwrap() // the first next() of any_of stream always has a value
+}
+
+/// Spawns eight futures as the tasks of the scope and returns the output of the first one
+/// completed, see [race_detached2()].
+#[allow(clippy::too_many_arguments)]
+pub async fn race_detached8<
+    'runtime,
+    'scope,
+    ReactorT,
+    FutT1,
+    FutT2,
+    FutT3,
+    FutT4,
+    FutT5,
+    FutT6,
+    FutT7,
+    FutT8,
+>(
+    scope: &Scope<'runtime, 'scope, ReactorT>,
+    f1: FutT1,
+    f2: FutT2,
+    f3: FutT3,
+    f4: FutT4,
+    f5: FutT5,
+    f6: FutT6,
+    f7: FutT7,
+    f8: FutT8,
+) -> OneOf8<
+    FutT1::Output,
+    FutT2::Output,
+    FutT3::Output,
+    FutT4::Output,
+    FutT5::Output,
+    FutT6::Output,
+    FutT7::Output,
+    FutT8::Output,
+>
+where
+    ReactorT: Reactor,
+    FutT1: Future + 'scope,
+    FutT2: Future + 'scope,
+    FutT3: Future + 'scope,
+    FutT4: Future + 'scope,
+    FutT5: Future + 'scope,
+    FutT6: Future + 'scope,
+    FutT7: Future + 'scope,
+    FutT8: Future + 'scope,
+    'runtime: 'scope,
+{
+    pinned_any_of!(
+        stream,
+        task_output(scope.spawn_owned(f1)),
+        task_output(scope.spawn_owned(f2)),
+        task_output(scope.spawn_owned(f3)),
+        task_output(scope.spawn_owned(f4)),
+        task_output(scope.spawn_owned(f5)),
+        task_output(scope.spawn_owned(f6)),
+        task_output(scope.spawn_owned(f7)),
+        task_output(scope.spawn_owned(f8)),
+    );
+    stream.next().await.unwrap() // the first next() of any_of stream always has a value
+}
@@ -2,7 +2,8 @@
 //  / * \    aiur: the home planet for the famous executors
 // |' | '|   (c) 2020 - present, Vladimir Zvezda
 //   / \
-use std::time::Duration;
+use core::task::Waker;
+use core::time::Duration;
 
 use crate::event_node::EventNode;
 
@@ -11,7 +12,45 @@ use crate::event_node::EventNode;
 /// External crate implements the trait to create a Runtime with both executor and reactor.
 pub trait Reactor {
     /// The only method Runtime needs from the reactor is to wait for I/O to complete.
+    ///
+    /// Reactor returns [EventId::terminated()] when it wants the runtime to stop, e.g. on
+    /// SIGTERM. [EventId::null()] means that reactor has returned without an event, runtime
+    /// then ends its loop step without polling anything.
     fn wait(&self) -> EventId;
+
+    /// Returns the event if there is one ready right now, without blocking. Runtime uses it
+    /// with [SchedulePolicy::ParkEagerly](crate::SchedulePolicy::ParkEagerly) to check the
+    /// reactor between the channel exchanges. The default is for the reactor that cannot
+    /// check without blocking: it returns None.
+    fn try_wait(&self) -> Option<EventId> {
+        None
+    }
+
+    /// Returns false if there is nothing to wait for, so wait() would never return. Runtime
+    /// then stops with [RunError::Deadlock](crate::RunError::Deadlock) instead of invoking
+    /// wait(). The default is for the reactor that cannot tell: it returns true.
+    fn has_pending(&self) -> bool {
+        true
+    }
+
+    /// Registers the event of a leaf future that is not a timer, e.g. the readiness of a socket
+    /// in epoll based reactor. Reactor returns the event_id from wait() when the event happens.
+    ///
+    /// The waker is the one from the future's Context. Runtime does not need it to be woken
+    /// because it finds the task by the event_id, but reactor may keep it if it is shared with
+    /// other executors. The default is for the reactor that has no such events: it does nothing.
+    fn register_event(&self, _waker: Waker, _event_id: EventId) {}
+
+    /// Removes the event registered with register_event() that has not happened yet, usually
+    /// from the leaf future's drop(). The default does nothing.
+    fn deregister_event(&self, _event_id: EventId) {}
+}
+
+/// Reactor which I/O wait can be limited by timeout, so it can be composed with the timers of
+/// another reactor in [CompositeReactor](crate::CompositeReactor).
+pub trait TimeoutReactor: Reactor {
+    /// Same as [Reactor::wait()], but returns None if there was no event during the timeout.
+    fn wait_timeout(&self, timeout: Duration) -> Option<EventId>;
 }
 
 /// Reactor with a very basic timers.
@@ -21,6 +60,39 @@ pub trait TemporalReactor: Reactor {
 
     fn schedule_timer(&self, event_id: EventId, duration: Duration);
     fn cancel_timer(&self, event_id: EventId);
+
+    /// Schedules the timer that fires at the deadline: the time in milliseconds by the clock
+    /// of [TemporalReactor::now()]. The deadline in the past fires as soon as possible.
+    ///
+    /// The default implementation converts the deadline to the duration from now for
+    /// schedule_timer(). The reactor that keeps the absolute time of its timers should
+    /// override it, so the time passed during the conversion is not added to the deadline.
+    fn schedule_timer_at(&self, event_id: EventId, deadline_ms: u32) {
+        let now_ms = self.now().as_millis() as u32;
+        let left_ms = deadline_ms.saturating_sub(now_ms);
+        self.schedule_timer(event_id, Duration::from_millis(left_ms as u64));
+    }
+
+    /// Returns the monotonic time passed since the reactor was created. It is the same clock
+    /// the timers are scheduled with, e.g. it is emulated when the sleeps are emulated.
+    ///
+    /// There is no default: since 0.0.9 the reactor has to implement it.
+    fn now(&self) -> Duration;
+
+    /// Returns the time left until the first scheduled timer is due (zero if it is overdue),
+    /// or None if there are no timers. [CompositeReactor](crate::CompositeReactor) uses it as
+    /// the deadline for the I/O wait.
+    ///
+    /// There is no default: since 0.0.9 the reactor has to implement it.
+    fn next_timer_in(&self) -> Option<Duration>;
+
+    /// Cancels all the timers scheduled in reactor, e.g. when runtime is shutting down. The
+    /// timer futures that are still alive may invoke cancel_timer() for their events after
+    /// that, reactor should ignore such calls.
+    ///
+    /// The default is for the reactor that cannot cancel its timers at once: it does nothing,
+    /// the timers fire as they were scheduled.
+    fn cancel_all_timers(&self) {}
 }
 
 /// EventId is a reactor's id for the event that was awoken.
@@ -29,17 +101,60 @@ pub trait TemporalReactor: Reactor {
 /// this id is used by the leaf futures to verify if executor has invoked the poll() method 
 /// because this future was awoken.
 ///
-/// EventId is created from EventNode.
-#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+/// EventId is created from EventNode, see [GetEventId]. Reactor can store EventId as the key
+/// of a map to look up the I/O it has scheduled for the event.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, PartialOrd, Ord, Debug)]
 pub struct EventId(pub(crate) *const ());
 
 impl EventId {
+    /// The EventId that does not refer any event. Reactor returns it from wait() when it
+    /// returns without an event.
     pub fn null() -> Self {
-        EventId(std::ptr::null())
+        EventId(core::ptr::null())
     }
 
-    pub fn as_event_node(&self) -> &EventNode {
-        assert!(self.0 != std::ptr::null());
+    /// Returns true if this is [EventId::null()].
+    pub fn is_null(&self) -> bool {
+        self.0.is_null()
+    }
+
+    /// Returns the address of the [EventNode] this id is made from, e.g. to pass it through
+    /// the OS API as the user data of I/O operation. It is only an address, the EventId
+    /// cannot be made back from it.
+    pub fn as_ptr(&self) -> *const () {
+        self.0
+    }
+
+    /// The sentinel EventId that reactor returns from wait() when it is terminated. Runtime
+    /// begins shutdown and exits its loops without completing the futures, see
+    /// [Runtime::try_nested_loop()](crate::Runtime::try_nested_loop).
+    pub fn terminated() -> Self {
+        static TERMINATED: u8 = 0;
+        EventId(&TERMINATED as *const u8 as *const ())
+    }
+
+    /// Returns true if this is [EventId::terminated()].
+    pub fn is_terminated(&self) -> bool {
+        *self == Self::terminated()
+    }
+
+    // Only the runtime makes EventNode back from the id: the public constructors give the ids
+    // that do not point to EventNode, so this is asserted here.
+    pub(crate) fn as_event_node(&self) -> &EventNode {
+        assert!(!self.is_null() && !self.is_terminated());
         unsafe { &*(self.0 as *const EventNode) }
     }
 }
+
+/// The way to obtain [EventId] from the leaf future or another type that has the [EventNode]
+/// inside. The EventId is only valid when the EventNode is pinned, see [EventNode::on_pin()].
+pub trait GetEventId {
+    /// Returns the EventId of the EventNode inside.
+    fn get_event_id(&self) -> EventId;
+}
+
+impl GetEventId for EventNode {
+    fn get_event_id(&self) -> EventId {
+        EventNode::get_event_id(self)
+    }
+}
@@ -0,0 +1,126 @@
+//  \ O /
+//  / * \    aiur: the homeplanet for the famous executors
+// |' | '|   (c) 2020 - present, Vladimir Zvezda
+//   / \
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+use crate::event_node::panic_polled_after_completion;
+use crate::{EventId, EventNode, Reactor, Runtime};
+
+/// The operation in reactor that [ReactorFuture] is waiting for, e.g. a timer or the
+/// readiness of a socket.
+///
+/// The leaf future for a custom reactor is made by implementing this trait and awaiting
+/// `ReactorFuture::new(rt, op)`, the future handles the [EventNode] and invokes the methods
+/// below at the right time.
+pub trait ReactorOp<ReactorT: Reactor> {
+    /// Returns true if the operation does not have to wait in reactor, then the future
+    /// completes on the first poll without scheduling. The default is false.
+    fn is_ready(&self, _rt: &Runtime<ReactorT>) -> bool {
+        false
+    }
+
+    /// Schedules the operation in reactor, which returns the event_id from wait() when the
+    /// operation is complete. Invoked once on the first poll.
+    fn schedule(&mut self, rt: &Runtime<ReactorT>, event_id: EventId);
+
+    /// Cancels the operation in reactor when the future is dropped before it is complete.
+    fn cancel(&mut self, rt: &Runtime<ReactorT>, event_id: EventId);
+
+    /// Invoked when the scheduled operation is complete, i.e. the runtime was awoken for its
+    /// event. The default does nothing.
+    fn complete(&mut self, _rt: &Runtime<ReactorT>, _event_id: EventId) {}
+}
+
+// Possible states for the reactor future.
+enum ReactorFutureState {
+    Created,
+    Scheduled,
+    Done,
+}
+
+/// Leaf future that schedules the [ReactorOp] in reactor and completes when the runtime was
+/// awoken for its event.
+pub struct ReactorFuture<'runtime, ReactorT: Reactor, OpT: ReactorOp<ReactorT>> {
+    rt: &'runtime Runtime<ReactorT>,
+    op: OpT,
+    state: ReactorFutureState,
+    event_node: EventNode,
+}
+
+impl<'runtime, ReactorT: Reactor, OpT: ReactorOp<ReactorT>> ReactorFuture<'runtime, ReactorT, OpT> {
+    /// Creates the future, the operation is scheduled in reactor on the first poll.
+    pub fn new(rt: &'runtime Runtime<ReactorT>, op: OpT) -> Self {
+        ReactorFuture {
+            rt,
+            op,
+            state: ReactorFutureState::Created,
+            event_node: EventNode::new(),
+        }
+    }
+
+    // Schedules the operation in the reactor.
+    fn schedule(&mut self, event_id: EventId) -> Poll<()> {
+        // Future has to be in "Created" state, so we cannot schedule the op twice.
+        debug_assert!(matches!(self.state, ReactorFutureState::Created));
+        self.state = ReactorFutureState::Scheduled;
+        self.op.schedule(self.rt, event_id);
+        Poll::Pending // always pending
+    }
+
+    // Verifies in runtime if the event of operation is ready
+    fn verify(&mut self) -> Poll<()> {
+        // Assumes that future has to be in "Scheduled" state
+        debug_assert!(matches!(self.state, ReactorFutureState::Scheduled));
+
+        if self.event_node.is_awoken_for(self.rt) {
+            self.state = ReactorFutureState::Done;
+            self.op.complete(self.rt, self.event_node.get_event_id());
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+// Cancels the operation in the reactor.
+impl<'runtime, ReactorT: Reactor, OpT: ReactorOp<ReactorT>> Drop
+    for ReactorFuture<'runtime, ReactorT, OpT>
+{
+    fn drop(&mut self) {
+        if let ReactorFutureState::Scheduled = self.state {
+            if let Some(event_id) = self.event_node.on_cancel() {
+                self.op.cancel(self.rt, event_id);
+            }
+        }
+    }
+}
+
+impl<'runtime, ReactorT: Reactor, OpT: ReactorOp<ReactorT>> Future
+    for ReactorFuture<'runtime, ReactorT, OpT>
+{
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        // Unsafe usage: this function does not moves out data from self, as required by
+        // Pin::get_unchecked_mut().
+        let this = unsafe { self.get_unchecked_mut() };
+
+        match this.state {
+            ReactorFutureState::Created if this.op.is_ready(this.rt) => {
+                this.state = ReactorFutureState::Done;
+                Poll::Ready(())
+            }
+            ReactorFutureState::Created => {
+                let event_id = unsafe { this.event_node.on_pin(ctx) };
+                this.schedule(event_id)
+            }
+            ReactorFutureState::Scheduled => this.verify(),
+            ReactorFutureState::Done => {
+                panic_polled_after_completion("ReactorFuture", &this.event_node.get_event_id())
+            }
+        }
+    }
+}
@@ -0,0 +1,97 @@
+//  \ O /
+//  / * \    aiur: the homeplanet for the famous executors
+// |' | '|   (c) 2020 - present, Vladimir Zvezda
+//   / \
+use alloc::boxed::Box;
+use core::future::Future;
+use core::pin::Pin;
+
+use crate::event_node::EventNode;
+use crate::reactor::{EventId, Reactor};
+use crate::runtime::{RunError, Runtime};
+use crate::task::Task;
+
+// enable/disable output of modtrace! macro
+const MODTRACE: bool = true;
+
+/// The root future driven step by step by the host event loop, see [Runtime::root_task()].
+///
+/// This is an alternative to [Runtime::nested_loop()] for embedding aiur into an external event
+/// loop: instead of running until the root future is completed, the host invokes
+/// [RootTask::poll_once()] whenever it is ready to let aiur make progress.
+pub struct RootTask<'runtime, ReactorT: Reactor, FutureT: Future> {
+    rt: &'runtime Runtime<ReactorT>,
+    task: Pin<Box<Task<FutureT>>>,
+    // EventNode is only used to have EventId for root completion, it is never pinned by a task
+    completion_node: Pin<Box<EventNode>>,
+    started: bool,
+    no_progress_iters: u32,
+}
+
+impl<'runtime, ReactorT: Reactor, FutureT: Future> RootTask<'runtime, ReactorT, FutureT> {
+    pub(crate) fn new(rt: &'runtime Runtime<ReactorT>, future: FutureT) -> Self {
+        let mut task = Box::pin(Task::new(future));
+        task.as_mut().on_pinned(); // assign self-references after pinning
+
+        RootTask {
+            rt,
+            task,
+            completion_node: Box::pin(EventNode::new()),
+            started: false,
+            no_progress_iters: 0,
+        }
+    }
+
+    /// Makes one step of the runtime loop: the first call polls the root future, the next calls
+    /// poll the tasks that are ready or wait for an event from reactor and poll the awoken task.
+    ///
+    /// Returns [RootTask::completion_event_id()] when the root future is completed,
+    /// [EventId::terminated()] when the reactor has been terminated, None otherwise. Must not
+    /// be invoked from async code running in this runtime.
+    ///
+    /// Panics if nothing can wake the root future anymore, see [RunError::Deadlock].
+    pub fn poll_once(&mut self) -> Option<EventId> {
+        if !self.is_root_complete() && !self.rt.is_terminated() {
+            if self.started {
+                let step_result = self.rt.loop_step(&self.task, &mut self.no_progress_iters);
+                if let Err(err @ RunError::Deadlock { .. }) = step_result {
+                    panic!("aiur: poll_once() cannot complete the root future: {:?}", err);
+                }
+            } else {
+                modtrace!(self.rt.tracer(), "root_task: first poll");
+                self.started = true;
+                self.rt.poll_task(&*self.task);
+            }
+        }
+
+        if self.is_root_complete() {
+            Some(self.completion_event_id())
+        } else if self.rt.is_terminated() {
+            Some(EventId::terminated())
+        } else {
+            None
+        }
+    }
+
+    /// Returns true if the root future has been completed.
+    pub fn is_root_complete(&self) -> bool {
+        self.task.is_completed()
+    }
+
+    /// The EventId that stands for the root future completion. The host loop that dispatches
+    /// events by EventId can use it to find out that aiur no longer has to be driven.
+    pub fn completion_event_id(&self) -> EventId {
+        self.completion_node.get_event_id()
+    }
+
+    /// Returns the result of the completed root future.
+    ///
+    /// Panics if the root future is not completed.
+    pub fn take_result(self) -> FutureT::Output {
+        assert!(
+            self.is_root_complete(),
+            "aiur: RootTask::take_result() invoked before the root future has been completed"
+        );
+        self.task.take_result()
+    }
+}
@@ -2,28 +2,246 @@
 //  / * \    aiur: the homeplanet for the famous executors
 // |' | '|   (c) 2020 - present, Vladimir Zvezda
 //   / \
-use std::cell::Cell;
-use std::cell::RefCell;
-use std::future::Future;
+use alloc::rc::Rc;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cell::Cell;
+use core::cell::RefCell;
+use core::future::Future;
+use core::time::Duration;
 
 use crate::channel_rt::ChannelRt;
 use crate::event_node::EventNode;
 use crate::oneshot_rt::OneshotRt;
 use crate::pin_local;
-use crate::reactor::{EventId, Reactor};
-use crate::task::{ITask, Task};
+use crate::reactor::{EventId, Reactor, TemporalReactor};
+use crate::root_task::RootTask;
+use crate::task::{current_task_id, ITask, PollResult, Task, TaskId};
 use crate::tracer::Tracer;
 
 // enable/disable output of modtrace! macro
 const MODTRACE: bool = true;
 
+// Bookmark in the list of frozen events, it removes itself from the list when dropped (even
+// if a task polled in poll_unfrozen() panics).
+struct Bookmark(EventNode);
+
+impl Drop for Bookmark {
+    fn drop(&mut self) {
+        unsafe { self.0.remove_bookmark() };
+    }
+}
+
+// Counts the nested_loop() in the stack while it is alive, so the depth is restored even if
+// the future in the nested loop panics and the panic is caught up in the stack.
+struct NestingGuard<'a>(&'a Cell<u32>);
+
+impl<'a> NestingGuard<'a> {
+    fn new(loop_depth: &'a Cell<u32>) -> Self {
+        loop_depth.set(loop_depth.get() + 1);
+        NestingGuard(loop_depth)
+    }
+}
+
+impl Drop for NestingGuard<'_> {
+    fn drop(&mut self) {
+        self.0.set(self.0.get() - 1);
+    }
+}
+
+/// Optional runtime settings, see [Runtime::set_config()].
+///
+/// ```
+/// let config = aiur::RuntimeConfig::new().stall_detector(1000);
+/// ```
+#[derive(Copy, Clone, Debug, Default)]
+pub struct RuntimeConfig {
+    max_no_progress_iters: Option<u32>,
+    schedule_policy: SchedulePolicy,
+    max_nesting_depth: Option<u32>, // None is DEFAULT_MAX_NESTING_DEPTH
+}
+
+/// The default limit for the number of [Runtime::nested_loop()] in the stack, see
+/// [RuntimeConfig::max_nesting_depth()].
+pub const DEFAULT_MAX_NESTING_DEPTH: u32 = 1024;
+
+/// How runtime orders the channel exchanges and the reactor events, see
+/// [RuntimeConfig::schedule_policy()].
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum SchedulePolicy {
+    /// All the channel and oneshot exchanges that are ready are done before runtime parks in
+    /// the reactor.
+    #[default]
+    DrainThenPark,
+    /// Runtime checks the reactor with [Reactor::try_wait()] after each round of exchanges,
+    /// so the ready I/O events are not delayed by a long chain of channel exchanges.
+    ParkEagerly,
+}
+
+impl RuntimeConfig {
+    /// Creates the default config: everything is off.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enables the stall detector: runtime panics when it has made `max_no_progress_iters`
+    /// iterations of its loop in a row without any progress. No progress means that runtime
+    /// has awoken a task, but no leaf future in the task has found the event for itself with
+    /// [Runtime::is_awoken_for()]. This can happen when a leaf future forgets to register its
+    /// event and busy-waits instead, such bugs would look like hang otherwise.
+    ///
+    /// Waiting in the reactor is not counted as no progress.
+    pub fn stall_detector(mut self, max_no_progress_iters: u32) -> Self {
+        self.max_no_progress_iters = Some(max_no_progress_iters);
+        self
+    }
+
+    /// Sets the schedule policy, the default is [SchedulePolicy::DrainThenPark].
+    pub fn schedule_policy(mut self, schedule_policy: SchedulePolicy) -> Self {
+        self.schedule_policy = schedule_policy;
+        self
+    }
+
+    /// Sets the limit for the number of [Runtime::nested_loop()] in the stack, the default is
+    /// [DEFAULT_MAX_NESTING_DEPTH]. The nested loop that would go deeper is not started and
+    /// returns [RunError::TooDeep], so the unbounded recursion of nested loops is reported
+    /// before it overflows the stack.
+    pub fn max_nesting_depth(mut self, max_nesting_depth: u32) -> Self {
+        self.max_nesting_depth = Some(max_nesting_depth);
+        self
+    }
+}
+
+/// Error returned by [Runtime::try_nested_loop()] and
+/// [try_with_runtime_base()](crate::try_with_runtime_base) when the loop ended without
+/// completing the future.
+#[derive(Debug, PartialEq)]
+pub enum RunError {
+    /// Reactor returned [EventId::terminated()] from wait().
+    Terminated,
+    /// Nothing can wake the future anymore: there are no oneshot or channel exchanges to do
+    /// and the reactor has nothing to wait for, see [Reactor::has_pending()]. The
+    /// `pending_tasks` is the number of tasks that are blocked: the task of the loop and the
+    /// tasks of the oneshot and channel futures waiting for exchange.
+    Deadlock { pending_tasks: usize },
+    /// The nested loop was not started because there are already `depth` nested loops in the
+    /// stack, see [RuntimeConfig::max_nesting_depth()].
+    TooDeep { depth: u32 },
+}
+
+/// Counters of the runtime returned by [Runtime::stats()], e.g. to find the channel that is
+/// never dropped.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct RuntimeStats {
+    /// The number of task polls made by runtime.
+    pub polls: u64,
+    /// The number of times runtime has asked the reactor for events, see
+    /// [Runtime::wait_count()].
+    pub waits: u64,
+    /// The number of channels alive right now.
+    pub channels: usize,
+    /// The number of oneshots alive right now.
+    pub oneshots: usize,
+    /// The number of reactor events saved for the frozen tasks.
+    pub frozen_events: usize,
+}
+
+/// Callbacks invoked by runtime at the points of its loop, e.g. to collect metrics like the
+/// number of polls without parsing the trace text. Installed with [Runtime::set_hooks()].
+///
+/// All methods do nothing by default. The hooks must not use the runtime.
+pub trait RuntimeHooks {
+    /// The task is about to be polled by runtime. The tasks polled by their parent task (e.g.
+    /// the tasks of [join_tasks!](crate::join_tasks)) are reported as the poll of the parent.
+    fn on_task_poll(&self, _task_id: TaskId) {}
+
+    /// The task polled by runtime has been completed.
+    fn on_task_complete(&self, _task_id: TaskId) {}
+
+    /// Runtime is about to ask the reactor for events.
+    fn on_reactor_wait(&self) {}
+
+    /// The event has awoken a task: the event from reactor or a channel or oneshot exchange.
+    fn on_event(&self, _event_id: EventId) {}
+}
+
+// What runtime does with the event from reactor
+enum ReactorEvent {
+    Terminated,
+    Frozen, // the event has been saved for the frozen task
+    Awoken(*const dyn ITask),
+}
+
+/// Builder of the [Runtime] that is created once and then drives several top-level futures one
+/// after another with [Runtime::block_on()], e.g. for benchmarks or REPL-like usage.
+///
+/// ```
+/// use aiur::toy_rt::{self, SleepMode, ToyReactor};
+/// use std::time::Duration;
+///
+/// let rt = toy_rt::RuntimeBuilder::new(ToyReactor::new_with_mode(SleepMode::Emulated))
+///     .config(aiur::RuntimeConfig::new().stall_detector(1000))
+///     .build();
+/// rt.block_on(toy_rt::sleep(&rt, Duration::from_millis(100)));
+/// assert_eq!(rt.block_on(async { 42 }), 42);
+/// ```
+pub struct RuntimeBuilder<ReactorT> {
+    reactor: ReactorT,
+    tracer: Tracer,
+    config: RuntimeConfig,
+}
+
+impl<ReactorT> RuntimeBuilder<ReactorT>
+where
+    ReactorT: Reactor,
+{
+    /// Starts building the runtime with the given reactor, no tracing and the default config.
+    pub fn new(reactor: ReactorT) -> Self {
+        Self {
+            reactor,
+            tracer: Tracer::new_empty(),
+            config: RuntimeConfig::new(),
+        }
+    }
+
+    /// Sets the tracer of the runtime.
+    pub fn tracer(mut self, tracer: Tracer) -> Self {
+        self.tracer = tracer;
+        self
+    }
+
+    /// Sets the runtime settings, see [Runtime::set_config()].
+    pub fn config(mut self, config: RuntimeConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Creates the runtime.
+    pub fn build(self) -> Runtime<ReactorT> {
+        let runtime = Runtime::new(self.reactor, self.tracer);
+        runtime.set_config(self.config);
+        runtime
+    }
+}
+
 /// The owner of the reactor (I/O event queue) and executor (task management) data structures.
 pub struct Runtime<ReactorT> {
     reactor: ReactorT,
     awoken_event_id: Cell<EventId>,
+    awoken_batch: RefCell<Vec<EventId>>, // more exchange events of the task being polled
+    consumed_events: Cell<u64>, // number of times is_awoken_for() returned true
+    wait_count: Cell<u64>,      // number of times reactor was asked for events
+    poll_count: Cell<u64>,      // number of task polls made by runtime
+    config: Cell<RuntimeConfig>,
+    shutting_down: Cell<bool>,
+    sleeps: RefCell<Vec<EventId>>, // events of the sleep() timers scheduled in reactor
+    terminated: Cell<bool>,
     oneshot_rt: OneshotRt,
     channel_rt: ChannelRt,
     frozen_list: RefCell<EventNode>, // can we have cell here?
+    frozen_scan_count: Cell<usize>,   // number of nodes visited in frozen_list
+    loop_depth: Cell<u32>,            // number of nested_loop() in the stack
+    hooks: RefCell<Option<Rc<dyn RuntimeHooks>>>,
     tracer: Tracer,
 }
 
@@ -31,13 +249,27 @@ impl<ReactorT> Runtime<ReactorT>
 where
     ReactorT: Reactor,
 {
-    pub(crate) fn new(reactor: ReactorT, tracer: Tracer) -> Self {
+    /// Creates the runtime. Usually the runtime is created by
+    /// [with_runtime_base()](crate::with_runtime_base), creating it directly is for embedding
+    /// with [Runtime::root_task()].
+    pub fn new(reactor: ReactorT, tracer: Tracer) -> Self {
         Self {
             reactor,
             awoken_event_id: Cell::new(EventId::null()),
+            awoken_batch: RefCell::new(Vec::new()),
+            consumed_events: Cell::new(0),
+            wait_count: Cell::new(0),
+            poll_count: Cell::new(0),
+            config: Cell::new(RuntimeConfig::new()),
+            shutting_down: Cell::new(false),
+            sleeps: RefCell::new(Vec::new()),
+            terminated: Cell::new(false),
             oneshot_rt: OneshotRt::new(&tracer),
             channel_rt: ChannelRt::new(&tracer),
             frozen_list: RefCell::new(EventNode::new()),
+            frozen_scan_count: Cell::new(0),
+            loop_depth: Cell::new(0),
+            hooks: RefCell::new(None),
             tracer,
         }
     }
@@ -51,33 +283,172 @@ where
     }
 
     pub(crate) fn jump_phase(&self) {
-        self.oneshot_phase();
-        self.channel_phase();
+        self.sleep_phase();
+        match self.config().schedule_policy {
+            SchedulePolicy::DrainThenPark => {
+                self.oneshot_phase();
+                self.channel_phase();
+            }
+            SchedulePolicy::ParkEagerly => {
+                while !self.is_terminated() && self.jump_round() {
+                    self.check_reactor();
+                }
+            }
+        }
     }
 
     fn channel_phase(&self) {
         // do the channel exchange until there is no more channels
+        let mut events = Vec::new();
         loop {
-            if let Some(event_id) = self.channels().get_awake_event_id() {
-                let awoken_task = event_id.as_event_node().get_itask_ptr();
-                self.awoken_event_id.set(event_id);
-                unsafe { (*awoken_task).poll() };
-            } else {
+            self.channels().awake_event_ids(&mut events);
+            if events.is_empty() {
                 break;
             }
+            self.poll_exchanges(&mut events);
+        }
+    }
+
+    // Completes the sleeps that were scheduled in reactor before the runtime began shutting
+    // down, they are awoken here as if their timers were due.
+    fn sleep_phase(&self) {
+        if !self.is_shutting_down() {
+            return;
+        }
+
+        loop {
+            let event_id = self.sleeps.borrow_mut().pop();
+            match event_id {
+                Some(event_id) => {
+                    modtrace!(self.tracer(), "runtime: {:?} sleep awoken by shutdown", event_id);
+                    if let ReactorEvent::Awoken(awoken_task) = self.accept_event(event_id) {
+                        unsafe { self.poll_task(&*awoken_task) };
+                    }
+                }
+                None => break,
+            }
+        }
+    }
+
+    // Registers the event of sleep() timer scheduled in reactor, so the sleep can be awoken
+    // by begin_shutdown().
+    pub(crate) fn add_sleep(&self, event_id: EventId) {
+        self.sleeps.borrow_mut().push(event_id);
+    }
+
+    // Unregisters the event of sleep() timer, returns false if it has not been registered,
+    // i.e. the sleep has been awoken by shutdown and its timer is still in reactor.
+    pub(crate) fn remove_sleep(&self, event_id: EventId) -> bool {
+        let mut sleeps = self.sleeps.borrow_mut();
+        match sleeps.iter().position(|sleep| *sleep == event_id) {
+            Some(index) => {
+                sleeps.swap_remove(index);
+                true
+            }
+            None => false,
         }
     }
 
     fn oneshot_phase(&self) {
-        // do the channel exchange until there is no more oneshots
+        // do the oneshot exchange until there is no more oneshots
+        let mut events = Vec::new();
         loop {
-            if let Some(event_id) = self.oneshots().get_awake_event_id() {
-                let awoken_task = event_id.as_event_node().get_itask_ptr();
-                self.awoken_event_id.set(event_id);
-                unsafe { (*awoken_task).poll() };
-            } else {
+            self.oneshots().awake_event_ids(&mut events);
+            if events.is_empty() {
                 break;
             }
+            self.poll_exchanges(&mut events);
+        }
+    }
+
+    // Polls the task of the first event once for all the events of this task, so the task
+    // awoken by several exchanges in the phase (e.g. join! of oneshots) is not polled for each
+    // of them. The poll may change the events of other tasks, so the events are cleared and
+    // the caller has to scan for them again.
+    fn poll_exchanges(&self, events: &mut Vec<EventId>) {
+        let awoken_task = Self::awoken_ancestor(events[0]);
+        let awoken_event_id = events[0];
+        {
+            let mut batch = self.awoken_batch.borrow_mut();
+            batch.clear();
+            batch.extend(events.drain(..).skip(1).filter(|event_id| {
+                Self::awoken_ancestor(*event_id) as *const () == awoken_task as *const ()
+            }));
+        }
+
+        self.awoken_event_id.set(awoken_event_id);
+        self.call_hooks(|hooks| {
+            hooks.on_event(awoken_event_id);
+            self.awoken_batch
+                .borrow()
+                .iter()
+                .for_each(|event_id| hooks.on_event(*event_id));
+        });
+        unsafe { self.poll_task(&*awoken_task) };
+        self.awoken_batch.borrow_mut().clear();
+    }
+
+    // Makes at most one oneshot and one channel exchange. Returns false if there was nothing
+    // to exchange.
+    fn jump_round(&self) -> bool {
+        let oneshot_event = self.oneshots().get_awake_event_id();
+        if let Some(event_id) = oneshot_event {
+            self.poll_exchange(event_id);
+        }
+
+        let channel_event = self.channels().get_awake_event_id();
+        if let Some(event_id) = channel_event {
+            self.poll_exchange(event_id);
+        }
+
+        oneshot_event.is_some() || channel_event.is_some()
+    }
+
+    // Polls the task that has oneshot or channel exchange event
+    fn poll_exchange(&self, event_id: EventId) {
+        let awoken_task = Self::awoken_ancestor(event_id);
+        self.awoken_event_id.set(event_id);
+        self.call_hooks(|hooks| hooks.on_event(event_id));
+        unsafe { self.poll_task(&*awoken_task) };
+    }
+
+    // Polls the task if the reactor has an event ready right now
+    fn check_reactor(&self) {
+        self.wait_count.set(self.wait_count.get() + 1);
+        self.call_hooks(|hooks| hooks.on_reactor_wait());
+        if let Some(event_id) = self.io().try_wait() {
+            if let ReactorEvent::Awoken(awoken_task) = self.accept_event(event_id) {
+                unsafe { self.poll_task(&*awoken_task) };
+            }
+        }
+    }
+
+    // All the polls of the tasks made by runtime are here, so the hooks see them
+    pub(crate) fn poll_task(&self, task: &dyn ITask) {
+        self.poll_count.set(self.poll_count.get() + 1);
+        self.call_hooks(|hooks| hooks.on_task_poll(task.id()));
+        if let PollResult::Ready = task.poll() {
+            self.call_hooks(|hooks| hooks.on_task_complete(task.id()));
+        }
+    }
+
+    // Invokes the hooks if they are installed
+    fn call_hooks<HooksFn: FnOnce(&dyn RuntimeHooks)>(&self, hooks_fn: HooksFn) {
+        if let Some(ref hooks) = *self.hooks.borrow() {
+            hooks_fn(&**hooks);
+        }
+    }
+
+    // Returns the task to poll for the event: the first unfrozen ancestor of the event task,
+    // so the parent tasks (e.g. join_tasks!) can see that the child has been completed.
+    fn awoken_ancestor(event_id: EventId) -> *const dyn ITask {
+        let itask_ptr = event_id.as_event_node().get_itask_ptr();
+        unsafe {
+            if (*itask_ptr).is_frozen() {
+                itask_ptr // poll() of the frozen task returns PollResult::Frozen
+            } else {
+                (*itask_ptr).unfrozen_ancestor()
+            }
         }
     }
 
@@ -85,26 +456,82 @@ where
         &self.tracer
     }
 
-    fn wait(&self) -> *const dyn ITask {
+    /// Returns the number of [Runtime::nested_loop()] invocations in the stack right now, it is
+    /// 0 outside of the runtime loop.
+    pub fn nesting_depth(&self) -> u32 {
+        self.loop_depth.get()
+    }
+
+    // Returns error if reactor has been terminated or there is nothing to wait, None if the
+    // reactor has returned without an event.
+    fn wait(&self, loop_task: &dyn ITask) -> Result<Option<*const dyn ITask>, RunError> {
         // loop because that event from reactor may come for a frozen task
         loop {
+            if !self.io().has_pending() {
+                break Err(self.deadlock(loop_task));
+            }
+
             // Waiting for an event from reactor. The itask pointer of the task in the awoken is
             // saved by Waker.wake().
+            self.wait_count.set(self.wait_count.get() + 1);
+            self.call_hooks(|hooks| hooks.on_reactor_wait());
             let event_id = self.io().wait();
-            let itask_ptr = event_id.as_event_node().get_itask_ptr();
+            if event_id == EventId::null() {
+                break Ok(None); // e.g. ToyReactor in manual mode has no timers due
+            }
 
-            unsafe {
-                if (*itask_ptr).is_frozen() {
-                    // we cannot poll the task because it is frozen. Save the event somewhere.
-                    self.save_event_for_frozen_task(event_id);
-                    continue; // have to wait for another task
-                } else {
-                    // Save the event_id to awoken.
-                    self.awoken_event_id.set(event_id);
-
-                    // return task pointer to root task or first unfrozen ancestor
-                    break (*itask_ptr).unfrozen_ancestor();
-                }
+            match self.accept_event(event_id) {
+                ReactorEvent::Terminated => break Err(RunError::Terminated),
+                ReactorEvent::Frozen => continue, // have to wait for another task
+                ReactorEvent::Awoken(awoken_task) => break Ok(Some(awoken_task)),
+            }
+        }
+    }
+
+    // Counts the tasks blocked by the deadlock: the task of the loop and the tasks of the
+    // registered oneshot and channel futures.
+    fn deadlock(&self, loop_task: &dyn ITask) -> RunError {
+        let mut events = Vec::new();
+        self.oneshots().registered_events(&mut events);
+        self.channels().registered_events(&mut events);
+
+        let mut tasks = vec![loop_task as *const dyn ITask as *const ()];
+        for event_id in events {
+            let task = event_id.as_event_node().get_itask_ptr() as *const ();
+            if !tasks.contains(&task) {
+                tasks.push(task);
+            }
+        }
+
+        modtrace!(self.tracer(), "runtime: deadlock, {} tasks blocked", tasks.len());
+        RunError::Deadlock {
+            pending_tasks: tasks.len(),
+        }
+    }
+
+    // Finds the task to poll for the event from reactor
+    fn accept_event(&self, event_id: EventId) -> ReactorEvent {
+        if event_id.is_terminated() {
+            modtrace!(self.tracer(), "runtime: reactor terminated");
+            self.terminated.set(true);
+            self.begin_shutdown();
+            return ReactorEvent::Terminated;
+        }
+
+        let itask_ptr = event_id.as_event_node().get_itask_ptr();
+
+        unsafe {
+            if (*itask_ptr).is_frozen() {
+                // we cannot poll the task because it is frozen. Save the event somewhere.
+                self.save_event_for_frozen_task(event_id);
+                ReactorEvent::Frozen
+            } else {
+                // Save the event_id to awoken.
+                self.awoken_event_id.set(event_id);
+                self.call_hooks(|hooks| hooks.on_event(event_id));
+
+                // return task pointer to root task or first unfrozen ancestor
+                ReactorEvent::Awoken((*itask_ptr).unfrozen_ancestor())
             }
         }
     }
@@ -116,28 +543,87 @@ where
 
     // Polls if there is something unfrozen in the list of frozen events
     fn poll_unfrozen(&self) {
+        // The scan continues from the bookmark instead of the head of the list. The events
+        // before the bookmark are for the tasks that remain frozen while this function runs:
+        // the tasks are frozen by the nested loops up in the stack. The new events are added
+        // to the end of the list, so they are after the bookmark.
+        let mut bookmark = Bookmark(EventNode::new());
+        let bookmark = &mut bookmark.0 as *mut EventNode;
+        unsafe { self.frozen_list.borrow_mut().insert_bookmark(bookmark) };
+
         // loop until there is something we can find in the list of frozen events
-        while let Some(unfrozen) = self.find_unfrozen_event() {
+        while let Some(unfrozen) = self.find_unfrozen_event(bookmark) {
             self.awoken_event_id.set(unfrozen);
+            self.call_hooks(|hooks| hooks.on_event(unfrozen));
             unsafe {
                 let itask_ptr = unfrozen.as_event_node().get_itask_ptr();
-                (*(*itask_ptr).unfrozen_ancestor()).poll();
+                self.poll_task(&*(*itask_ptr).unfrozen_ancestor());
             }
         }
     }
 
-    // Scans the frozen events array if there is an event for task that unfrozen right now.
-    // Removes such event from frozen_events array.
-    fn find_unfrozen_event(&self) -> Option<EventId> {
-        let mut list = self.frozen_list.borrow_mut();
-        unsafe { list.find_unfrozen().map(|event_id| event_id) }
+    // Scans the frozen events list after the bookmark if there is an event for task that
+    // unfrozen right now. Removes such event from the list.
+    fn find_unfrozen_event(&self, bookmark: *mut EventNode) -> Option<EventId> {
+        let _list = self.frozen_list.borrow_mut();
+        let (event_id, visited) = unsafe { (*bookmark).find_unfrozen() };
+        self.frozen_scan_count
+            .set(self.frozen_scan_count.get() + visited);
+        event_id
+    }
+
+    /// Runs the future to completion and returns its result: this is the top-level driver for
+    /// the runtime that has been created with [Runtime::new()].
+    ///
+    /// It is the same loop as [Runtime::nested_loop()], the different name is only to make the
+    /// intent clear at the call site. Use [with_runtime_base](crate::with_runtime_base()) when
+    /// the future borrows the runtime that is created for it.
+    ///
+    /// Panics if the reactor has been terminated or there is a deadlock before the future is
+    /// completed.
+    pub fn block_on<FutureT: Future>(&self, future: FutureT) -> FutureT::Output {
+        self.nested_loop(future)
+    }
+
+    /// Same as [Runtime::block_on()], but returns error instead of panic when the future cannot
+    /// be completed, e.g. [RunError::Deadlock] when the future waits for something that is
+    /// never going to happen.
+    pub fn try_block_on<FutureT: Future>(
+        &self,
+        future: FutureT,
+    ) -> Result<FutureT::Output, RunError> {
+        self.try_nested_loop(future)
     }
 
-    //
+    /// Runs the loop until the future is completed and returns its result.
+    ///
+    /// Panics if the reactor has been terminated or there is a deadlock before the future is
+    /// completed, see [Runtime::try_nested_loop()].
     pub fn nested_loop<FutureT, ResultT>(&self, future: FutureT) -> ResultT
     where
         FutureT: Future<Output = ResultT>,
     {
+        match self.try_nested_loop(future) {
+            Ok(res) => res,
+            Err(err) => panic!(
+                "aiur: nested_loop() exited with {:?} before the future completed",
+                err
+            ),
+        }
+    }
+
+    /// Same as [Runtime::nested_loop()], but returns error instead of panic when the loop exits
+    /// without completing the future. In this case the future is dropped.
+    pub fn try_nested_loop<FutureT, ResultT>(&self, future: FutureT) -> Result<ResultT, RunError>
+    where
+        FutureT: Future<Output = ResultT>,
+    {
+        let max_depth = self.config().max_nesting_depth;
+        let depth = self.loop_depth.get();
+        if depth >= max_depth.unwrap_or(DEFAULT_MAX_NESTING_DEPTH) {
+            return Err(RunError::TooDeep { depth });
+        }
+
         // Put a future in a task and pin the task
         let task = Task::new(future);
         pin_local!(task);
@@ -148,37 +634,190 @@ where
 
         modtrace!(self.tracer(), "runtime: nested loop for task");
 
+        let nesting = NestingGuard::new(&self.loop_depth);
+
         // Polls the future once to give it chance to schedule its i/o in reactor. It
         // is possible that this poll() call would make some other nested_loop().
-        task.poll();
+        self.poll_task(&*task);
+
+        let mut no_progress_iters = 0;
+        let mut step_result = Ok(());
+        while step_result.is_ok() && !task.is_completed() && !self.is_terminated() {
+            step_result = self.loop_step(&task, &mut no_progress_iters);
+        }
+        drop(nesting);
 
-        while !task.is_completed() {
-            self.poll_unfrozen();
+        // The events of this task left in the frozen list are removed from there by its leaf
+        // futures when the task is dropped, see EventNode::on_cancel().
+        modtrace!(self.tracer(), "runtime: exit nested loop for the task");
+        match step_result {
+            _ if task.is_completed() => Ok(task.take_result()),
+            Err(err) => Err(err),
+            Ok(()) => Err(RunError::Terminated), // terminated in a loop up in the stack
+        }
+    }
 
-            if task.is_completed() {
-                break;
-            }
+    /// Creates the root task for the future that can be driven by an external event loop with
+    /// [RootTask::poll_once()] instead of [Runtime::nested_loop()].
+    pub fn root_task<FutureT: Future>(&self, future: FutureT) -> RootTask<'_, ReactorT, FutureT> {
+        RootTask::new(self, future)
+    }
 
-            self.jump_phase();
+    // Makes one iteration of the runtime loop for the task: polls the tasks that can be polled
+    // without waiting or waits for the reactor event and polls the awoken task. Returns error
+    // if the reactor has been terminated or the task cannot be awoken anymore.
+    pub(crate) fn loop_step<FutureT: Future>(
+        &self,
+        task: &Task<FutureT>,
+        no_progress_iters: &mut u32,
+    ) -> Result<(), RunError> {
+        let consumed_events = self.consumed_events.get();
 
-            if task.is_completed() {
-                break;
-            }
+        self.poll_unfrozen();
 
-            // Await the reactor i/o
-            let awoken_task = self.wait();
-            unsafe { (*awoken_task).poll() };
+        if task.is_completed() {
+            return Ok(());
         }
 
-        // todo: remove this task from frozen events if any
+        self.jump_phase();
 
-        modtrace!(self.tracer(), "runtime: exit nested loop for the task");
-        task.take_result()
+        if task.is_completed() {
+            return Ok(());
+        }
+
+        // Await the reactor i/o
+        if let Some(awoken_task) = self.wait(task)? {
+            unsafe { self.poll_task(&*awoken_task) };
+        }
+
+        if self.consumed_events.get() == consumed_events {
+            *no_progress_iters += 1;
+            self.check_stall(*no_progress_iters);
+        } else {
+            *no_progress_iters = 0;
+        }
+        Ok(())
+    }
+
+    // Panics if stall detector is on and the threshold is hit
+    fn check_stall(&self, no_progress_iters: u32) {
+        if let Some(max_iters) = self.config().max_no_progress_iters {
+            if no_progress_iters >= max_iters {
+                panic!(
+                    concat!(
+                        "aiur: stall detected, {} runtime iterations without progress. The last ",
+                        "awoken {:?} was not consumed by any leaf future with is_awoken_for()."
+                    ),
+                    no_progress_iters,
+                    self.awoken_event_id.get()
+                );
+            }
+        }
     }
 
     /// Used by a leaf feature in poll() method to verify if it was the reason it was awoken.
     pub fn is_awoken_for(&self, event_id: EventId) -> bool {
-        self.awoken_event_id.get() == event_id
+        let awoken = self.awoken_event_id.get() == event_id || self.take_batched(event_id);
+        if awoken {
+            self.consumed_events.set(self.consumed_events.get() + 1);
+        }
+        awoken
+    }
+
+    // Removes the event from the batch of the exchange events of the task being polled, so
+    // the event is consumed only once. Returns false if the event is not there.
+    fn take_batched(&self, event_id: EventId) -> bool {
+        let mut batch = self.awoken_batch.borrow_mut();
+        match batch.iter().position(|batched| *batched == event_id) {
+            Some(pos) => {
+                batch.swap_remove(pos);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the id of the task that is being polled right now, e.g. to have it in log lines.
+    /// The futures in [join!](crate::join) share the same task, but each future in
+    /// [join_tasks!](crate::join_tasks) is a task on its own.
+    ///
+    /// Returns None if invoked not from a future, e.g. between [RootTask::poll_once()] calls.
+    pub fn current_task_id(&self) -> Option<TaskId> {
+        current_task_id()
+    }
+
+    /// Marks the runtime as shutting down, so the futures that wait for something that may
+    /// never happen complete with error instead of hanging:
+    ///   * channel send() and next() futures that are waiting for another channel half return
+    ///     error, `RecvError::Shutdown` for the receiver and the value for the sender,
+    ///   * a sleep() completes right away, both the one started after this call and the
+    ///     one that is already waiting for its timer.
+    ///
+    /// There is no way back from the shutting down state.
+    pub fn begin_shutdown(&self) {
+        modtrace!(self.tracer(), "runtime: begin shutdown");
+        self.shutting_down.set(true);
+        self.channels().begin_shutdown();
+    }
+
+    /// Returns true if the reactor has returned [EventId::terminated()]. Runtime is shutting
+    /// down then and the loops exit without waiting for the futures to complete.
+    pub fn is_terminated(&self) -> bool {
+        self.terminated.get()
+    }
+
+    /// Returns true if [Runtime::begin_shutdown()] has been invoked.
+    pub fn is_shutting_down(&self) -> bool {
+        self.shutting_down.get()
+    }
+
+    /// Installs the hooks that are invoked at the points of runtime loop, see [RuntimeHooks].
+    /// None removes the hooks.
+    pub fn set_hooks(&self, hooks: Option<Rc<dyn RuntimeHooks>>) {
+        *self.hooks.borrow_mut() = hooks;
+    }
+
+    /// Changes the runtime settings, see [RuntimeConfig].
+    pub fn set_config(&self, config: RuntimeConfig) {
+        self.config.set(config);
+    }
+
+    /// Returns the current runtime settings.
+    pub fn config(&self) -> RuntimeConfig {
+        self.config.get()
+    }
+
+    /// Returns the number of times runtime has asked the reactor for events: both waiting in
+    /// [Reactor::wait()] and checking with [Reactor::try_wait()].
+    pub fn wait_count(&self) -> u64 {
+        self.wait_count.get()
+    }
+
+    /// Returns the counters of the runtime, see [RuntimeStats].
+    pub fn stats(&self) -> RuntimeStats {
+        RuntimeStats {
+            polls: self.poll_count.get(),
+            waits: self.wait_count.get(),
+            channels: self.channels().node_count(),
+            oneshots: self.oneshots().node_count(),
+            frozen_events: unsafe { self.frozen_list.borrow().list_len() },
+        }
+    }
+
+    /// Panics if there are channels, oneshots or the events of frozen tasks left in runtime.
+    /// This is for the tests: when the top-level future is completed everything it has created
+    /// should be gone, otherwise some future or channel half has not cleaned up after itself.
+    ///
+    /// With the `debug-leak-check` feature [with_runtime_base()](crate::with_runtime_base)
+    /// invokes it after the async function is completed.
+    pub fn assert_no_leaks(&self) {
+        let stats = self.stats();
+        if stats.channels != 0 || stats.oneshots != 0 || stats.frozen_events != 0 {
+            panic!(
+                "aiur: leak detected: {} channels, {} oneshots, {} frozen events are left",
+                stats.channels, stats.oneshots, stats.frozen_events
+            );
+        }
     }
 
     /// Returns reference to reactor.
@@ -186,3 +825,56 @@ where
         &self.reactor
     }
 }
+
+impl<ReactorT> Runtime<ReactorT>
+where
+    ReactorT: TemporalReactor,
+{
+    /// Cancels all timers scheduled in reactor. This is for shutting down the runtime: the
+    /// sleeping futures are never awoken after that.
+    pub fn cancel_all_timers(&self) {
+        modtrace!(self.tracer(), "runtime: cancel all timers");
+        self.io().cancel_all_timers();
+    }
+
+    /// Returns the time passed since the reactor was created, see [TemporalReactor::now()].
+    pub fn elapsed(&self) -> Duration {
+        self.io().now()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::toy_rt::{self, SleepMode};
+    use core::time::Duration;
+
+    // Completes when all the n sleeps are completed
+    async fn sleep_all(rt: &toy_rt::Runtime, n: usize, ms: u64) {
+        let sleeps = (0..n).map(|_| toy_rt::sleep(rt, Duration::from_millis(ms)));
+        let mut stream = toy_rt::any_of_vec(sleeps.collect());
+        while stream.next().await.is_some() {}
+    }
+
+    // The events for the tasks frozen up in the stack are ahead of the events of the task that
+    // is unfrozen first. poll_unfrozen() should not rescan them for every event it finds.
+    #[test]
+    fn frozen_list_is_not_rescanned_from_head() {
+        const N: usize = 100; // events of the root task
+        const M: usize = 100; // events of the task in nested loop
+
+        async fn async_starter(rt: &toy_rt::Runtime, _: ()) -> usize {
+            toy_rt::join!(sleep_all(rt, N, 1000), async {
+                rt.nested_loop(toy_rt::join!(sleep_all(rt, M, 1500), async {
+                    rt.nested_loop(toy_rt::sleep(rt, Duration::from_millis(2000)))
+                }))
+            })
+            .await;
+
+            rt.frozen_scan_count.get()
+        }
+
+        let scan_count = toy_rt::with_runtime_in_mode(SleepMode::Emulated, async_starter, ());
+        // Scanning from the head each time is about N * M
+        assert!(scan_count <= 4 * (N + M), "scan count: {}", scan_count);
+    }
+}
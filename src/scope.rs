@@ -0,0 +1,222 @@
+//  \ O /
+//  / * \    aiur: the homeplanet for the famous executors
+// |' | '|   (c) 2020 - present, Vladimir Zvezda
+//   / \
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+use crate::oneshot::{oneshot, RecverOnce};
+use crate::reactor::Reactor;
+use crate::runtime::Runtime;
+use crate::task::{ITask, Task};
+
+/// The owner of the tasks spawned while the scope is running, see [Scope::run()].
+///
+/// The spawned future can borrow anything that outlives the scope (the `'scope` lifetime), the
+/// compiler rejects the borrows of data declared after the scope. To give the task its own
+/// data move it into the future with `async move`, see [Scope::spawn_owned()].
+///
+/// ```
+/// use aiur::toy_rt;
+///
+/// async fn async_main(rt: &toy_rt::Runtime, _: ()) -> u32 {
+///     let scope = toy_rt::Scope::new(rt);
+///     scope
+///         .run(async {
+///             let numbers = vec![1, 2, 3];
+///             let sum = scope.spawn_owned(async move { numbers.iter().sum::<u32>() });
+///             sum.await.unwrap()
+///         })
+///         .await
+/// }
+///
+/// let sum = toy_rt::with_runtime_in_mode(toy_rt::SleepMode::Emulated, async_main, ());
+/// assert_eq!(sum, 6);
+/// ```
+pub struct Scope<'runtime, 'scope, ReactorT: Reactor> {
+    rt: &'runtime Runtime<ReactorT>,
+    // Tasks are boxed, so they have stable address while the vector grows
+    tasks: RefCell<Vec<Pin<Box<dyn ScopeTask + 'scope>>>>,
+}
+
+impl<'runtime, 'scope, ReactorT: Reactor> Scope<'runtime, 'scope, ReactorT> {
+    /// Creates the scope without tasks.
+    pub fn new(rt: &'runtime Runtime<ReactorT>) -> Self {
+        Scope {
+            rt,
+            tasks: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Spawns the future as a task of this scope. The task is polled by [Scope::run()]
+    /// concurrently with the body and the other tasks. The task cannot borrow the scope
+    /// itself, so the tasks are spawned before the run() or from its body.
+    pub fn spawn<FutT>(&self, future: FutT)
+    where
+        FutT: Future<Output = ()> + 'scope,
+    {
+        self.tasks.borrow_mut().push(Box::pin(Task::new(future)));
+    }
+
+    /// Spawns the future that owns its data and returns the receiver for the future output,
+    /// so the receiver works as a join handle. The data is moved in with `async move`:
+    ///
+    /// ```ignore
+    /// let numbers = vec![1, 2, 3];
+    /// let sum = scope.spawn_owned(async move { numbers.iter().sum::<u32>() });
+    /// ```
+    ///
+    /// The task is completed when its output is received or the receiver is dropped.
+    pub fn spawn_owned<FutT>(&self, future: FutT) -> RecverOnce<'runtime, FutT::Output, ReactorT>
+    where
+        FutT: Future + 'scope,
+        'runtime: 'scope,
+    {
+        let (mut tx, rx) = oneshot(self.rt);
+        self.spawn(async move {
+            let _ = tx.send(future.await).await; // error: receiver dropped, nobody cares
+        });
+        rx
+    }
+
+    /// Spawns the future and returns the handle that yields the future output when awaited.
+    /// Unlike [Scope::spawn_owned()] the handle gives the output itself, not a `Result`.
+    ///
+    /// If the handle is dropped before the task is completed, the task keeps running and its
+    /// output is discarded.
+    pub fn spawn_handle<FutT>(&self, future: FutT) -> JoinHandle<'runtime, FutT::Output, ReactorT>
+    where
+        FutT: Future + 'scope,
+        'runtime: 'scope,
+    {
+        JoinHandle {
+            recver: self.spawn_owned(future),
+        }
+    }
+
+    /// Polls the body and the spawned tasks until all of them are completed. Returns the
+    /// output of the body.
+    ///
+    /// The tasks that are not completed when the scope is dropped are cancelled.
+    pub async fn run<FutT: Future>(&self, body: FutT) -> FutT::Output {
+        ScopeRun {
+            scope: self,
+            body,
+            result: None,
+        }
+        .await
+    }
+
+    // Polls all the tasks including the ones spawned during this poll and removes the
+    // completed ones. Returns true if there is no tasks left.
+    fn poll_tasks(&self, ctx: &Context<'_>) -> bool {
+        let mut index = 0;
+        loop {
+            // The task can spawn another one, so the vector must not be borrowed during poll
+            let task_ptr = match self.tasks.borrow().get(index) {
+                Some(task) => &**task as *const (dyn ScopeTask + 'scope),
+                None => break,
+            };
+
+            unsafe {
+                (*task_ptr).assign_parent(ctx);
+                (*task_ptr).poll();
+            }
+            index += 1;
+        }
+
+        let mut tasks = self.tasks.borrow_mut();
+        tasks.retain(|task| !task.is_completed());
+        tasks.is_empty()
+    }
+}
+
+/// The handle of the task spawned with [Scope::spawn_handle()], awaiting it yields the output
+/// of the task.
+///
+/// Panics when awaited if the task has been cancelled because the scope was dropped before
+/// the task was completed.
+pub struct JoinHandle<'runtime, T, ReactorT: Reactor> {
+    recver: RecverOnce<'runtime, T, ReactorT>,
+}
+
+impl<'runtime, T, ReactorT: Reactor> Future for JoinHandle<'runtime, T, ReactorT> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        // Unsafe usage: this function does not moves out data from self, as required by
+        // Pin::map_unchecked_mut().
+        let recver = unsafe { self.map_unchecked_mut(|this| &mut this.recver) };
+        match recver.poll(ctx) {
+            Poll::Ready(Ok(output)) => Poll::Ready(output),
+            Poll::Ready(Err(_)) => panic!("aiur: JoinHandle awaited for the cancelled task"),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+// The Task API the scope needs without knowing the future type
+trait ScopeTask {
+    fn assign_parent(&self, ctx: &Context<'_>);
+    fn poll(&self);
+    fn is_completed(&self) -> bool;
+}
+
+impl<FutT: Future<Output = ()>> ScopeTask for Task<FutT> {
+    fn assign_parent(&self, ctx: &Context<'_>) {
+        Task::assign_parent(self, ctx);
+    }
+
+    fn poll(&self) {
+        ITask::poll(self);
+    }
+
+    fn is_completed(&self) -> bool {
+        Task::is_completed(self)
+    }
+}
+
+// Future returned by Scope::run()
+struct ScopeRun<'scope_ref, 'runtime, 'scope, ReactorT: Reactor, FutT: Future> {
+    scope: &'scope_ref Scope<'runtime, 'scope, ReactorT>,
+    body: FutT,
+    result: Option<FutT::Output>,
+}
+
+impl<'scope_ref, 'runtime, 'scope, ReactorT, FutT> Future
+    for ScopeRun<'scope_ref, 'runtime, 'scope, ReactorT, FutT>
+where
+    ReactorT: Reactor,
+    FutT: Future,
+{
+    type Output = FutT::Output;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        // Unsafe usage: this function does not moves out data from self, as required by
+        // Pin::get_unchecked_mut().
+        let this = unsafe { self.get_unchecked_mut() };
+
+        if this.result.is_none() {
+            let body = unsafe { Pin::new_unchecked(&mut this.body) };
+            if let Poll::Ready(result) = body.poll(ctx) {
+                this.result = Some(result);
+            }
+        }
+
+        // It is important to verify completion after all polls, the poll of a task can make
+        // another one completed because of a nested loop.
+        let tasks_done = this.scope.poll_tasks(ctx);
+
+        match this.result.take() {
+            Some(result) if tasks_done => Poll::Ready(result),
+            result => {
+                this.result = result;
+                Poll::Pending
+            }
+        }
+    }
+}
@@ -0,0 +1,142 @@
+//  \ O /
+//  / * \    aiur: the homeplanet for the famous executors
+// |' | '|   (c) 2020 - present, Vladimir Zvezda
+//   / \
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+use crate::pin_local;
+
+/// Awaits the first of the arm futures and runs the body of its arm with the output bound to
+/// the arm pattern.
+///
+/// The arm is `pattern = future => body`, the pattern must be irrefutable. Only one arm body
+/// runs: the futures of other arms are dropped before it, so whatever they have registered in
+/// runtime (a timer, a pinned channel future) is cancelled. Internally it is a match on the
+/// result of [race2()](crate::race2) .. [race8()](crate::race8) based on the number of arms,
+/// so up to 8 arms are supported. It awaits, so it can only be used in async code.
+///
+/// ```
+/// use aiur::toy_rt;
+/// use std::time::Duration;
+///
+/// async fn async_main(rt: &toy_rt::Runtime, _: ()) -> Option<u32> {
+///     let (_tx, mut rx) = toy_rt::channel::<u32>(rt);
+///     toy_rt::select! {
+///         value = rx.next() => value.ok(),
+///         _ = toy_rt::sleep(rt, Duration::from_millis(100)) => None,
+///     }
+/// }
+///
+/// let value = toy_rt::with_runtime_in_mode(toy_rt::SleepMode::Emulated, async_main, ());
+/// assert_eq!(value, None);
+/// ```
+#[macro_export]
+macro_rules! select {
+    ($p1:pat = $f1:expr => $b1:expr, $p2:pat = $f2:expr => $b2:expr $(,)?) => {
+        match $crate::race2($f1, $f2).await {
+            $crate::OneOf2::First($p1) => $b1,
+            $crate::OneOf2::Second($p2) => $b2,
+        }
+    };
+    ($p1:pat = $f1:expr => $b1:expr, $p2:pat = $f2:expr => $b2:expr, $p3:pat = $f3:expr => $b3:expr $(,)?) => {
+        match $crate::race3($f1, $f2, $f3).await {
+            $crate::OneOf3::First($p1) => $b1,
+            $crate::OneOf3::Second($p2) => $b2,
+            $crate::OneOf3::Third($p3) => $b3,
+        }
+    };
+    ($p1:pat = $f1:expr => $b1:expr, $p2:pat = $f2:expr => $b2:expr, $p3:pat = $f3:expr => $b3:expr, $p4:pat = $f4:expr => $b4:expr $(,)?) => {
+        match $crate::race4($f1, $f2, $f3, $f4).await {
+            $crate::OneOf4::First($p1) => $b1,
+            $crate::OneOf4::Second($p2) => $b2,
+            $crate::OneOf4::Third($p3) => $b3,
+            $crate::OneOf4::Fourth($p4) => $b4,
+        }
+    };
+    ($p1:pat = $f1:expr => $b1:expr, $p2:pat = $f2:expr => $b2:expr, $p3:pat = $f3:expr => $b3:expr, $p4:pat = $f4:expr => $b4:expr, $p5:pat = $f5:expr => $b5:expr $(,)?) => {
+        match $crate::race5($f1, $f2, $f3, $f4, $f5).await {
+            $crate::OneOf5::First($p1) => $b1,
+            $crate::OneOf5::Second($p2) => $b2,
+            $crate::OneOf5::Third($p3) => $b3,
+            $crate::OneOf5::Fourth($p4) => $b4,
+            $crate::OneOf5::Fifth($p5) => $b5,
+        }
+    };
+    ($p1:pat = $f1:expr => $b1:expr, $p2:pat = $f2:expr => $b2:expr, $p3:pat = $f3:expr => $b3:expr, $p4:pat = $f4:expr => $b4:expr, $p5:pat = $f5:expr => $b5:expr, $p6:pat = $f6:expr => $b6:expr $(,)?) => {
+        match $crate::race6($f1, $f2, $f3, $f4, $f5, $f6).await {
+            $crate::OneOf6::First($p1) => $b1,
+            $crate::OneOf6::Second($p2) => $b2,
+            $crate::OneOf6::Third($p3) => $b3,
+            $crate::OneOf6::Fourth($p4) => $b4,
+            $crate::OneOf6::Fifth($p5) => $b5,
+            $crate::OneOf6::Sixth($p6) => $b6,
+        }
+    };
+    ($p1:pat = $f1:expr => $b1:expr, $p2:pat = $f2:expr => $b2:expr, $p3:pat = $f3:expr => $b3:expr, $p4:pat = $f4:expr => $b4:expr, $p5:pat = $f5:expr => $b5:expr, $p6:pat = $f6:expr => $b6:expr, $p7:pat = $f7:expr => $b7:expr $(,)?) => {
+        match $crate::race7($f1, $f2, $f3, $f4, $f5, $f6, $f7).await {
+            $crate::OneOf7::First($p1) => $b1,
+            $crate::OneOf7::Second($p2) => $b2,
+            $crate::OneOf7::Third($p3) => $b3,
+            $crate::OneOf7::Fourth($p4) => $b4,
+            $crate::OneOf7::Fifth($p5) => $b5,
+            $crate::OneOf7::Sixth($p6) => $b6,
+            $crate::OneOf7::Seventh($p7) => $b7,
+        }
+    };
+    ($p1:pat = $f1:expr => $b1:expr, $p2:pat = $f2:expr => $b2:expr, $p3:pat = $f3:expr => $b3:expr, $p4:pat = $f4:expr => $b4:expr, $p5:pat = $f5:expr => $b5:expr, $p6:pat = $f6:expr => $b6:expr, $p7:pat = $f7:expr => $b7:expr, $p8:pat = $f8:expr => $b8:expr $(,)?) => {
+        match $crate::race8($f1, $f2, $f3, $f4, $f5, $f6, $f7, $f8).await {
+            $crate::OneOf8::First($p1) => $b1,
+            $crate::OneOf8::Second($p2) => $b2,
+            $crate::OneOf8::Third($p3) => $b3,
+            $crate::OneOf8::Fourth($p4) => $b4,
+            $crate::OneOf8::Fifth($p5) => $b5,
+            $crate::OneOf8::Sixth($p6) => $b6,
+            $crate::OneOf8::Seventh($p7) => $b7,
+            $crate::OneOf8::Eighth($p8) => $b8,
+        }
+    };
+}
+
+/// Polls the source future once and returns its result if it is ready, otherwise returns the
+/// result of `default_fn` without suspending.
+///
+/// This is like `select` with `default` branch in Go. To select from several sources use
+/// the combined future as the source, e.g. [select2_recv()](crate::select2_recv) or
+/// `next()` of [AnyOfN](crate::AnyOfN) stream. Typically the source is ready right away when
+/// it is a receiver of buffered channel that has a value.
+///
+/// If the source was not ready it is dropped before `default_fn` is invoked, so everything the
+/// source has registered in runtime on its first poll is cancelled.
+pub async fn select_or_default<FutT, DefaultFnT>(
+    source: FutT,
+    default_fn: DefaultFnT,
+) -> FutT::Output
+where
+    FutT: Future,
+    DefaultFnT: FnOnce() -> FutT::Output,
+{
+    let poll = {
+        pin_local!(source);
+        PollOnce { source }.await
+    };
+
+    match poll {
+        Poll::Ready(value) => value,
+        Poll::Pending => default_fn(),
+    }
+}
+
+// Future that polls the source exactly once and returns the result of this poll.
+struct PollOnce<'source, FutT: Future> {
+    source: Pin<&'source mut FutT>,
+}
+
+impl<'source, FutT: Future> Future for PollOnce<'source, FutT> {
+    type Output = Poll<FutT::Output>;
+
+    fn poll(mut self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        Poll::Ready(self.source.as_mut().poll(ctx))
+    }
+}
@@ -2,10 +2,16 @@
 //  / * \    aiur: the homeplanet for the famous executors
 // |' | '|   (c) 2020 - present, Vladimir Zvezda
 //   / \
-use std::cell::{Cell, RefCell};
-use std::future::Future;
-use std::pin::Pin;
-use std::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+use alloc::boxed::Box;
+use core::any::Any;
+use core::cell::{Cell, RefCell};
+use core::future::Future;
+use core::pin::Pin;
+#[cfg(not(feature = "std"))]
+use core::sync::atomic::{AtomicU32, Ordering};
+use core::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+#[cfg(feature = "std")]
+use std::panic::{self, AssertUnwindSafe};
 
 // Unlike std::task::Poll there is also have a Frozen state meaning that task cannot
 // be polled right now because it mutually borrowed somewhere. Also the Ready result
@@ -17,11 +23,128 @@ pub(crate) enum PollResult {
     Frozen,
 }
 
+/// The identity of a task, see [Runtime::current_task_id()](crate::Runtime::current_task_id).
+///
+/// Ids are unique in the thread, the root future of runtime is a task too. Without the `std`
+/// feature there are no thread locals and the ids are unique in the program as long as only
+/// one thread runs the runtimes. The ids are 32 bit then, so they are reused after 2^32 - 1
+/// tasks.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TaskId(u64);
+
+#[cfg(feature = "std")]
+thread_local! {
+    static NEXT_TASK_ID: Cell<u64> = const { Cell::new(0) };
+    static CURRENT_TASK_ID: Cell<Option<TaskId>> = const { Cell::new(None) };
+}
+
+// Without thread locals the ids are in the statics, the load and store is enough as the
+// runtime is single threaded. The 32 bit atomics are used, because the targets like thumbv6m
+// have no 64 bit ones, and they do not need compare and swap either. The u32::MAX is for no
+// current task.
+#[cfg(not(feature = "std"))]
+static NEXT_TASK_ID: AtomicU32 = AtomicU32::new(0);
+#[cfg(not(feature = "std"))]
+static CURRENT_TASK_ID: AtomicU32 = AtomicU32::new(u32::MAX);
+
+impl TaskId {
+    #[cfg(feature = "std")]
+    fn next() -> Self {
+        TaskId(NEXT_TASK_ID.with(|next| next.replace(next.get() + 1)))
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn next() -> Self {
+        let id = NEXT_TASK_ID.load(Ordering::Relaxed);
+        NEXT_TASK_ID.store((id + 1) % u32::MAX, Ordering::Relaxed);
+        TaskId(id as u64)
+    }
+}
+
+// Returns the id of the task that is being polled right now
+#[cfg(feature = "std")]
+pub(crate) fn current_task_id() -> Option<TaskId> {
+    CURRENT_TASK_ID.with(|current| current.get())
+}
+
+#[cfg(not(feature = "std"))]
+pub(crate) fn current_task_id() -> Option<TaskId> {
+    match CURRENT_TASK_ID.load(Ordering::Relaxed) {
+        u32::MAX => None,
+        id => Some(TaskId(id as u64)),
+    }
+}
+
+// Makes the task current and returns the task that was current before
+#[cfg(feature = "std")]
+fn replace_current_task_id(task_id: Option<TaskId>) -> Option<TaskId> {
+    CURRENT_TASK_ID.with(|current| current.replace(task_id))
+}
+
+#[cfg(not(feature = "std"))]
+fn replace_current_task_id(task_id: Option<TaskId>) -> Option<TaskId> {
+    let prev = current_task_id();
+    let id = task_id.map_or(u32::MAX, |task_id| task_id.0 as u32);
+    CURRENT_TASK_ID.store(id, Ordering::Relaxed);
+    prev
+}
+
+// Makes the task current while it is polled, the task that was current before is restored when
+// the guard is dropped, even if the poll unwinds.
+struct CurrentTaskGuard(Option<TaskId>);
+
+impl CurrentTaskGuard {
+    fn new(task_id: TaskId) -> Self {
+        CurrentTaskGuard(replace_current_task_id(Some(task_id)))
+    }
+}
+
+impl Drop for CurrentTaskGuard {
+    fn drop(&mut self) {
+        replace_current_task_id(self.0);
+    }
+}
+
+// The output of the future or the payload of its panic, like std::thread::Result
+type TaskResult<T> = Result<T, Box<dyn Any + Send + 'static>>;
+
+// Polls the future and stores its panic as a result. The panic cannot be caught without std,
+// so it unwinds (or aborts) right away.
+#[cfg(feature = "std")]
+fn poll_catching<FutT: Future>(
+    future: Pin<&mut FutT>,
+    ctx: &mut Context,
+) -> Poll<TaskResult<FutT::Output>> {
+    match panic::catch_unwind(AssertUnwindSafe(|| future.poll(ctx))) {
+        Ok(poll) => poll.map(Ok),
+        Err(payload) => Poll::Ready(Err(payload)),
+    }
+}
+
+#[cfg(not(feature = "std"))]
+fn poll_catching<FutT: Future>(
+    future: Pin<&mut FutT>,
+    ctx: &mut Context,
+) -> Poll<TaskResult<FutT::Output>> {
+    future.poll(ctx).map(Ok)
+}
+
+#[cfg(feature = "std")]
+fn resume_panic(payload: Box<dyn Any + Send + 'static>) -> ! {
+    panic::resume_unwind(payload)
+}
+
+#[cfg(not(feature = "std"))]
+fn resume_panic(_payload: Box<dyn Any + Send + 'static>) -> ! {
+    unreachable!("aiur: the panic is not caught without std")
+}
+
 // This is for the access the future using a vtable
 pub(crate) trait ITask {
     fn poll(&self) -> PollResult;
     fn unfrozen_ancestor(&self) -> *const dyn ITask;
     fn is_frozen(&self) -> bool;
+    fn id(&self) -> TaskId;
 
     // navigation
     fn get_parent(&self) -> Option<*const dyn ITask>;
@@ -32,10 +155,12 @@ where
     FutT: Future,
 {
     waker_data: WakerData,
+    id: TaskId,
     future: RefCell<FutT>, // being borrowed means the task is frozen
-    result: RefCell<Option<FutT::Output>>, // can have UnsafeCell here
+    result: RefCell<Option<TaskResult<FutT::Output>>>, // can have UnsafeCell here
+    catch_panic: bool,     // panic in poll() is stored as result instead of unwinding
     parent: Cell<Option<*const dyn ITask>>, //
-    _pinned: std::marker::PhantomPinned, // because of self-referential
+    _pinned: core::marker::PhantomPinned, // because of self-referential
 }
 
 struct WakerData {
@@ -51,10 +176,22 @@ where
             waker_data: WakerData {
                 itask_ptr: Cell::new(None),
             },
+            id: TaskId::next(),
             future: RefCell::new(future),
             result: RefCell::new(None),
+            catch_panic: false,
             parent: Cell::new(None),
-            _pinned: std::marker::PhantomPinned,
+            _pinned: core::marker::PhantomPinned,
+        }
+    }
+
+    // Creates the task that does not let the panic of the future unwind through the runtime:
+    // the task is completed with the panic and take_result() resumes it. This way the panic
+    // appears at the join point instead of the executor internals.
+    pub fn new_catching(future: FutT) -> Self {
+        Self {
+            catch_panic: true,
+            ..Self::new(future)
         }
     }
 
@@ -72,7 +209,7 @@ where
         // We need to erase Future lifetime bound. This is up to the nested_loop() to ensure
         // that references in Future do not outlive the objects.
         let static_bounded_itask_trait_ptr = unsafe {
-            std::mem::transmute::<*const (dyn ITask + '_), *const (dyn ITask + 'static)>(&*this)
+            core::mem::transmute::<*const (dyn ITask + '_), *const (dyn ITask + 'static)>(&*this)
         };
 
         this.waker_data
@@ -81,9 +218,13 @@ where
     }
 
     // Returns the result of completed future. Panics if future is not completed or if result
-    // has been already taken.
+    // has been already taken. If the future has panicked, the panic is resumed here.
     pub fn take_result(&self) -> FutT::Output {
-        self.result.borrow_mut().take().unwrap()
+        let result = self.result.borrow_mut().take().unwrap();
+        match result {
+            Ok(res) => res,
+            Err(payload) => resume_panic(payload),
+        }
     }
 
     // When task-based any_of or join makes a poll of inner task, it provides its context and
@@ -97,7 +238,9 @@ where
             // We need to erase Future lifetime bound. This is up to the nested_loop() to ensure
             // that references in Future do not outlive the objects.
             let static_bounded_itask_trait_ptr = unsafe {
-                std::mem::transmute::<*const (dyn ITask + '_), *const (dyn ITask + 'static)>(&*self)
+                core::mem::transmute::<*const (dyn ITask + '_), *const (dyn ITask + 'static)>(
+                    &*self,
+                )
             };
 
             self.waker_data
@@ -111,29 +254,7 @@ where
     }
 
     // Make std::task::Waker from Task, which is basically a pointer to header.
-    fn as_waker(&self) -> std::task::Waker {
-        // Cloning the waker returns just the copy of the pointer.
-        unsafe fn clone_impl(raw_waker_ptr: *const ()) -> RawWaker {
-            // Just copy the pointer, which is works as a clone
-            RawWaker::new(raw_waker_ptr, &AIUR_WAKER_VTABLE)
-        }
-
-        // Wake the future
-        unsafe fn wake_impl(raw_waker_ptr: *const ()) {
-            wake_by_ref_impl(raw_waker_ptr);
-        }
-
-        // Wake the future by ref
-        unsafe fn wake_by_ref_impl(_raw_waker_ptr: *const ()) {
-            panic!("aiur: Don't use Waker.wake(), use waker_as_task_ptr()");
-        }
-
-        // Drop the waker
-        unsafe fn drop_impl(_raw_waker_ptr: *const ()) {}
-
-        const AIUR_WAKER_VTABLE: RawWakerVTable =
-            RawWakerVTable::new(clone_impl, wake_impl, wake_by_ref_impl, drop_impl);
-
+    fn as_waker(&self) -> core::task::Waker {
         let raw_waker = RawWaker::new(
             &self.waker_data as *const WakerData as *const (),
             &AIUR_WAKER_VTABLE,
@@ -143,21 +264,77 @@ where
     }
 }
 
-// 
-pub(crate) fn waker_as_task_ptr(waker: &Waker) -> *const dyn ITask {
-    // Waiting for "waker_getters" unstable feature, meantime solve this by awful transmutes
+// Cloning the waker returns just the copy of the pointer.
+unsafe fn clone_impl(raw_waker_ptr: *const ()) -> RawWaker {
+    // Just copy the pointer, which is works as a clone
+    RawWaker::new(raw_waker_ptr, &AIUR_WAKER_VTABLE)
+}
+
+// Wake the future
+unsafe fn wake_impl(raw_waker_ptr: *const ()) {
+    wake_by_ref_impl(raw_waker_ptr);
+}
+
+// Wake the future by ref
+unsafe fn wake_by_ref_impl(_raw_waker_ptr: *const ()) {
+    panic!("aiur: Don't use Waker.wake(), use waker_as_task_ptr()");
+}
+
+// Drop the waker
+unsafe fn drop_impl(_raw_waker_ptr: *const ()) {}
+
+// The vtable of the wakers made by Task::as_waker(). It is static, not const, so it has the
+// single address that tells the waker of aiur from the foreign one.
+static AIUR_WAKER_VTABLE: RawWakerVTable =
+    RawWakerVTable::new(clone_impl, wake_impl, wake_by_ref_impl, drop_impl);
+
+// Returns the data pointer of the waker made by Task::as_waker(), None for a foreign waker.
+#[cfg(feature = "waker-getters")]
+fn aiur_waker_data(waker: &Waker) -> Option<*const WakerData> {
+    // Waker::data() is the pointer given to RawWaker::new() in as_waker()
+    if core::ptr::eq(waker.vtable(), &AIUR_WAKER_VTABLE) {
+        Some(waker.data() as *const WakerData)
+    } else {
+        None
+    }
+}
+
+// Returns the data pointer of the waker made by Task::as_waker(), None for a foreign waker.
+#[cfg(not(feature = "waker-getters"))]
+fn aiur_waker_data(waker: &Waker) -> Option<*const WakerData> {
+    // The toolchains before "waker_getters" stabilization, solve this by awful transmutes
 
     // Copy of RawWaker struct
     struct RawWakerLayout {
         data: *const (),
-        _vtable: &'static RawWakerVTable,
+        vtable: &'static RawWakerVTable,
     }
 
     unsafe {
-        let raw_waker = std::mem::transmute::<*const Waker, *const RawWaker>(&*waker);
-        let raw_waker = std::mem::transmute::<*const RawWaker, *const RawWakerLayout>(raw_waker);
-        let waker_data = (*raw_waker).data as *const WakerData;
-        (*waker_data).itask_ptr.get().unwrap()
+        let raw_waker = core::mem::transmute::<*const Waker, *const RawWaker>(&*waker);
+        let raw_waker = core::mem::transmute::<*const RawWaker, *const RawWakerLayout>(raw_waker);
+        if core::ptr::eq((*raw_waker).vtable, &AIUR_WAKER_VTABLE) {
+            Some((*raw_waker).data as *const WakerData)
+        } else {
+            None
+        }
+    }
+}
+
+// Returns true if the waker has been made by Task::as_waker(), i.e. the future is polled by
+// the task of aiur runtime.
+#[cfg(feature = "futures")]
+pub(crate) fn is_aiur_waker(waker: &Waker) -> bool {
+    aiur_waker_data(waker).is_some()
+}
+
+// Returns the task which waker has been made by Task::as_waker(). Panics for a foreign waker,
+// e.g. the future of aiur is polled by an executor or combinator with its own Waker.
+#[track_caller]
+pub(crate) fn waker_as_task_ptr(waker: &Waker) -> *const dyn ITask {
+    match aiur_waker_data(waker) {
+        Some(waker_data) => unsafe { (*waker_data).itask_ptr.get().unwrap() },
+        None => panic!("aiur: the future is polled with a waker not made by aiur runtime"),
     }
 }
 
@@ -179,7 +356,17 @@ where
                 let waker = self.as_waker();
                 let mut ctx = Context::from_waker(&waker);
                 let future = unsafe { Pin::new_unchecked(&mut *future) };
-                match future.poll(&mut ctx) {
+
+                // The task polled is current until the poll returns, then it is the parent
+                let current = CurrentTaskGuard::new(self.id);
+                let poll = if self.catch_panic {
+                    poll_catching(future, &mut ctx)
+                } else {
+                    future.poll(&mut ctx).map(Ok)
+                };
+                drop(current);
+
+                match poll {
                     Poll::Ready(res) => {
                         *self.result.borrow_mut() = Some(res);
                         PollResult::Ready
@@ -198,7 +385,7 @@ where
 
         // "does not live long enough" when self as *const dyn ITask
         let mut cur = unsafe {
-            std::mem::transmute::<*const (dyn ITask + '_), *const (dyn ITask + 'static)>(&*self)
+            core::mem::transmute::<*const (dyn ITask + '_), *const (dyn ITask + 'static)>(&*self)
         };
 
         // Going up in hierarchy to find either frozen parent or root task. Implemented
@@ -231,4 +418,8 @@ where
     fn get_parent(&self) -> Option<*const dyn ITask> {
         self.parent.get()
     }
+
+    fn id(&self) -> TaskId {
+        self.id
+    }
 }
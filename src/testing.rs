@@ -0,0 +1,111 @@
+//  \ O /
+//  / * \    aiur: the homeplanet for the famous executors
+// |' | '|   (c) 2020 - present, Vladimir Zvezda
+//   / \
+//! Assertion helpers for the tests of runtimes built on aiur, requires `testing` feature.
+//!
+//! The durations are measured with [TemporalReactor::now()], so the helpers work the same
+//! way with emulated and actual sleeps.
+use std::cell::RefCell;
+use std::fmt::Debug;
+use std::future::Future;
+use std::time::Duration;
+
+use crate::reactor::TemporalReactor;
+use crate::runtime::Runtime;
+
+/// The tolerance used by [expect_future_duration()].
+pub const DEFAULT_TOLERANCE: Duration = Duration::from_millis(100);
+
+/// Panics if the actual duration differs from expected by the tolerance or more.
+pub fn assert_duration(actual: Duration, expected: Duration, tolerance: Duration) {
+    let diff = actual.abs_diff(expected);
+
+    assert!(
+        diff < tolerance,
+        "Duration is in unexpected range: actual: {:?}, expected: {:?}, diff: {:?} >= {:?}",
+        actual,
+        expected,
+        diff,
+        tolerance
+    );
+}
+
+/// Awaits the future and panics if it took unexpected time, see [assert_duration()] with
+/// [DEFAULT_TOLERANCE]. Returns the future output.
+pub async fn expect_future_duration<ReactorT, FutT>(
+    rt: &Runtime<ReactorT>,
+    future: FutT,
+    expected: Duration,
+) -> FutT::Output
+where
+    ReactorT: TemporalReactor,
+    FutT: Future,
+{
+    let start = rt.elapsed();
+    let result = future.await;
+    assert_duration(rt.elapsed() - start, expected, DEFAULT_TOLERANCE);
+    result
+}
+
+/// The log of events pushed by concurrent futures to verify the order they happened in.
+///
+/// ```
+/// use aiur::testing::OrderLog;
+/// use aiur::toy_rt;
+/// use std::time::Duration;
+///
+/// async fn async_main(rt: &toy_rt::Runtime, _: ()) {
+///     let log = OrderLog::new();
+///     toy_rt::join!(
+///         async {
+///             toy_rt::sleep(rt, Duration::from_millis(200)).await;
+///             log.push("slow");
+///         },
+///         async { log.push("fast") }
+///     )
+///     .await;
+///     log.assert_order(&["fast", "slow"]);
+/// }
+///
+/// toy_rt::with_runtime_in_mode(toy_rt::SleepMode::Emulated, async_main, ());
+/// ```
+pub struct OrderLog<T> {
+    events: RefCell<Vec<T>>,
+}
+
+impl<T: Debug + PartialEq> OrderLog<T> {
+    /// Creates the empty log.
+    pub fn new() -> Self {
+        OrderLog {
+            events: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Appends the event to the log.
+    pub fn push(&self, event: T) {
+        self.events.borrow_mut().push(event);
+    }
+
+    /// Panics if the events in the log are not the same as expected.
+    pub fn assert_order(&self, expected: &[T]) {
+        let events = self.events.borrow();
+        assert!(
+            events.as_slice() == expected,
+            "Events are in unexpected order: actual: {:?}, expected: {:?}",
+            events,
+            expected
+        );
+    }
+
+    /// Consumes the log and returns the events.
+    pub fn into_events(self) -> Vec<T> {
+        self.events.into_inner()
+    }
+}
+
+impl<T: Debug + PartialEq> Default for OrderLog<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -2,103 +2,113 @@
 //  / * \    aiur: the homeplanet for the famous executors
 // |' | '|   (c) 2020 - present, Vladimir Zvezda
 //   / \
-use std::future::Future;
-use std::pin::Pin;
-use std::task::{Context, Poll};
-use std::time::Duration;
+use core::time::Duration;
 
 use crate::Runtime;
 use crate::TemporalReactor;
-use crate::{EventId, EventNode};
+use crate::EventId;
+use crate::{ReactorFuture, ReactorOp};
 
 /// Performs the async sleep.
 ///
 /// This function requires reactor with timer operations implemented according to
 /// [TemporalReactor] trait. Sleeping used intensively in tests of this crate.
 ///
+/// The sleep started when runtime is shutting down completes right away.
+///
 /// Panics if provided duration exceeds the maximum value (MAX_TIMER_DURATION_MS)
 pub async fn sleep<ReactorT: TemporalReactor>(rt: &Runtime<ReactorT>, duration: Duration) {
-    TimerFuture::new(rt, duration).await
+    timer_future(rt, duration).await
 }
 
-// The rest code is private TimerFuture implementation
+/// Performs the async sleep like [sleep()] and returns how long it actually slept in
+/// milliseconds by the clock of [TemporalReactor::now()]. The reactor with real time can sleep
+/// longer than requested because of scheduling, so this is for detecting the oversleep, e.g.
+/// in adaptive backoff. The sleep completed by runtime shutdown returns the time until then.
+pub async fn sleep_measured<ReactorT: TemporalReactor>(
+    rt: &Runtime<ReactorT>,
+    duration: Duration,
+) -> u32 {
+    let started_ms = now_ms(rt);
+    timer_future(rt, duration).await;
+    now_ms(rt).wrapping_sub(started_ms)
+}
 
-// Possible states for the timer future.
-enum TimerState {
-    Created { duration: Duration },
-    Scheduled,
-    Done,
+/// Error returned when the operation with a time limit did not complete in time, e.g. by
+/// [Recver::next_timeout()](crate::Recver::next_timeout).
+#[derive(Debug, PartialEq)] // Debug is required for Result.unwrap()
+pub struct Elapsed;
+
+/// Performs the async sleep until the deadline: the time in milliseconds by the clock of
+/// [TemporalReactor::now()]. Unlike the [sleep()] in a loop, the deadlines computed from the
+/// same start time do not drift because of time spent between the awaits.
+///
+/// If the deadline is already in the past the sleep completes right away without scheduling
+/// the timer in the reactor.
+pub async fn sleep_until<ReactorT: TemporalReactor>(rt: &Runtime<ReactorT>, deadline_ms: u32) {
+    if deadline_ms > now_ms(rt) {
+        ReactorFuture::new(rt, TimerOp::At(deadline_ms)).await
+    }
 }
 
-// Leaf future for timer.
-struct TimerFuture<'runtime, ReactorT: TemporalReactor> {
-    rt: &'runtime Runtime<ReactorT>,
-    state: TimerState,
-    event_node: EventNode,
+/// Returns the time passed since `start` by the clock of [TemporalReactor::now()], where
+/// `start` is a value of [Runtime::elapsed()] taken before. It works with any reactor that
+/// has timers, so the time-dependent code does not need to know the reactor type.
+pub fn elapsed_since<ReactorT: TemporalReactor>(
+    rt: &Runtime<ReactorT>,
+    start: Duration,
+) -> Duration {
+    rt.elapsed().saturating_sub(start)
 }
 
-impl<'rt, ReactorT: TemporalReactor> TimerFuture<'rt, ReactorT> {
-    fn new(rt: &'rt Runtime<ReactorT>, duration: Duration) -> Self {
-        TimerFuture {
-            rt,
-            state: TimerState::Created { duration },
-            event_node: EventNode::new(),
-        }
-    }
+// The time by the clock of TemporalReactor::now() in the units of sleep_until()
+pub(crate) fn now_ms<ReactorT: TemporalReactor>(rt: &Runtime<ReactorT>) -> u32 {
+    rt.io().now().as_millis() as u32
+}
+
+// The rest code is private TimerFuture implementation
 
-    // Schedules the timer in the reactor.
-    fn schedule(&mut self, event_id: EventId, duration: Duration) -> Poll<()> {
-        // Timer in has to be in "Created" state, so we cannot schedule the timer twice.
-        debug_assert!(matches!(self.state, TimerState::Created { .. }));
+// Timer operation for ReactorFuture: the timer scheduled after the duration or at the
+// deadline in milliseconds.
+enum TimerOp {
+    After(Duration),
+    At(u32),
+}
 
-        self.state = TimerState::Scheduled;
-        self.rt.io().schedule_timer(event_id, duration);
-        Poll::Pending // always pending
-    }
+// Leaf future for timer.
+type TimerFuture<'runtime, ReactorT> = ReactorFuture<'runtime, ReactorT, TimerOp>;
 
-    // Verifies in reactor if given timer event is ready
-    fn verify(&mut self) -> Poll<()> {
-        // Assumes that timer has to be in "Scheduled" state
-        debug_assert!(matches!(self.state, TimerState::Scheduled));
+fn timer_future<ReactorT: TemporalReactor>(
+    rt: &Runtime<ReactorT>,
+    duration: Duration,
+) -> TimerFuture<'_, ReactorT> {
+    ReactorFuture::new(rt, TimerOp::After(duration))
+}
 
-        if self.event_node.is_awoken_for(self.rt) {
-            self.state = TimerState::Done;
-            Poll::Ready(())
-        } else {
-            Poll::Pending
-        }
+impl<ReactorT: TemporalReactor> ReactorOp<ReactorT> for TimerOp {
+    // The sleep started when runtime is shutting down completes right away
+    fn is_ready(&self, rt: &Runtime<ReactorT>) -> bool {
+        rt.is_shutting_down()
     }
-}
 
-// Cancels timer event in the reactor.
-impl<'rt, ReactorT: TemporalReactor> Drop for TimerFuture<'rt, ReactorT> {
-    fn drop(&mut self) {
-        match self.state {
-            TimerState::Scheduled => {
-                self.event_node
-                    .on_cancel()
-                    .map(|event_id| self.rt.io().cancel_timer(event_id));
-            }
-            _ => (),
+    // The sleep is registered in runtime, so it completes when the runtime begins shutdown
+    fn schedule(&mut self, rt: &Runtime<ReactorT>, event_id: EventId) {
+        match *self {
+            TimerOp::After(duration) => rt.io().schedule_timer(event_id, duration),
+            TimerOp::At(deadline_ms) => rt.io().schedule_timer_at(event_id, deadline_ms),
         }
+        rt.add_sleep(event_id);
     }
-}
-
-impl<'rt, ReactorT: TemporalReactor> Future for TimerFuture<'rt, ReactorT> {
-    type Output = ();
 
-    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
-        // Unsafe usage: this function does not moves out data from self, as required by
-        // Pin::get_unchecked_mut().
-        let this = unsafe { self.get_unchecked_mut() };
+    fn cancel(&mut self, rt: &Runtime<ReactorT>, event_id: EventId) {
+        rt.remove_sleep(event_id);
+        rt.io().cancel_timer(event_id);
+    }
 
-        return match this.state {
-            TimerState::Created { duration } => {
-                let event_id = unsafe { this.event_node.on_pin(ctx) };
-                this.schedule(event_id, duration)
-            }
-            TimerState::Scheduled => this.verify(),
-            TimerState::Done => panic!("aiur/TimerFuture: was polled after completion."),
-        };
+    // The sleep awoken by shutdown has its timer still scheduled in reactor
+    fn complete(&mut self, rt: &Runtime<ReactorT>, event_id: EventId) {
+        if !rt.remove_sleep(event_id) {
+            rt.io().cancel_timer(event_id);
+        }
     }
 }
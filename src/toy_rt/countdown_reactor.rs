@@ -0,0 +1,67 @@
+//  \ O /
+//  / * \    aiur: the homeplanet for the famous executors
+// |' | '|   (c) 2020 - present, Vladimir Zvezda
+//   / \
+use core::cell::{Cell, RefCell};
+use core::task::Waker;
+
+use crate::EventId;
+use crate::Reactor;
+
+/// CountdownReactor is an example of reactor with the events that are not timers: the event
+/// registered with [Reactor::register_event()] happens after the fixed number of wait() calls.
+///
+/// It shows how the leaf future for a real I/O reactor is made and it is used for testing
+/// the runtime with such a reactor.
+pub struct CountdownReactor {
+    waits_per_event: u32,
+    waits: Cell<u32>,
+    // The registered events with the wait() number when they happen
+    events: RefCell<Vec<(EventId, u32)>>,
+}
+
+impl CountdownReactor {
+    /// Creates the reactor where each registered event happens after `waits_per_event` calls
+    /// of wait().
+    pub fn new(waits_per_event: u32) -> Self {
+        CountdownReactor {
+            waits_per_event,
+            waits: Cell::new(0),
+            events: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Returns the number of wait() calls made so far.
+    pub fn waits(&self) -> u32 {
+        self.waits.get()
+    }
+}
+
+impl Reactor for CountdownReactor {
+    fn wait(&self) -> EventId {
+        let waits = self.waits.get() + 1;
+        self.waits.set(waits);
+
+        let mut events = self.events.borrow_mut();
+        match events
+            .iter()
+            .position(|(_, happens_at)| *happens_at <= waits)
+        {
+            Some(pos) => events.remove(pos).0,
+            None => EventId::null(), // nothing happened in this wait
+        }
+    }
+
+    fn has_pending(&self) -> bool {
+        !self.events.borrow().is_empty()
+    }
+
+    fn register_event(&self, _waker: Waker, event_id: EventId) {
+        let happens_at = self.waits.get() + self.waits_per_event;
+        self.events.borrow_mut().push((event_id, happens_at));
+    }
+
+    fn deregister_event(&self, event_id: EventId) {
+        self.events.borrow_mut().retain(|(id, _)| *id != event_id);
+    }
+}
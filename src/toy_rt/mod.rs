@@ -5,8 +5,10 @@
 //
 // Toy Runtime is a runtime based on aiur with reactor that only support sleeping. Sleeping
 // works as emulation of any long IO, so this runtime is used for testing.
+mod countdown_reactor;
 mod toy_reactor;
 
+pub use countdown_reactor::CountdownReactor;
 // has to export for the macro
 pub use toy_reactor::SleepMode;
 pub use toy_reactor::ToyReactor;
@@ -30,3 +32,20 @@ where
         init,
     )
 }
+
+pub fn try_with_runtime_in_mode<FuncT, InitT, ResT>(
+    sleep_mode: SleepMode,
+    async_function: FuncT,
+    init: InitT,
+) -> Result<ResT, RunError>
+where
+    // async fn foo(rt: &Runtime, param: ParamT) -> ResT
+    FuncT: for<'runtime> crate::LifetimeLinkerFn<'runtime, ToyReactor, InitT, ResT>,
+{
+    try_with_runtime(
+        move || ToyReactor::new_with_mode(sleep_mode),
+        crate::Tracer::new_testing(),
+        async_function,
+        init,
+    )
+}
@@ -4,7 +4,8 @@
 //   / \
 use std::cell::{Cell, RefCell};
 use std::cmp::Ordering;
-use std::collections::BinaryHeap;
+use std::collections::{BinaryHeap, VecDeque};
+use std::task::Waker;
 use std::time::{Duration, Instant};
 
 use crate::EventId;
@@ -17,12 +18,15 @@ use crate::TemporalReactor;
 pub enum SleepMode {
     Actual,   // Makes actual delays, e.g. sleep(5s) seconds actually waits 5 sec before wake
     Emulated, // Does not wait, shoot timer right away in a relative sorted order
+    Manual,   // Time only moves by ToyReactor::advance()/set_now(), see ToyReactor::advance()
 }
 
-/// ToyReactor is reactor that can only schedule timers.
+/// ToyReactor is reactor that schedules timers.
 ///
 /// It is used for testing the executor as a lot of I/O can be emulated just by sleeping 
-/// (by pretending that we are reading something from network).
+/// (by pretending that we are reading something from network). The other events registered
+/// with [Reactor::register_event()] only happen when the test invokes
+/// [ToyReactor::wake_manually()].
 pub struct ToyReactor {
     // Reactor is a part of Runtime which is only allowed to work as non-unique ref, so
     // we have to make it with interior mutability.
@@ -47,18 +51,114 @@ impl ToyReactor {
         }
     }
 
+    /// Creates ToyReactor with the maximum duration of the timer in milliseconds other than
+    /// [TemporalReactor::MAX_TIMER_DURATION_MS] (24h), scheduling the longer timer panics.
+    pub fn new_with_max_duration(mode: SleepMode, max_ms: u32) -> Self {
+        let mut rimpl = ToyReactorImpl::new(mode);
+        rimpl.max_duration_ms = max_ms;
+        ToyReactor {
+            rimpl: RefCell::new(rimpl),
+        }
+    }
+
+    /// Creates ToyReactor in emulated sleep mode that fires the timers which are due at the
+    /// same time in a pseudo random order made from the seed, rather than in the order they
+    /// were scheduled. The timer is still never fired before its time. This is for testing
+    /// that futures do not depend on the order of events from reactor.
+    pub fn new_with_chaos(seed: u64) -> Self {
+        let mut rimpl = ToyReactorImpl::new(SleepMode::Emulated);
+        rimpl.chaos_state = Some(seed.max(1)); // xorshift state must not be zero
+        ToyReactor {
+            rimpl: RefCell::new(rimpl),
+        }
+    }
+
     /// Returns a monotonically increasing current time in milliseconds. It can be used
     /// for testing to verify if the time of sleep() actually passes. In emulated sleep
     /// mode the value this function returns is also emulated.
     pub fn now32(&self) -> u32 {
         self.rimpl.borrow_mut().now32()
     }
+
+    /// Returns true if there are timers scheduled that have not been fired or cancelled yet, or
+    /// the events made happen by [ToyReactor::wake_manually()] that wait() has not returned.
+    pub fn has_pending(&self) -> bool {
+        self.rimpl.borrow().has_pending()
+    }
+
+    /// Returns the number of timers kept by reactor including the cancelled ones that have
+    /// not been removed yet. It is for testing that the cancelled timers do not pile up.
+    pub fn timers_in_heap(&self) -> usize {
+        self.rimpl.borrow().timers.len()
+    }
+
+    /// Switches the sleep mode, e.g. to warm up in emulated mode and then measure something
+    /// with actual timing. The time returned by [ToyReactor::now32()] continues from the same
+    /// value after switching, so it stays monotonic and already scheduled timers are fired
+    /// according to their remaining time in the new mode.
+    pub fn set_mode(&self, mode: SleepMode) {
+        self.rimpl.borrow_mut().set_mode(mode);
+    }
+
+    /// Moves the time forward in [SleepMode::Manual], the timers that are due after that are
+    /// returned by the next wait() calls one by one. When no timer is due wait() returns
+    /// [EventId::null()] without changing the time, so the test drives the runtime with
+    /// [RootTask::poll_once()](crate::RootTask::poll_once) and decides when the time goes.
+    ///
+    /// Panics if the reactor is not in manual mode.
+    pub fn advance(&self, duration: Duration) {
+        let now32 = self.now32();
+        let duration_ms = self.rimpl.borrow().get_duration_u32(duration);
+        self.set_now(now32 + duration_ms);
+    }
+
+    /// Sets the time in [SleepMode::Manual], see [ToyReactor::advance()].
+    ///
+    /// Panics if the reactor is not in manual mode or if the time would go backwards.
+    pub fn set_now(&self, now32: u32) {
+        self.rimpl.borrow_mut().set_now(now32);
+    }
+
+    /// Makes the next wait() return [EventId::terminated()] like a real reactor would do on
+    /// SIGTERM, so the runtime shuts down.
+    pub fn terminate(&self) {
+        self.rimpl.borrow_mut().terminate();
+    }
+
+    /// Makes the event registered with [Reactor::register_event()] happen, so the next wait()
+    /// returns it as if the I/O was completed, e.g. to emulate a socket that became readable.
+    /// The timers that are already due are returned first, but wait() does not sleep for the
+    /// timers that are not due yet while there are events that happened.
+    ///
+    /// Panics if the event is not registered.
+    pub fn wake_manually(&self, event_id: EventId) {
+        self.rimpl.borrow_mut().wake_manually(event_id);
+    }
 }
 
 impl Reactor for ToyReactor {
     fn wait(&self) -> EventId {
         self.rimpl.borrow_mut().wait()
     }
+
+    fn try_wait(&self) -> Option<EventId> {
+        self.rimpl.borrow_mut().try_wait()
+    }
+
+    // The termination is also an event to wait for
+    fn has_pending(&self) -> bool {
+        let rimpl = self.rimpl.borrow();
+        rimpl.has_pending() || rimpl.terminated
+    }
+
+    // The registered events only happen with ToyReactor::wake_manually()
+    fn register_event(&self, _waker: Waker, event_id: EventId) {
+        self.rimpl.borrow_mut().register_event(event_id);
+    }
+
+    fn deregister_event(&self, event_id: EventId) {
+        self.rimpl.borrow_mut().deregister_event(event_id);
+    }
 }
 
 impl TemporalReactor for ToyReactor {
@@ -71,6 +171,24 @@ impl TemporalReactor for ToyReactor {
     fn cancel_timer(&self, event_id: EventId) {
         self.rimpl.borrow_mut().cancel_timer(event_id);
     }
+
+    fn schedule_timer_at(&self, event_id: EventId, deadline_ms: u32) {
+        self.rimpl
+            .borrow_mut()
+            .schedule_timer_at(event_id, deadline_ms);
+    }
+
+    fn cancel_all_timers(&self) {
+        self.rimpl.borrow_mut().cancel_all_timers();
+    }
+
+    fn now(&self) -> Duration {
+        Duration::from_millis(self.now32() as u64)
+    }
+
+    fn next_timer_in(&self) -> Option<Duration> {
+        self.rimpl.borrow().next_timer_in()
+    }
 }
 
 // This is the data struct that describes a scheduled timer in our toy reactor.
@@ -80,14 +198,18 @@ struct TimerNode {
     // The BinaryHeap does not support element deletion, so we just mark if node was
     // deleted. TODO: a more optimal mark mode (e.g. nullable eventid?)
     cancelled: Cell<bool>,
+    // Cancelled by cancel_timer(), so nobody refers the node anymore and compaction can
+    // remove it. The nodes cancelled by cancel_all_timers() are still found by cancel_timer().
+    forgotten: Cell<bool>,
 }
 
 impl TimerNode {
-    fn new(now32: u32, duration: Duration, event_id: EventId) -> Self {
+    fn new(wake_on: u32, event_id: EventId) -> Self {
         TimerNode {
-            wake_on: now32 + Self::get_duration_u32(duration),
+            wake_on,
             event_id,
             cancelled: Cell::new(false),
+            forgotten: Cell::new(false),
         }
     }
 
@@ -95,18 +217,9 @@ impl TimerNode {
         self.cancelled.set(true);
     }
 
-    // Ensure that sleep duration is no longer then MAX_TIMER_DURATION_MS (24h)
-    fn get_duration_u32(duration: Duration) -> u32 {
-        let duration: u128 = duration.as_millis();
-
-        assert!(
-            duration <= ToyReactor::MAX_TIMER_DURATION_MS as u128,
-            "aiur: Sleep duration is too big: {}ms (max is {}ms)",
-            duration,
-            ToyReactor::MAX_TIMER_DURATION_MS
-        );
-
-        duration as u32
+    fn forget(&self) {
+        self.cancel();
+        self.forgotten.set(true);
     }
 }
 
@@ -133,17 +246,45 @@ impl Ord for TimerNode {
 // This private version of SleepMode that hides how the timer works: e.g. if it is
 // emulated or actual system timer.
 enum SleepModeImpl {
-    Actual { system_now32_origin: Instant },
-    Emulated { emulated_now32: u32 },
+    // now32_offset is the time when the actual mode started, it is not 0 if reactor had
+    // worked in emulated mode before that.
+    Actual {
+        system_now32_origin: Instant,
+        now32_offset: u32,
+    },
+    Emulated {
+        emulated_now32: u32,
+    },
+    Manual {
+        manual_now32: u32,
+    },
 }
 
 impl SleepModeImpl {
+    // Creates a SleepModeImpl that starts counting time from now32
+    fn new(sleep_mode: SleepMode, now32: u32) -> Self {
+        match sleep_mode {
+            SleepMode::Actual => SleepModeImpl::Actual {
+                system_now32_origin: Instant::now(),
+                now32_offset: now32,
+            },
+            SleepMode::Emulated => SleepModeImpl::Emulated {
+                emulated_now32: now32,
+            },
+            SleepMode::Manual => SleepModeImpl::Manual {
+                manual_now32: now32,
+            },
+        }
+    }
+
     fn now32(&self) -> u32 {
         match self {
             SleepModeImpl::Actual {
                 system_now32_origin,
-            } => system_now32_origin.elapsed().as_millis() as u32,
+                now32_offset,
+            } => now32_offset + system_now32_origin.elapsed().as_millis() as u32,
             SleepModeImpl::Emulated { emulated_now32 } => *emulated_now32,
+            SleepModeImpl::Manual { manual_now32 } => *manual_now32,
         }
     }
 
@@ -152,6 +293,7 @@ impl SleepModeImpl {
         match self {
             SleepModeImpl::Actual { .. } => Self::actual_sleep(ms),
             SleepModeImpl::Emulated { emulated_now32 } => Self::emulated_sleep(emulated_now32, ms),
+            SleepModeImpl::Manual { .. } => unreachable!("aiur: no sleep in manual mode"),
         }
     }
 
@@ -169,12 +311,7 @@ impl SleepModeImpl {
 // Init SleepModeImpl from SleepMode
 impl From<SleepMode> for SleepModeImpl {
     fn from(sleep_mode: SleepMode) -> Self {
-        match sleep_mode {
-            SleepMode::Actual => SleepModeImpl::Actual {
-                system_now32_origin: Instant::now(),
-            },
-            SleepMode::Emulated => SleepModeImpl::Emulated { emulated_now32: 0 },
-        }
+        SleepModeImpl::new(sleep_mode, 0)
     }
 }
 
@@ -182,21 +319,78 @@ struct ToyReactorImpl {
     // Timers are stored as binary heap, so we always know what is the first timer
     timers: BinaryHeap<TimerNode>,
     sleep_mode: SleepModeImpl,
+    terminated: bool,
+    // The state of xorshift generator for ToyReactor::new_with_chaos()
+    chaos_state: Option<u64>,
+    // The heap is compacted when it grows to this length, see compact_if_needed()
+    compact_at: usize,
+    // MAX_TIMER_DURATION_MS unless ToyReactor::new_with_max_duration() is used
+    max_duration_ms: u32,
+    // The events registered by Reactor::register_event() that have not happened yet
+    io_events: Vec<EventId>,
+    // The events made happen by ToyReactor::wake_manually(), in the order they happened
+    woken: VecDeque<EventId>,
 }
 
+// Heap is not compacted until it has at least this number of timers
+const MIN_COMPACT_AT: usize = 64;
+
 impl ToyReactorImpl {
     fn new(sleep_mode: SleepMode) -> Self {
         ToyReactorImpl {
             timers: BinaryHeap::new(),
             sleep_mode: SleepModeImpl::from(sleep_mode),
+            terminated: false,
+            chaos_state: None,
+            compact_at: MIN_COMPACT_AT,
+            max_duration_ms: ToyReactor::MAX_TIMER_DURATION_MS,
+            io_events: Vec::new(),
+            woken: VecDeque::new(),
         }
     }
 
+    // Ensure that sleep duration is no longer then max_duration_ms (24h by default)
+    fn get_duration_u32(&self, duration: Duration) -> u32 {
+        let duration: u128 = duration.as_millis();
+
+        assert!(
+            duration <= self.max_duration_ms as u128,
+            "aiur: Sleep duration is too big: {}ms (max is {}ms)",
+            duration,
+            self.max_duration_ms
+        );
+
+        duration as u32
+    }
+
     fn schedule_timer(&mut self, event_id: EventId, duration: Duration) {
         println!("schedule_timer: {:?}", event_id);
 
+        let duration_ms = self.get_duration_u32(duration);
         self.timers
-            .push(TimerNode::new(self.now32(), duration, event_id));
+            .push(TimerNode::new(self.now32() + duration_ms, event_id));
+        self.compact_if_needed();
+    }
+
+    // The deadline is kept as the wake_on time of the timer, so the time passed since the
+    // deadline was computed is not added to it.
+    fn schedule_timer_at(&mut self, event_id: EventId, deadline_ms: u32) {
+        println!("schedule_timer_at: {:?}", event_id);
+
+        let left_ms = deadline_ms.saturating_sub(self.now32());
+        self.get_duration_u32(Duration::from_millis(left_ms as u64));
+        self.timers.push(TimerNode::new(deadline_ms, event_id));
+        self.compact_if_needed();
+    }
+
+    // Cancelled timers are only removed from heap when they are popped, so without wait() they
+    // would pile up. When the heap doubles its length since the last compaction the forgotten
+    // timers are removed, this keeps the heap within twice the number of live timers.
+    fn compact_if_needed(&mut self) {
+        if self.timers.len() >= self.compact_at {
+            self.timers.retain(|timer_node| !timer_node.forgotten.get());
+            self.compact_at = MIN_COMPACT_AT.max(self.timers.len() * 2);
+        }
     }
 
     fn cancel_timer(&mut self, event_id: EventId) {
@@ -211,10 +405,87 @@ impl ToyReactorImpl {
             .iter()
             .find(|x| {
                 println!("{:?}", x.event_id);
-                x.event_id == event_id
+                !x.forgotten.get() && x.event_id == event_id
             })
             .expect("Attempt to remove unknown timer")
-            .cancel();
+            .forget();
+    }
+
+    // Cancelled timers are just marked like in cancel_timer(), so the cancel_timer() invoked
+    // later by the timer futures still finds them.
+    fn cancel_all_timers(&mut self) {
+        println!("cancel_all_timers");
+        self.timers.iter().for_each(|timer_node| timer_node.cancel());
+    }
+
+    // Timers keep their wake_on time, it is the new mode that continues the time from now32(),
+    // so timers do not require rebasing.
+    fn set_mode(&mut self, sleep_mode: SleepMode) {
+        println!("set_mode");
+        self.sleep_mode = SleepModeImpl::new(sleep_mode, self.now32());
+    }
+
+    fn set_now(&mut self, now32: u32) {
+        match self.sleep_mode {
+            SleepModeImpl::Manual {
+                ref mut manual_now32,
+            } => {
+                assert!(
+                    now32 >= *manual_now32,
+                    "aiur: ToyReactor time cannot go backwards: {} -> {}",
+                    *manual_now32,
+                    now32
+                );
+                *manual_now32 = now32;
+            }
+            _ => panic!("aiur: ToyReactor time can only be set in SleepMode::Manual"),
+        }
+    }
+
+    fn terminate(&mut self) {
+        println!("terminate");
+        self.terminated = true;
+    }
+
+    fn register_event(&mut self, event_id: EventId) {
+        println!("register_event: {:?}", event_id);
+        self.io_events.push(event_id);
+    }
+
+    // The event is removed even if it has happened, so wait() does not return it anymore
+    fn deregister_event(&mut self, event_id: EventId) {
+        println!("deregister_event: {:?}", event_id);
+        self.io_events.retain(|registered| *registered != event_id);
+        self.woken.retain(|woken| *woken != event_id);
+    }
+
+    fn wake_manually(&mut self, event_id: EventId) {
+        println!("wake_manually: {:?}", event_id);
+        let pos = self
+            .io_events
+            .iter()
+            .position(|registered| *registered == event_id)
+            .expect("aiur: ToyReactor::wake_manually() for the event that is not registered");
+        self.woken.push_back(self.io_events.swap_remove(pos));
+    }
+
+    // Cancelled timers stay in the heap, so the first timer to wake is searched among all of them
+    fn next_timer_in(&self) -> Option<Duration> {
+        let now32 = self.now32();
+        self.timers
+            .iter()
+            .filter(|timer_node| !timer_node.cancelled.get())
+            .map(|timer_node| timer_node.wake_on.saturating_sub(now32))
+            .min()
+            .map(|ms| Duration::from_millis(ms as u64))
+    }
+
+    fn has_pending(&self) -> bool {
+        !self.woken.is_empty()
+            || self
+                .timers
+                .iter()
+                .any(|timer_node| !timer_node.cancelled.get())
     }
 
     fn now32(&self) -> u32 {
@@ -246,8 +517,45 @@ impl ToyReactorImpl {
         }
     }
 
+    // Returns the first timer if it is due, never sleeps
+    fn try_wait(&mut self) -> Option<EventId> {
+        if self.terminated {
+            self.terminated = false;
+            return Some(EventId::terminated());
+        }
+
+        let now32 = self.now32();
+        while let Some(timer_node) = self.timers.peek() {
+            if timer_node.cancelled.get() {
+                self.timers.pop();
+            } else if timer_node.wake_on <= now32 {
+                return self.timers.pop().map(|timer_node| timer_node.event_id);
+            } else {
+                break;
+            }
+        }
+
+        // The events that happened are after the timers that were due before
+        self.woken.pop_front()
+    }
+
     fn wait(&mut self) -> EventId {
         println!("toy reactor wait");
+        if self.terminated {
+            self.terminated = false;
+            return EventId::terminated();
+        }
+
+        // Time does not go by itself in manual mode: only the timers that are due are fired
+        if let SleepModeImpl::Manual { .. } = self.sleep_mode {
+            return self.try_wait().unwrap_or(EventId::null());
+        }
+
+        // No sleep for the timers while there are events that happened
+        if !self.woken.is_empty() {
+            return self.try_wait().unwrap();
+        }
+
         let timer_node = self.get_first_timer_to_wake();
 
         let now32 = self.now32();
@@ -257,7 +565,41 @@ impl ToyReactorImpl {
             self.sleep_mode.sleep(timer_node.wake_on - now32);
         }
 
+        if self.chaos_state.is_some() {
+            return self.pick_due_timer(timer_node);
+        }
+
         // Returns the waker and event_id to aiur::Runtime
         timer_node.event_id
     }
+
+    // Chaos mode: fires any of the timers that are due now, the rest goes back to heap
+    fn pick_due_timer(&mut self, first_timer: TimerNode) -> EventId {
+        let now32 = self.now32();
+        let mut due_timers = vec![first_timer];
+        while self
+            .timers
+            .peek()
+            .is_some_and(|timer_node| timer_node.wake_on <= now32)
+        {
+            let timer_node = self.timers.pop().unwrap();
+            if !timer_node.cancelled.get() {
+                due_timers.push(timer_node);
+            }
+        }
+
+        let idx = (self.next_chaos() % due_timers.len() as u64) as usize;
+        let timer_node = due_timers.swap_remove(idx);
+        self.timers.extend(due_timers);
+        timer_node.event_id
+    }
+
+    // xorshift64
+    fn next_chaos(&mut self) -> u64 {
+        let state = self.chaos_state.as_mut().unwrap();
+        *state ^= *state << 13;
+        *state ^= *state >> 7;
+        *state ^= *state << 17;
+        *state
+    }
 }
@@ -2,6 +2,7 @@
 //  / * \    aiur: the homeplanet for the famous executors
 // |' | '|   (c) 2020 - present, Vladimir Zvezda
 //   / \
+use alloc::rc::Rc;
 use core::fmt::Arguments;
 
 /// A C-like callback which is a pointer to logger function and data.
@@ -12,13 +13,33 @@ use core::fmt::Arguments;
 /// Why using C-like callback? Well, I don't want aiur to depend on any logger crate, so API
 /// has to be constructed from scratch. I have tried the approach that app should provide
 /// `trait Tracer` and it made everything much more messy internally.
-#[derive(Copy, Clone)]
+///
+/// Later the [TraceSink] has been added for the sinks that have their own state, e.g. to
+/// collect the traces for assertions in tests, see [Tracer::with_sink()].
+#[derive(Clone)]
 pub struct Tracer {
     log_fn: fn(usize, Arguments),
     data: usize,
+    enabled: bool,
+    // Runtime and its channels clone the tracer, so the sink is shared
+    sink: Option<Rc<dyn TraceSink>>,
+}
+
+/// The destination for trace events of [Tracer::with_sink()]. It is implemented for closures
+/// like `|args: std::fmt::Arguments| ...`.
+pub trait TraceSink {
+    /// Receives the trace event.
+    fn trace(&self, args: Arguments);
+}
+
+impl<FuncT: Fn(Arguments)> TraceSink for FuncT {
+    fn trace(&self, args: Arguments) {
+        self(args)
+    }
 }
 
 // Private impl of Tracer used in tests to print traces into stdout
+#[cfg(feature = "std")]
 fn local_print(_data: usize, args: Arguments) {
     // We can improve to avoid dynamic memory, but this function is private and only used for
     // testing.
@@ -38,21 +59,56 @@ impl Tracer {
         Self {
             log_fn: log_fn,
             data: data,
+            enabled: true,
+            sink: None,
+        }
+    }
+
+    /// Constructs the tracer that sends the trace events to the sink.
+    ///
+    /// ```
+    /// use std::cell::RefCell;
+    /// use std::rc::Rc;
+    ///
+    /// let lines = Rc::new(RefCell::new(Vec::new()));
+    /// let sink_lines = lines.clone();
+    /// let tracer = aiur::Tracer::with_sink(move |args: std::fmt::Arguments| {
+    ///     sink_lines.borrow_mut().push(args.to_string())
+    /// });
+    /// assert!(tracer.is_enabled());
+    /// ```
+    pub fn with_sink<SinkT: TraceSink + 'static>(sink: SinkT) -> Self {
+        Self {
+            sink: Some(Rc::new(sink)),
+            ..Self::new(0, local_nothing)
         }
     }
 
     /// Constructs the tracer when traces goes nowhere.
     pub fn new_empty() -> Self {
-        Self::new(0, local_nothing)
+        Self {
+            enabled: false,
+            ..Self::new(0, local_nothing)
+        }
+    }
+
+    /// Returns false for the tracer made by [Tracer::new_empty()], so aiur can skip the work
+    /// that is only needed to make the trace event.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
     }
 
     // Constructs the tracer for testing that prints! the event.
+    #[cfg(feature = "std")]
     pub(crate) fn new_testing() -> Self {
         Self::new(0, local_print)
     }
 
     //
     pub(crate) fn fmt(&self, args: Arguments) {
-        (self.log_fn)(self.data, args);
+        match self.sink {
+            Some(ref sink) => sink.trace(args),
+            None => (self.log_fn)(self.data, args),
+        }
     }
 }
@@ -0,0 +1,159 @@
+//  \ O /
+//  / * \    aiur: the homeplanet for the famous executors
+// |' | '|   (c) 2020 - present, Vladimir Zvezda
+//   / \
+//
+// The buffer of the values sent into buffered channel but not yet received. ChannelNode is not
+// generic, so the type of values is erased, but the values are kept in one array of channel
+// capacity allocated on the first push, so sending into the buffer does not allocate.
+use alloc::alloc::{alloc, dealloc, handle_alloc_error, Layout};
+use core::ptr::NonNull;
+
+// Drops the value in the slot of the ring, see ValueRing::push().
+unsafe fn drop_slot<T>(slot_ptr: *mut u8) {
+    core::ptr::drop_in_place(slot_ptr as *mut T);
+}
+
+// Ring buffer of up to capacity values of the same type.
+pub(crate) struct ValueRing {
+    slots: *mut u8, // null until the first push()
+    layout: Layout, // of the slots array
+    value_size: usize,
+    drop_value: Option<unsafe fn(*mut u8)>, // remembers the type of values for drop
+    head: usize,
+    len: usize,
+    capacity: usize,
+}
+
+impl ValueRing {
+    pub(crate) fn new(capacity: usize) -> Self {
+        ValueRing {
+            slots: core::ptr::null_mut(),
+            layout: Layout::new::<()>(),
+            value_size: 0,
+            drop_value: None,
+            head: 0,
+            len: 0,
+            capacity,
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    // Unsafe: T must be the same for all the values of the ring
+    unsafe fn slot_ptr<T>(&self, index: usize) -> *mut T {
+        (self.slots as *mut T).add((self.head + index) % self.capacity)
+    }
+
+    // Moves the value to the back of the ring. Panics if the ring is full.
+    //
+    // Unsafe: T must be the same for all the values of the ring
+    pub(crate) unsafe fn push<T>(&mut self, value: T) {
+        assert!(self.len < self.capacity, "aiur: channel buffer is full");
+
+        if self.slots.is_null() {
+            self.layout = Layout::array::<T>(self.capacity).unwrap();
+            self.slots = match self.layout.size() {
+                0 => NonNull::<T>::dangling().as_ptr() as *mut u8,
+                _ => alloc(self.layout),
+            };
+            if self.slots.is_null() {
+                handle_alloc_error(self.layout);
+            }
+            self.value_size = core::mem::size_of::<T>();
+            self.drop_value = Some(drop_slot::<T>);
+        }
+
+        self.slot_ptr::<T>(self.len).write(value);
+        self.len += 1;
+    }
+
+    // Takes the value from the front of the ring.
+    //
+    // Unsafe: T must be the same for all the values of the ring
+    pub(crate) unsafe fn pop<T>(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+
+        let value = self.slot_ptr::<T>(0).read();
+        self.head = (self.head + 1) % self.capacity;
+        self.len -= 1;
+        Some(value)
+    }
+}
+
+impl Drop for ValueRing {
+    fn drop(&mut self) {
+        let drop_value = match self.drop_value {
+            Some(drop_value) => drop_value,
+            None => return, // nothing has been pushed, the slots are not allocated
+        };
+
+        // The value is removed from the ring before its drop, so the panic in the drop leaks
+        // the rest of values instead of dropping the value twice.
+        while self.len > 0 {
+            let slot_ptr = unsafe { self.slots.add(self.head * self.value_size) };
+            self.head = (self.head + 1) % self.capacity;
+            self.len -= 1;
+            unsafe { drop_value(slot_ptr) };
+        }
+
+        if self.layout.size() > 0 {
+            unsafe { dealloc(self.slots, self.layout) };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::rc::Rc;
+
+    // The values are taken in the order they were pushed after the ring wraps around
+    #[test]
+    fn value_ring_keeps_order_when_wrapped() {
+        let mut ring = ValueRing::new(3);
+        unsafe {
+            ring.push(1u32);
+            ring.push(2u32);
+            assert_eq!(ring.pop::<u32>(), Some(1));
+            ring.push(3u32);
+            ring.push(4u32);
+            assert_eq!(ring.len(), 3);
+            assert_eq!(ring.pop::<u32>(), Some(2));
+            assert_eq!(ring.pop::<u32>(), Some(3));
+            assert_eq!(ring.pop::<u32>(), Some(4));
+            assert_eq!(ring.pop::<u32>(), None);
+        }
+        assert!(ring.is_empty());
+    }
+
+    // The values left in the ring are dropped with the ring, zero sized values work too
+    #[test]
+    fn value_ring_drops_values_left() {
+        let value = Rc::new(());
+        let mut ring = ValueRing::new(2);
+        unsafe {
+            ring.push(value.clone());
+            ring.push(value.clone());
+            drop(ring.pop::<Rc<()>>());
+            ring.push(value.clone());
+        }
+        assert_eq!(Rc::strong_count(&value), 3);
+        drop(ring);
+        assert_eq!(Rc::strong_count(&value), 1);
+
+        let mut ring = ValueRing::new(2);
+        unsafe {
+            ring.push(());
+            assert_eq!(ring.pop::<()>(), Some(()));
+        }
+    }
+}
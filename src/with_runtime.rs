@@ -2,9 +2,10 @@
 //  }/_\{   (c) 2020-present Vladimir Zvezda
 //  |\ /|
 //    v
-use std::future::Future;
+use core::future::Future;
 
 use crate::Reactor;
+use crate::RunError;
 use crate::Runtime;
 use crate::Tracer;
 
@@ -52,6 +53,10 @@ where
 /// By default with_runtime() specialized with reactor type is created by export_runtime!(),
 /// but this is up to reactor crate to do more, for example [toy_rt::with_runtime_in_mode]()
 /// function that also accept additional parameter for reactor.
+///
+/// When the runtime is created with [Runtime::new()] the [Runtime::block_on()] is a simpler
+/// way to run a future, with_runtime() is for the cases when the async function has to get
+/// the lifetime of the runtime it has created.
 pub fn with_runtime_base<ReactorT, FuncT, InitT, ResT>(
     reactor: ReactorT,
     tracer: Tracer,
@@ -67,5 +72,29 @@ where
     let future = async_function.call(&runtime, init);
 
     // return the result of the execution of the future
-    runtime.nested_loop(future)
+    let result = runtime.nested_loop(future);
+
+    #[cfg(feature = "debug-leak-check")]
+    runtime.assert_no_leaks();
+
+    result
+}
+
+/// Same as [with_runtime_base()], but returns error instead of panic when the async function
+/// cannot be completed, e.g. [RunError::Deadlock] when nothing can wake it anymore.
+pub fn try_with_runtime_base<ReactorT, FuncT, InitT, ResT>(
+    reactor: ReactorT,
+    tracer: Tracer,
+    async_function: FuncT,
+    init: InitT,
+) -> Result<ResT, RunError>
+where
+    // async fn foo(rt: &Runtime, param: ParamT) -> ResT
+    FuncT: for<'runtime> LifetimeLinkerFn<'runtime, ReactorT, InitT, ResT>,
+    ReactorT: Reactor,
+{
+    let runtime = Runtime::<ReactorT>::new(reactor, tracer);
+    let future = async_function.call(&runtime, init);
+
+    runtime.try_nested_loop(future)
 }
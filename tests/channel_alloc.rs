@@ -0,0 +1,70 @@
+//  \ O /
+//  / * \    aiur: the homeplanet for the famous executors
+// |' | '|   (c) 2020 - present, Vladimir Zvezda
+//   / \
+//
+// The tests that count the allocations. They are in own test binary because the global
+// allocator is replaced for the whole binary.
+use aiur::toy_rt::{self};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+// Allocator that counts the allocations made by the current thread, so the tests are not
+// affected by the tests running in parallel.
+struct CountingAlloc;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+fn count_allocations<FuncT: FnOnce() -> R, R>(func: FuncT) -> (usize, R) {
+    let before = ALLOCATIONS.with(|count| count.get());
+    let res = func();
+    (ALLOCATIONS.with(|count| count.get()) - before, res)
+}
+
+// The state change of channel node does not allocate to render the old state when the tracer
+// is disabled.
+#[test]
+fn channel_traced_does_not_allocate_when_tracing_is_off() {
+    let rt = toy_rt::Runtime::new(toy_rt::ToyReactor::new(), aiur::Tracer::new_empty());
+    let (tx, _rx) = toy_rt::channel::<u32>(&rt);
+
+    let (allocations, tx2) = count_allocations(|| tx.clone());
+    assert_eq!(allocations, 0);
+    assert_eq!(count_allocations(|| drop(tx2)).0, 0);
+
+    // the same with enabled tracer allocates, so the counting works
+    let rt = toy_rt::Runtime::new(toy_rt::ToyReactor::new(), aiur::Tracer::new(0, |_, _| ()));
+    let (tx, _rx) = toy_rt::channel::<u32>(&rt);
+    let (allocations, _tx2) = count_allocations(|| tx.clone());
+    assert!(allocations > 0);
+}
+
+// The buffer of channel is allocated once, the values sent into it are not boxed
+#[test]
+fn channel_buffered_send_does_not_allocate() {
+    let rt = toy_rt::Runtime::new(toy_rt::ToyReactor::new(), aiur::Tracer::new_empty());
+    let (mut tx, mut rx) = toy_rt::channel_buffered::<u32>(&rt, 2);
+
+    tx.try_send(1).unwrap(); // allocates the buffer
+    assert_eq!(count_allocations(|| rx.try_recv().unwrap()), (0, 1));
+    for value in 2..10 {
+        assert_eq!(count_allocations(|| tx.try_send(value).unwrap()).0, 0);
+        assert_eq!(count_allocations(|| rx.try_recv().unwrap()), (0, value));
+    }
+}
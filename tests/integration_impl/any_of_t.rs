@@ -7,6 +7,9 @@
 use aiur::toy_rt::{self};
 use super::measure::{self};
 
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
 use std::time::Duration;
 
 // With emulated sleep test run instantly, actual sleep actually wait for specified
@@ -94,3 +97,380 @@ fn consume_any_of8() {
 
     toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
 }
+
+// Fused any_of_vec compacts the storage once a half of futures is completed
+#[test]
+fn any_of_vec_fused_compacts_completed() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let futures = (0..100u64)
+            .map(|i| toy_rt::sleep(rt, Duration::from_millis(10 * (i + 1))))
+            .collect();
+        let mut stream = toy_rt::any_of_vec_fused(futures);
+
+        for expected in 0..50 {
+            assert_eq!(stream.next().await.unwrap().0, expected);
+        }
+
+        // Only 50 remaining futures to scan and the indexes are the same after compaction
+        assert_eq!(stream.slot_count(), 50);
+        assert_eq!(stream.len(), 50);
+        assert_eq!(stream.next().await.unwrap().0, 50);
+        assert_eq!(stream.slot_count(), 50);
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// next_indexed() returns the position of the completed future in any_of3()
+#[test]
+fn any_of_next_indexed() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) -> Vec<u8> {
+        toy_rt::pinned_any_of!(
+            stream,
+            toy_rt::sleep(rt, Duration::from_millis(300)),
+            toy_rt::sleep(rt, Duration::from_millis(100)),
+            toy_rt::sleep(rt, Duration::from_millis(200))
+        );
+
+        let mut order = Vec::new();
+        while let Some((index, _)) = stream.next_indexed().await {
+            order.push(index);
+        }
+        order
+    }
+
+    let order = toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+    assert_eq!(order, vec![1, 2, 0]);
+}
+
+// make_any_of! with 12 futures completed in the order of their sleep durations
+#[test]
+fn make_any_of_12_futures() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) -> Vec<u8> {
+        let sleep = |ms| toy_rt::sleep(rt, Duration::from_millis(ms));
+        let stream = toy_rt::make_any_of!(
+            sleep(100),
+            sleep(200),
+            sleep(300),
+            sleep(400),
+            sleep(500),
+            sleep(600),
+            sleep(700),
+            sleep(800),
+            sleep(900),
+            sleep(1000),
+            sleep(1100),
+            sleep(1200),
+        );
+        toy_rt::pin_local!(stream);
+
+        let mut order = Vec::new();
+        while let Some((index, _)) = stream.next_indexed().await {
+            order.push(index);
+        }
+
+        assert_eq!(rt.elapsed(), Duration::from_millis(1200));
+        order
+    }
+
+    let order = toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+    assert_eq!(order, (0..12).collect::<Vec<u8>>());
+}
+
+// The futures that have not been completed are moved out of the stream and joined
+#[test]
+fn any_of_into_remaining() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let mut stream = toy_rt::any_of3(
+            Box::pin(toy_rt::sleep(rt, Duration::from_millis(100))),
+            Box::pin(toy_rt::sleep(rt, Duration::from_millis(300))),
+            Box::pin(toy_rt::sleep(rt, Duration::from_millis(200))),
+        );
+
+        {
+            let mut pinned = Pin::new(&mut stream);
+            assert!(matches!(pinned.next().await, Some(toy_rt::OneOf3::First(()))));
+        }
+
+        let (f1, f2, f3) = stream.into_remaining();
+        assert!(f1.is_none());
+        toy_rt::join!(f2.unwrap(), f3.unwrap()).await;
+        assert_eq!(rt.elapsed(), Duration::from_millis(300));
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// Future that is ready after it has been polled pending_polls times
+struct ReadyAfter {
+    pending_polls: u32,
+}
+
+impl Future for ReadyAfter {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, _ctx: &mut Context) -> Poll<Self::Output> {
+        if self.pending_polls == 0 {
+            return Poll::Ready(());
+        }
+        self.pending_polls -= 1;
+        Poll::Pending
+    }
+}
+
+// When several futures are ready the one next to the last completed wins
+#[test]
+fn any_of_vec_polls_from_next_to_completed() {
+    async fn async_starter(_rt: &toy_rt::Runtime, _: ()) -> Vec<usize> {
+        let futures = [1, 0, 0]
+            .iter()
+            .map(|pending_polls| ReadyAfter {
+                pending_polls: *pending_polls,
+            })
+            .collect();
+        let mut stream = toy_rt::any_of_vec(futures);
+
+        let mut order = Vec::new();
+        while let Some((index, _)) = stream.next().await {
+            order.push(index);
+        }
+        order
+    }
+
+    // the first future is ready in the second next(), but the third one goes before it
+    let order = toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+    assert_eq!(order, vec![1, 2, 0]);
+}
+
+// Explicit compact() reports where the slots have been moved
+#[test]
+fn any_of_vec_compact_returns_remap() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let futures = [300, 100, 200, 400]
+            .iter()
+            .map(|ms| measure::sleep_and_ret(rt, Duration::from_millis(*ms), *ms as u32))
+            .collect();
+        let mut stream = toy_rt::any_of_vec(futures);
+
+        assert_eq!(stream.next().await, Some((1, 100)));
+        assert_eq!(stream.next().await, Some((2, 200)));
+        assert_eq!(stream.slot_count(), 4);
+
+        assert_eq!(stream.compact(), vec![Some(0), None, None, Some(1)]);
+        assert_eq!(stream.slot_count(), 2);
+
+        assert_eq!(stream.next().await, Some((0, 300)));
+        assert_eq!(stream.next().await, Some((3, 400)));
+        assert_eq!(stream.next().await, None);
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// Future that is ready on its n-th poll, it does not need a runtime.
+struct ReadyOnPoll {
+    polls_left: u32,
+}
+
+impl Future for ReadyOnPoll {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, _ctx: &mut Context) -> Poll<Self::Output> {
+        if self.polls_left == 0 {
+            Poll::Ready(())
+        } else {
+            self.polls_left -= 1;
+            Poll::Pending
+        }
+    }
+}
+
+// The futures that become ready at the same poll are taken in turns, so the second future is
+// not starved by the first one.
+#[test]
+fn any_of2_polls_fairly() {
+    let mut first_count = 0;
+    let mut second_count = 0;
+    for n in 0..1000 {
+        toy_rt::pinned_any_of!(
+            stream,
+            ReadyOnPoll { polls_left: n },
+            ReadyOnPoll { polls_left: n }
+        );
+        let next = stream.next();
+        toy_rt::pin_local!(next);
+
+        let mut ctx = Context::from_waker(std::task::Waker::noop());
+        loop {
+            match next.as_mut().poll(&mut ctx) {
+                Poll::Ready(Some(toy_rt::OneOf2::First(()))) => first_count += 1,
+                Poll::Ready(Some(toy_rt::OneOf2::Second(()))) => second_count += 1,
+                Poll::Ready(None) => panic!("the stream is not done yet"),
+                Poll::Pending => continue,
+            }
+            break;
+        }
+    }
+
+    assert_eq!(first_count + second_count, 1000);
+    assert!(first_count >= 400 && second_count >= 400);
+}
+
+// The index of each variant, into_inner() of homogeneous and fold() of different types
+#[test]
+fn one_of_helpers() {
+    type Homogeneous = toy_rt::OneOf3<u32, u32, u32>;
+    let values = [
+        Homogeneous::First(10),
+        Homogeneous::Second(20),
+        Homogeneous::Third(30),
+    ];
+    for (index, value) in values.into_iter().enumerate() {
+        assert_eq!(value.index(), index as u8);
+        assert_eq!(value.into_inner(), (index as u32 + 1) * 10);
+    }
+
+    type Mixed = toy_rt::OneOf8<u8, u16, u32, u64, i8, i16, i32, &'static str>;
+    let values = [
+        Mixed::First(1),
+        Mixed::Second(2),
+        Mixed::Third(3),
+        Mixed::Fourth(4),
+        Mixed::Fifth(5),
+        Mixed::Sixth(6),
+        Mixed::Seventh(7),
+        Mixed::Eighth("8"),
+    ];
+    for (index, value) in values.into_iter().enumerate() {
+        assert_eq!(value.index(), index as u8);
+        let folded = value.fold(
+            |x| x.to_string(),
+            |x| x.to_string(),
+            |x| x.to_string(),
+            |x| x.to_string(),
+            |x| x.to_string(),
+            |x| x.to_string(),
+            |x| x.to_string(),
+            |x| x.to_string(),
+        );
+        assert_eq!(folded, (index + 1).to_string());
+    }
+}
+
+// The index of the result of next() is the same as next_indexed() gives
+#[test]
+fn one_of_index_of_next() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        toy_rt::pinned_any_of!(
+            stream,
+            toy_rt::sleep(rt, Duration::from_millis(300)),
+            toy_rt::sleep(rt, Duration::from_millis(100)),
+            toy_rt::sleep(rt, Duration::from_millis(200))
+        );
+
+        let mut order = Vec::new();
+        while let Some(one_of) = stream.next().await {
+            order.push(one_of.index());
+        }
+        assert_eq!(order, vec![1, 2, 0]);
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// The futures pinned outside of the stream are not dropped with it, so the pending one can be
+// selected again by a new stream
+#[test]
+fn any_of_pinned_reselect() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let long = toy_rt::sleep(rt, Duration::from_millis(300));
+        let short = toy_rt::sleep(rt, Duration::from_millis(100));
+        toy_rt::pin_local!(long);
+        toy_rt::pin_local!(short);
+
+        {
+            let mut stream = toy_rt::any_of_pinned2(long.as_mut(), short.as_mut());
+            let mut stream = Pin::new(&mut stream);
+            assert!(matches!(stream.next().await, Some(toy_rt::OneOf2::Second(()))));
+        }
+        assert_eq!(rt.elapsed(), Duration::from_millis(100));
+
+        // Retry loop: the long sleep is selected with a new tick until it is completed
+        let mut ticks = 0;
+        loop {
+            let tick = toy_rt::sleep(rt, Duration::from_millis(70));
+            toy_rt::pin_local!(tick);
+            let tick: Pin<&mut dyn Future<Output = ()>> = tick;
+
+            let mut stream = toy_rt::any_of_pinned2(long.as_mut(), tick);
+            let mut stream = Pin::new(&mut stream);
+            match stream.next().await.unwrap() {
+                toy_rt::OneOf2::First(()) => break,
+                toy_rt::OneOf2::Second(()) => ticks += 1,
+            }
+        }
+
+        assert_eq!(ticks, 2);
+        assert_eq!(rt.elapsed(), Duration::from_millis(300));
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// The stream pinned with Box::pin() is driven by poll_next() without the runtime
+#[test]
+fn any_of2_poll_next_manually() {
+    let mut stream = Box::pin(toy_rt::any_of2(
+        ReadyOnPoll { polls_left: 2 },
+        ReadyOnPoll { polls_left: 0 },
+    ));
+    let mut ctx = Context::from_waker(std::task::Waker::noop());
+
+    assert!(matches!(
+        stream.as_mut().poll_next(&mut ctx),
+        Poll::Ready(Some(toy_rt::OneOf2::Second(())))
+    ));
+    assert!(stream.as_mut().poll_next(&mut ctx).is_pending());
+    assert!(matches!(
+        stream.as_mut().poll_next(&mut ctx),
+        Poll::Ready(Some(toy_rt::OneOf2::First(())))
+    ));
+    assert!(matches!(stream.as_mut().poll_next(&mut ctx), Poll::Ready(None)));
+
+    // next_of() is the future for the same pinned reference
+    let mut stream = Box::pin(toy_rt::any_of2(async { 1 }, async { 2 }));
+    let next = toy_rt::next_of(stream.as_mut());
+    toy_rt::pin_local!(next);
+    match next.as_mut().poll(&mut ctx) {
+        Poll::Ready(Some(one_of)) => assert_eq!(one_of.into_inner(), 1),
+        _ => panic!("the first future is ready"),
+    }
+}
+
+// The future pinned before the stream is given with "pinned:" marker: the stream polls it by
+// reference and it keeps running after the stream is dropped.
+#[test]
+fn pinned_any_of_with_pinned_local() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let long_sleep = toy_rt::sleep(rt, Duration::from_millis(300));
+        toy_rt::pin_local!(long_sleep);
+
+        {
+            toy_rt::pinned_any_of!(
+                stream,
+                pinned: long_sleep,
+                toy_rt::sleep(rt, Duration::from_millis(100)),
+                toy_rt::sleep(rt, Duration::from_millis(200)),
+            );
+            assert!(matches!(stream.next().await, Some(toy_rt::OneOf3::Second(()))));
+            assert!(matches!(stream.next().await, Some(toy_rt::OneOf3::Third(()))));
+        }
+        measure::assert_duration(rt.io().now32(), 200);
+
+        // the sleep is not restarted: it is completed at 300ms from the start
+        long_sleep.await;
+        measure::assert_duration(rt.io().now32(), 300);
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
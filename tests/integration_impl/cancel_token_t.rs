@@ -0,0 +1,83 @@
+//  \ O /
+//  / * \    aiur: the homeplanet for the famous executors
+// |' | '|   (c) 2020 - present, Vladimir Zvezda
+//   / \
+//
+// Tests for CancelToken
+use aiur::toy_rt::{self};
+use std::time::Duration;
+
+//const SLEEP_MODE: toy_rt::SleepMode = toy_rt::SleepMode::Actual;
+const SLEEP_MODE: toy_rt::SleepMode = toy_rt::SleepMode::Emulated;
+
+// The long sleep is raced against the token that is cancelled after 1s
+#[test]
+fn cancel_token_stops_sleep() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let token = toy_rt::CancelToken::new(rt);
+        let canceller = token.clone();
+
+        let (res, _) = toy_rt::join!(
+            toy_rt::race2(
+                toy_rt::sleep(rt, Duration::from_secs(10)),
+                token.cancelled()
+            ),
+            async {
+                toy_rt::sleep(rt, Duration::from_secs(1)).await;
+                canceller.cancel();
+            }
+        )
+        .await;
+
+        assert!(matches!(res, toy_rt::OneOf2::Second(())));
+        assert!(token.is_cancelled());
+        assert_eq!(rt.elapsed(), Duration::from_secs(1));
+
+        // the token that is already cancelled completes right away
+        token.cancelled().await;
+        assert_eq!(rt.elapsed(), Duration::from_secs(1));
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+async fn wait_token(rt: &toy_rt::Runtime, token: toy_rt::CancelToken<'_>) -> Duration {
+    token.cancelled().await;
+    rt.elapsed()
+}
+
+// All the futures waiting for the token are awoken by one cancel(), the token that has been
+// dropped without cancel() wakes nobody
+#[test]
+fn cancel_token_wakes_all_waiters() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let token = toy_rt::CancelToken::new(rt);
+        let dropped = toy_rt::CancelToken::new(rt);
+        let waiting_for_dropped = wait_token(rt, dropped.clone());
+
+        let (first, second, third, not_awoken) = toy_rt::join!(
+            wait_token(rt, token.clone()),
+            wait_token(rt, token.clone()),
+            async move {
+                toy_rt::sleep(rt, Duration::from_secs(1)).await;
+                drop(dropped);
+                toy_rt::sleep(rt, Duration::from_secs(1)).await;
+                token.cancel();
+                token.cancel(); // second cancel() does nothing
+                rt.elapsed()
+            },
+            toy_rt::race2(
+                waiting_for_dropped,
+                toy_rt::sleep(rt, Duration::from_secs(3))
+            )
+        )
+        .await;
+
+        assert_eq!(first, Duration::from_secs(2));
+        assert_eq!(second, Duration::from_secs(2));
+        assert_eq!(third, Duration::from_secs(2));
+        assert!(matches!(not_awoken, toy_rt::OneOf2::Second(())));
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
@@ -8,7 +8,9 @@
 
 // Use the toy runtime
 use super::future_utils::{self};
+use super::measure::{self};
 use aiur::toy_rt::{self};
+use std::time::Duration;
 
 // With emulated sleep tests are run instantly, with actual sleep mode it wait for specified
 // amount of time.
@@ -157,7 +159,10 @@ fn channel_drop_recver_with_sender_pinned_gives_error() {
         let (mut tx, rx) = toy_rt::channel::<u32>(&rt);
         toy_rt::join!(reader(rx), async {
             // verify that sender receiver the value back as error
-            assert_eq!(tx.send(42).await.unwrap_err(), 42);
+            assert_eq!(
+                tx.send(42).await.unwrap_err(),
+                toy_rt::SendError::Disconnected(42)
+            );
         })
         .await;
     }
@@ -338,3 +343,892 @@ fn channel_drop_pinned_peer_during_exchange_is_ok() {
 
     toy_rt::with_runtime_in_mode(SLEEP_MODE, start_dropping_peers, ());
 }
+
+/// Rendezvous channel: sender is blocked until receiver is ready to take the value.
+#[test]
+fn channel_rendezvous_send_blocks_until_recv() {
+    async fn start_rendezvous(rt: &toy_rt::Runtime, _: ()) {
+        let (mut tx, mut rx) = toy_rt::channel::<u32>(rt);
+        assert_eq!(tx.capacity(), 0);
+
+        let (send_time, value) = toy_rt::join!(
+            async {
+                tx.send(42).await.unwrap();
+                rt.io().now32()
+            },
+            async {
+                toy_rt::sleep(rt, Duration::from_millis(1000)).await;
+                rx.next().await.unwrap()
+            }
+        )
+        .await;
+
+        measure::assert_duration(send_time, 1000);
+        assert_eq!(value, 42);
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, start_rendezvous, ());
+}
+
+/// Buffered channel with capacity 1: the first send completes immediately, the second one
+/// waits until the receiver makes a room in buffer.
+#[test]
+fn channel_buffered_send_completes_while_buffer_has_room() {
+    async fn start_buffered(rt: &toy_rt::Runtime, _: ()) {
+        let (mut tx, mut rx) = toy_rt::channel_buffered::<u32>(rt, 1);
+        assert_eq!(tx.capacity(), 1);
+        assert_eq!(rx.capacity(), 1);
+
+        let (send_times, values) = toy_rt::join!(
+            async {
+                tx.send(1).await.unwrap();
+                let first = rt.io().now32();
+                tx.send(2).await.unwrap();
+                (first, rt.io().now32())
+            },
+            async {
+                toy_rt::sleep(rt, Duration::from_millis(1000)).await;
+                (rx.next().await.unwrap(), rx.next().await.unwrap())
+            }
+        )
+        .await;
+
+        measure::assert_duration(send_times.0, 0);
+        measure::assert_duration(send_times.1, 1000);
+        assert_eq!(values, (1, 2));
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, start_buffered, ());
+}
+
+/// Buffered values are still delivered after the senders are gone.
+#[test]
+fn channel_buffered_recv_gets_values_after_senders_dropped() {
+    async fn start_buffered(rt: &toy_rt::Runtime, _: ()) {
+        let (mut tx, mut rx) = toy_rt::channel_buffered::<u32>(rt, 2);
+        tx.send(1).await.unwrap();
+        tx.send(2).await.unwrap();
+        drop(tx);
+
+        assert_eq!(rx.next().await.unwrap(), 1);
+        assert_eq!(rx.next().await.unwrap(), 2);
+        rx.next().await.expect_err("channel is disconnected");
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, start_buffered, ());
+}
+
+/// N values fit into the capacity-N channel before the receiver is pinned, the next send waits
+/// for the receiver. The values are received in the order they were sent.
+#[test]
+fn channel_buffered_send_capacity_values_without_receiver() {
+    async fn start_buffered(rt: &toy_rt::Runtime, _: ()) {
+        const CAPACITY: u32 = 5;
+        let (mut tx, mut rx) = toy_rt::channel_buffered::<u32>(rt, CAPACITY as usize);
+
+        for value in 0..CAPACITY {
+            tx.send(value).await.unwrap();
+        }
+        measure::assert_duration(rt.io().now32(), 0);
+
+        let (sent_at, values) = toy_rt::join!(
+            async {
+                tx.send(CAPACITY).await.unwrap();
+                rt.io().now32()
+            },
+            async {
+                toy_rt::sleep(rt, Duration::from_millis(1000)).await;
+                let mut values = Vec::new();
+                for _ in 0..=CAPACITY {
+                    values.push(rx.next().await.unwrap());
+                }
+                values
+            }
+        )
+        .await;
+
+        measure::assert_duration(sent_at, 1000);
+        assert_eq!(values, (0..=CAPACITY).collect::<Vec<u32>>());
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, start_buffered, ());
+}
+
+/// try_recv() gets the value of the sender awaiting in send(), it is Empty without such
+/// sender and Disconnected when senders are gone.
+#[test]
+fn channel_try_recv_empty_ready_disconnected() {
+    async fn start_rendezvous(rt: &toy_rt::Runtime, _: ()) {
+        let (mut tx, mut rx) = toy_rt::channel::<u32>(rt);
+        assert_eq!(rx.try_recv(), Err(toy_rt::TryRecvError::Empty));
+
+        // join polls send() first, so the sender is pinned when try_recv() is invoked
+        let (sent, received) = toy_rt::join!(tx.send(5), async { rx.try_recv() }).await;
+        assert_eq!(sent, Ok(()));
+        assert_eq!(received, Ok(5));
+        assert_eq!(rx.try_recv(), Err(toy_rt::TryRecvError::Empty));
+
+        drop(tx);
+        assert_eq!(rx.try_recv(), Err(toy_rt::TryRecvError::Disconnected));
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, start_rendezvous, ());
+}
+
+/// Sender::is_connected() and Recver::sender_count() follow the halves being dropped.
+#[test]
+fn channel_state_queries() {
+    async fn start_rendezvous(rt: &toy_rt::Runtime, _: ()) {
+        let (tx, rx) = toy_rt::channel::<u32>(rt);
+        assert!(tx.is_connected());
+        assert_eq!(rx.sender_count(), 1);
+
+        let tx2 = tx.clone();
+        assert_eq!(rx.sender_count(), 2);
+        drop(tx);
+        assert_eq!(rx.sender_count(), 1);
+
+        drop(rx);
+        assert!(!tx2.is_connected());
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, start_rendezvous, ());
+}
+
+/// try_send() of rendezvous channel succeeds only when receiver awaits the value.
+#[test]
+fn channel_try_send_rendezvous() {
+    async fn start_rendezvous(rt: &toy_rt::Runtime, _: ()) {
+        let (mut tx, mut rx) = toy_rt::channel::<u32>(rt);
+        assert_eq!(tx.try_send(1), Err(toy_rt::TrySendError::Full(1)));
+
+        let (received, sent) = toy_rt::join!(rx.next(), async {
+            let sent = tx.try_send(2);
+            // the receiver has not taken the value yet
+            assert_eq!(tx.try_send(3), Err(toy_rt::TrySendError::Full(3)));
+            sent
+        })
+        .await;
+        assert_eq!(sent, Ok(()));
+        assert_eq!(received, Ok(2));
+
+        drop(rx);
+        assert_eq!(tx.try_send(4), Err(toy_rt::TrySendError::Disconnected(4)));
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, start_rendezvous, ());
+}
+
+/// try_send() of rendezvous channel gives the value right to the awaiting receiver: when
+/// the receiver future is cancelled after that, the value goes with it and nothing is left in
+/// the channel. The cancelled receiver does not take the values anymore.
+#[test]
+fn channel_try_send_rendezvous_receiver_cancelled() {
+    async fn start_rendezvous(rt: &toy_rt::Runtime, _: ()) {
+        let (mut tx, mut rx) = toy_rt::channel::<std::rc::Rc<u32>>(rt);
+        let value = std::rc::Rc::new(1);
+
+        {
+            let mut next = std::pin::pin!(rx.next());
+            // the first poll pins the receiver
+            std::future::poll_fn(|ctx| {
+                let _ = std::future::Future::poll(next.as_mut(), ctx);
+                std::task::Poll::Ready(())
+            })
+            .await;
+
+            assert_eq!(tx.try_send(value.clone()), Ok(()));
+            assert_eq!(std::rc::Rc::strong_count(&value), 2);
+        }
+        assert_eq!(std::rc::Rc::strong_count(&value), 1);
+
+        assert_eq!(rx.try_recv(), Err(toy_rt::TryRecvError::Empty));
+        assert_eq!(
+            tx.try_send(value.clone()),
+            Err(toy_rt::TrySendError::Full(value.clone()))
+        );
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, start_rendezvous, ());
+}
+
+/// try_send() of buffered channel succeeds while there is a room in buffer.
+#[test]
+fn channel_try_send_buffered() {
+    async fn start_buffered(rt: &toy_rt::Runtime, _: ()) {
+        let (mut tx, mut rx) = toy_rt::channel_buffered::<u32>(rt, 2);
+        assert_eq!(tx.try_send(1), Ok(()));
+        assert_eq!(tx.try_send(2), Ok(()));
+        assert_eq!(tx.try_send(3), Err(toy_rt::TrySendError::Full(3)));
+
+        assert_eq!(rx.try_recv(), Ok(1));
+        assert_eq!(tx.try_send(3), Ok(()));
+        assert_eq!(rx.next().await, Ok(2));
+        assert_eq!(rx.next().await, Ok(3));
+
+        drop(rx);
+        assert_eq!(tx.try_send(4), Err(toy_rt::TrySendError::Disconnected(4)));
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, start_buffered, ());
+}
+
+/// Two receivers of channel_mpmc() compete for values of three senders: every value is
+/// received exactly once and both receivers get the RecvError when senders are gone.
+#[test]
+fn channel_mpmc_two_receivers_three_senders() {
+    async fn send_values(rt: &toy_rt::Runtime, mut tx: toy_rt::Sender<'_, u32>, first: u32) {
+        for value in first..first + 5 {
+            tx.send(value).await.unwrap();
+            toy_rt::sleep(rt, Duration::from_millis(100)).await;
+        }
+    }
+
+    async fn recv_values(rt: &toy_rt::Runtime, mut rx: toy_rt::MpmcRecver<'_, u32>) -> Vec<u32> {
+        let mut values = Vec::new();
+        while let Ok(value) = rx.next().await {
+            values.push(value);
+            toy_rt::sleep(rt, Duration::from_millis(150)).await;
+        }
+        values
+    }
+
+    async fn start_mpmc(rt: &toy_rt::Runtime, _: ()) {
+        let (tx, rx) = toy_rt::channel_mpmc::<u32>(rt);
+
+        let (_, _, _, values1, values2) = toy_rt::join!(
+            send_values(rt, tx.clone(), 0),
+            send_values(rt, tx.clone(), 10),
+            send_values(rt, tx, 20),
+            recv_values(rt, rx.clone()),
+            recv_values(rt, rx),
+        )
+        .await;
+
+        assert!(!values1.is_empty());
+        assert!(!values2.is_empty());
+
+        let mut all: Vec<u32> = values1.into_iter().chain(values2).collect();
+        all.sort_unstable();
+        assert_eq!(all, (0..5).chain(10..15).chain(20..25).collect::<Vec<u32>>());
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, start_mpmc, ());
+}
+
+/// The value of channel_mpmc() goes to the receiver that awaits it first, the receiver that
+/// cancelled the awaiting does not take it.
+#[test]
+fn channel_mpmc_cancelled_receiver_skipped() {
+    async fn start_mpmc(rt: &toy_rt::Runtime, _: ()) {
+        let (mut tx, mut rx1) = toy_rt::channel_mpmc::<u32>(rt);
+        let mut rx2 = rx1.clone();
+
+        let (timed_out, received, _) = toy_rt::join!(
+            async {
+                // the first receiver in queue is cancelled by the sleep
+                let sleep = toy_rt::sleep(rt, Duration::from_millis(500));
+                matches!(toy_rt::race2(rx1.next(), sleep).await, toy_rt::OneOf2::Second(()))
+            },
+            rx2.next(),
+            async {
+                toy_rt::sleep(rt, Duration::from_millis(1000)).await;
+                tx.send(7).await.unwrap();
+            }
+        )
+        .await;
+
+        assert!(timed_out);
+        assert_eq!(received, Ok(7));
+
+        drop(rx2);
+        assert!(tx.is_connected());
+        drop(rx1);
+        assert!(!tx.is_connected());
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, start_mpmc, ());
+}
+
+/// The value of channel_mpmc() can be taken by try_recv() of one receiver after the other
+/// receiver was awoken to swap with the same sender: the awoken receiver keeps waiting.
+#[test]
+fn channel_mpmc_try_recv_steals_from_awoken_receiver() {
+    async fn start_mpmc(rt: &toy_rt::Runtime, _: ()) {
+        let (mut tx, mut rx1) = toy_rt::channel_mpmc::<u32>(rt);
+        let mut rx2 = rx1.clone();
+
+        let ((stolen, received), _) = toy_rt::join_tasks2(
+            toy_rt::join!(
+                // polled before rx1 in the same task, so it takes the value first
+                std::future::poll_fn(|_| match rx2.try_recv() {
+                    Ok(value) => std::task::Poll::Ready(value),
+                    Err(_) => std::task::Poll::Pending,
+                }),
+                rx1.next()
+            ),
+            async move {
+                toy_rt::sleep(rt, Duration::from_millis(100)).await;
+                tx.send(7).await.unwrap();
+            },
+        )
+        .await;
+
+        assert_eq!(stolen, 7);
+        assert_eq!(received, Err(toy_rt::RecvError::Disconnected));
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, start_mpmc, ());
+}
+
+/// Recver::collect() returns all the values sent until the sender is dropped.
+#[test]
+fn channel_recver_collect() {
+    async fn start_rendezvous(rt: &toy_rt::Runtime, _: ()) {
+        let (mut tx, rx) = toy_rt::channel::<u32>(rt);
+
+        let (_, values) = toy_rt::join!(
+            async move {
+                for value in 0..10 {
+                    tx.send(value).await.unwrap();
+                }
+                // sender is dropped here
+            },
+            rx.collect()
+        )
+        .await;
+
+        assert_eq!(values, (0..10).collect::<Vec<u32>>());
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, start_rendezvous, ());
+}
+
+/// Recver::for_each() takes the buffered values after the senders are dropped too.
+#[test]
+fn channel_recver_for_each() {
+    async fn start_buffered(rt: &toy_rt::Runtime, _: ()) {
+        let (mut tx, rx) = toy_rt::channel_buffered::<u32>(rt, 3);
+        for value in 1..=3 {
+            tx.send(value).await.unwrap();
+        }
+        drop(tx);
+
+        let mut sum = 0;
+        rx.for_each(|value| sum += value).await;
+        assert_eq!(sum, 6);
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, start_buffered, ());
+}
+
+/// next_timeout() gives up when nothing is sent in time, the channel is still usable after
+/// the cancelled receive.
+#[test]
+fn channel_next_timeout_elapsed() {
+    async fn start_rendezvous(rt: &toy_rt::Runtime, _: ()) {
+        let (mut tx, mut rx) = toy_rt::channel::<u32>(rt);
+
+        let elapsed = rx.next_timeout(Duration::from_millis(500)).await;
+        assert_eq!(elapsed, Err(toy_rt::Elapsed));
+        measure::assert_duration(rt.io().now32(), 500);
+
+        let (_, received) = toy_rt::join!(
+            async {
+                toy_rt::sleep(rt, Duration::from_millis(100)).await;
+                tx.send(1).await.unwrap();
+            },
+            rx.next_timeout(Duration::from_millis(500))
+        )
+        .await;
+        assert_eq!(received, Ok(Ok(1)));
+
+        let (_, received) = toy_rt::join!(tx.send(2), rx.next()).await;
+        assert_eq!(received, Ok(2));
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, start_rendezvous, ());
+}
+
+/// Buffered channel with capacity 0 is a programming error
+#[test]
+#[should_panic]
+fn channel_buffered_zero_capacity_panics() {
+    async fn start_buffered(rt: &toy_rt::Runtime, _: ()) {
+        let (_tx, _rx) = toy_rt::channel_buffered::<u32>(rt, 0);
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, start_buffered, ());
+}
+
+/// select2_recv() returns the value from the channel that has it first, another channel is
+/// still usable after that.
+#[test]
+fn channel_select2_recv_heterogeneous() {
+    async fn start_select(rt: &toy_rt::Runtime, _: ()) {
+        let (mut tx_a, mut rx_a) = toy_rt::channel::<u32>(rt);
+        let (mut tx_b, mut rx_b) = toy_rt::channel::<String>(rt);
+
+        let (selected, _) = toy_rt::join!(
+            toy_rt::select2_recv(&mut rx_a, &mut rx_b),
+            async {
+                toy_rt::sleep(rt, Duration::from_millis(100)).await;
+                tx_b.send("second".to_string()).await.unwrap();
+            }
+        )
+        .await;
+
+        match selected {
+            toy_rt::OneOf2::Second(value) => assert_eq!(value.unwrap(), "second"),
+            toy_rt::OneOf2::First(_) => panic!("the value is expected from the second channel"),
+        }
+
+        let (_, value) = toy_rt::join!(tx_a.send(1), rx_a.next()).await;
+        assert_eq!(value.unwrap(), 1);
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, start_select, ());
+}
+
+/// Receiver blocked on empty channel gets the Shutdown error when runtime begins shutdown, the
+/// new channel operations fail right away.
+#[test]
+fn channel_recv_returns_shutdown_error() {
+    async fn start_shutdown(rt: &toy_rt::Runtime, _: ()) {
+        let (mut tx, mut rx) = toy_rt::channel::<u32>(rt);
+
+        let (result, shutdown_at) = toy_rt::join!(rx.next(), async {
+            toy_rt::sleep(rt, Duration::from_millis(100)).await;
+            rt.begin_shutdown();
+            rt.io().now32()
+        })
+        .await;
+
+        assert_eq!(result.unwrap_err(), toy_rt::RecvError::Shutdown);
+        assert_eq!(rt.io().now32(), shutdown_at);
+
+        assert_eq!(tx.send(1).await.unwrap_err(), toy_rt::SendError::Shutdown(1));
+        assert_eq!(rx.next().await.unwrap_err(), toy_rt::RecvError::Shutdown);
+        toy_rt::sleep(rt, Duration::from_millis(1000)).await; // no sleeping
+        assert_eq!(rt.io().now32(), shutdown_at);
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, start_shutdown, ());
+}
+
+/// Sender blocked on rendezvous channel gets its value back on shutdown
+#[test]
+fn channel_send_returns_value_on_shutdown() {
+    async fn start_shutdown(rt: &toy_rt::Runtime, _: ()) {
+        let (mut tx, _rx) = toy_rt::channel::<u32>(rt);
+
+        let (result, _) = toy_rt::join!(tx.send(42), async {
+            toy_rt::sleep(rt, Duration::from_millis(100)).await;
+            rt.begin_shutdown();
+        })
+        .await;
+
+        assert_eq!(result.unwrap_err(), toy_rt::SendError::Shutdown(42));
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, start_shutdown, ());
+}
+
+/// Two stage pipeline transforms the values in order
+#[test]
+fn channel_pipeline2_transforms_in_order() {
+    async fn double(mut rx: toy_rt::Recver<'_, u32>, mut tx: toy_rt::Sender<'_, u32>) {
+        while let Ok(value) = rx.next().await {
+            tx.send(value * 2).await.unwrap();
+        }
+    }
+
+    async fn add_one(mut rx: toy_rt::Recver<'_, u32>, mut tx: toy_rt::Sender<'_, u32>) {
+        while let Ok(value) = rx.next().await {
+            tx.send(value + 1).await.unwrap();
+        }
+    }
+
+    async fn start_pipeline(rt: &toy_rt::Runtime, _: ()) {
+        let (mut tx, mut rx, stages) = toy_rt::pipeline2(rt, double, add_one);
+
+        let (_, _, outputs) = toy_rt::join!(
+            stages,
+            async move {
+                for value in 1..=5 {
+                    tx.send(value).await.unwrap();
+                }
+                // dropping tx here stops the pipeline
+            },
+            async move {
+                let mut outputs = Vec::new();
+                while let Ok(value) = rx.next().await {
+                    outputs.push(value);
+                }
+                outputs
+            }
+        )
+        .await;
+
+        assert_eq!(outputs, vec![3, 5, 7, 9, 11]);
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, start_pipeline, ());
+}
+
+// The big value is received into the caller's slot. The channel stays usable after that: the
+// next value replaces the one in the slot and the disconnect leaves the slot empty.
+#[test]
+fn channel_recv_into_slot_big_value() {
+    type BigValue = [u64; 1024];
+
+    async fn start_exchange(rt: &toy_rt::Runtime, _: ()) {
+        let (mut tx, mut rx) = toy_rt::channel::<BigValue>(rt);
+        let mut slot: Option<BigValue> = None;
+
+        toy_rt::join!(
+            async move {
+                tx.send([1; 1024]).await.unwrap();
+                tx.send([2; 1024]).await.unwrap();
+                // dropping tx here disconnects the receiver
+            },
+            async {
+                rx.recv_into(&mut slot).await.unwrap();
+                assert!(slot.as_ref().unwrap().iter().all(|v| *v == 1));
+
+                rx.recv_into(&mut slot).await.unwrap();
+                assert!(slot.as_ref().unwrap().iter().all(|v| *v == 2));
+
+                assert_eq!(rx.recv_into(&mut slot).await, Err(toy_rt::RecvError::Disconnected));
+                assert!(slot.is_none());
+            }
+        )
+        .await;
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, start_exchange, ());
+}
+
+// The values left in the buffer are dropped when the channel is gone. Their Drop can use the
+// channels too, e.g. when the value is a sender of another channel.
+#[test]
+fn channel_buffered_senders_dropped_with_channel() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let (tx_inner, mut rx_inner) = toy_rt::channel::<u32>(rt);
+        let (mut tx, rx) = toy_rt::channel_buffered::<toy_rt::Sender<u32>>(rt, 1);
+
+        assert!(tx.send(tx_inner).await.is_ok());
+        drop(tx);
+        drop(rx); // drops the buffered sender of inner channel
+
+        assert_eq!(rx_inner.next().await, Err(toy_rt::RecvError::Disconnected));
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// The Drop of buffered value that panics does not leave the channels of runtime borrowed
+#[test]
+fn channel_buffered_value_drop_panics() {
+    struct PanicOnDrop;
+
+    impl Drop for PanicOnDrop {
+        fn drop(&mut self) {
+            panic!("PanicOnDrop");
+        }
+    }
+
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let (mut tx, rx) = toy_rt::channel_buffered::<PanicOnDrop>(rt, 1);
+        assert!(tx.send(PanicOnDrop).await.is_ok());
+        drop(tx);
+
+        let drop_result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| drop(rx)));
+        assert!(drop_result.is_err());
+
+        // channels are still working
+        let (mut tx, mut rx) = toy_rt::channel::<u32>(rt);
+        let (_, value) = toy_rt::join!(tx.send(42), rx.next()).await;
+        assert_eq!(value, Ok(42));
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// Sender::writable() on the rendezvous channel completes when the receiver awaits a value
+#[test]
+fn channel_writable_rendezvous() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let (mut tx, mut rx) = toy_rt::channel::<u32>(rt);
+
+        let (writable_at, value) = toy_rt::join!(
+            async {
+                tx.writable().await;
+                let writable_at = rt.elapsed();
+                assert!(tx.send(42).await.is_ok());
+                writable_at
+            },
+            async {
+                toy_rt::sleep(rt, Duration::from_millis(100)).await;
+                rx.next().await
+            }
+        )
+        .await;
+
+        assert_eq!(writable_at, Duration::from_millis(100));
+        assert_eq!(value, Ok(42));
+
+        // receiver is gone: writable right away, but the value is returned back by send()
+        drop(rx);
+        tx.writable().await;
+        assert_eq!(tx.send(1).await.unwrap_err().into_inner(), 1);
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// Sender::writable() on the buffered channel completes when there is a room in buffer
+#[test]
+fn channel_writable_buffered() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let (mut tx, mut rx) = toy_rt::channel_buffered::<u32>(rt, 1);
+
+        tx.writable().await; // empty buffer
+        assert!(tx.send(1).await.is_ok());
+        assert_eq!(rt.elapsed(), Duration::from_millis(0));
+
+        let (writable_at, value) = toy_rt::join!(
+            async {
+                tx.writable().await;
+                rt.elapsed()
+            },
+            async {
+                toy_rt::sleep(rt, Duration::from_millis(100)).await;
+                rx.next().await
+            }
+        )
+        .await;
+
+        assert_eq!(writable_at, Duration::from_millis(100));
+        assert_eq!(value, Ok(1));
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// The senders blocked on the rendezvous channel are seen by Recver::queued_senders()
+#[test]
+fn channel_queued_senders_shows_backpressure() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let (mut tx1, mut rx) = toy_rt::channel::<u32>(rt);
+        let mut tx2 = tx1.clone();
+        let mut tx3 = tx1.clone();
+        assert_eq!(rx.queued_senders(), 0);
+
+        let (_, _, _, received) = toy_rt::join!(tx1.send(1), tx2.send(2), tx3.send(3), async {
+            toy_rt::sleep(rt, Duration::from_millis(100)).await;
+            assert_eq!(rx.queued_senders(), 3);
+            assert_eq!(rx.queued_senders(), 3); // the query does not change the state
+
+            let mut received = Vec::new();
+            for _ in 0..3 {
+                received.push(rx.next().await.unwrap());
+            }
+            received
+        })
+        .await;
+
+        assert_eq!(received, vec![1, 2, 3]);
+        assert_eq!(rx.queued_senders(), 0);
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// The endless producer is stopped by Sender::closed() when the receiver is dropped at 1s
+#[test]
+fn channel_sender_closed_stops_producer() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let (mut tx, mut rx) = toy_rt::channel::<u32>(rt);
+        let watcher = tx.clone();
+
+        let producer = async {
+            for value in 0.. {
+                let _ = tx.send(value).await;
+                toy_rt::sleep(rt, Duration::from_millis(100)).await;
+            }
+        };
+
+        let consumer = async move {
+            let mut received = 0;
+            let deadline = toy_rt::sleep(rt, Duration::from_millis(1000));
+            toy_rt::pin_local!(deadline);
+            while let toy_rt::OneOf2::First(value) = toy_rt::race2(rx.next(), &mut deadline).await {
+                assert_eq!(value, Ok(received));
+                received += 1;
+            }
+            received // rx is dropped here
+        };
+
+        let (stopped, received) = toy_rt::join!(
+            toy_rt::race2(producer, async {
+                watcher.closed().await;
+                rt.elapsed()
+            }),
+            consumer
+        )
+        .await;
+
+        assert!(matches!(stopped, toy_rt::OneOf2::Second(at) if at == Duration::from_millis(1000)));
+        assert!(received >= 9);
+
+        // the receiver is gone already: completes right away, and again
+        watcher.closed().await;
+        watcher.closed().await;
+        assert_eq!(rt.elapsed(), Duration::from_millis(1000));
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// send_all() gives back the values that were not sent when receiver is gone in the middle
+#[test]
+fn channel_send_all_receiver_dropped() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let (mut tx, mut rx) = toy_rt::channel::<u32>(rt);
+
+        let (sent, received) = toy_rt::join!(tx.send_all(vec![1, 2, 3, 4, 5]), async move {
+            let mut received = Vec::new();
+            for _ in 0..3 {
+                received.push(rx.next().await.unwrap());
+            }
+            received // rx is dropped here
+        })
+        .await;
+
+        assert_eq!(received, vec![1, 2, 3]);
+        assert_eq!(sent, Err(toy_rt::SendError::Disconnected(vec![4, 5])));
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// The peeked value is returned again by peek() and then consumed by next()
+#[test]
+fn channel_peek_then_next() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let (mut tx, mut rx) = toy_rt::channel::<u32>(rt);
+
+        let sender = async move { tx.send_all(vec![1, 2]).await.unwrap() }; // tx is dropped then
+        let (_, received) = toy_rt::join!(sender, async {
+            assert_eq!(*rx.peek().await.unwrap(), 1);
+            assert_eq!(*rx.peek().await.unwrap(), 1);
+            let first = rx.next().await.unwrap();
+            assert_eq!(*rx.peek().await.unwrap(), 2);
+            (first, rx.try_recv().unwrap())
+        })
+        .await;
+
+        assert_eq!(received, (1, 2));
+        assert!(rx.peek().await.is_err());
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// The peeked value is dropped with the receiver
+#[test]
+fn channel_peeked_value_dropped_with_receiver() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let value = std::rc::Rc::new(42);
+        let (mut tx, mut rx) = toy_rt::channel_buffered::<std::rc::Rc<u32>>(rt, 1);
+
+        tx.send(value.clone()).await.unwrap();
+        assert_eq!(**rx.peek().await.unwrap(), 42);
+        assert_eq!(std::rc::Rc::strong_count(&value), 2);
+
+        drop(rx);
+        assert_eq!(std::rc::Rc::strong_count(&value), 1);
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// The send raced against a timeout gives the value back by cancel(), the value received before
+// cancel() is not returned.
+#[test]
+fn channel_send_cancellable_returns_value() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let (mut tx, mut rx) = toy_rt::channel::<u32>(rt);
+
+        // nobody receives: the timeout wins and the value is returned
+        {
+            let permit = tx.send_cancellable(42);
+            toy_rt::pin_local!(permit);
+            let first = toy_rt::race2(permit.as_mut(), toy_rt::sleep(rt, Duration::ZERO)).await;
+            assert!(matches!(first, toy_rt::OneOf2::Second(())));
+            assert_eq!(permit.cancel(), Some(42));
+        }
+        assert_eq!(rx.try_recv(), Err(toy_rt::TryRecvError::Empty));
+
+        // the receiver has got the value, so there is nothing to return
+        {
+            let permit = tx.send_cancellable(43);
+            toy_rt::pin_local!(permit);
+            let (sent, received) = toy_rt::join!(permit.as_mut(), rx.next()).await;
+            assert_eq!((sent, received), (Ok(()), Ok(43)));
+            assert_eq!(permit.cancel(), None);
+        }
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// recv_many() takes the buffered values up to the limit in one await, the error comes only
+// when there is nothing left to receive
+#[test]
+fn channel_recv_many_drains_buffer() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let (mut tx, mut rx) = toy_rt::channel_buffered::<u32>(rt, 8);
+        for value in 0..8 {
+            tx.try_send(value).unwrap();
+        }
+
+        let mut buf = Vec::new();
+        assert_eq!(rx.recv_many(&mut buf, 5).await, Ok(5));
+        assert_eq!(buf, vec![0, 1, 2, 3, 4]);
+
+        // the rest is received after the sender is dropped, then the error
+        drop(tx);
+        assert_eq!(rx.recv_many(&mut buf, 5).await, Ok(3));
+        assert_eq!(buf, vec![0, 1, 2, 3, 4, 5, 6, 7]);
+        assert_eq!(rx.recv_many(&mut buf, 5).await, Err(toy_rt::RecvError::Disconnected));
+
+        // rendezvous channel receives the value of the sender awaiting in send()
+        let (mut tx, mut rx) = toy_rt::channel::<u32>(rt);
+        let mut buf = Vec::new();
+        let (sent, received) = toy_rt::join!(tx.send(42), rx.recv_many(&mut buf, 5)).await;
+        assert_eq!((sent, received, buf), (Ok(()), Ok(1), vec![42]));
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// Debug of the channel halves has the channel id and the state of the channel
+#[test]
+fn channel_debug_format() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let (tx, rx) = toy_rt::channel::<u32>(rt);
+        let live = format!("{:?}", tx);
+        assert!(live.starts_with("Sender(chan:"), "{}", live);
+        assert!(live.contains("Idle"), "{}", live);
+        assert!(format!("{:?}", rx).starts_with("Recver(chan:"));
+
+        // the sender of the channel without the receiver
+        drop(rx);
+        let disconnected = format!("{:?}", tx);
+        assert!(disconnected.contains("Gone"), "{}", disconnected);
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
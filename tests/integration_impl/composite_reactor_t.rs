@@ -0,0 +1,161 @@
+//  \ O /
+//  / * \    aiur: the homeplanet for the famous executors
+// |' | '|   (c) 2020 - present, Vladimir Zvezda
+//   / \
+//
+// Tests for CompositeReactor: mock I/O reactor with the timers of toy reactor.
+use aiur::toy_rt::{self};
+use aiur::{CompositeReactor, EventId, EventNode, Reactor, TimeoutReactor, Tracer};
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+// The I/O that is ready right away: the watched events are returned by the next wait.
+struct MockIo {
+    watched: RefCell<VecDeque<EventId>>,
+    timeouts: Cell<u32>,
+}
+
+impl MockIo {
+    fn new() -> Self {
+        MockIo {
+            watched: RefCell::new(VecDeque::new()),
+            timeouts: Cell::new(0),
+        }
+    }
+
+    fn watch(&self, event_id: EventId) {
+        self.watched.borrow_mut().push_back(event_id);
+    }
+
+    fn unwatch(&self, event_id: EventId) {
+        self.watched.borrow_mut().retain(|watched| *watched != event_id);
+    }
+}
+
+impl Reactor for MockIo {
+    fn wait(&self) -> EventId {
+        self.watched
+            .borrow_mut()
+            .pop_front()
+            .expect("MockIo: wait() invoked with nothing to wait")
+    }
+}
+
+impl TimeoutReactor for MockIo {
+    fn wait_timeout(&self, _timeout: Duration) -> Option<EventId> {
+        let event_id = self.watched.borrow_mut().pop_front();
+        if event_id.is_none() {
+            self.timeouts.set(self.timeouts.get() + 1);
+        }
+        event_id
+    }
+}
+
+type Runtime = aiur::Runtime<CompositeReactor<MockIo, toy_rt::ToyReactor>>;
+
+// Leaf future that waits for readiness in MockIo
+struct Readable<'runtime> {
+    rt: &'runtime Runtime,
+    event_node: EventNode,
+    watching: bool,
+}
+
+impl<'runtime> Readable<'runtime> {
+    fn new(rt: &'runtime Runtime) -> Self {
+        Readable {
+            rt,
+            event_node: EventNode::new(),
+            watching: false,
+        }
+    }
+}
+
+impl<'runtime> Future for Readable<'runtime> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let this = unsafe { self.get_unchecked_mut() };
+
+        if !this.watching {
+            let event_id = unsafe { this.event_node.on_pin(ctx) };
+            this.rt.io().io_reactor().watch(event_id);
+            this.watching = true;
+            Poll::Pending
+        } else if this.event_node.is_awoken_for(this.rt) {
+            this.watching = false;
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+impl<'runtime> Drop for Readable<'runtime> {
+    fn drop(&mut self) {
+        if self.watching {
+            if let Some(event_id) = self.event_node.on_cancel() {
+                self.rt.io().io_reactor().unwatch(event_id);
+            }
+        }
+    }
+}
+
+fn with_composite_runtime<FuncT>(async_function: FuncT)
+where
+    FuncT: for<'runtime> aiur::LifetimeLinkerFn<
+        'runtime,
+        CompositeReactor<MockIo, toy_rt::ToyReactor>,
+        (),
+        (),
+    >,
+{
+    let reactor = CompositeReactor::new(
+        MockIo::new(),
+        toy_rt::ToyReactor::new_with_mode(toy_rt::SleepMode::Emulated),
+    );
+    aiur::with_runtime_base(reactor, Tracer::new_empty(), async_function, ());
+}
+
+// The readiness comes from the I/O reactor while the sleep is waiting in timer reactor
+#[test]
+fn composite_reactor_io_and_sleep_concurrently() {
+    async fn async_starter(rt: &Runtime, _: ()) {
+        let (readable_at, slept_at) = toy_rt::join!(
+            async {
+                Readable::new(rt).await;
+                rt.elapsed()
+            },
+            async {
+                aiur::sleep(rt, Duration::from_millis(1000)).await;
+                rt.elapsed()
+            }
+        )
+        .await;
+
+        assert_eq!(readable_at, Duration::from_millis(0));
+        assert_eq!(slept_at, Duration::from_millis(1000));
+
+        // the second wait for I/O was timed out by the sleep
+        assert_eq!(rt.io().io_reactor().timeouts.get(), 1);
+    }
+
+    with_composite_runtime(async_starter);
+}
+
+// Without the timers the I/O reactor waits without timeout
+#[test]
+fn composite_reactor_io_only() {
+    async fn async_starter(rt: &Runtime, _: ()) {
+        Readable::new(rt).await;
+        Readable::new(rt).await;
+
+        assert_eq!(rt.io().io_reactor().timeouts.get(), 0);
+        assert!(!rt.io().timer_reactor().has_pending());
+    }
+
+    with_composite_runtime(async_starter);
+}
@@ -0,0 +1,93 @@
+//  \ O /
+//  / * \    aiur: the homeplanet for the famous executors
+// |' | '|   (c) 2020 - present, Vladimir Zvezda
+//   / \
+//
+// Tests for Context: the shared state that can be borrowed across await points.
+use aiur::toy_rt::{self};
+use std::time::Duration;
+
+//const SLEEP_MODE: toy_rt::SleepMode = toy_rt::SleepMode::Actual;
+const SLEEP_MODE: toy_rt::SleepMode = toy_rt::SleepMode::Emulated;
+
+// Concurrent branches take turns to borrow the context and hold the borrow across await
+#[test]
+fn context_borrowed_by_branches_in_turn() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) -> Vec<u32> {
+        let log = toy_rt::Context::new(Vec::new());
+        toy_rt::join!(
+            async {
+                let mut log = log.borrow_mut(rt);
+                log.push(1);
+                toy_rt::sleep(rt, Duration::from_millis(1000)).await;
+                log.push(2);
+            },
+            async {
+                toy_rt::sleep(rt, Duration::from_millis(2000)).await;
+                log.borrow_mut(rt).push(3);
+            }
+        )
+        .await;
+
+        assert!(!log.is_borrowed());
+        log.into_inner()
+    }
+
+    let log = toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+    assert_eq!(log, vec![1, 2, 3]);
+}
+
+// The borrow by concurrent branch that is held across await gives an error
+#[test]
+fn context_borrowed_by_another_branch_is_error() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let context = toy_rt::Context::new(0);
+        let (_, res) = toy_rt::join!(
+            async {
+                let _guard = context.borrow_mut(rt);
+                toy_rt::sleep(rt, Duration::from_millis(1000)).await;
+            },
+            async { context.try_borrow_mut(rt).map(|_| ()) }
+        )
+        .await;
+
+        assert_eq!(res, Err(toy_rt::ContextError::Borrowed));
+        assert!(context.try_borrow_mut(rt).is_ok());
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// The borrow held across the nested loop is reported as such
+#[test]
+fn context_borrow_across_nested_loop_is_error() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let context = toy_rt::Context::new(0);
+        let guard = context.borrow_mut(rt);
+        let res = rt.nested_loop(async {
+            toy_rt::sleep(rt, Duration::from_millis(1000)).await;
+            context.try_borrow_mut(rt).map(|_| ())
+        });
+        drop(guard);
+
+        assert_eq!(res, Err(toy_rt::ContextError::BorrowedByOuterLoop));
+        assert_eq!(*context.borrow_mut(rt), 0);
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// borrow_mut() panics with message that explains the reason
+#[test]
+#[should_panic(expected = "aiur: Context is already borrowed by a future in the outer loop")]
+fn context_borrow_mut_across_nested_loop_panics() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let context = toy_rt::Context::new(0);
+        let _guard = context.borrow_mut(rt);
+        rt.nested_loop(async {
+            *context.borrow_mut(rt) += 1;
+        });
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
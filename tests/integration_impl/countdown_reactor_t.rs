@@ -0,0 +1,81 @@
+//  \ O /
+//  / * \    aiur: the homeplanet for the famous executors
+// |' | '|   (c) 2020 - present, Vladimir Zvezda
+//   / \
+//
+// Tests for the event registration API of Reactor with CountdownReactor from toy_rt.
+use aiur::toy_rt::{self, CountdownReactor};
+use aiur::{EventNode, Reactor, Tracer};
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+type Runtime = aiur::Runtime<CountdownReactor>;
+
+// Leaf future that registers its event in reactor and completes when reactor emits it
+struct Countdown<'runtime> {
+    rt: &'runtime Runtime,
+    event_node: EventNode,
+    registered: bool,
+}
+
+impl<'runtime> Countdown<'runtime> {
+    fn new(rt: &'runtime Runtime) -> Self {
+        Countdown {
+            rt,
+            event_node: EventNode::new(),
+            registered: false,
+        }
+    }
+}
+
+impl<'runtime> Future for Countdown<'runtime> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let this = unsafe { self.get_unchecked_mut() };
+
+        if !this.registered {
+            let event_id = unsafe { this.event_node.on_pin(ctx) };
+            this.rt.io().register_event(ctx.waker().clone(), event_id);
+            this.registered = true;
+            Poll::Pending
+        } else if this.event_node.is_awoken_for(this.rt) {
+            this.registered = false;
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+impl<'runtime> Drop for Countdown<'runtime> {
+    fn drop(&mut self) {
+        if self.registered {
+            if let Some(event_id) = self.event_node.on_cancel() {
+                self.rt.io().deregister_event(event_id);
+            }
+        }
+    }
+}
+
+// The events registered at the same time happen after the same number of waits
+#[test]
+fn countdown_reactor_wakes_registered_events() {
+    async fn async_starter(rt: &Runtime, _: ()) {
+        toy_rt::join!(Countdown::new(rt), Countdown::new(rt)).await;
+        // two waits with nothing happened, then the waits for each of events
+        assert_eq!(rt.io().waits(), 4);
+
+        Countdown::new(rt).await;
+        assert_eq!(rt.io().waits(), 7);
+        assert!(!rt.io().has_pending());
+    }
+
+    aiur::with_runtime_base(
+        CountdownReactor::new(3),
+        Tracer::new_empty(),
+        async_starter,
+        (),
+    );
+}
@@ -0,0 +1,124 @@
+//  \ O /
+//  / * \    aiur: the homeplanet for the famous executors
+// |' | '|   (c) 2020 - present, Vladimir Zvezda
+//   / \
+//
+// Tests for the programs that cannot make progress: runtime returns RunError::Deadlock.
+use aiur::toy_rt::{self};
+use std::time::Duration;
+
+//const SLEEP_MODE: toy_rt::SleepMode = toy_rt::SleepMode::Actual;
+const SLEEP_MODE: toy_rt::SleepMode = toy_rt::SleepMode::Emulated;
+
+// Two tasks, each one waits for the value from another one before sending its own
+#[test]
+fn deadlock_mutually_waiting_channels() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let (mut tx1, mut rx1) = toy_rt::channel::<u32>(rt);
+        let (mut tx2, mut rx2) = toy_rt::channel::<u32>(rt);
+
+        toy_rt::join_tasks!(
+            async {
+                let value = rx1.next().await.unwrap();
+                let _ = tx2.send(value).await;
+            },
+            async {
+                let value = rx2.next().await.unwrap();
+                let _ = tx1.send(value).await;
+            }
+        )
+        .await;
+    }
+
+    // the root task and two tasks of join_tasks!
+    assert_eq!(
+        toy_rt::try_with_runtime_in_mode(SLEEP_MODE, async_starter, ()),
+        Err(toy_rt::RunError::Deadlock { pending_tasks: 3 })
+    );
+}
+
+// The sender is alive, but nobody is going to send and there are no timers
+#[test]
+fn deadlock_receiver_without_sender() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let (_tx, mut rx) = toy_rt::channel::<u32>(rt);
+        let _ = rx.next().await;
+    }
+
+    assert_eq!(
+        toy_rt::try_with_runtime_in_mode(SLEEP_MODE, async_starter, ()),
+        Err(toy_rt::RunError::Deadlock { pending_tasks: 1 })
+    );
+}
+
+// The same with oneshot and the sleep that is completed before deadlock is found
+#[test]
+fn deadlock_oneshot_receiver_after_sleep() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let (_tx, rx) = toy_rt::oneshot::<u32>(rt);
+        toy_rt::sleep(rt, Duration::from_millis(100)).await;
+        let _ = rx.await;
+    }
+
+    assert_eq!(
+        toy_rt::try_with_runtime_in_mode(SLEEP_MODE, async_starter, ()),
+        Err(toy_rt::RunError::Deadlock { pending_tasks: 1 })
+    );
+}
+
+// Nothing is registered anywhere: the futures just never complete
+#[test]
+fn deadlock_join_of_never_completing_futures() {
+    async fn async_starter(_rt: &toy_rt::Runtime, _: ()) {
+        toy_rt::join!(std::future::pending::<()>(), std::future::pending::<()>()).await;
+    }
+
+    assert_eq!(
+        toy_rt::try_with_runtime_in_mode(SLEEP_MODE, async_starter, ()),
+        Err(toy_rt::RunError::Deadlock { pending_tasks: 1 })
+    );
+}
+
+// The deadlock in nested loop is returned to the caller that can continue
+#[test]
+fn deadlock_in_nested_loop_is_catchable() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) -> u32 {
+        let result = rt.try_nested_loop(std::future::pending::<()>());
+        assert_eq!(result, Err(toy_rt::RunError::Deadlock { pending_tasks: 1 }));
+
+        toy_rt::sleep(rt, Duration::from_millis(100)).await;
+        42
+    }
+
+    assert_eq!(toy_rt::try_with_runtime_in_mode(SLEEP_MODE, async_starter, ()), Ok(42));
+}
+
+// Without try_ the deadlock is a panic
+#[test]
+#[should_panic(expected = "Deadlock")]
+fn deadlock_panics_in_with_runtime() {
+    async fn async_starter(_rt: &toy_rt::Runtime, _: ()) {
+        std::future::pending::<()>().await;
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// The runtime created by caller returns the deadlock from try_block_on() instead of panic
+#[test]
+fn deadlock_try_block_on_oneshot_without_send() {
+    let rt = toy_rt::Runtime::new(
+        toy_rt::ToyReactor::new_with_mode(SLEEP_MODE),
+        aiur::Tracer::new_empty(),
+    );
+
+    let res = rt.try_block_on(async {
+        let (_tx, rx) = toy_rt::oneshot::<u32>(&rt);
+        rx.await.is_ok()
+    });
+    assert_eq!(res, Err(toy_rt::RunError::Deadlock { pending_tasks: 1 }));
+
+    // runtime still works after that
+    let res = toy_rt::try_block_on(&rt, toy_rt::sleep(&rt, Duration::from_millis(100)));
+    assert_eq!(res, Ok(()));
+}
@@ -0,0 +1,142 @@
+//  \ O /
+//  / * \    aiur: the homeplanet for the famous executors
+// |' | '|   (c) 2020 - present, Vladimir Zvezda
+//   / \
+//
+// Tests for EventId API used by the reactor of external crate: the runtime is exported with
+// export_runtime!() like toy_rt does.
+use aiur::{EventId, EventNode, GetEventId, Reactor, Tracer};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+// The reactor that keeps the scheduled reads in a map by EventId like the reactor for OS I/O
+// would do. The reads are completed in the order of their file descriptors.
+struct MapReactor {
+    reads: RefCell<HashMap<EventId, u32>>,
+}
+
+impl MapReactor {
+    fn new() -> Self {
+        MapReactor {
+            reads: RefCell::new(HashMap::new()),
+        }
+    }
+
+    fn schedule_read(&self, event_id: EventId, fd: u32) {
+        assert!(!event_id.is_null());
+        self.reads.borrow_mut().insert(event_id, fd);
+    }
+
+    fn cancel_read(&self, event_id: EventId) {
+        self.reads.borrow_mut().remove(&event_id);
+    }
+}
+
+impl Reactor for MapReactor {
+    fn wait(&self) -> EventId {
+        let mut reads = self.reads.borrow_mut();
+        let (event_id, _) = reads
+            .iter()
+            .map(|(event_id, fd)| (*event_id, *fd))
+            .min_by_key(|(_, fd)| *fd)
+            .expect("MapReactor: wait() invoked with nothing to wait");
+        reads.remove(&event_id);
+        event_id
+    }
+}
+
+mod map_rt {
+    use super::MapReactor;
+    aiur::export_runtime!(MapReactor);
+}
+
+// Leaf future that reads the file descriptor with MapReactor
+struct Read<'runtime> {
+    rt: &'runtime map_rt::Runtime,
+    event_node: EventNode,
+    fd: u32,
+    scheduled: bool,
+}
+
+impl<'runtime> Read<'runtime> {
+    fn new(rt: &'runtime map_rt::Runtime, fd: u32) -> Self {
+        Read {
+            rt,
+            event_node: EventNode::new(),
+            fd,
+            scheduled: false,
+        }
+    }
+}
+
+impl<'runtime> GetEventId for Read<'runtime> {
+    fn get_event_id(&self) -> EventId {
+        self.event_node.get_event_id()
+    }
+}
+
+impl<'runtime> Future for Read<'runtime> {
+    type Output = u32;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let this = unsafe { self.get_unchecked_mut() };
+
+        if !this.scheduled {
+            let event_id = unsafe { this.event_node.on_pin(ctx) };
+            assert_eq!(event_id, this.get_event_id());
+            this.rt.io().schedule_read(event_id, this.fd);
+            this.scheduled = true;
+            Poll::Pending
+        } else if this.event_node.is_awoken_for(this.rt) {
+            this.scheduled = false;
+            Poll::Ready(this.fd)
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+impl<'runtime> Drop for Read<'runtime> {
+    fn drop(&mut self) {
+        if self.scheduled {
+            if let Some(event_id) = self.event_node.on_cancel() {
+                self.rt.io().cancel_read(event_id);
+            }
+        }
+    }
+}
+
+// EventId is the address of EventNode and null() is not an event
+#[test]
+fn event_id_of_event_node() {
+    let node = EventNode::new();
+    let event_id = GetEventId::get_event_id(&node);
+
+    assert_eq!(event_id, node.get_event_id());
+    assert_eq!(event_id.as_ptr(), &node as *const EventNode as *const ());
+    assert!(!event_id.is_null());
+    assert!(EventId::null().is_null());
+    assert_ne!(event_id, EventId::null());
+    assert_ne!(event_id, EventId::terminated());
+}
+
+// The reads completed by the reactor that looks up the events in the map
+#[test]
+fn event_id_as_map_key_in_reactor() {
+    async fn async_starter(rt: &map_rt::Runtime, _: ()) -> Vec<u32> {
+        let mut completed = Vec::new();
+        map_rt::pinned_any_of!(stream, Read::new(rt, 7), Read::new(rt, 3), Read::new(rt, 5));
+        while let Some(one_of) = stream.next().await {
+            completed.push(one_of.into_inner());
+        }
+
+        assert!(rt.io().reads.borrow().is_empty());
+        completed
+    }
+
+    let completed = map_rt::with_runtime(MapReactor::new, Tracer::new_empty(), async_starter, ());
+    assert_eq!(completed, vec![3, 5, 7]);
+}
@@ -38,6 +38,71 @@ impl<FutureT: Future> Future for PollInnerOnceFuture<FutureT> {
     }
 }
 
+// -----------------------------------------------------------------------------------------
+// PollNTimesFuture
+//
+// Generalization of PollInnerOnceFuture: polls the inner future up to `k` times. Between the
+// polls the future returns Pending, so runtime can deliver the events, e.g. exchange the
+// channel values. Resolves with the inner poll result (Poll::Pending if inner was not ready
+// after `k` polls).
+// -----------------------------------------------------------------------------------------
+struct PollNTimesFuture<FutureT: Future> {
+    inner: FutureT,
+    polls_left: u32,
+}
+
+impl<FutureT: Future> PollNTimesFuture<FutureT> {
+    fn new(inner: FutureT, k: u32) -> Self {
+        PollNTimesFuture {
+            inner,
+            polls_left: k,
+        }
+    }
+}
+
+impl<FutureT: Future> Future for PollNTimesFuture<FutureT> {
+    type Output = Poll<FutureT::Output>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let this = unsafe { self.get_unchecked_mut() };
+
+        if this.polls_left == 0 {
+            return Poll::Ready(Poll::Pending);
+        }
+
+        this.polls_left -= 1;
+        //  This is okay because `inner` is pinned when `self` is.
+        match unsafe { Pin::new_unchecked(&mut this.inner) }.poll(ctx) {
+            Poll::Ready(value) => Poll::Ready(Poll::Ready(value)),
+            Poll::Pending if this.polls_left == 0 => Poll::Ready(Poll::Pending),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------------------
+// PollAfterReadyFuture
+//
+// Polls the inner future until it is ready and then polls it once again right away. This is
+// the misuse the leaf futures have to detect.
+// -----------------------------------------------------------------------------------------
+struct PollAfterReadyFuture<FutureT: Future> {
+    inner: FutureT,
+}
+
+impl<FutureT: Future> Future for PollAfterReadyFuture<FutureT> {
+    type Output = Poll<FutureT::Output>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        //  This is okay because `inner` is pinned when `self` is.
+        let mut inner = unsafe { self.map_unchecked_mut(|s| &mut s.inner) };
+        match inner.as_mut().poll(ctx) {
+            Poll::Ready(_) => Poll::Ready(inner.poll(ctx)),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
 // -----------------------------------------------------------------------------------------
 // Any2Void
 //
@@ -87,6 +152,17 @@ pub async fn poll_inner_once<FutureT: Future>(inner: FutureT) {
     PollInnerOnceFuture::new(inner).await;
 }
 
+// Polls the inner future at most `k` times, gives runtime a chance to handle events between
+// the polls.
+pub async fn poll_n_times<FutureT: Future>(inner: FutureT, k: u32) -> Poll<FutureT::Output> {
+    PollNTimesFuture::new(inner, k).await
+}
+
+// Polls the inner future once again after it has been completed
+pub async fn poll_after_ready<FutureT: Future>(inner: FutureT) -> Poll<FutureT::Output> {
+    PollAfterReadyFuture { inner }.await
+}
+
 pub async fn any2void<FutureT1: Future, FutureT2: Future>(f1: FutureT1, f2: FutureT2) {
     Any2Void::new(f1, f2).await;
 }
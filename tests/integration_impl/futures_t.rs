@@ -0,0 +1,145 @@
+//  \ O /
+//  / * \    aiur: the homeplanet for the famous executors
+// |' | '|   (c) 2020 - present, Vladimir Zvezda
+//   / \
+//
+// Tests for the futures feature: the channel halves used with the futures combinators.
+use aiur::toy_rt::{self};
+use futures_util::{Sink, SinkExt, Stream, StreamExt};
+use std::pin::Pin;
+use std::task::Context;
+use std::time::Duration;
+
+const SLEEP_MODE: toy_rt::SleepMode = toy_rt::SleepMode::Emulated;
+
+// Recver is consumed as a Stream: the stream ends when the sender is dropped
+#[test]
+fn futures_recver_stream_next() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let (mut tx, mut rx) = toy_rt::channel::<u32>(rt);
+
+        let (_, received) = toy_rt::join!(
+            async move {
+                for value in 1..=3 {
+                    toy_rt::sleep(rt, Duration::from_millis(100)).await;
+                    tx.send(value).await.unwrap();
+                }
+            },
+            async {
+                let mut received = Vec::new();
+                while let Some(value) = StreamExt::next(&mut rx).await {
+                    received.push(value);
+                }
+                received
+            }
+        )
+        .await;
+
+        assert_eq!(received, vec![1, 2, 3]);
+        assert_eq!(rt.elapsed(), Duration::from_millis(300));
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// The stream combinators work with the buffered channel too
+#[test]
+fn futures_recver_stream_map() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let (mut tx, rx) = toy_rt::channel_buffered::<u32>(rt, 2);
+
+        let (_, doubled) = toy_rt::join!(
+            async move { tx.send_all(vec![1, 2, 3, 4]).await.unwrap() },
+            rx.map(|value| value * 2).collect::<Vec<u32>>()
+        )
+        .await;
+
+        assert_eq!(doubled, vec![2, 4, 6, 8]);
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// Sender is a Sink: the value not sent is given back when the receiver is gone
+#[test]
+fn futures_sender_sink() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let (mut tx, mut rx) = toy_rt::channel::<u32>(rt);
+
+        let (sent, received) = toy_rt::join!(
+            async move {
+                SinkExt::send(&mut tx, 1).await.unwrap();
+                SinkExt::send(&mut tx, 2).await
+            },
+            async move {
+                let value = rx.next().await.unwrap();
+                toy_rt::sleep(rt, Duration::from_millis(100)).await;
+                value // rx is dropped here
+            }
+        )
+        .await;
+
+        assert_eq!(received, 1);
+        assert_eq!(sent, Err(toy_rt::SendError::Disconnected(2)));
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// The stream receive abandoned by the combinator is cancelled by Recver::next()
+#[test]
+fn futures_recver_stream_next_abandoned() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let (mut tx, mut rx) = toy_rt::channel::<u32>(rt);
+
+        let (_, received) = toy_rt::join!(
+            async move {
+                toy_rt::sleep(rt, Duration::from_millis(200)).await;
+                tx.send(42).await.unwrap();
+            },
+            async {
+                let timed_out = toy_rt::race2(
+                    StreamExt::next(&mut rx),
+                    toy_rt::sleep(rt, Duration::from_millis(100)),
+                )
+                .await;
+                assert!(matches!(timed_out, toy_rt::OneOf2::Second(())));
+                rx.next().await.unwrap()
+            }
+        )
+        .await;
+
+        assert_eq!(received, 42);
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// The stream polled with a foreign waker (e.g. by the executor of futures crate) panics
+#[test]
+#[should_panic(expected = "aiur/channel_recver: polled with a foreign waker")]
+fn futures_recver_stream_foreign_waker() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let (_tx, mut rx) = toy_rt::channel::<u32>(rt);
+
+        let mut ctx = Context::from_waker(futures_util::task::noop_waker_ref());
+        let _ = Stream::poll_next(Pin::new(&mut rx), &mut ctx);
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// The same for the sink that flushes the value with a foreign waker
+#[test]
+#[should_panic(expected = "aiur/channel_sender: polled with a foreign waker")]
+fn futures_sender_sink_foreign_waker() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let (mut tx, _rx) = toy_rt::channel::<u32>(rt);
+
+        let mut ctx = Context::from_waker(futures_util::task::noop_waker_ref());
+        Sink::start_send(Pin::new(&mut tx), 1).unwrap();
+        let _ = Sink::poll_flush(Pin::new(&mut tx), &mut ctx);
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
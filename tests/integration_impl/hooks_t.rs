@@ -0,0 +1,93 @@
+//  \ O /
+//  / * \    aiur: the homeplanet for the famous executors
+// |' | '|   (c) 2020 - present, Vladimir Zvezda
+//   / \
+//
+// Tests for RuntimeHooks
+use aiur::toy_rt::{self};
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::Duration;
+
+//const SLEEP_MODE: toy_rt::SleepMode = toy_rt::SleepMode::Actual;
+const SLEEP_MODE: toy_rt::SleepMode = toy_rt::SleepMode::Emulated;
+
+#[derive(Default)]
+struct Counters {
+    polls: Cell<u32>,
+    completions: Cell<u32>,
+    waits: Cell<u32>,
+    events: Cell<u32>,
+}
+
+impl aiur::RuntimeHooks for Counters {
+    fn on_task_poll(&self, _task_id: aiur::TaskId) {
+        self.polls.set(self.polls.get() + 1);
+    }
+
+    fn on_task_complete(&self, _task_id: aiur::TaskId) {
+        self.completions.set(self.completions.get() + 1);
+    }
+
+    fn on_reactor_wait(&self) {
+        self.waits.set(self.waits.get() + 1);
+    }
+
+    fn on_event(&self, _event_id: aiur::EventId) {
+        self.events.set(self.events.get() + 1);
+    }
+}
+
+// The task of two joined sleeps is polled once to start and once for each sleep
+#[test]
+fn hooks_count_polls_of_join() {
+    let rt = toy_rt::Runtime::new(
+        toy_rt::ToyReactor::new_with_mode(SLEEP_MODE),
+        aiur::Tracer::new_empty(),
+    );
+
+    let counters = Rc::new(Counters::default());
+    rt.set_hooks(Some(counters.clone()));
+
+    rt.block_on(toy_rt::join!(
+        toy_rt::sleep(&rt, Duration::from_millis(100)),
+        toy_rt::sleep(&rt, Duration::from_millis(200))
+    ));
+
+    assert_eq!(counters.polls.get(), 3);
+    assert_eq!(counters.completions.get(), 1);
+    assert_eq!(counters.waits.get(), 2);
+    assert_eq!(counters.events.get(), 2);
+
+    // removed hooks are not invoked anymore
+    rt.set_hooks(None);
+    rt.block_on(toy_rt::sleep(&rt, Duration::from_millis(100)));
+    assert_eq!(counters.polls.get(), 3);
+}
+
+// The task awoken by three oneshots in one phase is polled once for all of them
+#[test]
+fn hooks_count_polls_of_coalesced_exchanges() {
+    let rt = toy_rt::Runtime::new(
+        toy_rt::ToyReactor::new_with_mode(SLEEP_MODE),
+        aiur::Tracer::new_empty(),
+    );
+
+    let counters = Rc::new(Counters::default());
+    rt.set_hooks(Some(counters.clone()));
+
+    let (tx1, rx1) = toy_rt::oneshot::<u32>(&rt);
+    let (tx2, rx2) = toy_rt::oneshot::<u32>(&rt);
+    let (tx3, rx3) = toy_rt::oneshot::<u32>(&rt);
+
+    // The receivers are awaited by the task, the sleep gives the values to all of them at once
+    let ((v1, v2, v3), _) = rt.block_on(toy_rt::join!(toy_rt::join!(rx1, rx2, rx3), async {
+        toy_rt::sleep(&rt, Duration::from_millis(100)).await;
+        (tx1.send_now(1), tx2.send_now(2), tx3.send_now(3))
+    }));
+
+    assert_eq!((v1.unwrap(), v2.unwrap(), v3.unwrap()), (1, 2, 3));
+    // the first poll, the poll for the sleep and one poll for the three exchanges
+    assert_eq!(counters.polls.get(), 3);
+    assert_eq!(counters.events.get(), 4);
+}
@@ -8,6 +8,8 @@
 use aiur::toy_rt::{self};
 use super::measure::{self};
 
+use std::cell::Cell;
+use std::panic::{self, AssertUnwindSafe};
 use std::time::Duration;
 
 // With emulated sleep test run instantly, actual sleep actually wait for specified
@@ -87,3 +89,200 @@ fn join8_returns_tupple() {
 
     toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
 }
+
+// Join of futures which outputs have destructors
+#[test]
+fn join_outputs_with_drop() {
+    async fn make_vec(rt: &toy_rt::Runtime, ms: u64, len: usize) -> Vec<u32> {
+        toy_rt::sleep(rt, Duration::from_millis(ms)).await;
+        vec![7; len]
+    }
+
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let (v1, v2, v3) =
+            toy_rt::join!(make_vec(rt, 300, 1), make_vec(rt, 100, 2), make_vec(rt, 200, 3)).await;
+        assert_eq!((v1.len(), v2.len(), v3.len()), (1, 2, 3));
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// join_vec returns the outputs in the order of futures, not in the order of completion
+#[test]
+fn join_vec_keeps_order() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) -> Vec<u32> {
+        let futures = (0..50u32)
+            .map(|i| measure::sleep_and_ret(rt, Duration::from_millis(10 * (50 - i) as u64), i))
+            .collect();
+        let res = toy_rt::join_vec(futures).await;
+        assert_eq!(rt.elapsed(), Duration::from_millis(500));
+        res
+    }
+
+    let res = toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+    assert_eq!(res, (0..50).collect::<Vec<u32>>());
+}
+
+// join_buffered never runs more than max_concurrent futures and keeps the order of outputs
+#[test]
+fn join_buffered_limits_concurrency() {
+    async fn tracked(rt: &toy_rt::Runtime, active: &Cell<u32>, peak: &Cell<u32>, i: u32) -> u32 {
+        active.set(active.get() + 1);
+        peak.set(peak.get().max(active.get()));
+        toy_rt::sleep(rt, Duration::from_millis(100 * (i % 4 + 1) as u64)).await;
+        active.set(active.get() - 1);
+        i
+    }
+
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let active = Cell::new(0);
+        let peak = Cell::new(0);
+        let futures = (0..10u32).map(|i| tracked(rt, &active, &peak, i)).collect();
+
+        let res = toy_rt::join_buffered(futures, 3).await;
+        assert_eq!(res, (0..10).collect::<Vec<u32>>());
+        assert_eq!(peak.get(), 3);
+        assert_eq!(active.get(), 0);
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// a fallible future to try_join: sleep for given duration and return the given result
+async fn sleep_and_try(
+    rt: &toy_rt::Runtime,
+    ms: u64,
+    result: Result<u32, &'static str>,
+) -> Result<u32, &'static str> {
+    toy_rt::sleep(rt, Duration::from_millis(ms)).await;
+    result
+}
+
+// try_join of futures that all succeed returns all the results
+#[test]
+fn try_join3_returns_tuple() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let res = toy_rt::try_join3(
+            sleep_and_try(rt, 300, Ok(1)),
+            sleep_and_try(rt, 100, Ok(2)),
+            sleep_and_try(rt, 200, Ok(3)),
+        )
+        .await;
+        assert_eq!(res, Ok((1, 2, 3)));
+        assert_eq!(rt.elapsed(), Duration::from_millis(300));
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// The second future fails while the first one is still sleeping: the first future is dropped
+// and its timer is cancelled.
+#[test]
+fn try_join2_error_drops_pending_future() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let res = toy_rt::try_join2(
+            sleep_and_try(rt, 1000, Ok(1)),
+            sleep_and_try(rt, 100, Err("second")),
+        )
+        .await;
+        assert_eq!(res, Err("second"));
+        assert_eq!(rt.elapsed(), Duration::from_millis(100));
+        assert!(!rt.io().has_pending());
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// The first future has its result ready before the second one fails, the result is discarded
+#[test]
+fn try_join2_error_discards_completed_result() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let res = toy_rt::try_join2(
+            async { sleep_and_try(rt, 100, Ok(1)).await.map(|v| vec![v; 3]) },
+            sleep_and_try(rt, 200, Err("second")),
+        )
+        .await;
+        assert_eq!(res, Err("second"));
+        assert_eq!(rt.elapsed(), Duration::from_millis(200));
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+
+// Output of the joined future that counts its drops
+struct DropCounter<'counter>(&'counter Cell<u32>);
+
+impl<'counter> Drop for DropCounter<'counter> {
+    fn drop(&mut self) {
+        self.0.set(self.0.get() + 1);
+    }
+}
+
+// The output of completed future is dropped once when the other future panics
+#[test]
+fn join2_panic_drops_completed_output() {
+    async fn async_starter(rt: &toy_rt::Runtime, drops: &Cell<u32>) {
+        toy_rt::join2(async { DropCounter(drops) }, async {
+            toy_rt::sleep(rt, Duration::from_millis(100)).await;
+            panic!("join panic");
+        })
+        .await;
+    }
+
+    let drops = Cell::new(0);
+    let res = panic::catch_unwind(AssertUnwindSafe(|| {
+        toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, &drops)
+    }));
+
+    let payload = res.unwrap_err();
+    assert_eq!(payload.downcast_ref::<&str>(), Some(&"join panic"));
+    assert_eq!(drops.get(), 1);
+}
+
+// The output of completed future is dropped once when the join is cancelled
+#[test]
+fn join3_cancel_drops_completed_outputs() {
+    async fn async_starter(rt: &toy_rt::Runtime, drops: &Cell<u32>) {
+        let join = toy_rt::join3(
+            async { DropCounter(drops) },
+            async {
+                toy_rt::sleep(rt, Duration::from_millis(100)).await;
+                DropCounter(drops)
+            },
+            toy_rt::sleep(rt, Duration::from_millis(300)),
+        );
+        let res = toy_rt::race2(join, toy_rt::sleep(rt, Duration::from_millis(200))).await;
+        assert!(matches!(res, toy_rt::OneOf2::Second(())));
+    }
+
+    let drops = Cell::new(0);
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, &drops);
+    assert_eq!(drops.get(), 2);
+}
+
+// join!() polls the future marked with "pinned:" by reference together with the owned ones
+#[test]
+fn join_with_pinned_local() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let pinned = async {
+            toy_rt::sleep(rt, Duration::from_millis(100)).await;
+            1
+        };
+        toy_rt::pin_local!(pinned);
+
+        let res = toy_rt::join!(
+            async {
+                toy_rt::sleep(rt, Duration::from_millis(200)).await;
+                2
+            },
+            pinned: pinned,
+            async { 3 },
+        )
+        .await;
+        assert_eq!(res, (2, 1, 3));
+        measure::assert_duration(rt.io().now32(), 200);
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
@@ -8,6 +8,8 @@
 use aiur::toy_rt::{self};
 use super::measure::{self};
 
+use std::cell::Cell;
+use std::panic::{self, AssertUnwindSafe};
 use std::time::Duration;
 
 // With emulated sleep test run instantly, actual sleep actually wait for specified
@@ -87,3 +89,133 @@ fn join_task8_returns_tupple() {
 
     toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
 }
+
+// Each task in join_tasks!() has its own id which is different from the parent task id
+#[test]
+fn join_tasks_have_distinct_task_ids() {
+    async fn task_id_after_sleep(rt: &toy_rt::Runtime, ms: u64) -> toy_rt::TaskId {
+        let task_id = rt.current_task_id().unwrap();
+        toy_rt::sleep(rt, Duration::from_millis(ms)).await;
+
+        // still the same task after await
+        assert_eq!(rt.current_task_id(), Some(task_id));
+        task_id
+    }
+
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let root_id = rt.current_task_id().unwrap();
+        let (id1, id2) =
+            toy_rt::join_tasks!(task_id_after_sleep(rt, 1000), task_id_after_sleep(rt, 500)).await;
+
+        assert_ne!(id1, id2);
+        assert_ne!(id1, root_id);
+        assert_ne!(id2, root_id);
+        assert_eq!(rt.current_task_id(), Some(root_id));
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// The oneshot exchange between the tasks completes the join_tasks!(): the parent task must be
+// polled when the child is awoken by the channel, not only by the reactor.
+#[test]
+fn join_tasks_oneshot_between_tasks() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let (mut tx, rx) = toy_rt::oneshot::<u32>(rt);
+        let (value, _) = toy_rt::join_tasks!(async { rx.await.unwrap() }, async {
+            toy_rt::sleep(rt, Duration::from_millis(1000)).await;
+            tx.send(5).await.unwrap();
+        })
+        .await;
+
+        assert_eq!(value, 5);
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// The panic of one task does not stop the sibling task, the panic is resumed by the join
+#[test]
+fn join_tasks_panic_resumed_at_join_point() {
+    async fn async_starter(rt: &toy_rt::Runtime, sibling_done: &Cell<bool>) {
+        toy_rt::join_tasks2(
+            async {
+                toy_rt::sleep(rt, Duration::from_millis(100)).await;
+                panic!("task panic");
+            },
+            async {
+                toy_rt::sleep(rt, Duration::from_millis(300)).await;
+                sibling_done.set(true);
+            },
+        )
+        .await;
+    }
+
+    let sibling_done = Cell::new(false);
+    let res = panic::catch_unwind(AssertUnwindSafe(|| {
+        toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, &sibling_done)
+    }));
+
+    let payload = res.unwrap_err();
+    assert_eq!(payload.downcast_ref::<&str>(), Some(&"task panic"));
+    assert!(sibling_done.get());
+}
+
+// Verifies that join_tasks_select() returns the task completed first while the other task keeps
+// running and can be awaited after that.
+#[test]
+fn join_tasks_select_returns_shorter_first() {
+    async fn sleep_and_ret(rt: &toy_rt::Runtime, duration: Duration, value: u32) -> u32 {
+        toy_rt::sleep(rt, duration).await;
+        value
+    }
+
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let start = rt.io().now32();
+        let mut select = toy_rt::join_tasks_select(vec![
+            sleep_and_ret(rt, Duration::from_millis(300), 1),
+            sleep_and_ret(rt, Duration::from_millis(100), 2),
+        ]);
+        assert_eq!(select.len(), 2);
+
+        assert_eq!(select.next().await, Some((1, 2)));
+        measure::assert_duration(rt.io().now32() - start, 100);
+        assert_eq!(select.len(), 1);
+
+        // The longer task has been running meanwhile, so only the rest of its sleep is awaited
+        assert_eq!(select.next().await, Some((0, 1)));
+        measure::assert_duration(rt.io().now32() - start, 300);
+
+        assert_eq!(select.next().await, None);
+        assert!(select.is_empty());
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// join_tasks_each() reports each task as soon as it is completed, in the order of durations
+#[test]
+fn join_tasks_each_reports_in_completion_order() {
+    async fn sleep_and_ret(rt: &toy_rt::Runtime, duration: Duration, value: u32) -> u32 {
+        toy_rt::sleep(rt, duration).await;
+        value
+    }
+
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let start = rt.io().now32();
+        let mut reported = Vec::new();
+        toy_rt::join_tasks_each(
+            vec![
+                sleep_and_ret(rt, Duration::from_millis(300), 10),
+                sleep_and_ret(rt, Duration::from_millis(100), 20),
+                sleep_and_ret(rt, Duration::from_millis(200), 30),
+            ],
+            |index, value| reported.push((index, value, rt.io().now32() - start)),
+        )
+        .await;
+
+        assert_eq!(reported, vec![(1, 20, 100), (2, 30, 200), (0, 10, 300)]);
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
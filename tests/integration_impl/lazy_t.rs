@@ -0,0 +1,58 @@
+//  \ O /
+//  / * \    aiur: the homeplanet for the famous executors
+// |' | '|   (c) 2020 - present, Vladimir Zvezda
+//   / \
+//
+// Tests for lazy(), any_of_with2() and join_with2(): the futures built on their first poll.
+use aiur::toy_rt::{self};
+use std::cell::{Cell, RefCell};
+use std::time::Duration;
+
+//const SLEEP_MODE: toy_rt::SleepMode = toy_rt::SleepMode::Actual;
+const SLEEP_MODE: toy_rt::SleepMode = toy_rt::SleepMode::Emulated;
+
+// Two closures borrow the same RefCell mutably: building both futures up front would panic,
+// built on poll each borrow ends with its future.
+#[test]
+fn any_of_with2_borrows_on_poll() {
+    async fn async_starter(_rt: &toy_rt::Runtime, _: ()) {
+        let log = RefCell::new(Vec::new());
+        let push = |value: u32| {
+            let mut log = log.borrow_mut();
+            async move { log.push(value) }
+        };
+
+        let stream = toy_rt::any_of_with2(|| push(1), || push(2));
+        toy_rt::pin_local!(stream);
+        while stream.next().await.is_some() {}
+        assert_eq!(*log.borrow(), vec![1, 2]);
+
+        toy_rt::join_with2(|| push(3), || push(4)).await;
+        assert_eq!(*log.borrow(), vec![1, 2, 3, 4]);
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// The closure of the lazy future that is never polled is not invoked
+#[test]
+fn lazy_not_polled_is_not_built() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let built = Cell::new(0);
+        let build = |ms: u64| {
+            built.set(built.get() + 1);
+            toy_rt::sleep(rt, Duration::from_millis(ms))
+        };
+
+        // the first future wins on the first poll, the second is dropped without being built
+        let first = toy_rt::race2(async {}, toy_rt::lazy(|| build(100))).await;
+        assert!(matches!(first, toy_rt::OneOf2::First(())));
+        assert_eq!(built.get(), 0);
+
+        toy_rt::lazy(|| build(100)).await;
+        assert_eq!(built.get(), 1);
+        assert_eq!(rt.elapsed(), Duration::from_millis(100));
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
@@ -5,6 +5,7 @@
 //
 // Shared code for tests
 
+use aiur::testing;
 use aiur::toy_rt::{self};
 use std::time::Duration;
 use std::future::Future;
@@ -14,17 +15,12 @@ pub async fn sleep_and_ret(rt: &toy_rt::Runtime, duration: Duration, value: u32)
     value
 }
 
-///
+// Same as aiur::testing::assert_duration() with default tolerance, but in milliseconds
 pub fn assert_duration(actual: u32, expected: u32) {
-    let actual = actual as i64;
-    let expected = expected as i64;
-
-    assert!(
-        i64::abs(actual - expected) < 100,
-        "Duration is in unexpected range: actual: {}, expected: {}, diff: {} > 100",
-        actual,
-        expected,
-        i64::abs(actual - expected)
+    testing::assert_duration(
+        Duration::from_millis(actual as u64),
+        Duration::from_millis(expected as u64),
+        testing::DEFAULT_TOLERANCE,
     );
 }
 
@@ -36,9 +32,6 @@ pub async fn expect_future_duration<FutT, ResT>(
 where
     FutT: Future<Output = ResT>,
 {
-    let start = rt.io().now32();
-    let result = future.await;
-    let elapsed = rt.io().now32() - start;
-    assert_duration(elapsed, expected_duration);
-    result
+    testing::expect_future_duration(rt, future, Duration::from_millis(expected_duration as u64))
+        .await
 }
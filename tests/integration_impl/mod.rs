@@ -5,13 +5,36 @@
 
 pub mod any_of_t;
 pub mod async_drop_t;
+pub mod cancel_token_t;
 pub mod cancel_frozen_event_t;
 pub mod channel_t;
+pub mod composite_reactor_t;
+pub mod context_t;
+pub mod countdown_reactor_t;
+pub mod deadlock_t;
+pub mod event_id_t;
+#[cfg(feature = "futures")]
+pub mod futures_t;
+pub mod hooks_t;
 pub mod join_t;
 pub mod join_tasks_t;
+pub mod lazy_t;
 pub mod nested_loop_t;
 pub mod oneshot_t;
+pub mod pin_local_t;
+pub mod race_t;
+pub mod race_detached_t;
+pub mod reactor_future_t;
+pub mod root_task_t;
+pub mod runtime_config_t;
+pub mod runtime_stats_t;
+pub mod scope_t;
+pub mod select_t;
 pub mod spawn_t;
+pub mod testing_t;
+pub mod timer_t;
+pub mod toy_reactor_t;
+pub mod tracer_t;
 
 mod future_utils;
 mod measure;
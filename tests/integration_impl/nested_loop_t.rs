@@ -73,6 +73,41 @@ fn nested_loop_in_join_tasks2() {
     toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
 }
 
+// Makes this task tree:
+//
+// join_tasks_vec---Sleep(1s)
+//                \-Sleep(3s)
+//                \-NestedLoop(Sleep(2s))
+//
+// The first task is awoken while frozen by the nested loop of the third one.
+#[test]
+fn nested_loop_in_join_tasks_vec() {
+    async fn entry(rt: &toy_rt::Runtime, ms: u64, value: u32) -> u32 {
+        let duration = Duration::from_millis(ms);
+        if value == 3 {
+            rt.nested_loop(measure::sleep_and_ret(rt, duration, value))
+        } else {
+            measure::sleep_and_ret(rt, duration, value).await
+        }
+    }
+
+    async fn measured(rt: &toy_rt::Runtime) -> Vec<u32> {
+        let futures = vec![
+            entry(rt, 1000, 1),
+            entry(rt, 3000, 2),
+            entry(rt, 2000, 3),
+        ];
+        toy_rt::join_tasks_vec(futures).await
+    }
+
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let sequence = measure::expect_future_duration(rt, measured(rt), 3000).await;
+        assert_eq!(vec!(1, 2, 3), sequence);
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
 // There is only one task but two futures running concurrent using join:
 //
 // fut_join ----> a:sleep(2s)
@@ -97,3 +132,37 @@ fn frozen_events_postponed() {
 
     toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
 }
+
+// Terminating the reactor in the middle of sleep makes the nested loop exit with error, the
+// future is dropped without being completed.
+#[test]
+fn terminated_reactor_exits_nested_loop() {
+    struct DropFlag<'flag>(&'flag std::cell::Cell<bool>);
+
+    impl<'flag> Drop for DropFlag<'flag> {
+        fn drop(&mut self) {
+            self.0.set(true);
+        }
+    }
+
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let dropped = std::cell::Cell::new(false);
+        let res = rt.try_nested_loop(toy_rt::join!(
+            async {
+                let _flag = DropFlag(&dropped);
+                toy_rt::sleep(rt, Duration::from_millis(2000)).await;
+            },
+            async {
+                toy_rt::sleep(rt, Duration::from_millis(500)).await;
+                rt.io().terminate();
+            }
+        ));
+
+        assert_eq!(res, Err(toy_rt::RunError::Terminated));
+        assert!(dropped.get());
+        assert!(rt.is_terminated());
+        assert!(rt.is_shutting_down());
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
@@ -280,3 +280,274 @@ fn oneshot_two_channels_echo_server() {
     // Verify that that sent data was actually read by receiver.
     assert_eq!(state.echo_data, 42);
 }
+
+// Receiver registers in the first poll, the value is exchanged right after the sender has
+// registered as well, so the receiver must be done in a couple of polls.
+#[test]
+fn oneshot_recv_completes_in_three_polls() {
+    async fn messenger(rt: &toy_rt::Runtime, _: ()) {
+        let (mut tx, rx) = toy_rt::oneshot::<u32>(rt);
+        let (recv_poll, _) = toy_rt::join!(future_utils::poll_n_times(rx, 3), async {
+            tx.send(42).await.unwrap();
+        })
+        .await;
+
+        match recv_poll {
+            std::task::Poll::Ready(value) => assert_eq!(value.unwrap(), 42),
+            std::task::Poll::Pending => panic!("oneshot was not received in 3 polls"),
+        }
+    }
+
+    // State transitions for this test:
+    // (C,C)->(C,R)->(R,R}->{R,E)->{R,D*}->(E,D)->(D,D)
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, messenger, ());
+}
+
+// The first oneshot to resolve is returned by index, the rest are cancelled and their senders
+// get the value back.
+#[test]
+fn oneshot_select_oneshots_returns_first_resolved() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let (mut senders, recvers): (Vec<_>, Vec<_>) =
+            (0..5).map(|_| toy_rt::oneshot::<u32>(rt)).unzip();
+
+        let (selected, _) = toy_rt::join!(toy_rt::select_oneshots(recvers), async {
+            toy_rt::sleep(rt, std::time::Duration::from_millis(100)).await;
+            senders[3].send(33).await.unwrap();
+        })
+        .await;
+
+        assert_eq!(selected.0, 3);
+        assert_eq!(selected.1.unwrap(), 33);
+
+        for (idx, tx) in senders.iter_mut().enumerate().filter(|(idx, _)| *idx != 3) {
+            assert_eq!(tx.send(idx as u32).await, Err(idx as u32));
+        }
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// try_recv() does not wait: nothing is sent yet, then the value of the pinned sender.
+#[test]
+fn oneshot_try_recv_empty_then_ready() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let (mut tx, mut rx) = toy_rt::oneshot::<u32>(rt);
+        assert!(matches!(rx.try_recv(), Ok(None)));
+
+        // State transitions for this test:
+        // (C,C)->(R,C)->{R,E)->(E,E)->(D,E)->(D,D)
+        let (sent, received) = toy_rt::join!(tx.send(42), async { rx.try_recv() }).await;
+        assert_eq!(sent, Ok(()));
+        assert_eq!(received.unwrap(), Some(42));
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// try_recv() gives the error when sender is dropped without sending
+#[test]
+fn oneshot_try_recv_sender_dropped() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let (tx, mut rx) = toy_rt::oneshot::<u32>(rt);
+        drop(tx);
+        assert!(rx.try_recv().is_err());
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// is_closed() lets the sender skip producing the value nobody awaits
+#[test]
+fn oneshot_sender_is_closed() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let (tx, rx) = toy_rt::oneshot::<u32>(rt);
+        assert!(!tx.is_closed());
+        drop(rx);
+        assert!(tx.is_closed());
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// Thousands of oneshots are created and dropped interleaved with the ones that are alive, the
+// values are not mixed up when the dropped oneshots have their runtime slots reused.
+#[test]
+fn oneshot_many_created_and_dropped_interleaved() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let mut alive = std::collections::VecDeque::new();
+        for i in 0..5000u32 {
+            let (mut tx, rx) = toy_rt::oneshot::<u32>(rt);
+            match i % 3 {
+                0 => alive.push_back((i, tx, rx)),
+                1 => {
+                    drop(rx);
+                    assert!(tx.is_closed());
+                }
+                _ => {
+                    let (sent, received) = toy_rt::join!(tx.send(i), rx).await;
+                    sent.unwrap();
+                    assert_eq!(received.unwrap(), i);
+                }
+            }
+
+            // complete the older ones while the new ones are created
+            if i % 7 == 0 {
+                let (value, mut tx, rx) = alive.pop_front().unwrap();
+                let (sent, received) = toy_rt::join!(tx.send(value), rx).await;
+                sent.unwrap();
+                assert_eq!(received.unwrap(), value);
+            }
+        }
+
+        for (value, mut tx, rx) in alive {
+            let (sent, received) = toy_rt::join!(tx.send(value), rx).await;
+            sent.unwrap();
+            assert_eq!(received.unwrap(), value);
+        }
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// send_now() gives the value to the receiver that is already awaiting
+#[test]
+fn oneshot_send_now_receiver_pinned_first() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let (tx, rx) = toy_rt::oneshot::<u32>(rt);
+
+        // State transitions for this test:
+        // (C,C)->(C,R)->(D,R}->(D,E)->(D,D)
+        let (received, sent) = toy_rt::join!(rx, async { tx.send_now(42) }).await;
+        assert_eq!(sent, Ok(()));
+        assert_eq!(received.unwrap(), 42);
+        assert_eq!(rt.stats().oneshots, 0);
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// send_now() leaves the value for the receiver that awaits later
+#[test]
+fn oneshot_send_now_receiver_not_pinned() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        // State transitions: (C,C)->(P,C)->(P,R}->(D,E)->(D,D)
+        let (tx, rx) = toy_rt::oneshot::<u32>(rt);
+        assert_eq!(tx.send_now(42), Ok(()));
+        assert_eq!(rx.await.unwrap(), 42);
+
+        // State transitions: (C,C)->(P,C)->(D,E)->(D,D)
+        let (tx, mut rx) = toy_rt::oneshot::<u32>(rt);
+        assert_eq!(tx.send_now(7), Ok(()));
+        assert_eq!(rx.try_recv().unwrap(), Some(7));
+        drop(rx);
+        assert_eq!(rt.stats().oneshots, 0);
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// send_now() returns the value back when receiver is dropped, the value left for the receiver
+// is dropped with it.
+#[test]
+fn oneshot_send_now_receiver_dropped() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let value = std::rc::Rc::new(42);
+
+        let (tx, rx) = toy_rt::oneshot::<std::rc::Rc<u32>>(rt);
+        drop(rx);
+        assert_eq!(tx.send_now(value.clone()), Err(value.clone()));
+
+        let (tx, rx) = toy_rt::oneshot::<std::rc::Rc<u32>>(rt);
+        assert_eq!(tx.send_now(value.clone()), Ok(()));
+        assert_eq!(std::rc::Rc::strong_count(&value), 2);
+        drop(rx);
+        assert_eq!(std::rc::Rc::strong_count(&value), 1);
+        assert_eq!(rt.stats().oneshots, 0);
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// The value left by send_now() is dropped with the receiver outside of the oneshot runtime
+// borrow, so its drop can use oneshots
+#[test]
+fn oneshot_send_now_posted_value_drop_creates_oneshot() {
+    struct OneshotOnDrop<'rt>(&'rt toy_rt::Runtime);
+
+    impl Drop for OneshotOnDrop<'_> {
+        fn drop(&mut self) {
+            let (tx, rx) = toy_rt::oneshot::<u32>(self.0);
+            drop((tx, rx));
+        }
+    }
+
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let (tx, rx) = toy_rt::oneshot::<OneshotOnDrop>(rt);
+        assert!(tx.send_now(OneshotOnDrop(rt)).is_ok());
+        drop(rx);
+        assert_eq!(rt.stats().oneshots, 0);
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// Polling the receiver after it has returned the value is reported by the uniform message
+#[test]
+#[should_panic(expected = "aiur/oneshot_recver_future: oneshot:")]
+fn oneshot_recver_polled_after_completion_panics() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let (mut tx, rx) = toy_rt::oneshot::<u32>(rt);
+        let _ = toy_rt::join!(future_utils::poll_after_ready(rx), async {
+            tx.send(42).await.unwrap();
+        })
+        .await;
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// One send is delivered to all the receivers of oneshot_broadcast(), also to the receivers
+// subscribed after the send
+#[test]
+fn oneshot_broadcast_clones_value() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let (mut tx, rxs) = toy_rt::oneshot_broadcast::<String>(rt, 3);
+        let mut rxs = rxs.into_iter();
+        let (rx1, rx2, rx3) = (rxs.next().unwrap(), rxs.next().unwrap(), rxs.next().unwrap());
+
+        let (r1, r2, r3, delivered) = toy_rt::join!(rx1, rx2, rx3, async {
+            toy_rt::sleep(rt, std::time::Duration::from_millis(100)).await;
+            tx.send("ready".to_string())
+        })
+        .await;
+        assert_eq!(delivered, 3);
+        assert_eq!(
+            (r1.unwrap(), r2.unwrap(), r3.unwrap()),
+            ("ready".to_string(), "ready".to_string(), "ready".to_string())
+        );
+
+        assert!(tx.is_sent());
+        assert_eq!(tx.subscribe().await.unwrap(), "ready");
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// The dropped receivers are not counted and the receivers get error if nothing is sent
+#[test]
+fn oneshot_broadcast_dropped_peers() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let (mut tx, mut rxs) = toy_rt::oneshot_broadcast::<u32>(rt, 2);
+        drop(rxs.pop());
+        assert_eq!(tx.send(42), 1);
+
+        let (tx, rxs) = toy_rt::oneshot_broadcast::<u32>(rt, 1);
+        drop(tx);
+        for rx in rxs {
+            assert!(rx.await.is_err());
+        }
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
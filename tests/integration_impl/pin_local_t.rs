@@ -0,0 +1,52 @@
+//  \ O /
+//  / * \    aiur: the homeplanet for the famous executors
+// |' | '|   (c) 2020 - present, Vladimir Zvezda
+//   / \
+//
+// Tests for pin_local!(): the same tests run with and without the `std-pin` feature.
+use aiur::toy_rt::{self};
+use std::pin::Pin;
+use std::time::Duration;
+
+const SLEEP_MODE: toy_rt::SleepMode = toy_rt::SleepMode::Emulated;
+
+// The pinned binding is a Pin<&mut T> accepted by AnyOfN::next()
+#[test]
+fn pin_local_any_of_next() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        // async blocks are !Unpin, so the stream cannot be used without pinning
+        let stream = toy_rt::any_of2(
+            async { toy_rt::sleep(rt, Duration::from_millis(200)).await },
+            async { toy_rt::sleep(rt, Duration::from_millis(100)).await },
+        );
+        toy_rt::pin_local!(stream);
+        let pinned: &mut Pin<&mut _> = &mut stream;
+
+        assert!(matches!(
+            pinned.next().await,
+            Some(toy_rt::OneOf2::Second(()))
+        ));
+        assert!(matches!(
+            stream.next().await,
+            Some(toy_rt::OneOf2::First(()))
+        ));
+        assert!(stream.next().await.is_none());
+        assert_eq!(rt.elapsed(), Duration::from_millis(200));
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// The pinned future can be polled by reference and the value is shadowed
+#[test]
+fn pin_local_future() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let fut = async { toy_rt::sleep(rt, Duration::from_millis(100)).await };
+        toy_rt::pin_local!(fut);
+
+        (&mut fut).await;
+        assert_eq!(rt.elapsed(), Duration::from_millis(100));
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
@@ -0,0 +1,86 @@
+//  \ O /
+//  / * \    aiur: the homeplanet for the famous executors
+// |' | '|   (c) 2020 - present, Vladimir Zvezda
+//   / \
+//
+// Tests for race_detachedN(): the first output is returned, the rest keep running in scope.
+use aiur::toy_rt::{self};
+use aiur::{OneOf2, OneOf3};
+use std::cell::Cell;
+use std::time::Duration;
+
+//const SLEEP_MODE: toy_rt::SleepMode = toy_rt::SleepMode::Actual;
+const SLEEP_MODE: toy_rt::SleepMode = toy_rt::SleepMode::Emulated;
+
+// The fast future output is returned while the slow one is still running, its side effect is
+// observed after it is done.
+#[test]
+fn race_detached2_slow_future_keeps_running() {
+    async fn sleep_and_count(rt: &toy_rt::Runtime, counter: &Cell<u32>, ms: u64) -> u64 {
+        toy_rt::sleep(rt, Duration::from_millis(ms)).await;
+        counter.set(counter.get() + 1);
+        ms
+    }
+
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let counter = Cell::new(0);
+        let scope = toy_rt::Scope::new(rt);
+        let start = rt.elapsed();
+
+        scope
+            .run(async {
+                let first = toy_rt::race_detached2(
+                    &scope,
+                    sleep_and_count(rt, &counter, 3000),
+                    sleep_and_count(rt, &counter, 1000),
+                )
+                .await;
+
+                assert!(matches!(first, OneOf2::Second(1000)));
+                assert_eq!(rt.elapsed() - start, Duration::from_millis(1000));
+                assert_eq!(counter.get(), 1); // slow one is not done yet, but not cancelled
+
+                toy_rt::sleep(rt, Duration::from_millis(2500)).await;
+                assert_eq!(counter.get(), 2);
+            })
+            .await;
+
+        assert_eq!(rt.elapsed() - start, Duration::from_millis(3500));
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// Scope::run() waits for the futures that lost the race
+#[test]
+fn race_detached3_run_waits_for_losers() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let done = Cell::new(0);
+        let scope = toy_rt::Scope::new(rt);
+
+        let first = scope
+            .run(async {
+                toy_rt::race_detached3(
+                    &scope,
+                    async {
+                        toy_rt::sleep(rt, Duration::from_millis(2000)).await;
+                        done.set(done.get() + 1);
+                    },
+                    async { "ready" },
+                    async {
+                        toy_rt::sleep(rt, Duration::from_millis(1000)).await;
+                        done.set(done.get() + 1);
+                        1000
+                    },
+                )
+                .await
+            })
+            .await;
+
+        assert!(matches!(first, OneOf3::Second("ready")));
+        assert_eq!(done.get(), 2);
+        assert_eq!(rt.elapsed(), Duration::from_millis(2000));
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
@@ -0,0 +1,77 @@
+//  \ O /
+//  / * \    aiur: the homeplanet for the famous executors
+// |' | '|   (c) 2020 - present, Vladimir Zvezda
+//   / \
+//
+// Tests for race2..race8: the losing futures are dropped when the race is completed.
+use aiur::toy_rt::{self};
+use std::time::Duration;
+
+//const SLEEP_MODE: toy_rt::SleepMode = toy_rt::SleepMode::Actual;
+const SLEEP_MODE: toy_rt::SleepMode = toy_rt::SleepMode::Emulated;
+
+// The timer of the losing sleep is cancelled in reactor right away
+#[test]
+fn race_cancels_losing_sleep() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let first = toy_rt::race2(
+            toy_rt::sleep(rt, Duration::from_millis(100)),
+            toy_rt::sleep(rt, Duration::from_millis(1000)),
+        )
+        .await;
+
+        assert!(matches!(first, toy_rt::OneOf2::First(())));
+        assert_eq!(rt.elapsed(), Duration::from_millis(100));
+        assert!(!rt.io().has_pending());
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// The losing receiver future is unpinned from the channel when race is completed, so the
+// channel is not writable anymore.
+#[test]
+fn race_unpins_losing_receiver() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let (mut tx, mut rx) = toy_rt::channel::<u32>(rt);
+
+        let first = toy_rt::race2(rx.next(), toy_rt::sleep(rt, Duration::from_millis(100))).await;
+        assert!(matches!(first, toy_rt::OneOf2::Second(())));
+
+        let writable = toy_rt::select_or_default(
+            async {
+                tx.writable().await;
+                true
+            },
+            || false,
+        )
+        .await;
+        assert!(!writable);
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// The last future is the first one completed
+#[test]
+fn race3_returns_third() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) -> u32 {
+        let first = toy_rt::race3(
+            toy_rt::sleep(rt, Duration::from_millis(300)),
+            toy_rt::sleep(rt, Duration::from_millis(200)),
+            async {
+                toy_rt::sleep(rt, Duration::from_millis(100)).await;
+                42
+            },
+        )
+        .await;
+
+        assert!(!rt.io().has_pending());
+        match first {
+            toy_rt::OneOf3::Third(value) => value,
+            _ => panic!("the third future should be the first one completed"),
+        }
+    }
+
+    assert_eq!(toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ()), 42);
+}
@@ -0,0 +1,92 @@
+//  \ O /
+//  / * \    aiur: the homeplanet for the famous executors
+// |' | '|   (c) 2020 - present, Vladimir Zvezda
+//   / \
+//
+// Tests for ReactorFuture: the sleep made from ReactorOp for the toy reactor.
+use aiur::toy_rt::{self};
+use aiur::{EventId, ReactorOp, TemporalReactor};
+use std::cell::Cell;
+use std::time::Duration;
+
+//const SLEEP_MODE: toy_rt::SleepMode = toy_rt::SleepMode::Actual;
+const SLEEP_MODE: toy_rt::SleepMode = toy_rt::SleepMode::Emulated;
+
+// Timer op that counts its cancellations
+struct MySleepOp<'a> {
+    duration: Duration,
+    cancels: &'a Cell<u32>,
+}
+
+impl<'a> ReactorOp<toy_rt::ToyReactor> for MySleepOp<'a> {
+    fn is_ready(&self, _rt: &toy_rt::Runtime) -> bool {
+        self.duration.is_zero()
+    }
+
+    fn schedule(&mut self, rt: &toy_rt::Runtime, event_id: EventId) {
+        rt.io().schedule_timer(event_id, self.duration);
+    }
+
+    fn cancel(&mut self, rt: &toy_rt::Runtime, event_id: EventId) {
+        self.cancels.set(self.cancels.get() + 1);
+        rt.io().cancel_timer(event_id);
+    }
+}
+
+fn my_sleep<'runtime, 'a>(
+    rt: &'runtime toy_rt::Runtime,
+    duration: Duration,
+    cancels: &'a Cell<u32>,
+) -> toy_rt::ReactorFuture<'runtime, MySleepOp<'a>> {
+    toy_rt::ReactorFuture::new(rt, MySleepOp { duration, cancels })
+}
+
+// The sleep made with ReactorFuture waits like the aiur::sleep()
+#[test]
+fn reactor_future_sleep() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let cancels = Cell::new(0);
+        my_sleep(rt, Duration::from_millis(1000), &cancels).await;
+        assert_eq!(rt.elapsed(), Duration::from_millis(1000));
+
+        // ready op completes without reactor
+        my_sleep(rt, Duration::ZERO, &cancels).await;
+        assert_eq!(rt.elapsed(), Duration::from_millis(1000));
+        assert_eq!(cancels.get(), 0);
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// The op is cancelled in reactor when the future is dropped before the event
+#[test]
+fn reactor_future_sleep_cancelled() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let cancels = Cell::new(0);
+        let res = toy_rt::race2(
+            my_sleep(rt, Duration::from_millis(1000), &cancels),
+            my_sleep(rt, Duration::from_millis(500), &cancels),
+        )
+        .await;
+
+        assert!(matches!(res, toy_rt::OneOf2::Second(())));
+        assert_eq!(rt.elapsed(), Duration::from_millis(500));
+        assert_eq!(cancels.get(), 1);
+        assert!(!rt.io().has_pending());
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// Polling the completed ReactorFuture (e.g. the sleep) is reported by the uniform message
+#[test]
+#[should_panic(expected = "aiur/ReactorFuture:")]
+fn reactor_future_polled_after_completion_panics() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let cancels = Cell::new(0);
+        let sleep = my_sleep(rt, Duration::from_millis(100), &cancels);
+        let _ = super::future_utils::poll_after_ready(sleep).await;
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
@@ -0,0 +1,119 @@
+//  \ O /
+//  / * \    aiur: the homeplanet for the famous executors
+// |' | '|   (c) 2020 - present, Vladimir Zvezda
+//   / \
+//
+// Tests for driving the runtime step by step like an external event loop would do.
+use aiur::toy_rt::{self};
+use std::time::Duration;
+
+//const SLEEP_MODE: toy_rt::SleepMode = toy_rt::SleepMode::Actual;
+const SLEEP_MODE: toy_rt::SleepMode = toy_rt::SleepMode::Emulated;
+
+// Root task is reported as complete exactly at the step when the last sleep is done
+#[test]
+fn root_task_is_complete_after_last_step() {
+    let rt = toy_rt::Runtime::new(
+        toy_rt::ToyReactor::new_with_mode(SLEEP_MODE),
+        aiur::Tracer::new_empty(),
+    );
+
+    let mut root = rt.root_task(async {
+        toy_rt::sleep(&rt, Duration::from_millis(300)).await;
+        toy_rt::sleep(&rt, Duration::from_millis(200)).await;
+        42
+    });
+
+    assert!(!root.is_root_complete());
+    assert_eq!(rt.current_task_id(), None);
+
+    // the first step schedules the first timer, then one step per sleep
+    assert_eq!(root.poll_once(), None);
+    assert_eq!(root.poll_once(), None);
+    assert!(!root.is_root_complete());
+    assert_eq!(rt.io().now32(), 300);
+
+    assert_eq!(root.poll_once(), Some(root.completion_event_id()));
+    assert!(root.is_root_complete());
+    assert_eq!(rt.io().now32(), 500);
+
+    // nothing to do anymore
+    assert_eq!(root.poll_once(), Some(root.completion_event_id()));
+    assert_eq!(root.take_result(), 42);
+}
+
+// The panic of the root future unwinds from poll_once(), no task is current after that
+#[test]
+fn root_task_panic_restores_current_task() {
+    let rt = toy_rt::Runtime::new(
+        toy_rt::ToyReactor::new_with_mode(SLEEP_MODE),
+        aiur::Tracer::new_empty(),
+    );
+
+    let mut root = rt.root_task(async {
+        toy_rt::sleep(&rt, Duration::from_millis(300)).await;
+        panic!("root future panics");
+    });
+
+    assert_eq!(root.poll_once(), None);
+    let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| root.poll_once()));
+    assert!(res.is_err());
+    assert_eq!(rt.current_task_id(), None);
+}
+
+// The host loop learns that reactor is terminated from poll_once()
+#[test]
+fn root_task_reports_terminated_reactor() {
+    let rt = toy_rt::Runtime::new(
+        toy_rt::ToyReactor::new_with_mode(SLEEP_MODE),
+        aiur::Tracer::new_empty(),
+    );
+
+    let mut root = rt.root_task(async {
+        toy_rt::sleep(&rt, Duration::from_millis(300)).await;
+        rt.io().terminate();
+        toy_rt::sleep(&rt, Duration::from_millis(200)).await;
+    });
+
+    assert_eq!(root.poll_once(), None);
+    assert_eq!(root.poll_once(), None);
+    assert_eq!(root.poll_once(), Some(toy_rt::EventId::terminated()));
+    assert!(!root.is_root_complete());
+
+    // stays terminated
+    assert_eq!(root.poll_once(), Some(toy_rt::EventId::terminated()));
+}
+
+// Runtime created by the caller is driven with block_on() without with_runtime()
+#[test]
+fn block_on_drives_sleep() {
+    let rt = toy_rt::Runtime::new(
+        toy_rt::ToyReactor::new_with_mode(SLEEP_MODE),
+        aiur::Tracer::new_empty(),
+    );
+
+    let res = rt.block_on(async {
+        toy_rt::sleep(&rt, Duration::from_millis(300)).await;
+        42
+    });
+    assert_eq!(res, 42);
+    assert_eq!(rt.io().now32(), 300);
+
+    // the same via the function exported by export_runtime!()
+    toy_rt::block_on(&rt, toy_rt::sleep(&rt, Duration::from_millis(200)));
+    assert_eq!(rt.io().now32(), 500);
+}
+
+// Runtime made by the builder is created once and runs several top-level futures in sequence
+#[test]
+fn runtime_builder_runs_futures_in_sequence() {
+    let rt = toy_rt::RuntimeBuilder::new(toy_rt::ToyReactor::new_with_mode(SLEEP_MODE))
+        .tracer(aiur::Tracer::new_empty())
+        .config(aiur::RuntimeConfig::new().stall_detector(1000))
+        .build();
+
+    for (n, expected_now) in [(1, 100), (2, 300), (3, 600)] {
+        rt.block_on(toy_rt::sleep(&rt, Duration::from_millis(100 * n)));
+        assert_eq!(rt.io().now32(), expected_now);
+    }
+}
@@ -0,0 +1,189 @@
+//  \ O /
+//  / * \    aiur: the homeplanet for the famous executors
+// |' | '|   (c) 2020 - present, Vladimir Zvezda
+//   / \
+use aiur::toy_rt::{self};
+use aiur::{EventNode, TemporalReactor};
+use std::cell::Cell;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+//const SLEEP_MODE: toy_rt::SleepMode = toy_rt::SleepMode::Actual;
+const SLEEP_MODE: toy_rt::SleepMode = toy_rt::SleepMode::Emulated;
+
+// Buggy leaf future: it schedules the timer for one event node, but waits for another event
+// node that is never scheduled. So it is awoken again and again without any progress.
+struct StuckFuture<'rt> {
+    rt: &'rt toy_rt::Runtime,
+    scheduled: EventNode,
+    awaited: EventNode,
+}
+
+impl<'rt> Future for StuckFuture<'rt> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let this = unsafe { self.get_unchecked_mut() };
+
+        if this.awaited.is_awoken_for(this.rt) {
+            return Poll::Ready(());
+        }
+
+        let event_id = unsafe { this.scheduled.on_pin(ctx) };
+        this.rt.io().schedule_timer(event_id, Duration::from_millis(10));
+        Poll::Pending
+    }
+}
+
+// Stall detector reports the future that busy-waits for an event that never comes
+#[test]
+#[should_panic(expected = "aiur: stall detected, 100 runtime iterations without progress")]
+fn runtime_stall_detector_fires_on_stuck_future() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        rt.set_config(toy_rt::RuntimeConfig::new().stall_detector(100));
+
+        StuckFuture {
+            rt,
+            scheduled: EventNode::new(),
+            awaited: EventNode::new(),
+        }
+        .await;
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// Waiting in the reactor is not a stall even with the smallest threshold
+#[test]
+fn runtime_stall_detector_ignores_reactor_waits() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        rt.set_config(toy_rt::RuntimeConfig::new().stall_detector(1));
+
+        for _ in 0..10 {
+            toy_rt::sleep(rt, Duration::from_millis(100)).await;
+        }
+
+        let (mut tx, rx) = toy_rt::oneshot::<u32>(rt);
+        let (_, value) = toy_rt::join!(tx.send(1), rx).await;
+        assert_eq!(value.unwrap(), 1);
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// Runs the program with many channel exchanges and a timer that is due right away. Returns
+// the number of values received when the timer was awoken and the reactor wait count.
+fn run_channel_heavy(policy: aiur::SchedulePolicy) -> (u32, u64) {
+    async fn async_starter(rt: &toy_rt::Runtime, policy: aiur::SchedulePolicy) -> (u32, u64) {
+        rt.set_config(toy_rt::RuntimeConfig::new().schedule_policy(policy));
+
+        let received = Cell::new(0);
+        let (mut tx, mut rx) = toy_rt::channel::<u32>(rt);
+        let (_, _, received_on_timer) = toy_rt::join!(
+            async move {
+                for value in 0..50 {
+                    tx.send(value).await.unwrap();
+                }
+            },
+            async {
+                while rx.next().await.is_ok() {
+                    received.set(received.get() + 1);
+                }
+            },
+            async {
+                toy_rt::sleep(rt, Duration::from_millis(0)).await;
+                received.get()
+            }
+        )
+        .await;
+
+        assert_eq!(received.get(), 50);
+        (received_on_timer, rt.wait_count())
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, policy)
+}
+
+// With DrainThenPark all the exchanges are done before the timer event, with ParkEagerly the
+// reactor is checked after each round of exchanges.
+#[test]
+fn runtime_schedule_policy_park_eagerly_checks_reactor_between_exchanges() {
+    let (drain_received, drain_waits) = run_channel_heavy(aiur::SchedulePolicy::DrainThenPark);
+    let (eager_received, eager_waits) = run_channel_heavy(aiur::SchedulePolicy::ParkEagerly);
+
+    assert_eq!(drain_received, 50);
+    assert!(eager_received < 50, "timer awoken after {} values", eager_received);
+    assert!(
+        eager_waits > drain_waits,
+        "wait count: eager {}, drain {}",
+        eager_waits,
+        drain_waits
+    );
+}
+
+// Recursive nested loops stop at the configured depth with RunError::TooDeep
+#[test]
+fn runtime_nested_loop_too_deep() {
+    fn recurse(rt: &toy_rt::Runtime, max_depth: &Cell<u32>) -> Result<(), toy_rt::RunError> {
+        max_depth.set(max_depth.get().max(rt.nesting_depth()));
+        rt.try_nested_loop(async { recurse(rt, max_depth) })?
+    }
+
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        assert_eq!(rt.nesting_depth(), 1);
+        rt.set_config(toy_rt::RuntimeConfig::new().max_nesting_depth(8));
+
+        let max_depth = Cell::new(0);
+        assert_eq!(
+            recurse(rt, &max_depth),
+            Err(toy_rt::RunError::TooDeep { depth: 8 })
+        );
+        assert_eq!(max_depth.get(), 8);
+        assert_eq!(rt.nesting_depth(), 1);
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// The default limit is generous, but the nested_loop() beyond it panics with TooDeep
+#[test]
+#[should_panic(expected = "TooDeep")]
+fn runtime_nested_loop_too_deep_panics() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        assert_eq!(toy_rt::DEFAULT_MAX_NESTING_DEPTH, 1024);
+        rt.set_config(toy_rt::RuntimeConfig::new().max_nesting_depth(1));
+        rt.nested_loop(async {});
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// The panic in the nested loop caught by join_tasks does not leave the depth counted
+#[test]
+fn runtime_nested_loop_panic_restores_depth() {
+    async fn async_starter(rt: &toy_rt::Runtime, sibling_depth: &Cell<u32>) {
+        toy_rt::join_tasks2(
+            async {
+                rt.nested_loop(async {
+                    toy_rt::sleep(rt, Duration::from_millis(100)).await;
+                    panic!("nested panic");
+                })
+            },
+            async {
+                toy_rt::sleep(rt, Duration::from_millis(300)).await;
+                sibling_depth.set(rt.nesting_depth());
+            },
+        )
+        .await;
+    }
+
+    let sibling_depth = Cell::new(0);
+    let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, &sibling_depth)
+    }));
+
+    assert!(res.is_err());
+    assert_eq!(sibling_depth.get(), 1);
+}
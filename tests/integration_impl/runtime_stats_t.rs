@@ -0,0 +1,82 @@
+//  \ O /
+//  / * \    aiur: the homeplanet for the famous executors
+// |' | '|   (c) 2020 - present, Vladimir Zvezda
+//   / \
+//
+// Tests for Runtime::stats().
+use aiur::toy_rt::{self};
+use std::time::Duration;
+
+//const SLEEP_MODE: toy_rt::SleepMode = toy_rt::SleepMode::Actual;
+const SLEEP_MODE: toy_rt::SleepMode = toy_rt::SleepMode::Emulated;
+
+// The channels and oneshots are counted while alive and not after they are dropped
+#[test]
+fn runtime_stats_live_nodes_return_to_zero() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        assert_eq!(rt.stats().channels, 0);
+        {
+            let (mut tx, mut rx) = toy_rt::channel::<u32>(rt);
+            let (mut tx_once, rx_once) = toy_rt::oneshot::<u32>(rt);
+            assert_eq!(rt.stats().channels, 1);
+            assert_eq!(rt.stats().oneshots, 1);
+
+            let (sent, received, _, received_once) =
+                toy_rt::join!(tx.send(42), rx.next(), tx_once.send(7), rx_once).await;
+            assert!(sent.is_ok());
+            assert_eq!(received, Ok(42));
+            assert_eq!(received_once.unwrap(), 7);
+        }
+
+        let stats = rt.stats();
+        assert_eq!(stats.channels, 0);
+        assert_eq!(stats.oneshots, 0);
+        assert_eq!(stats.frozen_events, 0);
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// The polls and waits grow with the sleeps
+#[test]
+fn runtime_stats_counts_polls_and_waits() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let before = rt.stats();
+        toy_rt::sleep(rt, Duration::from_millis(100)).await;
+        toy_rt::sleep(rt, Duration::from_millis(100)).await;
+        let after = rt.stats();
+
+        assert_eq!(after.waits, before.waits + 2);
+        assert_eq!(after.polls, before.polls + 2);
+        assert_eq!(after.waits, rt.wait_count());
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// The channel that is never dropped is caught by the leak check of with_runtime()
+#[test]
+#[cfg(feature = "debug-leak-check")]
+#[should_panic(expected = "aiur: leak detected: 1 channels, 0 oneshots, 0 frozen events are left")]
+fn runtime_leaked_channel_is_detected() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        std::mem::forget(toy_rt::channel::<u32>(rt));
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// The leak check can be invoked directly for the runtime that is not made by with_runtime()
+#[test]
+fn runtime_assert_no_leaks_explicit() {
+    let rt = toy_rt::Runtime::new(
+        toy_rt::ToyReactor::new_with_mode(SLEEP_MODE),
+        aiur::Tracer::new_empty(),
+    );
+    let (tx_once, rx_once) = toy_rt::oneshot::<u32>(&rt);
+    let leak = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| rt.assert_no_leaks()));
+    assert!(leak.is_err());
+
+    drop((tx_once, rx_once));
+    rt.assert_no_leaks();
+}
@@ -0,0 +1,136 @@
+//  \ O /
+//  / * \    aiur: the homeplanet for the famous executors
+// |' | '|   (c) 2020 - present, Vladimir Zvezda
+//   / \
+//
+// Tests for Scope: tasks spawned while the scope is running.
+use aiur::toy_rt::{self};
+use std::cell::Cell;
+use std::time::Duration;
+
+//const SLEEP_MODE: toy_rt::SleepMode = toy_rt::SleepMode::Actual;
+const SLEEP_MODE: toy_rt::SleepMode = toy_rt::SleepMode::Emulated;
+
+// The spawned task owns the vector and its result is received with the handle
+#[test]
+fn scope_spawn_owned_returns_sum() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) -> u32 {
+        let scope = toy_rt::Scope::new(rt);
+        scope
+            .run(async {
+                let numbers: Vec<u32> = (1..=10).collect();
+                let handle = scope.spawn_owned(async move {
+                    toy_rt::sleep(rt, Duration::from_millis(1000)).await;
+                    numbers.iter().sum::<u32>()
+                });
+                handle.await.unwrap()
+            })
+            .await
+    }
+
+    assert_eq!(toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ()), 55);
+}
+
+// Scope::run() returns when the body and all the tasks spawned are completed, including the
+// tasks spawned after the body awaited something.
+#[test]
+fn scope_run_waits_for_spawned_tasks() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let counter = Cell::new(0);
+        let scope = toy_rt::Scope::new(rt);
+        let start = rt.io().now32();
+
+        let body_result = scope
+            .run(async {
+                scope.spawn(async {
+                    toy_rt::sleep(rt, Duration::from_millis(2000)).await;
+                    counter.set(counter.get() + 1);
+                });
+                toy_rt::sleep(rt, Duration::from_millis(1000)).await;
+                scope.spawn(async {
+                    toy_rt::sleep(rt, Duration::from_millis(2000)).await;
+                    counter.set(counter.get() + 1);
+                });
+                42
+            })
+            .await;
+
+        assert_eq!(body_result, 42);
+        assert_eq!(counter.get(), 2);
+        assert_eq!(rt.io().now32() - start, 3000);
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// The tasks that are not completed are cancelled when scope is dropped
+#[test]
+fn scope_drop_cancels_tasks() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let scope = toy_rt::Scope::new(rt);
+        scope.spawn(async {
+            toy_rt::sleep(rt, Duration::from_millis(5000)).await;
+            panic!("must be cancelled");
+        });
+
+        toy_rt::select_or_default(scope.run(async {}), || ()).await;
+        drop(scope);
+
+        assert!(!rt.io().has_pending());
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// The values of two spawned tasks are collected by awaiting their handles
+#[test]
+fn scope_spawn_handle_returns_values() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let scope = toy_rt::Scope::new(rt);
+        let start = rt.io().now32();
+
+        let (first, second) = scope
+            .run(async {
+                let first = scope.spawn_handle(async {
+                    toy_rt::sleep(rt, Duration::from_millis(2000)).await;
+                    "first"
+                });
+                let second = scope.spawn_handle(async {
+                    toy_rt::sleep(rt, Duration::from_millis(1000)).await;
+                    2
+                });
+                (first.await, second.await)
+            })
+            .await;
+
+        assert_eq!((first, second), ("first", 2));
+        assert_eq!(rt.io().now32() - start, 2000);
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// The task keeps running when its handle is dropped, the output is discarded
+#[test]
+fn scope_spawn_handle_dropped() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let done = Cell::new(false);
+        let scope = toy_rt::Scope::new(rt);
+        let start = rt.io().now32();
+
+        scope
+            .run(async {
+                drop(scope.spawn_handle(async {
+                    toy_rt::sleep(rt, Duration::from_millis(1000)).await;
+                    done.set(true);
+                    5
+                }));
+            })
+            .await;
+
+        assert!(done.get());
+        assert_eq!(rt.io().now32() - start, 1000);
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
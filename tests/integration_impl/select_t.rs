@@ -0,0 +1,112 @@
+//  \ O /
+//  / * \    aiur: the homeplanet for the famous executors
+// |' | '|   (c) 2020 - present, Vladimir Zvezda
+//   / \
+use aiur::toy_rt::{self};
+use std::time::Duration;
+
+//const SLEEP_MODE: toy_rt::SleepMode = toy_rt::SleepMode::Actual;
+const SLEEP_MODE: toy_rt::SleepMode = toy_rt::SleepMode::Emulated;
+
+// Nothing is ready in channels, so default branch is selected and channels remain usable
+#[test]
+fn select_or_default_runs_default_when_nothing_ready() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let (mut tx_a, mut rx_a) = toy_rt::channel::<u32>(rt);
+        let (_tx_b, mut rx_b) = toy_rt::channel_buffered::<String>(rt, 1);
+
+        let selected = toy_rt::select_or_default(
+            async { Some(toy_rt::select2_recv(&mut rx_a, &mut rx_b).await) },
+            || None,
+        )
+        .await;
+        assert!(selected.is_none());
+
+        let (_, value) = toy_rt::join!(tx_a.send(1), rx_a.next()).await;
+        assert_eq!(value.unwrap(), 1);
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// Buffered channel has a value, so it wins over the default branch
+#[test]
+fn select_or_default_returns_ready_source() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let (_tx_a, mut rx_a) = toy_rt::channel::<u32>(rt);
+        let (mut tx_b, mut rx_b) = toy_rt::channel_buffered::<String>(rt, 1);
+        tx_b.send("ready".to_string()).await.unwrap();
+
+        let selected = toy_rt::select_or_default(
+            async { Some(toy_rt::select2_recv(&mut rx_a, &mut rx_b).await) },
+            || None,
+        )
+        .await;
+
+        match selected {
+            Some(toy_rt::OneOf2::Second(value)) => assert_eq!(value.unwrap(), "ready"),
+            _ => panic!("the value is expected from the second channel"),
+        }
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// The channel delivers at 1s, so the channel arm runs and the timer of sleep(2s) is cancelled
+#[test]
+fn select_macro_runs_channel_arm() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let (mut tx, mut rx) = toy_rt::channel::<u32>(rt);
+
+        let (selected, _) = toy_rt::join!(
+            async {
+                let selected = toy_rt::select! {
+                    value = rx.next() => format!("channel {}", value.unwrap()),
+                    _ = toy_rt::sleep(rt, Duration::from_millis(2000)) => "timer".to_string(),
+                };
+                assert!(!rt.io().has_pending());
+                selected
+            },
+            async {
+                toy_rt::sleep(rt, Duration::from_millis(1000)).await;
+                tx.send(5).await.unwrap();
+            }
+        )
+        .await;
+
+        assert_eq!(selected, "channel 5");
+        assert_eq!(rt.elapsed(), Duration::from_millis(1000));
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// Oneshot receiver is an arm future too, the arm of the first completed future runs only
+#[test]
+fn select_macro_with_oneshot() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let (mut tx, rx) = toy_rt::oneshot::<u32>(rt);
+        let (_tx_chan, mut rx_chan) = toy_rt::channel::<u32>(rt);
+        let mut arms_run = 0;
+
+        let (value, _) = toy_rt::join!(
+            async {
+                toy_rt::select! {
+                    _ = rx_chan.next() => { arms_run += 1; None },
+                    value = rx => { arms_run += 1; value.ok() },
+                    _ = toy_rt::sleep(rt, Duration::from_millis(2000)) => { arms_run += 1; None },
+                }
+            },
+            async {
+                toy_rt::sleep(rt, Duration::from_millis(500)).await;
+                tx.send(7).await.unwrap();
+            }
+        )
+        .await;
+
+        assert_eq!(value, Some(7));
+        assert_eq!(arms_run, 1);
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
@@ -0,0 +1,98 @@
+//  \ O /
+//  / * \    aiur: the homeplanet for the famous executors
+// |' | '|   (c) 2020 - present, Vladimir Zvezda
+//   / \
+//
+// Tests for aiur::testing helpers
+use aiur::testing::{self, OrderLog};
+use aiur::toy_rt::{self};
+use std::time::Duration;
+
+//const SLEEP_MODE: toy_rt::SleepMode = toy_rt::SleepMode::Actual;
+const SLEEP_MODE: toy_rt::SleepMode = toy_rt::SleepMode::Emulated;
+
+fn ms(value: u64) -> Duration {
+    Duration::from_millis(value)
+}
+
+// The difference below the tolerance is accepted in both directions
+#[test]
+fn testing_assert_duration_within_tolerance() {
+    testing::assert_duration(ms(2050), ms(2000), ms(100));
+    testing::assert_duration(ms(1950), ms(2000), ms(100));
+}
+
+#[test]
+#[should_panic(expected = "Duration is in unexpected range")]
+fn testing_assert_duration_out_of_tolerance_panics() {
+    testing::assert_duration(ms(2100), ms(2000), ms(100));
+}
+
+// Measures join!() of two sleeps the same way the tests in join_t.rs do
+#[test]
+fn testing_expect_future_duration_of_join() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let (v1, v2) = testing::expect_future_duration(
+            rt,
+            toy_rt::join!(
+                async {
+                    toy_rt::sleep(rt, ms(1000)).await;
+                    1
+                },
+                async {
+                    toy_rt::sleep(rt, ms(2000)).await;
+                    2
+                }
+            ),
+            ms(2000),
+        )
+        .await;
+
+        assert_eq!((v1, v2), (1, 2));
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+#[test]
+#[should_panic(expected = "Duration is in unexpected range")]
+fn testing_expect_future_duration_panics_on_wrong_duration() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        testing::expect_future_duration(rt, toy_rt::sleep(rt, ms(1000)), ms(2000)).await;
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// The events of the concurrent branches are logged in order of their sleep durations
+#[test]
+fn testing_order_log_of_join_tasks() {
+    async fn log_after(rt: &toy_rt::Runtime, log: &OrderLog<u32>, value: u32) {
+        toy_rt::sleep(rt, ms(value as u64)).await;
+        log.push(value);
+    }
+
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let log = OrderLog::new();
+        toy_rt::join_tasks!(
+            log_after(rt, &log, 3000),
+            log_after(rt, &log, 1000),
+            log_after(rt, &log, 2000)
+        )
+        .await;
+
+        log.assert_order(&[1000, 2000, 3000]);
+        assert_eq!(log.into_events(), vec![1000, 2000, 3000]);
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+#[test]
+#[should_panic(expected = "Events are in unexpected order")]
+fn testing_order_log_wrong_order_panics() {
+    let log = OrderLog::new();
+    log.push(2);
+    log.push(1);
+    log.assert_order(&[1, 2]);
+}
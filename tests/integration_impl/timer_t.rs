@@ -0,0 +1,168 @@
+//  \ O /
+//  / * \    aiur: the homeplanet for the famous executors
+// |' | '|   (c) 2020 - present, Vladimir Zvezda
+//   / \
+//
+// Tests for sleep_until(), sleep_measured() and interval()
+use super::measure::{self};
+use aiur::toy_rt::{self};
+use std::time::Duration;
+
+// With emulated sleep test run instantly, actual sleep actually wait for specified
+// amount of time.
+//const SLEEP_MODE: toy_rt::SleepMode = toy_rt::SleepMode::Actual;
+const SLEEP_MODE: toy_rt::SleepMode = toy_rt::SleepMode::Emulated;
+
+// The sleep until the deadline in the future takes the time left until the deadline
+#[test]
+fn sleep_until_future_deadline() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let start = rt.io().now32();
+        toy_rt::sleep_until(rt, start + 1000).await;
+        measure::assert_duration(rt.io().now32() - start, 1000);
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// The deadline in the past completes right away, nothing is scheduled in the reactor
+#[test]
+fn sleep_until_past_deadline() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        toy_rt::sleep(rt, Duration::from_millis(1000)).await;
+        let start = rt.io().now32();
+
+        let (_, pending) = toy_rt::join!(toy_rt::sleep_until(rt, start - 500), async {
+            rt.io().has_pending()
+        })
+        .await;
+
+        assert!(!pending);
+        measure::assert_duration(rt.io().now32() - start, 0);
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// The deadlines computed from the same start do not drift with the work between the sleeps
+#[test]
+fn sleep_until_deadlines_do_not_drift() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let start = rt.io().now32();
+        for tick in 1..=3 {
+            toy_rt::sleep(rt, Duration::from_millis(300)).await; // the work
+            toy_rt::sleep_until(rt, start + tick * 1000).await;
+        }
+        measure::assert_duration(rt.io().now32() - start, 3000);
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// The sleep that is waiting for its timer when the runtime begins shutdown completes at the
+// shutdown, the sleep started after that completes right away
+#[test]
+fn sleep_completes_on_shutdown() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let start = rt.io().now32();
+        let (_, shutdown_at) = toy_rt::join!(toy_rt::sleep(rt, Duration::from_secs(10)), async {
+            toy_rt::sleep(rt, Duration::from_millis(100)).await;
+            rt.begin_shutdown();
+            rt.io().now32()
+        })
+        .await;
+
+        measure::assert_duration(shutdown_at - start, 100);
+        assert_eq!(rt.io().now32(), shutdown_at);
+
+        toy_rt::sleep(rt, Duration::from_secs(10)).await;
+        assert_eq!(rt.io().now32(), shutdown_at);
+        assert!(!rt.io().has_pending());
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// Three ticks of 1s interval take 3s
+#[test]
+fn interval_three_ticks() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let start = rt.io().now32();
+        let mut interval = toy_rt::interval(rt, Duration::from_millis(1000));
+        for _ in 0..3 {
+            interval.tick().await;
+        }
+        measure::assert_duration(rt.io().now32() - start, 3000);
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// The work between ticks does not make ticks drift, the late tick fires right away and the
+// missed ticks are skipped.
+#[test]
+fn interval_late_tick_fires_right_away() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let start = rt.io().now32();
+        let mut interval = toy_rt::interval(rt, Duration::from_millis(1000));
+
+        toy_rt::sleep(rt, Duration::from_millis(300)).await;
+        interval.tick().await;
+        measure::assert_duration(rt.io().now32() - start, 1000);
+
+        toy_rt::sleep(rt, Duration::from_millis(2500)).await; // late for two ticks
+        interval.tick().await;
+        measure::assert_duration(rt.io().now32() - start, 3500);
+
+        interval.tick().await;
+        measure::assert_duration(rt.io().now32() - start, 4500);
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// In emulated mode the sleep takes exactly the time requested
+#[test]
+fn sleep_measured_emulated() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        toy_rt::sleep(rt, Duration::from_millis(100)).await;
+
+        let (slept, _) = toy_rt::join!(
+            toy_rt::sleep_measured(rt, Duration::from_millis(300)),
+            toy_rt::sleep(rt, Duration::from_millis(200))
+        )
+        .await;
+
+        assert_eq!(slept, 300);
+        assert_eq!(rt.io().now32(), 400);
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// The time measured by the generic code that does not know the reactor type
+#[test]
+fn elapsed_since_generic_reactor() {
+    async fn timed_sleep<ReactorT: aiur::TemporalReactor>(
+        rt: &aiur::Runtime<ReactorT>,
+        duration: Duration,
+    ) -> Duration {
+        let start = rt.elapsed();
+        aiur::sleep(rt, duration).await;
+        aiur::elapsed_since(rt, start)
+    }
+
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        toy_rt::sleep(rt, Duration::from_millis(50)).await;
+        let elapsed = timed_sleep(rt, Duration::from_millis(200)).await;
+        measure::assert_duration(elapsed.as_millis() as u32, 200);
+
+        // the start in the future is not an underflow
+        assert_eq!(
+            toy_rt::elapsed_since(rt, rt.elapsed() + Duration::from_secs(1)),
+            Duration::ZERO
+        );
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
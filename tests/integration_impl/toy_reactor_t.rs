@@ -0,0 +1,294 @@
+//  \ O /
+//  / * \    aiur: the homeplanet for the famous executors
+// |' | '|   (c) 2020 - present, Vladimir Zvezda
+//   / \
+//
+// Tests for the toy reactor API used directly, without a runtime.
+
+use aiur::toy_rt::{self};
+use aiur::{EventId, EventNode, Reactor, TemporalReactor};
+use std::cell::Cell;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll, Waker};
+use std::time::Duration;
+
+//const SLEEP_MODE: toy_rt::SleepMode = toy_rt::SleepMode::Actual;
+const SLEEP_MODE: toy_rt::SleepMode = toy_rt::SleepMode::Emulated;
+
+// Schedules timers and cancels them all at once like runtime does on shutdown.
+#[test]
+fn toy_reactor_cancel_all_timers_leaves_nothing_pending() {
+    let reactor = toy_rt::ToyReactor::new_with_mode(SLEEP_MODE);
+    let nodes = [EventNode::new(), EventNode::new(), EventNode::new()];
+
+    assert!(!reactor.has_pending());
+    for (i, node) in nodes.iter().enumerate() {
+        reactor.schedule_timer(node.get_event_id(), Duration::from_millis(100 * i as u64));
+    }
+    assert!(reactor.has_pending());
+
+    reactor.cancel_all_timers();
+    assert!(!reactor.has_pending());
+
+    // Timer futures still can cancel their timers after that
+    reactor.cancel_timer(nodes[1].get_event_id());
+    assert!(!reactor.has_pending());
+}
+
+// Switching the sleep mode in the middle of run keeps the time monotonic
+#[test]
+fn toy_reactor_set_mode_keeps_now_monotonic() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        rt.io().set_mode(toy_rt::SleepMode::Emulated);
+        toy_rt::sleep(rt, Duration::from_millis(1000)).await;
+        let emulated_now = rt.io().now32();
+        assert!(emulated_now >= 1000);
+
+        // the timer scheduled in emulated mode is fired in actual mode
+        let (_, switched_at) = toy_rt::join!(toy_rt::sleep(rt, Duration::from_millis(20)), async {
+            rt.io().set_mode(toy_rt::SleepMode::Actual);
+            rt.io().now32()
+        })
+        .await;
+        assert!(switched_at >= emulated_now);
+        let actual_now = rt.io().now32();
+        assert!(actual_now >= emulated_now + 20);
+
+        rt.io().set_mode(toy_rt::SleepMode::Emulated);
+        assert!(rt.io().now32() >= actual_now);
+        toy_rt::sleep(rt, Duration::from_millis(1000)).await;
+        assert!(rt.io().now32() >= actual_now + 1000);
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// Runtime::elapsed() grows by the sleep duration
+#[test]
+fn toy_reactor_elapsed_grows_across_sleep() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let before = rt.elapsed();
+        toy_rt::sleep(rt, Duration::from_millis(1500)).await;
+        let after = rt.elapsed();
+
+        assert!(after >= before + Duration::from_millis(1500));
+        assert!(rt.io().now() >= after);
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
+
+// In manual mode the timers are fired only when the test moves the time past their deadline
+#[test]
+fn toy_reactor_manual_mode_fires_due_timers() {
+    let reactor = toy_rt::ToyReactor::new_with_mode(toy_rt::SleepMode::Manual);
+    let nodes = [EventNode::new(), EventNode::new()];
+
+    reactor.schedule_timer(nodes[0].get_event_id(), Duration::from_millis(100));
+    reactor.schedule_timer(nodes[1].get_event_id(), Duration::from_millis(300));
+    assert_eq!(reactor.wait(), EventId::null());
+    assert_eq!(reactor.now32(), 0);
+
+    reactor.advance(Duration::from_millis(150));
+    assert_eq!(reactor.wait(), nodes[0].get_event_id());
+    assert_eq!(reactor.wait(), EventId::null());
+
+    reactor.set_now(300);
+    assert_eq!(reactor.wait(), nodes[1].get_event_id());
+    assert!(!reactor.has_pending());
+}
+
+// The timers scheduled at absolute deadlines fire in the order of deadlines, not the order of
+// scheduling, and exactly at the deadline
+#[test]
+fn toy_reactor_schedule_timer_at_fires_in_deadline_order() {
+    let reactor = toy_rt::ToyReactor::new_with_mode(SLEEP_MODE);
+    let nodes = [EventNode::new(), EventNode::new()];
+
+    reactor.schedule_timer_at(nodes[0].get_event_id(), 500);
+    reactor.schedule_timer_at(nodes[1].get_event_id(), 200);
+
+    assert_eq!(reactor.wait(), nodes[1].get_event_id());
+    assert_eq!(reactor.now32(), 200);
+    assert_eq!(reactor.wait(), nodes[0].get_event_id());
+    assert_eq!(reactor.now32(), 500);
+    assert!(!reactor.has_pending());
+}
+
+// The root task is driven step by step while the time is moved by the test
+#[test]
+fn toy_reactor_manual_mode_drives_root_task() {
+    let rt = toy_rt::Runtime::new(
+        toy_rt::ToyReactor::new_with_mode(toy_rt::SleepMode::Manual),
+        aiur::Tracer::new_empty(),
+    );
+
+    let mut root = rt.root_task(async {
+        toy_rt::sleep(&rt, Duration::from_millis(100)).await;
+        toy_rt::sleep(&rt, Duration::from_millis(200)).await;
+    });
+
+    assert_eq!(root.poll_once(), None);
+    assert_eq!(root.poll_once(), None); // nothing is due, the time stays
+    assert_eq!(rt.io().now32(), 0);
+
+    rt.io().advance(Duration::from_millis(100));
+    assert_eq!(root.poll_once(), None); // the second sleep is scheduled
+    rt.io().advance(Duration::from_millis(199));
+    assert_eq!(root.poll_once(), None);
+    assert!(!root.is_root_complete());
+
+    rt.io().advance(Duration::from_millis(1));
+    assert_eq!(root.poll_once(), Some(root.completion_event_id()));
+}
+
+// The sleeps due at the same time complete in the order chosen by the chaos seed, but every
+// sleep still completes at its time and the outputs of join!() are in place
+#[test]
+fn toy_reactor_chaos_keeps_join_outputs() {
+    async fn async_starter(rt: &toy_rt::Runtime, order: &std::cell::RefCell<Vec<u32>>) {
+        let sleep_and_ret = |id: u32, ms: u64| async move {
+            toy_rt::sleep(rt, Duration::from_millis(ms)).await;
+            order.borrow_mut().push(id);
+            (id, rt.io().now32())
+        };
+
+        let res = toy_rt::join!(
+            sleep_and_ret(1, 100),
+            sleep_and_ret(2, 100),
+            sleep_and_ret(3, 100),
+            sleep_and_ret(4, 200),
+            sleep_and_ret(5, 200)
+        )
+        .await;
+
+        assert_eq!(res, ((1, 100), (2, 100), (3, 100), (4, 200), (5, 200)));
+    }
+
+    let mut orders = Vec::new();
+    for seed in 1..=10 {
+        let order = std::cell::RefCell::new(Vec::new());
+        toy_rt::with_runtime(
+            || toy_rt::ToyReactor::new_with_chaos(seed),
+            aiur::Tracer::new_empty(),
+            async_starter,
+            &order,
+        );
+
+        let order = order.into_inner();
+        let mut first_three = order[..3].to_vec();
+        first_three.sort();
+        assert_eq!(first_three, vec![1, 2, 3]); // 100ms sleeps are never after 200ms ones
+        orders.push(order);
+    }
+
+    orders.dedup();
+    assert!(orders.len() > 1, "chaos seeds have not changed the order");
+}
+
+// The cancelled timers do not pile up in reactor when there is no wait() to pop them
+#[test]
+fn toy_reactor_cancelled_timers_are_compacted() {
+    let reactor = toy_rt::ToyReactor::new_with_mode(SLEEP_MODE);
+    let live = EventNode::new();
+    let node = EventNode::new();
+
+    reactor.schedule_timer(live.get_event_id(), Duration::from_millis(100));
+    for _ in 0..10000 {
+        reactor.schedule_timer(node.get_event_id(), Duration::from_millis(50));
+        reactor.cancel_timer(node.get_event_id());
+        assert!(reactor.timers_in_heap() <= 64);
+    }
+
+    // the timer that was not cancelled survives the compaction
+    assert_eq!(reactor.try_wait(), None);
+    assert_eq!(reactor.next_timer_in(), Some(Duration::from_millis(100)));
+}
+
+// The timer of the lowered maximum duration is scheduled
+#[test]
+fn toy_reactor_custom_max_duration_allows_max() {
+    let reactor = toy_rt::ToyReactor::new_with_max_duration(SLEEP_MODE, 500);
+    let node = EventNode::new();
+
+    reactor.schedule_timer(node.get_event_id(), Duration::from_millis(500));
+    assert_eq!(reactor.wait(), node.get_event_id());
+    assert_eq!(reactor.now32(), 500);
+}
+
+// The timer longer than the lowered maximum duration panics
+#[test]
+#[should_panic(expected = "aiur: Sleep duration is too big: 501ms (max is 500ms)")]
+fn toy_reactor_custom_max_duration_panics_above_max() {
+    let reactor = toy_rt::ToyReactor::new_with_max_duration(SLEEP_MODE, 500);
+    let node = EventNode::new();
+
+    reactor.schedule_timer(node.get_event_id(), Duration::from_millis(501));
+}
+
+// The registered event made happen by wake_manually() is returned by wait() before the timers
+// that are not due yet, so wait() does not sleep for them
+#[test]
+fn toy_reactor_wake_manually_before_timers() {
+    let reactor = toy_rt::ToyReactor::new_with_mode(SLEEP_MODE);
+    let (timer, io) = (EventNode::new(), EventNode::new());
+
+    reactor.schedule_timer(timer.get_event_id(), Duration::from_millis(100));
+    reactor.register_event(Waker::noop().clone(), io.get_event_id());
+    reactor.wake_manually(io.get_event_id());
+
+    assert_eq!(reactor.wait(), io.get_event_id());
+    assert_eq!(reactor.now32(), 0);
+    assert_eq!(reactor.wait(), timer.get_event_id());
+    assert!(!reactor.has_pending());
+}
+
+// Leaf future that waits for the I/O event registered in reactor, like a socket read would do
+struct IoFuture<'rt> {
+    rt: &'rt toy_rt::Runtime,
+    event_node: EventNode,
+    registered: &'rt Cell<Option<EventId>>,
+}
+
+impl Future for IoFuture<'_> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let this = unsafe { self.get_unchecked_mut() };
+        if this.registered.get().is_none() {
+            let event_id = unsafe { this.event_node.on_pin(ctx) };
+            this.rt.io().register_event(ctx.waker().clone(), event_id);
+            this.registered.set(Some(event_id));
+            Poll::Pending
+        } else if this.event_node.is_awoken_for(this.rt) {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+// The leaf future awaiting the I/O event is awoken by runtime when wake_manually() is invoked
+// before the runtime waits in reactor
+#[test]
+fn toy_reactor_wake_manually_awakes_leaf_future() {
+    async fn async_starter(rt: &toy_rt::Runtime, _: ()) {
+        let registered = Cell::new(None);
+        let io = IoFuture {
+            rt,
+            event_node: EventNode::new(),
+            registered: &registered,
+        };
+
+        toy_rt::join!(io, async {
+            rt.io().wake_manually(registered.get().unwrap());
+        })
+        .await;
+
+        assert_eq!(rt.elapsed(), Duration::ZERO);
+        assert!(!rt.io().has_pending());
+    }
+
+    toy_rt::with_runtime_in_mode(SLEEP_MODE, async_starter, ());
+}
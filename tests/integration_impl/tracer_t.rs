@@ -0,0 +1,52 @@
+//  \ O /
+//  / * \    aiur: the homeplanet for the famous executors
+// |' | '|   (c) 2020 - present, Vladimir Zvezda
+//   / \
+//
+// Tests for Tracer with the sink.
+use aiur::toy_rt::{self};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+// The state transitions of the channel are collected by the sink
+#[test]
+fn tracer_sink_collects_channel_transitions() {
+    let lines = Rc::new(RefCell::new(Vec::new()));
+    let sink_lines = lines.clone();
+    let tracer = aiur::Tracer::with_sink(move |args: std::fmt::Arguments| {
+        sink_lines.borrow_mut().push(args.to_string())
+    });
+
+    let rt = toy_rt::Runtime::new(
+        toy_rt::ToyReactor::new_with_mode(toy_rt::SleepMode::Emulated),
+        tracer,
+    );
+
+    rt.block_on(async {
+        let (mut tx, mut rx) = toy_rt::channel::<u32>(&rt);
+        let (sent, received) = toy_rt::join!(tx.send(5), rx.next()).await;
+        assert_eq!((sent, received), (Ok(()), Ok(5)));
+    });
+
+    let channel_lines: Vec<String> = lines
+        .borrow()
+        .iter()
+        .filter(|line| line.starts_with("channel_rt:"))
+        .cloned()
+        .collect();
+
+    assert_eq!(
+        channel_lines,
+        vec![
+            "channel_rt: new chan:1 (Idle <- [0]:0)",
+            "channel_rt: chan:1 inc senders (Idle <- [0]:0) -> (Idle <- [0]:1) ",
+            "channel_rt: chan:1 add sender future (Idle <- [0]:1) -> (Idle <- [Pin]:1) ",
+            "channel_rt: chan:1 reg receiver future (Idle <- [Pin]:1) -> (@Pin <- [Pin]:1) ",
+            "channel_rt: chan:1 mem::swapped (@Pin <- [Pin]:1) -> (Idle <- [@Empt]:1) ",
+            "channel_rt: chan:1 awoken sender (Idle <- [@Empt]:1) -> (Idle <- [0]:1) ",
+            "channel_rt: chan:1 receiver gone (Idle <- [0]:1) -> (Gone <- [0]:1) ",
+            "channel_rt: chan:1 dec senders (Gone <- [0]:1) -> (Gone <- [0]:0) ",
+            "channel_rt: chan:1 has been removed",
+        ]
+    );
+}